@@ -0,0 +1,674 @@
+use crate::proxy::upstream_tls::UpstreamTls;
+use crate::vojo::app_config::UpstreamTlsConfig;
+use base64::{engine::general_purpose, Engine as _};
+use dashmap::DashMap;
+use http::header::{CONTENT_TYPE, LOCATION};
+use hyper::{Body, Method, Request, Response};
+use lazy_static::lazy_static;
+use openssl::bn::{BigNum, BigNumContext};
+use openssl::ec::{EcGroup, EcKey};
+use openssl::ecdsa::EcdsaSig;
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private};
+use openssl::sha::sha256;
+use openssl::stack::Stack;
+use openssl::x509::extension::SubjectAlternativeName;
+use openssl::x509::{X509NameBuilder, X509ReqBuilder};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::time::sleep;
+pub const LETS_ENCRYPT_DIRECTORY_URL: &str = "https://acme-v02.api.letsencrypt.org/directory";
+const ACME_CHALLENGE_PATH: &str = "/.well-known/acme-challenge/";
+const REPLAY_NONCE: &str = "replay-nonce";
+const BAD_NONCE_ERROR: &str = "urn:ietf:params:acme:error:badNonce";
+const POLL_INTERVAL_MILLIS: u64 = 1000;
+const POLL_TIMES: usize = 60;
+lazy_static! {
+    static ref GLOBAL_ACME_CHALLENGES: DashMap<String, String> = Default::default();
+}
+/**
+*The key authorization of the pending http-01 challenge,which is answered by the http listeners.
+*/
+pub fn acme_challenge_response(path: &str) -> Option<String> {
+    let token = path.strip_prefix(ACME_CHALLENGE_PATH)?;
+    GLOBAL_ACME_CHALLENGES
+        .get(token)
+        .map(|item| item.value().clone())
+}
+fn base64_url(data: &[u8]) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(data)
+}
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Directory {
+    new_nonce: String,
+    new_account: String,
+    new_order: String,
+}
+#[derive(Debug, Clone, Deserialize)]
+struct Order {
+    status: String,
+    authorizations: Vec<String>,
+    finalize: String,
+    certificate: Option<String>,
+}
+#[derive(Debug, Clone, Deserialize)]
+struct Authorization {
+    status: String,
+    challenges: Vec<Challenge>,
+}
+#[derive(Debug, Clone, Deserialize)]
+struct Challenge {
+    #[serde(rename = "type")]
+    challenge_type: String,
+    url: String,
+    token: String,
+}
+/**
+*The account key signs the requests with ES256 as RFC 8555 requires.
+*/
+pub struct AccountKey {
+    ec_key: EcKey<Private>,
+}
+impl AccountKey {
+    pub fn generate() -> Result<Self, anyhow::Error> {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+        Ok(AccountKey {
+            ec_key: EcKey::generate(&group)?,
+        })
+    }
+    pub fn from_pem(pem_str: &str) -> Result<Self, anyhow::Error> {
+        let ec_key = EcKey::private_key_from_pem(pem_str.as_bytes())
+            .map_err(|e| anyhow!("Parse the acme account key error,the error is {}.", e))?;
+        Ok(AccountKey { ec_key })
+    }
+    pub fn to_pem(&self) -> Result<String, anyhow::Error> {
+        Ok(String::from_utf8(self.ec_key.private_key_to_pem()?)?)
+    }
+    fn coordinates(&self) -> Result<(String, String), anyhow::Error> {
+        let mut x = BigNum::new()?;
+        let mut y = BigNum::new()?;
+        let mut ctx = BigNumContext::new()?;
+        self.ec_key.public_key().affine_coordinates_gfp(
+            self.ec_key.group(),
+            &mut x,
+            &mut y,
+            &mut ctx,
+        )?;
+        Ok((
+            base64_url(&x.to_vec_padded(32)?),
+            base64_url(&y.to_vec_padded(32)?),
+        ))
+    }
+    pub fn jwk(&self) -> Result<Value, anyhow::Error> {
+        let (x, y) = self.coordinates()?;
+        Ok(json!({ "crv": "P-256", "kty": "EC", "x": x, "y": y }))
+    }
+    /**
+     *The thumbprint of RFC 7638 hashes the required members of the jwk in lexicographic order.
+     */
+    pub fn thumbprint(&self) -> Result<String, anyhow::Error> {
+        let (x, y) = self.coordinates()?;
+        let jwk = format!(r#"{{"crv":"P-256","kty":"EC","x":"{}","y":"{}"}}"#, x, y);
+        Ok(base64_url(&sha256(jwk.as_bytes())))
+    }
+    fn sign(&self, protected: &Value, payload: &str) -> Result<Value, anyhow::Error> {
+        let protected = base64_url(protected.to_string().as_bytes());
+        let signing_input = format!("{}.{}", protected, payload);
+        let signature = EcdsaSig::sign(&sha256(signing_input.as_bytes()), &self.ec_key)?;
+        let mut signature_bytes = signature.r().to_vec_padded(32)?;
+        signature_bytes.extend(signature.s().to_vec_padded(32)?);
+        Ok(json!({
+            "protected": protected,
+            "payload": payload,
+            "signature": base64_url(&signature_bytes),
+        }))
+    }
+}
+/**
+*The acme client orders the certificates with the http-01 challenges.
+*/
+pub struct AcmeClient {
+    upstream_tls: UpstreamTls,
+    directory: Directory,
+    account_key: AccountKey,
+    account_url: Option<String>,
+    nonce: Option<String>,
+}
+impl AcmeClient {
+    pub async fn new(
+        directory_url: &str,
+        directory_tls: Option<UpstreamTlsConfig>,
+        account_key: AccountKey,
+    ) -> Result<Self, anyhow::Error> {
+        let upstream_tls = UpstreamTls::new(directory_tls.unwrap_or_default())?;
+        let request = Request::get(directory_url).body(Body::empty())?;
+        let response = upstream_tls.https_client.request(request).await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Get the acme directory error,the status is {}.",
+                response.status()
+            ));
+        }
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        let directory = serde_json::from_slice(&body)?;
+        Ok(AcmeClient {
+            upstream_tls,
+            directory,
+            account_key,
+            account_url: None,
+            nonce: None,
+        })
+    }
+    async fn new_nonce(&self) -> Result<String, anyhow::Error> {
+        let request = Request::builder()
+            .method(Method::HEAD)
+            .uri(self.directory.new_nonce.as_str())
+            .body(Body::empty())?;
+        let response = self.upstream_tls.https_client.request(request).await?;
+        get_header(&response, REPLAY_NONCE).ok_or(anyhow!("Can not get the acme nonce."))
+    }
+    async fn post_once(
+        &mut self,
+        url: &str,
+        payload: &str,
+    ) -> Result<Response<Body>, anyhow::Error> {
+        let nonce = match self.nonce.take() {
+            Some(nonce) => nonce,
+            None => self.new_nonce().await?,
+        };
+        let mut protected = json!({ "alg": "ES256", "nonce": nonce, "url": url });
+        match &self.account_url {
+            Some(account_url) => protected["kid"] = json!(account_url),
+            None => protected["jwk"] = self.account_key.jwk()?,
+        }
+        let body = self.account_key.sign(&protected, payload)?;
+        let request = Request::post(url)
+            .header(CONTENT_TYPE, "application/jose+json")
+            .body(Body::from(body.to_string()))?;
+        let response = self.upstream_tls.https_client.request(request).await?;
+        self.nonce = get_header(&response, REPLAY_NONCE);
+        Ok(response)
+    }
+    /**
+    *Post the payload as the jws,or post as get if the payload is none.The request is sent again
+    with a new nonce if the server rejects the nonce.
+    */
+    async fn post(
+        &mut self,
+        url: &str,
+        payload: Option<Value>,
+    ) -> Result<Response<Body>, anyhow::Error> {
+        let payload = payload
+            .map(|item| base64_url(item.to_string().as_bytes()))
+            .unwrap_or_default();
+        let mut retry_bad_nonce = true;
+        loop {
+            let response = self.post_once(url, &payload).await?;
+            if response.status().is_success() {
+                return Ok(response);
+            }
+            let status = response.status();
+            let body = hyper::body::to_bytes(response.into_body()).await?;
+            let problem: Value = serde_json::from_slice(&body).unwrap_or_default();
+            if retry_bad_nonce && problem["type"] == BAD_NONCE_ERROR {
+                retry_bad_nonce = false;
+                continue;
+            }
+            return Err(anyhow!(
+                "Request the acme url {} error,the status is {},the body is {}.",
+                url,
+                status,
+                String::from_utf8_lossy(&body)
+            ));
+        }
+    }
+    async fn post_json<T: DeserializeOwned>(
+        &mut self,
+        url: &str,
+        payload: Option<Value>,
+    ) -> Result<(T, Option<String>), anyhow::Error> {
+        let response = self.post(url, payload).await?;
+        let location = get_header(&response, LOCATION.as_str());
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        Ok((serde_json::from_slice(&body)?, location))
+    }
+    async fn create_account(&mut self, contact_email: &str) -> Result<(), anyhow::Error> {
+        let payload = json!({
+            "termsOfServiceAgreed": true,
+            "contact": [format!("mailto:{}", contact_email)],
+        });
+        let new_account = self.directory.new_account.clone();
+        let (_, location) = self.post_json::<Value>(&new_account, Some(payload)).await?;
+        self.account_url = Some(location.ok_or(anyhow!("Can not get the acme account url."))?);
+        Ok(())
+    }
+    async fn authorize(&mut self, authorization_url: &str) -> Result<(), anyhow::Error> {
+        let (authorization, _) = self
+            .post_json::<Authorization>(authorization_url, None)
+            .await?;
+        if authorization.status == "valid" {
+            return Ok(());
+        }
+        let challenge = authorization
+            .challenges
+            .into_iter()
+            .find(|item| item.challenge_type == "http-01")
+            .ok_or(anyhow!("Can not find the http-01 challenge."))?;
+        let key_authorization = format!("{}.{}", challenge.token, self.account_key.thumbprint()?);
+        GLOBAL_ACME_CHALLENGES.insert(challenge.token.clone(), key_authorization);
+        let result = self.validate(authorization_url, &challenge.url).await;
+        GLOBAL_ACME_CHALLENGES.remove(&challenge.token);
+        result
+    }
+    async fn validate(
+        &mut self,
+        authorization_url: &str,
+        challenge_url: &str,
+    ) -> Result<(), anyhow::Error> {
+        self.post(challenge_url, Some(json!({}))).await?;
+        for _ in 0..POLL_TIMES {
+            let (authorization, _) = self
+                .post_json::<Authorization>(authorization_url, None)
+                .await?;
+            match authorization.status.as_str() {
+                "valid" => return Ok(()),
+                "pending" | "processing" => {
+                    sleep(Duration::from_millis(POLL_INTERVAL_MILLIS)).await
+                }
+                status => {
+                    return Err(anyhow!(
+                        "The authorization {} is {}.",
+                        authorization_url,
+                        status
+                    ))
+                }
+            }
+        }
+        Err(anyhow!(
+            "The authorization {} is timeout.",
+            authorization_url
+        ))
+    }
+    async fn wait_for_certificate(
+        &mut self,
+        order_url: &str,
+        mut order: Order,
+    ) -> Result<String, anyhow::Error> {
+        for _ in 0..POLL_TIMES {
+            match (order.status.as_str(), order.certificate) {
+                ("valid", Some(certificate_url)) => return Ok(certificate_url),
+                ("pending" | "ready" | "processing" | "valid", _) => {
+                    sleep(Duration::from_millis(POLL_INTERVAL_MILLIS)).await;
+                    (order, _) = self.post_json::<Order>(order_url, None).await?;
+                }
+                (status, _) => return Err(anyhow!("The order {} is {}.", order_url, status)),
+            }
+        }
+        Err(anyhow!("The order {} is timeout.", order_url))
+    }
+    /**
+     *Order the certificate of the domains,and return the pem chain and the pkcs8 private key.
+     */
+    pub async fn order_certificate(
+        &mut self,
+        contact_email: &str,
+        domains: &[String],
+    ) -> Result<(String, String), anyhow::Error> {
+        if self.account_url.is_none() {
+            self.create_account(contact_email).await?;
+        }
+        let identifiers = domains
+            .iter()
+            .map(|item| json!({ "type": "dns", "value": item }))
+            .collect::<Vec<Value>>();
+        let new_order = self.directory.new_order.clone();
+        let (order, location) = self
+            .post_json::<Order>(&new_order, Some(json!({ "identifiers": identifiers })))
+            .await?;
+        let order_url = location.ok_or(anyhow!("Can not get the acme order url."))?;
+        for authorization_url in order.authorizations.iter() {
+            self.authorize(authorization_url).await?;
+        }
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+        let private_key = PKey::from_ec_key(EcKey::generate(&group)?)?;
+        let csr = new_csr(&private_key, domains)?;
+        let (order, _) = self
+            .post_json::<Order>(&order.finalize, Some(json!({ "csr": base64_url(&csr) })))
+            .await?;
+        let certificate_url = self.wait_for_certificate(&order_url, order).await?;
+        let response = self.post(&certificate_url, None).await?;
+        let certificate = hyper::body::to_bytes(response.into_body()).await?;
+        Ok((
+            String::from_utf8(certificate.to_vec())?,
+            String::from_utf8(private_key.private_key_to_pem_pkcs8()?)?,
+        ))
+    }
+}
+fn get_header(response: &Response<Body>, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|item| item.to_str().ok())
+        .map(String::from)
+}
+fn new_csr(private_key: &PKey<Private>, domains: &[String]) -> Result<Vec<u8>, anyhow::Error> {
+    let mut builder = X509ReqBuilder::new()?;
+    let mut name = X509NameBuilder::new()?;
+    name.append_entry_by_nid(
+        Nid::COMMONNAME,
+        domains
+            .first()
+            .ok_or(anyhow!("The domains could not be empty."))?,
+    )?;
+    builder.set_subject_name(&name.build())?;
+    builder.set_pubkey(private_key)?;
+    let mut subject_alt_name = SubjectAlternativeName::new();
+    for domain in domains.iter() {
+        subject_alt_name.dns(domain);
+    }
+    let mut extensions = Stack::new()?;
+    extensions.push(subject_alt_name.build(&builder.x509v3_context(None))?)?;
+    builder.add_extensions(&extensions)?;
+    builder.sign(private_key, MessageDigest::sha256())?;
+    Ok(builder.build().to_der()?)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::certificate_resolver::parse_certified_key;
+    use crate::proxy::http1::http_proxy::HttpProxy;
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Server, StatusCode};
+    use openssl::asn1::Asn1Time;
+    use openssl::x509::{X509Builder, X509Req, X509};
+    use std::collections::HashSet;
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+    use std::sync::{Arc, Mutex};
+    const MOCK_ACME_URL: &str = "http://127.0.0.1:4458";
+    const MOCK_TOKEN: &str = "mock-token";
+    #[derive(Default)]
+    struct MockState {
+        nonce_index: u64,
+        nonces: HashSet<String>,
+        is_nonce_rejected: bool,
+        jwk: Option<Value>,
+        is_authorized: bool,
+        certificate: Option<String>,
+    }
+    fn decode(data: &str) -> Vec<u8> {
+        general_purpose::URL_SAFE_NO_PAD.decode(data).unwrap()
+    }
+    fn new_nonce(state: &mut MockState) -> String {
+        state.nonce_index += 1;
+        let nonce = format!("nonce-{}", state.nonce_index);
+        state.nonces.insert(nonce.clone());
+        nonce
+    }
+    fn response(state: &mut MockState, status: StatusCode, body: Value) -> Response<Body> {
+        Response::builder()
+            .status(status)
+            .header(REPLAY_NONCE, new_nonce(state))
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+    /**
+     *Verify the jws like the acme server,and return the url and the payload.
+     */
+    fn verify_jws(state: &mut MockState, body: &[u8]) -> Result<(String, String), String> {
+        let jws: Value = serde_json::from_slice(body).unwrap();
+        let protected_str = jws["protected"].as_str().unwrap();
+        let payload = jws["payload"].as_str().unwrap();
+        let protected: Value = serde_json::from_slice(&decode(protected_str)).unwrap();
+        assert_eq!(protected["alg"], "ES256");
+        if !state.nonces.remove(protected["nonce"].as_str().unwrap()) || !state.is_nonce_rejected {
+            state.is_nonce_rejected = true;
+            return Err(String::from(BAD_NONCE_ERROR));
+        }
+        if protected["jwk"].is_object() {
+            state.jwk = Some(protected["jwk"].clone());
+        } else {
+            assert_eq!(protected["kid"], format!("{}/account/1", MOCK_ACME_URL));
+        }
+        let jwk = state.jwk.clone().unwrap();
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let public_key = EcKey::from_public_key_affine_coordinates(
+            &group,
+            &BigNum::from_slice(&decode(jwk["x"].as_str().unwrap())).unwrap(),
+            &BigNum::from_slice(&decode(jwk["y"].as_str().unwrap())).unwrap(),
+        )
+        .unwrap();
+        let signature = decode(jws["signature"].as_str().unwrap());
+        let signature = EcdsaSig::from_private_components(
+            BigNum::from_slice(&signature[..32]).unwrap(),
+            BigNum::from_slice(&signature[32..]).unwrap(),
+        )
+        .unwrap();
+        let signing_input = format!("{}.{}", protected_str, payload);
+        assert!(signature
+            .verify(&sha256(signing_input.as_bytes()), &public_key)
+            .unwrap());
+        Ok((
+            protected["url"].as_str().unwrap().to_string(),
+            String::from_utf8(decode(payload)).unwrap(),
+        ))
+    }
+    fn issue_certificate(csr: &[u8]) -> String {
+        let request = X509Req::from_der(csr).unwrap();
+        let public_key = request.public_key().unwrap();
+        assert!(request.verify(&public_key).unwrap());
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let ca_key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(request.subject_name()).unwrap();
+        let mut issuer = X509NameBuilder::new().unwrap();
+        issuer
+            .append_entry_by_nid(Nid::COMMONNAME, "Mock Acme CA")
+            .unwrap();
+        builder.set_issuer_name(&issuer.build()).unwrap();
+        builder.set_pubkey(&public_key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(90).unwrap())
+            .unwrap();
+        builder.sign(&ca_key, MessageDigest::sha256()).unwrap();
+        String::from_utf8(builder.build().to_pem().unwrap()).unwrap()
+    }
+    async fn fetch_key_authorization() -> String {
+        let uri = format!("http://127.0.0.1:4457{}{}", ACME_CHALLENGE_PATH, MOCK_TOKEN);
+        let response = hyper::Client::new()
+            .get(uri.parse().unwrap())
+            .await
+            .unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+    async fn handle(
+        state: Arc<Mutex<MockState>>,
+        req: Request<Body>,
+    ) -> Result<Response<Body>, Infallible> {
+        let path = req.uri().path().to_string();
+        if req.method() == Method::GET {
+            let directory = json!({
+                "newNonce": format!("{}/nonce", MOCK_ACME_URL),
+                "newAccount": format!("{}/account", MOCK_ACME_URL),
+                "newOrder": format!("{}/order", MOCK_ACME_URL),
+            });
+            return Ok(response(
+                &mut state.lock().unwrap(),
+                StatusCode::OK,
+                directory,
+            ));
+        }
+        if req.method() == Method::HEAD {
+            return Ok(response(
+                &mut state.lock().unwrap(),
+                StatusCode::OK,
+                json!({}),
+            ));
+        }
+        let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+        let verify_result = verify_jws(&mut state.lock().unwrap(), &body);
+        let (url, payload) = match verify_result {
+            Ok(item) => item,
+            Err(problem_type) => {
+                return Ok(response(
+                    &mut state.lock().unwrap(),
+                    StatusCode::BAD_REQUEST,
+                    json!({ "type": problem_type }),
+                ))
+            }
+        };
+        assert_eq!(url, format!("{}{}", MOCK_ACME_URL, path));
+        if path == "/challenge" {
+            let key_authorization = fetch_key_authorization().await;
+            let mut state = state.lock().unwrap();
+            let jwk = state.jwk.clone().unwrap();
+            let thumbprint = base64_url(&sha256(
+                format!(
+                    r#"{{"crv":"P-256","kty":"EC","x":{},"y":{}}}"#,
+                    jwk["x"], jwk["y"]
+                )
+                .as_bytes(),
+            ));
+            state.is_authorized = key_authorization == format!("{}.{}", MOCK_TOKEN, thumbprint);
+            return Ok(response(&mut state, StatusCode::OK, json!({})));
+        }
+        let mut state = state.lock().unwrap();
+        let authorization_status = if state.is_authorized {
+            "valid"
+        } else {
+            "pending"
+        };
+        let order_status = match (state.is_authorized, &state.certificate) {
+            (_, Some(_)) => "valid",
+            (true, None) => "ready",
+            _ => "pending",
+        };
+        let order = json!({
+            "status": order_status,
+            "authorizations": [format!("{}/authorization", MOCK_ACME_URL)],
+            "finalize": format!("{}/finalize", MOCK_ACME_URL),
+            "certificate": state.certificate.as_ref().map(|_| format!("{}/certificate", MOCK_ACME_URL)),
+        });
+        let mut response = match path.as_str() {
+            "/account" => {
+                let payload: Value = serde_json::from_str(&payload).unwrap();
+                assert_eq!(payload["contact"][0], "mailto:admin@example.com");
+                let mut response = response(&mut state, StatusCode::CREATED, json!({}));
+                response.headers_mut().insert(
+                    LOCATION,
+                    format!("{}/account/1", MOCK_ACME_URL).parse().unwrap(),
+                );
+                response
+            }
+            "/order" => response(&mut state, StatusCode::CREATED, order),
+            "/authorization" => {
+                let authorization = json!({
+                    "status": authorization_status,
+                    "challenges": [
+                        { "type": "dns-01", "url": format!("{}/dns", MOCK_ACME_URL), "token": "dns" },
+                        { "type": "http-01", "url": format!("{}/challenge", MOCK_ACME_URL), "token": MOCK_TOKEN },
+                    ],
+                });
+                response(&mut state, StatusCode::OK, authorization)
+            }
+            "/finalize" => {
+                let payload: Value = serde_json::from_str(&payload).unwrap();
+                state.certificate =
+                    Some(issue_certificate(&decode(payload["csr"].as_str().unwrap())));
+                let mut order = order;
+                order["status"] = json!("processing");
+                response(&mut state, StatusCode::OK, order)
+            }
+            _ => {
+                let certificate = state.certificate.clone().unwrap();
+                let mut response = response(&mut state, StatusCode::OK, json!({}));
+                *response.body_mut() = Body::from(certificate);
+                response
+            }
+        };
+        if path == "/order" {
+            response.headers_mut().insert(
+                LOCATION,
+                format!("{}/order", MOCK_ACME_URL).parse().unwrap(),
+            );
+        }
+        Ok(response)
+    }
+    async fn start_mock_acme_server(port: u16) {
+        let state = Arc::new(Mutex::new(MockState::default()));
+        let make_service = make_service_fn(move |_| {
+            let state = state.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |req| handle(state.clone(), req))) }
+        });
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], port))).serve(make_service);
+        tokio::spawn(server);
+    }
+    #[tokio::test]
+    async fn test_acme_client_order_certificate_ok() {
+        start_mock_acme_server(4458).await;
+        let (_sender, receiver) = tokio::sync::mpsc::channel(10);
+        tokio::spawn(async move {
+            let mut http_proxy = HttpProxy {
+                port: 4457,
+                channel: receiver,
+                mapping_key: String::from("4457-HTTP"),
+            };
+            let _result = http_proxy.start_http_server().await;
+        });
+        sleep(Duration::from_millis(100)).await;
+        let mut acme_client = AcmeClient::new(
+            &format!("{}/directory", MOCK_ACME_URL),
+            None,
+            AccountKey::generate().unwrap(),
+        )
+        .await
+        .unwrap();
+        let domains = vec![String::from("acme.example.com")];
+        let (cert_str, key_str) = acme_client
+            .order_certificate("admin@example.com", &domains)
+            .await
+            .unwrap();
+        assert!(parse_certified_key(&cert_str, &key_str, None).is_ok());
+        let certificate = X509::from_pem(cert_str.as_bytes()).unwrap();
+        let private_key = PKey::private_key_from_pem(key_str.as_bytes()).unwrap();
+        assert!(certificate.public_key().unwrap().public_eq(&private_key));
+        assert!(
+            acme_challenge_response(&format!("{}{}", ACME_CHALLENGE_PATH, MOCK_TOKEN)).is_none()
+        );
+    }
+    #[test]
+    fn test_account_key_pem_ok() {
+        let account_key = AccountKey::generate().unwrap();
+        let pem_str = account_key.to_pem().unwrap();
+        let loaded_account_key = AccountKey::from_pem(&pem_str).unwrap();
+        assert_eq!(
+            account_key.thumbprint().unwrap(),
+            loaded_account_key.thumbprint().unwrap()
+        );
+        assert_eq!(
+            account_key.jwk().unwrap(),
+            loaded_account_key.jwk().unwrap()
+        );
+        assert!(AccountKey::from_pem("test").is_err());
+    }
+    #[test]
+    fn test_acme_challenge_response_ok() {
+        GLOBAL_ACME_CHALLENGES.insert(String::from("token"), String::from("token.thumbprint"));
+        assert_eq!(
+            acme_challenge_response("/.well-known/acme-challenge/token"),
+            Some(String::from("token.thumbprint"))
+        );
+        assert!(acme_challenge_response("/.well-known/acme-challenge/other").is_none());
+        assert!(acme_challenge_response("/token").is_none());
+        GLOBAL_ACME_CHALLENGES.remove("token");
+    }
+}
@@ -0,0 +1,319 @@
+use crate::acme::acme_client::{AccountKey, AcmeClient, LETS_ENCRYPT_DIRECTORY_URL};
+use crate::configuration_service::app_config_service::GLOBAL_CONFIG_MAPPING;
+use crate::constants::common_constants::{DEFAULT_TEMPORARY_DIR, TIMER_WAIT_SECONDS};
+use crate::monitor::prometheus_exporter::set_certificate_expiry;
+use crate::proxy::certificate_resolver::update_server_config;
+use crate::vojo::app_config::{AcmeConfig, CertificateConfig, ServiceConfig};
+use dashmap::DashMap;
+use futures::FutureExt;
+use lazy_static::lazy_static;
+use openssl::asn1::Asn1Time;
+use openssl::x509::X509;
+#[cfg(unix)]
+use std::fs::Permissions;
+use std::fs::{DirBuilder, OpenOptions};
+use std::io::{ErrorKind, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::time::{sleep, Duration, Instant};
+const ACME_DIR: &str = "acme";
+const ACCOUNT_KEY_FILE: &str = "account.pem";
+const DEFAULT_RENEW_BEFORE_DAYS: i64 = 30;
+const ORDER_RETRY_SECONDS: u64 = 3600;
+lazy_static! {
+    static ref GLOBAL_ACME_CERTIFICATES: DashMap<String, CertificateConfig> = Default::default();
+    static ref GLOBAL_ATTACHED_CERTIFICATES: DashMap<String, String> = Default::default();
+    static ref GLOBAL_ORDER_RETRY_TIME: DashMap<String, Instant> = Default::default();
+}
+fn certificate_key(acme_config: &AcmeConfig) -> String {
+    acme_config.domains.join(",")
+}
+fn acme_dir() -> PathBuf {
+    PathBuf::from(DEFAULT_TEMPORARY_DIR).join(ACME_DIR)
+}
+fn certificate_paths(certificate_key: &str) -> (PathBuf, PathBuf) {
+    let file_name = certificate_key.replace(['*', ','], "_");
+    (
+        acme_dir().join(format!("{}.crt", file_name)),
+        acme_dir().join(format!("{}.key", file_name)),
+    )
+}
+/**
+*The managed certificate of the acme config,which is attached to the listener as the one of the
+ domains.
+*/
+pub fn get_acme_certificate(acme_config: &AcmeConfig) -> Option<CertificateConfig> {
+    GLOBAL_ACME_CERTIFICATES
+        .get(&certificate_key(acme_config))
+        .map(|item| item.value().clone())
+}
+fn load_certificate(acme_config: &AcmeConfig) -> Option<CertificateConfig> {
+    let (cert_path, key_path) = certificate_paths(&certificate_key(acme_config));
+    Some(CertificateConfig {
+        server_names: acme_config.domains.clone(),
+        cert_str: std::fs::read_to_string(cert_path).ok()?,
+        key_str: std::fs::read_to_string(key_path).ok()?,
+        key_passphrase: None,
+    })
+}
+/**
+*The acme dir contains the private keys,so only the owner could access it.
+*/
+fn create_acme_dir() -> Result<(), anyhow::Error> {
+    std::fs::create_dir_all(DEFAULT_TEMPORARY_DIR)?;
+    let mut dir_builder = DirBuilder::new();
+    #[cfg(unix)]
+    dir_builder.mode(0o700);
+    if let Err(err) = dir_builder.create(acme_dir()) {
+        if err.kind() != ErrorKind::AlreadyExists {
+            return Err(anyhow!(err));
+        }
+    }
+    #[cfg(unix)]
+    std::fs::set_permissions(acme_dir(), Permissions::from_mode(0o700))?;
+    Ok(())
+}
+/**
+*The private key is created as 0600,and the permissions of the existing file are fixed as well.
+*/
+fn write_private_key(path: PathBuf, key_str: &str) -> Result<(), anyhow::Error> {
+    let mut open_options = OpenOptions::new();
+    open_options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    open_options.mode(0o600);
+    let mut file = open_options.open(path)?;
+    #[cfg(unix)]
+    file.set_permissions(Permissions::from_mode(0o600))?;
+    file.write_all(key_str.as_bytes())?;
+    file.sync_all()?;
+    Ok(())
+}
+fn save_certificate(certificate_config: &CertificateConfig) -> Result<(), anyhow::Error> {
+    let (cert_path, key_path) = certificate_paths(&certificate_config.server_names.join(","));
+    create_acme_dir()?;
+    std::fs::write(cert_path, &certificate_config.cert_str)?;
+    write_private_key(key_path, &certificate_config.key_str)?;
+    Ok(())
+}
+fn load_account_key() -> Result<AccountKey, anyhow::Error> {
+    let path = acme_dir().join(ACCOUNT_KEY_FILE);
+    if let Ok(pem_str) = std::fs::read_to_string(&path) {
+        return AccountKey::from_pem(&pem_str);
+    }
+    let account_key = AccountKey::generate()?;
+    create_acme_dir()?;
+    write_private_key(path, &account_key.to_pem()?)?;
+    Ok(account_key)
+}
+/**
+*The unix timestamp when the first certificate of the pem expires.
+*/
+pub fn get_expiry_timestamp(cert_str: &str) -> Result<i64, anyhow::Error> {
+    let certificate = X509::from_pem(cert_str.as_bytes())?;
+    let time_diff = Asn1Time::from_unix(0)?.diff(certificate.not_after())?;
+    Ok(time_diff.days as i64 * 86400 + time_diff.secs as i64)
+}
+fn needs_renewal(acme_config: &AcmeConfig, expiry_timestamp: i64) -> bool {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|item| item.as_secs() as i64)
+        .unwrap_or_default();
+    let renew_before_days = acme_config
+        .renew_before_days
+        .unwrap_or(DEFAULT_RENEW_BEFORE_DAYS);
+    expiry_timestamp - now < renew_before_days * 86400
+}
+async fn order_certificate(acme_config: &AcmeConfig) -> Result<CertificateConfig, anyhow::Error> {
+    let directory_url = acme_config
+        .directory_url
+        .as_deref()
+        .unwrap_or(LETS_ENCRYPT_DIRECTORY_URL);
+    let mut acme_client = AcmeClient::new(
+        directory_url,
+        acme_config.directory_tls.clone(),
+        load_account_key()?,
+    )
+    .await?;
+    let (cert_str, key_str) = acme_client
+        .order_certificate(&acme_config.contact_email, &acme_config.domains)
+        .await?;
+    Ok(CertificateConfig {
+        server_names: acme_config.domains.clone(),
+        cert_str,
+        key_str,
+        key_passphrase: None,
+    })
+}
+/**
+*Obtain the certificate if it is missing or going to expire,and attach it to the listener.The
+ failed order is retried an hour later.
+*/
+async fn sync_acme_certificate(
+    mapping_key: &str,
+    service_config: &ServiceConfig,
+    acme_config: &AcmeConfig,
+) -> Result<(), anyhow::Error> {
+    let certificate_key = certificate_key(acme_config);
+    let mut certificate_option = get_acme_certificate(acme_config);
+    if certificate_option.is_none() {
+        certificate_option = load_certificate(acme_config);
+    }
+    let expiry_timestamp_option = certificate_option
+        .as_ref()
+        .and_then(|item| get_expiry_timestamp(&item.cert_str).ok());
+    let is_retry_time = GLOBAL_ORDER_RETRY_TIME
+        .get(&certificate_key)
+        .is_none_or(|item| *item.value() <= Instant::now());
+    if expiry_timestamp_option.is_none_or(|item| needs_renewal(acme_config, item)) && is_retry_time
+    {
+        info!("Order the certificate of {} from acme.", certificate_key);
+        match order_certificate(acme_config).await {
+            Ok(certificate) => {
+                save_certificate(&certificate)?;
+                GLOBAL_ORDER_RETRY_TIME.remove(&certificate_key);
+                certificate_option = Some(certificate);
+            }
+            Err(err) => {
+                GLOBAL_ORDER_RETRY_TIME.insert(
+                    certificate_key.clone(),
+                    Instant::now() + Duration::from_secs(ORDER_RETRY_SECONDS),
+                );
+                error!(
+                    "Order the certificate of {} error,the error is {}.",
+                    certificate_key, err
+                );
+            }
+        }
+    }
+    let certificate = match certificate_option {
+        Some(certificate) => certificate,
+        None => return Ok(()),
+    };
+    set_certificate_expiry(
+        &certificate_key,
+        get_expiry_timestamp(&certificate.cert_str)?,
+    );
+    GLOBAL_ACME_CERTIFICATES.insert(certificate_key, certificate.clone());
+    let is_attached = GLOBAL_ATTACHED_CERTIFICATES
+        .get(mapping_key)
+        .is_some_and(|item| *item.value() == certificate.cert_str);
+    if !is_attached {
+        update_server_config(mapping_key, service_config)?;
+        GLOBAL_ATTACHED_CERTIFICATES.insert(mapping_key.to_string(), certificate.cert_str);
+    }
+    Ok(())
+}
+pub async fn sync_acme_certificates() {
+    for (mapping_key, api_service_manager) in GLOBAL_CONFIG_MAPPING.load().iter() {
+        let service_config = &api_service_manager.service_config;
+        if let Some(acme_config) = &service_config.acme {
+            if let Err(err) = sync_acme_certificate(mapping_key, service_config, acme_config).await
+            {
+                error!(
+                    "Sync the acme certificate of the listener-{} error,the error is {}.",
+                    mapping_key, err
+                );
+            }
+        }
+    }
+}
+pub async fn start_acme_loop() {
+    loop {
+        let async_result = std::panic::AssertUnwindSafe(sync_acme_certificates())
+            .catch_unwind()
+            .await;
+        if async_result.is_err() {
+            error!("start_acme_loop catch panic successfully!");
+        }
+        sleep(std::time::Duration::from_secs(TIMER_WAIT_SECONDS)).await;
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    fn read_config_file(file_name: &str) -> String {
+        let path = env::current_dir().unwrap().join("config").join(file_name);
+        std::fs::read_to_string(path).unwrap()
+    }
+    fn new_acme_config(domain: &str) -> AcmeConfig {
+        AcmeConfig {
+            domains: vec![String::from(domain)],
+            contact_email: String::from("admin@example.com"),
+            ..Default::default()
+        }
+    }
+    #[test]
+    fn test_get_expiry_timestamp_ok() {
+        let expiry_timestamp = get_expiry_timestamp(&read_config_file("test_cert.pem")).unwrap();
+        assert_eq!(expiry_timestamp, 1772761870);
+        assert!(get_expiry_timestamp("test").is_err());
+    }
+    #[test]
+    fn test_needs_renewal_ok() {
+        let acme_config = new_acme_config("acme.example.com");
+        let expired_timestamp = get_expiry_timestamp(&read_config_file("test_cert.pem")).unwrap();
+        assert!(needs_renewal(&acme_config, expired_timestamp));
+        let expiry_timestamp =
+            get_expiry_timestamp(&read_config_file("test_rotated_cert.pem")).unwrap();
+        assert!(!needs_renewal(&acme_config, expiry_timestamp));
+        let acme_config = AcmeConfig {
+            renew_before_days: Some(365 * 200),
+            ..acme_config
+        };
+        assert!(needs_renewal(&acme_config, expiry_timestamp));
+    }
+    #[cfg(unix)]
+    #[test]
+    fn test_save_certificate_permissions_ok() {
+        let certificate = CertificateConfig {
+            server_names: vec![String::from("permissions.example.com")],
+            cert_str: read_config_file("test_rotated_cert.pem"),
+            key_str: read_config_file("test_rotated_key.pem"),
+            key_passphrase: None,
+        };
+        let (cert_path, key_path) = certificate_paths("permissions.example.com");
+        std::fs::create_dir_all(acme_dir()).unwrap();
+        std::fs::write(key_path.clone(), "test").unwrap();
+        std::fs::set_permissions(key_path.clone(), Permissions::from_mode(0o644)).unwrap();
+        save_certificate(&certificate).unwrap();
+        let mode_of =
+            |path: &PathBuf| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode_of(&acme_dir()), 0o700);
+        assert_eq!(mode_of(&key_path), 0o600);
+        assert_eq!(
+            std::fs::read_to_string(key_path.clone()).unwrap(),
+            certificate.key_str
+        );
+        std::fs::remove_file(cert_path).unwrap();
+        std::fs::remove_file(key_path).unwrap();
+    }
+    #[tokio::test]
+    async fn test_sync_acme_certificate_ok() {
+        let acme_config = new_acme_config("sync.example.com");
+        let certificate = CertificateConfig {
+            server_names: acme_config.domains.clone(),
+            cert_str: read_config_file("test_rotated_cert.pem"),
+            key_str: read_config_file("test_rotated_key.pem"),
+            key_passphrase: None,
+        };
+        GLOBAL_ACME_CERTIFICATES.insert(certificate_key(&acme_config), certificate.clone());
+        let service_config = ServiceConfig {
+            acme: Some(acme_config.clone()),
+            ..Default::default()
+        };
+        let result = sync_acme_certificate("acme-sync-test", &service_config, &acme_config).await;
+        assert!(result.is_ok());
+        assert_eq!(
+            GLOBAL_ATTACHED_CERTIFICATES
+                .get("acme-sync-test")
+                .map(|item| item.value().clone()),
+            Some(certificate.cert_str)
+        );
+        assert!(GLOBAL_ORDER_RETRY_TIME
+            .get(&certificate_key(&acme_config))
+            .is_none());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod acme_client;
+pub mod acme_manager;
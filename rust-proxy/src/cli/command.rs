@@ -0,0 +1,179 @@
+use crate::configuration_service::app_config_service::from_api_service_vistors;
+use crate::constants::common_constants::ENV_ADMIN_BIND_ADDRESS;
+use crate::constants::common_constants::ENV_ADMIN_PORT;
+use crate::constants::common_constants::ENV_CONFIG_FILE_PATH;
+use crate::constants::common_constants::ENV_LOG_LEVEL;
+use crate::vojo::app_config_vistor::ApiServiceVistor;
+use clap::{Args, Parser, Subcommand};
+use log::LevelFilter;
+use std::env;
+use std::path::PathBuf;
+pub const DEFAULT_CONFIG: &str = include_str!("../../config/app_config.yaml");
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum Commands {
+    /// Start the proxy and the admin api.
+    Run(RunArgs),
+    /// Validate the config file and report every error.
+    Validate { file: PathBuf },
+    /// Print the default config file.
+    PrintDefaultConfig,
+}
+impl Default for Commands {
+    fn default() -> Self {
+        Commands::Run(RunArgs::default())
+    }
+}
+#[derive(Args, Debug, Default, PartialEq)]
+pub struct RunArgs {
+    /// The path of the config file,which overrides the CONFIG_FILE_PATH.
+    #[arg(long)]
+    pub config: Option<String>,
+    /// The port of the admin api,which overrides the ADMIN_PORT.
+    #[arg(long)]
+    pub admin_port: Option<u16>,
+    /// The bind address of the admin api,which overrides the ADMIN_BIND_ADDRESS.
+    #[arg(long)]
+    pub admin_bind_address: Option<String>,
+    /// The log level,which overrides the LOG_LEVEL.
+    #[arg(long)]
+    pub log_level: Option<LevelFilter>,
+}
+impl RunArgs {
+    /**
+    *The flags are written into the env vars before the runtime starts,so that the rest of
+     the initialization keeps reading the env vars only.
+    */
+    pub fn apply_to_env(&self) {
+        if let Some(config) = self.config.clone() {
+            env::set_var(ENV_CONFIG_FILE_PATH, config);
+        }
+        if let Some(admin_port) = self.admin_port {
+            env::set_var(ENV_ADMIN_PORT, admin_port.to_string());
+        }
+        if let Some(admin_bind_address) = self.admin_bind_address.clone() {
+            env::set_var(ENV_ADMIN_BIND_ADDRESS, admin_bind_address);
+        }
+        if let Some(log_level) = self.log_level {
+            env::set_var(ENV_LOG_LEVEL, log_level.to_string());
+        }
+    }
+}
+pub async fn validate_config_file(file: PathBuf) -> Result<usize, Vec<String>> {
+    let config_str = std::fs::read_to_string(file.as_path()).map_err(|err| {
+        vec![format!(
+            "Can not read the config file {},the error is {}.",
+            file.display(),
+            err
+        )]
+    })?;
+    validate_config_str(config_str.as_str()).await
+}
+async fn validate_config_str(config_str: &str) -> Result<usize, Vec<String>> {
+    let api_service_vistors: Vec<ApiServiceVistor> =
+        serde_yaml::from_str(config_str).map_err(|err| {
+            vec![format!(
+                "Can not parse the config file,the error is {}.",
+                err
+            )]
+        })?;
+    let api_services = from_api_service_vistors(api_service_vistors).await?;
+    Ok(api_services.len())
+}
+pub async fn run_validate_command(file: PathBuf) -> i32 {
+    match validate_config_file(file.clone()).await {
+        Ok(count) => {
+            println!(
+                "The config file {} is valid,and it contains {} api services.",
+                file.display(),
+                count
+            );
+            0
+        }
+        Err(errors) => {
+            for error in errors.iter() {
+                eprintln!("error: {}", error);
+            }
+            eprintln!(
+                "The config file {} is invalid,found {} errors.",
+                file.display(),
+                errors.len()
+            );
+            1
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_parse_run_command_ok() {
+        let cli = Cli::parse_from([
+            "silverwind",
+            "run",
+            "--config",
+            "/root/app_config.yaml",
+            "--admin-port",
+            "9000",
+            "--admin-bind-address",
+            "127.0.0.1",
+            "--log-level",
+            "debug",
+        ]);
+        assert_eq!(
+            cli.command,
+            Some(Commands::Run(RunArgs {
+                config: Some(String::from("/root/app_config.yaml")),
+                admin_port: Some(9000),
+                admin_bind_address: Some(String::from("127.0.0.1")),
+                log_level: Some(LevelFilter::Debug),
+            }))
+        );
+        let cli = Cli::parse_from(["silverwind"]);
+        assert_eq!(cli.command.unwrap_or_default(), Commands::default());
+    }
+    #[test]
+    fn test_parse_validate_command_ok() {
+        let cli = Cli::parse_from(["silverwind", "validate", "app_config.yaml"]);
+        assert_eq!(
+            cli.command,
+            Some(Commands::Validate {
+                file: PathBuf::from("app_config.yaml")
+            })
+        );
+        let cli = Cli::parse_from(["silverwind", "print-default-config"]);
+        assert_eq!(cli.command, Some(Commands::PrintDefaultConfig));
+        assert!(Cli::try_parse_from(["silverwind", "run", "--admin-port", "abc"]).is_err());
+    }
+    #[tokio::test]
+    async fn test_validate_default_config_ok() {
+        let res = validate_config_str(DEFAULT_CONFIG).await;
+        assert_eq!(res, Ok(2));
+    }
+    #[tokio::test]
+    async fn test_validate_config_with_every_error() {
+        let config_str = r#"
+- listen_port: 4486
+  service_config:
+    server_type: Https
+    routes: []
+- listen_port: 4486
+  service_config:
+    server_type: Http
+    routes: []
+"#;
+        let errors = validate_config_str(config_str).await.unwrap_err();
+        assert_eq!(errors.len(), 4);
+        let errors = validate_config_str("- listen_port: abc").await.unwrap_err();
+        assert_eq!(errors.len(), 1);
+        let errors = validate_config_file(PathBuf::from("not_exist.yaml"))
+            .await
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+    }
+}
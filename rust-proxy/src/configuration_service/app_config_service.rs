@@ -1,66 +1,115 @@
+use crate::acme::acme_manager::start_acme_loop;
+use crate::configuration_service::config_file_watcher::start_config_file_watcher;
+use crate::configuration_service::config_propagation::{
+    publish_config_generation, reconcile_config,
+};
+use crate::configuration_service::config_store::{
+    create_config_store, get_config_store, set_config_store,
+};
 use crate::configuration_service::logger;
 use crate::constants;
 use crate::constants::common_constants::ENV_ACCESS_LOG;
+use crate::constants::common_constants::ENV_ADMIN_BIND_ADDRESS;
+use crate::constants::common_constants::ENV_ADMIN_CORS_ORIGINS;
 use crate::constants::common_constants::ENV_ADMIN_PORT;
 use crate::constants::common_constants::ENV_CONFIG_FILE_PATH;
+use crate::constants::common_constants::ENV_CONFIG_VERSION_RETENTION;
 use crate::constants::common_constants::ENV_DATABASE_URL;
-use crate::constants::common_constants::TIMER_WAIT_SECONDS;
-use crate::health_check::health_check_task::HealthCheck;
+use crate::discovery::dns_discovery::start_dns_discovery_loop;
+use crate::discovery::service_registry::start_service_registry_loop;
+use crate::proxy::certificate_resolver::{
+    parse_ca_certificates, parse_certified_key, remove_server_config, update_server_config,
+    validate_tls_policy,
+};
 use crate::proxy::http1::http_proxy::HttpProxy;
 use crate::proxy::http2::grpc_proxy::GrpcProxy;
+use crate::proxy::ocsp_stapling::start_ocsp_stapling_loop;
 use crate::proxy::tcp::tcp_proxy::TcpProxy;
+use crate::proxy::upstream_tls::UpstreamTls;
 use crate::vojo::api_service_manager::ApiServiceManager;
+use crate::vojo::api_service_manager::ConfigMapping;
+use crate::vojo::app_config::Route;
 use crate::vojo::app_config::ServiceConfig;
-use crate::vojo::app_config::{ApiService, AppConfig, ServiceType};
+use crate::vojo::app_config::{AcmeConfig, ApiService, AppConfig, ServiceType};
+use crate::vojo::app_config_vistor::from_api_service;
 use crate::vojo::app_config_vistor::ApiServiceVistor;
-use dashmap::DashMap;
-use futures::FutureExt;
+use crate::vojo::app_config_vistor::ServiceConfigVistor;
+use crate::vojo::route::BaseRoute;
 use lazy_static::lazy_static;
 use log::Level;
-use std::collections::HashMap;
+use openssl::sha::sha256;
+use std::collections::{HashMap, HashSet};
 use std::env;
+use std::sync::Arc;
 use tokio::sync::mpsc;
-use tokio::sync::RwLock;
-use tokio::time::sleep;
+use tokio::sync::{RwLock, RwLockWriteGuard};
 lazy_static! {
     pub static ref GLOBAL_APP_CONFIG: RwLock<AppConfig> = RwLock::new(Default::default());
-    pub static ref GLOBAL_CONFIG_MAPPING: DashMap<String, ApiServiceManager> = Default::default();
+    pub static ref GLOBAL_CONFIG_MAPPING: ConfigMapping = Default::default();
 }
 
 pub async fn init() {
     init_static_config().await;
+    init_config_store().await;
     match init_app_service_config().await {
         Ok(_) => info!("Initialize app service config successfully!"),
         Err(err) => error!("{}", err.to_string()),
     }
+    let generation = GLOBAL_APP_CONFIG.read().await.generation;
+    if let Err(err) = reconcile_config(generation).await {
+        error!("Apply the app service config error,the error is {}.", err);
+    }
     tokio::task::spawn(async {
-        sync_mapping_from_global_app_config().await;
+        start_dns_discovery_loop().await;
     });
     tokio::task::spawn(async {
-        let mut health_check = HealthCheck::new();
-        health_check.start_health_check_loop().await;
+        start_service_registry_loop().await;
     });
-}
-async fn sync_mapping_from_global_app_config() {
-    loop {
-        let async_result = std::panic::AssertUnwindSafe(update_mapping_from_global_appconfig())
-            .catch_unwind()
-            .await;
-        if async_result.is_err() {
-            error!("sync_mapping_from_global_app_config catch panic successfully!");
+    tokio::task::spawn(async {
+        start_acme_loop().await;
+    });
+    tokio::task::spawn(async {
+        start_ocsp_stapling_loop().await;
+    });
+    tokio::task::spawn(async {
+        if let Err(err) = start_config_file_watcher().await {
+            error!("Start the config file watcher error,the error is {}.", err);
         }
-        sleep(std::time::Duration::from_secs(TIMER_WAIT_SECONDS)).await;
-    }
+    });
+}
+/**
+*Replace the api services in the global config and publish the new generation,which returns
+ the generation without applying it.
+*/
+pub fn set_api_services(app_config: &mut AppConfig, api_services: Vec<ApiService>) -> u64 {
+    app_config.api_service_config = api_services;
+    app_config.generation += 1;
+    publish_config_generation(app_config.generation);
+    app_config.generation
+}
+/**
+*Commit the api services under the write lock,and return after the listeners,the routes and the
+ health check tasks have been reconciled with the new generation.
+*/
+pub async fn commit_api_services(
+    mut rw_global_lock: RwLockWriteGuard<'static, AppConfig>,
+    api_services: Vec<ApiService>,
+) -> Result<u64, anyhow::Error> {
+    let generation = set_api_services(&mut rw_global_lock, api_services);
+    drop(rw_global_lock);
+    reconcile_config(generation).await?;
+    Ok(generation)
 }
 /**
 *Key in Old Map:[1,2]
  Key in Current Map:[2,4,5]
+ And the generation of the global config which has been read is returned.
 */
-async fn update_mapping_from_global_appconfig() -> Result<(), anyhow::Error> {
-    let rw_global_app_config = GLOBAL_APP_CONFIG
-        .try_read()
-        .map_err(|err| anyhow!(err.to_string()))?;
+pub async fn update_mapping_from_global_appconfig() -> Result<u64, anyhow::Error> {
+    let rw_global_app_config = GLOBAL_APP_CONFIG.read().await;
     let api_services = rw_global_app_config.api_service_config.clone();
+    let generation = rw_global_app_config.generation;
+    drop(rw_global_app_config);
 
     let new_item_hash = api_services
         .iter()
@@ -72,18 +121,50 @@ async fn update_mapping_from_global_appconfig() -> Result<(), anyhow::Error> {
         })
         .collect::<HashMap<String, ServiceConfig>>();
 
-    let difference_ports = GLOBAL_CONFIG_MAPPING
-        .iter()
-        .map(|s| s.key().clone())
-        .filter(|item| !new_item_hash.contains_key(item))
+    let old_mapping = GLOBAL_CONFIG_MAPPING.load();
+    let difference_ports = old_mapping
+        .keys()
+        .filter(|item| !new_item_hash.contains_key(*item))
+        .cloned()
         .collect::<Vec<String>>();
     if log_enabled!(Level::Info) {
         debug!("The len of different ports is {}", difference_ports.len());
     }
+    //build the new mapping,and keep the sender of the old
+    let mut new_mapping = HashMap::new();
+    let mut new_proxies = vec![];
+    for (key, value) in new_item_hash {
+        match old_mapping.get(&key) {
+            //update
+            Some(old_value) => {
+                update_changed_server_config(&key, &old_value.service_config, &value);
+                new_mapping.insert(
+                    key,
+                    Arc::new(ApiServiceManager {
+                        service_config: value,
+                        sender: old_value.sender.clone(),
+                    }),
+                );
+            }
+            //add
+            None => {
+                let (sender, receiver) = tokio::sync::mpsc::channel(10);
+                new_mapping.insert(
+                    key.clone(),
+                    Arc::new(ApiServiceManager {
+                        service_config: value.clone(),
+                        sender,
+                    }),
+                );
+                new_proxies.push((key, value.server_type, receiver));
+            }
+        }
+    }
+    GLOBAL_CONFIG_MAPPING.store(new_mapping);
     //delete the old mapping
-    for item in difference_ports {
-        let key = item.clone();
-        let value = GLOBAL_CONFIG_MAPPING.get(&key).unwrap().sender.clone();
+    for key in difference_ports {
+        remove_server_config(&key);
+        let value = old_mapping.get(&key).unwrap().sender.clone();
         match value.send(()).await {
             Ok(_) => info!("close the socket on the port {}", key),
             Err(err) => {
@@ -94,39 +175,47 @@ async fn update_mapping_from_global_appconfig() -> Result<(), anyhow::Error> {
                 )
             }
         };
-        GLOBAL_CONFIG_MAPPING.remove(&key);
     }
-    //add the new mapping and update the old
-    for (key, value) in new_item_hash {
-        //update
-        if GLOBAL_CONFIG_MAPPING.contains_key(&key) {
-            let mut ref_value = GLOBAL_CONFIG_MAPPING.get(&key).unwrap().clone();
-            ref_value.service_config = value.clone();
-            GLOBAL_CONFIG_MAPPING.insert(key.clone(), ref_value);
-            //add
-        } else {
-            let (sender, receiver) = tokio::sync::mpsc::channel(10);
-            GLOBAL_CONFIG_MAPPING.insert(
-                key.clone(),
-                ApiServiceManager {
-                    service_config: value.clone(),
-                    sender,
-                },
-            );
-            let item_list: Vec<&str> = key.split('-').collect();
-            let port_str = item_list.first().unwrap();
-            let port: i32 = port_str.parse().unwrap();
+    for (key, server_type, receiver) in new_proxies {
+        let item_list: Vec<&str> = key.split('-').collect();
+        let port_str = item_list.first().unwrap();
+        let port: i32 = port_str.parse().unwrap();
 
-            tokio::task::spawn(async move {
-                if let Err(err) = start_proxy(port, receiver, value.server_type, key.clone()).await
-                {
-                    error!("{}", err.to_string());
-                }
-            });
-        }
+        tokio::task::spawn(async move {
+            if let Err(err) = start_proxy(port, receiver, server_type, key.clone()).await {
+                error!("{}", err.to_string());
+            }
+        });
     }
 
-    Ok(())
+    Ok(generation)
+}
+/**
+*Update the certificates and the client authentication of the running listener,so they take
+ effect without restarting the listener.
+*/
+fn update_changed_server_config(
+    mapping_key: &str,
+    old_service_config: &ServiceConfig,
+    new_service_config: &ServiceConfig,
+) {
+    if old_service_config.cert_str == new_service_config.cert_str
+        && old_service_config.key_str == new_service_config.key_str
+        && old_service_config.key_passphrase == new_service_config.key_passphrase
+        && old_service_config.certificates == new_service_config.certificates
+        && old_service_config.client_auth == new_service_config.client_auth
+        && old_service_config.tls_policy == new_service_config.tls_policy
+        && old_service_config.acme == new_service_config.acme
+        && old_service_config.ocsp_stapling == new_service_config.ocsp_stapling
+    {
+        return;
+    }
+    if let Err(err) = update_server_config(mapping_key, new_service_config) {
+        error!(
+            "Update the server config of the listener-{} error,the error is {}.",
+            mapping_key, err
+        );
+    }
 }
 pub async fn start_proxy(
     port: i32,
@@ -142,20 +231,18 @@ pub async fn start_proxy(
         };
         http_proxy.start_http_server().await
     } else if server_type == ServiceType::Https {
-        let key_clone = mapping_key.clone();
-        let service_config = GLOBAL_CONFIG_MAPPING
-            .get(&key_clone)
-            .unwrap()
-            .service_config
-            .clone();
-        let pem_str = service_config.cert_str.unwrap();
-        let key_str = service_config.key_str.unwrap();
+        let api_service_manager = GLOBAL_CONFIG_MAPPING.get(&mapping_key).ok_or(anyhow!(
+            "Can not find the config mapping on the key {}!",
+            mapping_key
+        ))?;
         let mut http_proxy = HttpProxy {
             port,
             channel,
             mapping_key: mapping_key.clone(),
         };
-        http_proxy.start_https_server(pem_str, key_str).await
+        http_proxy
+            .start_https_server(&api_service_manager.service_config)
+            .await
     } else if server_type == ServiceType::Tcp {
         let mut tcp_proxy = TcpProxy {
             port,
@@ -171,20 +258,18 @@ pub async fn start_proxy(
         };
         grpc_proxy.start_proxy().await
     } else {
-        let key_clone = mapping_key.clone();
-        let service_config = GLOBAL_CONFIG_MAPPING
-            .get(&key_clone)
-            .unwrap()
-            .service_config
-            .clone();
-        let pem_str = service_config.cert_str.unwrap();
-        let key_str = service_config.key_str.unwrap();
+        let api_service_manager = GLOBAL_CONFIG_MAPPING.get(&mapping_key).ok_or(anyhow!(
+            "Can not find the config mapping on the key {}!",
+            mapping_key
+        ))?;
         let mut grpc_proxy = GrpcProxy {
             port,
             mapping_key,
             channel,
         };
-        grpc_proxy.start_tls_proxy(pem_str, key_str).await
+        grpc_proxy
+            .start_tls_proxy(&api_service_manager.service_config)
+            .await
     }
 }
 async fn init_static_config() {
@@ -192,8 +277,11 @@ async fn init_static_config() {
     let api_port = env::var(ENV_ADMIN_PORT).unwrap_or(String::from(
         constants::common_constants::DEFAULT_ADMIN_PORT,
     ));
+    let admin_bind_address_result = env::var(ENV_ADMIN_BIND_ADDRESS);
+    let admin_cors_origins_result = env::var(ENV_ADMIN_CORS_ORIGINS);
     let access_log_result = env::var(ENV_ACCESS_LOG);
     let config_file_path_result = env::var(ENV_CONFIG_FILE_PATH);
+    let config_version_retention_result = env::var(ENV_CONFIG_VERSION_RETENTION);
 
     let mut global_app_config = GLOBAL_APP_CONFIG.write().await;
 
@@ -204,6 +292,14 @@ async fn init_static_config() {
 
     logger::start_logger();
 
+    if let Ok(admin_bind_address) = admin_bind_address_result {
+        global_app_config.static_config.admin_bind_address = Some(admin_bind_address);
+    }
+    if let Ok(admin_cors_origins) = admin_cors_origins_result {
+        global_app_config.static_config.admin_cors_origins =
+            Some(parse_cors_origins(admin_cors_origins.as_str()));
+    }
+
     if let Ok(access_log) = access_log_result {
         global_app_config.static_config.access_log = Some(access_log);
     }
@@ -211,44 +307,469 @@ async fn init_static_config() {
     if let Ok(config_file_path) = config_file_path_result {
         global_app_config.static_config.config_file_path = Some(config_file_path);
     }
+    if let Ok(config_version_retention) = config_version_retention_result {
+        match config_version_retention.parse::<usize>() {
+            Ok(retention) => {
+                global_app_config.static_config.config_version_retention = Some(retention)
+            }
+            Err(err) => error!(
+                "Parse the {} error,the default retention will be used,the error is {}.",
+                ENV_CONFIG_VERSION_RETENTION, err
+            ),
+        }
+    }
+}
+/**
+*The invalid origins are skipped,so a typo never widens the allowed origins.
+*/
+fn parse_cors_origins(cors_origins: &str) -> Vec<String> {
+    cors_origins
+        .split(',')
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .filter_map(|item| match url::Url::parse(item) {
+            Ok(url) if url.has_host() && (url.scheme() == "http" || url.scheme() == "https") => {
+                Some(url.origin().ascii_serialization())
+            }
+            _ => {
+                error!(
+                    "The cors origin {} of the {} is invalid,it will be skipped.",
+                    item, ENV_ADMIN_CORS_ORIGINS
+                );
+                None
+            }
+        })
+        .collect()
+}
+async fn init_config_store() {
+    let static_config = GLOBAL_APP_CONFIG.read().await.static_config.clone();
+    match create_config_store(
+        static_config.database_url,
+        static_config.config_version_retention,
+    ) {
+        Ok(config_store) => set_config_store(config_store).await,
+        Err(err) => error!(
+            "Create the config store error,the file store will be used,the error is {}.",
+            err
+        ),
+    }
 }
+/**
+*The config file is imported as a new version only when it has been changed since it was imported
+ last,otherwise the latest version is loaded,as it contains the changes from the admin api.The
+ content is compared instead when the config store has no hash of the imported file.
+*/
 async fn init_app_service_config() -> Result<(), anyhow::Error> {
-    let rw_app_config_read = GLOBAL_APP_CONFIG.read().await;
-
-    let config_file_path = rw_app_config_read.static_config.config_file_path.clone();
-    if config_file_path.is_none() {
-        return Ok(());
-    }
-    drop(rw_app_config_read);
-    let file_path = config_file_path.unwrap().clone();
-    info!("the config file is in{}", file_path.clone());
-    let file = std::fs::File::open(file_path)?;
-    let scrape_config: Vec<ApiServiceVistor> = match serde_yaml::from_reader(file) {
+    let config_file_path = GLOBAL_APP_CONFIG
+        .read()
+        .await
+        .static_config
+        .config_file_path
+        .clone();
+    let config_store = get_config_store().await;
+    let snapshot_option = config_store.load_latest().await?;
+    let config_file = match config_file_path.clone() {
+        Some(file_path) => Some(load_api_services_from_file(file_path).await?),
+        None => None,
+    };
+    if let Some(snapshot) = snapshot_option {
+        let is_imported = match config_file.as_ref() {
+            Some((file_api_services, file_hash)) => match config_store.load_file_hash().await? {
+                Some(imported_file_hash) => imported_file_hash == *file_hash,
+                None => {
+                    let is_same =
+                        is_same_api_services(file_api_services, &snapshot.api_services).await?;
+                    if is_same {
+                        config_store.save_file_hash(file_hash.clone()).await?;
+                    }
+                    is_same
+                }
+            },
+            None => true,
+        };
+        if is_imported {
+            let api_services = from_api_service_vistors(snapshot.api_services)
+                .await
+                .map_err(|errors| anyhow!(errors.join("\n")))?;
+            info!(
+                "Load the config of the version {} from the config store.",
+                snapshot.config_version.version
+            );
+            set_api_services(&mut *GLOBAL_APP_CONFIG.write().await, api_services);
+            return Ok(());
+        }
+    }
+    let (api_services, file_hash) = match config_file {
+        Some(config_file) => config_file,
+        None => return Ok(()),
+    };
+    let new_version = save_api_services(
+        api_services.clone(),
+        Some(String::from("Load the config file")),
+    )
+    .await?;
+    config_store.save_file_hash(file_hash).await?;
+    info!(
+        "Load the config from the file {},and save it as the version {}.",
+        config_file_path.unwrap_or_default(),
+        new_version
+    );
+    set_api_services(&mut *GLOBAL_APP_CONFIG.write().await, api_services);
+    Ok(())
+}
+/**
+*The ids and the alive status are ignored in the comparison,as the ids are generated when they are
+ missing in the config file and the alive status is changed at runtime.
+*/
+async fn is_same_api_services(
+    api_services: &[ApiService],
+    api_service_vistors: &[ApiServiceVistor],
+) -> Result<bool, anyhow::Error> {
+    fn remove_runtime_fields(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for key in ["api_service_id", "route_id", "is_alive"] {
+                    map.remove(key);
+                }
+                map.values_mut().for_each(remove_runtime_fields);
+            }
+            serde_json::Value::Array(items) => items.iter_mut().for_each(remove_runtime_fields),
+            _ => {}
+        }
+    }
+    let mut file_value = serde_json::to_value(from_api_service(api_services.to_vec()).await?)?;
+    let mut store_value = serde_json::to_value(api_service_vistors)?;
+    remove_runtime_fields(&mut file_value);
+    remove_runtime_fields(&mut store_value);
+    Ok(file_value == store_value)
+}
+/**
+*Save the api services into the config store as a new version,which should be called before the
+ api services are applied to the global config.
+*/
+pub async fn save_api_services(
+    api_services: Vec<ApiService>,
+    message: Option<String>,
+) -> Result<i64, anyhow::Error> {
+    let api_service_vistors = from_api_service(api_services).await?;
+    get_config_store()
+        .await
+        .save(api_service_vistors, message)
+        .await
+}
+/**
+*The whole file is validated and converted before the global lock is taken,so the old config
+ is kept if there is any error in the file.
+*/
+pub async fn reload_app_service_config() -> Result<u64, anyhow::Error> {
+    let config_file_path = GLOBAL_APP_CONFIG
+        .read()
+        .await
+        .static_config
+        .config_file_path
+        .clone()
+        .ok_or(anyhow!("The config file path is empty!"))?;
+    let (api_services, file_hash) = load_api_services_from_file(config_file_path).await?;
+    let rw_app_config_write = GLOBAL_APP_CONFIG.write().await;
+    inherit_api_services_status(&api_services, &rw_app_config_write.api_service_config).await?;
+    save_api_services(
+        api_services.clone(),
+        Some(String::from("Reload the config file")),
+    )
+    .await?;
+    get_config_store().await.save_file_hash(file_hash).await?;
+    let generation = commit_api_services(rw_app_config_write, api_services).await?;
+    info!(
+        "Reload the config file successfully,and the generation is {}.",
+        generation
+    );
+    Ok(generation)
+}
+/**
+*Apply the old version as a new version,so the rollback itself could be rolled back too.The new
+ version and the generation are returned.
+*/
+pub async fn rollback_app_service_config(version: i64) -> Result<(i64, u64), anyhow::Error> {
+    let snapshot = get_config_store()
+        .await
+        .load_version(version)
+        .await?
+        .ok_or(anyhow!("Can not find the config version {}!", version))?;
+    let api_services = from_api_service_vistors(snapshot.api_services)
+        .await
+        .map_err(|errors| anyhow!(errors.join("\n")))?;
+    let rw_app_config_write = GLOBAL_APP_CONFIG.write().await;
+    inherit_api_services_status(&api_services, &rw_app_config_write.api_service_config).await?;
+    let new_version = save_api_services(
+        api_services.clone(),
+        Some(format!("Rollback to the version {}", version)),
+    )
+    .await?;
+    let generation = commit_api_services(rw_app_config_write, api_services).await?;
+    info!(
+        "Rollback to the config version {} successfully,and the new version is {}.",
+        version, new_version
+    );
+    Ok((new_version, generation))
+}
+/**
+*The route is matched by the route id first,and then by the identical endpoints on the same port,
+ a route at the same index with other endpoints never inherits the status.
+*/
+pub async fn inherit_api_services_status(
+    api_services: &[ApiService],
+    old_api_services: &[ApiService],
+) -> Result<(), anyhow::Error> {
+    for api_service in api_services.iter() {
+        let old_api_service_option = old_api_services
+            .iter()
+            .find(|item| item.listen_port == api_service.listen_port);
+        if let Some(old_api_service) = old_api_service_option {
+            let old_routes = &old_api_service.service_config.routes;
+            for route in api_service.service_config.routes.iter() {
+                let mut old_route_option = old_routes
+                    .iter()
+                    .find(|item| item.route_id == route.route_id);
+                if old_route_option.is_none() {
+                    let endpoints = get_route_endpoints(route).await?;
+                    for old_route in old_routes.iter() {
+                        if get_route_endpoints(old_route).await? == endpoints {
+                            old_route_option = Some(old_route);
+                            break;
+                        }
+                    }
+                }
+                if let Some(old_route) = old_route_option {
+                    inherit_route_status(route, old_route).await?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+async fn get_route_endpoints(route: &Route) -> Result<HashSet<String>, anyhow::Error> {
+    let base_routes = route.route_cluster.clone().get_all_route().await?;
+    Ok(base_routes
+        .into_iter()
+        .map(|item| item.endpoint)
+        .collect::<HashSet<String>>())
+}
+/**
+*Load the api services from the config file with the sha256 of the file,which tells whether the
+ file has been changed since it was imported.
+*/
+pub async fn load_api_services_from_file(
+    file_path: String,
+) -> Result<(Vec<ApiService>, String), anyhow::Error> {
+    let content = tokio::fs::read(file_path).await?;
+    let file_hash = sha256(&content)
+        .iter()
+        .map(|item| format!("{:02x}", item))
+        .collect::<String>();
+    let api_service_vistors: Vec<ApiServiceVistor> = match serde_yaml::from_slice(&content) {
         Ok(apiservices) => apiservices,
         Err(err) => return Err(anyhow!(err.to_string())),
     };
-    let mut rw_app_config_write = GLOBAL_APP_CONFIG.write().await;
-
+    let api_services = from_api_service_vistors(api_service_vistors)
+        .await
+        .map_err(|errors| anyhow!(errors.join("\n")))?;
+    Ok((api_services, file_hash))
+}
+/**
+*Validate all the api services and collect every error instead of returning the first one.
+*/
+pub async fn from_api_service_vistors(
+    api_service_vistors: Vec<ApiServiceVistor>,
+) -> Result<Vec<ApiService>, Vec<String>> {
+    let mut errors = vec![];
+    let mut listen_ports = HashSet::new();
     let mut res = vec![];
-    for item in scrape_config {
-        res.push(ApiService::from(item).await?);
+    for item in api_service_vistors {
+        let listen_port = item.listen_port;
+        if !listen_ports.insert(listen_port) {
+            errors.push(format!(
+                "The listen port {} is duplicated in the config file!",
+                listen_port
+            ));
+        }
+        let server_type = item.service_config.server_type.clone();
+        if server_type == ServiceType::Https || server_type == ServiceType::Http2Tls {
+            if let Err(err) = validate_service_tls_config(&item.service_config) {
+                errors.push(format!("The listen port {}:{}", listen_port, err));
+            }
+        }
+        if item.service_config.routes.is_empty() {
+            errors.push(format!(
+                "The routes of the listen port {} could not be empty!",
+                listen_port
+            ));
+        }
+        match ApiService::from(item).await {
+            Ok(api_service) => res.push(api_service),
+            Err(err) => errors.push(format!("The listen port {}:{}", listen_port, err)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(res)
+}
+/**
+*Keep the liveness status of the route and the health of the endpoints which exist in both routes.
+*/
+pub async fn inherit_route_status(
+    new_route: &Route,
+    old_route: &Route,
+) -> Result<(), anyhow::Error> {
+    new_route
+        .liveness_status
+        .store(old_route.liveness_status.load());
+
+    let old_base_clusters = old_route.clone().route_cluster.get_all_route().await?;
+    let hashmap = old_base_clusters
+        .iter()
+        .map(|item| (item.endpoint.clone(), item.clone()))
+        .collect::<HashMap<String, BaseRoute>>();
+    let new_routes = new_route.clone().route_cluster.get_all_route().await?;
+    for new_base_route in new_routes.iter() {
+        if let Some(old_base_route) = hashmap.get(&new_base_route.endpoint) {
+            new_base_route
+                .is_alive
+                .store(old_base_route.is_alive.load());
+            new_base_route
+                .anomaly_detection_status
+                .store(old_base_route.anomaly_detection_status.load());
+        }
+    }
+    Ok(())
+}
+/**
+*The tls listener needs the cert_str and the key_str,or at least one certificate in the list,
+ unless the certificate is managed by acme.The client ca must contain at least one certificate if
+ the client authentication is enabled.The tls policy should enable at least one protocol version
+ with the allowed cipher suites.
+*/
+pub fn validate_service_tls_config(
+    service_config_vistor: &ServiceConfigVistor,
+) -> Result<(), anyhow::Error> {
+    if (service_config_vistor.certificates.is_empty() && service_config_vistor.acme.is_none())
+        || service_config_vistor.cert_str.is_some()
+        || service_config_vistor.key_str.is_some()
+    {
+        validate_tls_config(
+            service_config_vistor.cert_str.clone(),
+            service_config_vistor.key_str.clone(),
+            service_config_vistor.key_passphrase.clone(),
+        )?;
+    }
+    for (index, item) in service_config_vistor.certificates.iter().enumerate() {
+        if item.server_names.is_empty() {
+            return Err(anyhow!(
+                "The server names of the certificate {} could not be empty.",
+                index
+            ));
+        }
+        validate_tls_config(
+            Some(item.cert_str.clone()),
+            Some(item.key_str.clone()),
+            item.key_passphrase.clone(),
+        )
+        .map_err(|e| anyhow!("The certificate {}:{}", index, e))?;
+    }
+    if let Some(client_auth) = &service_config_vistor.client_auth {
+        parse_ca_certificates(&client_auth.ca_str)?;
+        if let Some(identity_header) = &client_auth.identity_header {
+            http::header::HeaderName::from_bytes(identity_header.as_bytes()).map_err(|_| {
+                anyhow!(
+                    "The identity header {} is not a valid header name.",
+                    identity_header
+                )
+            })?;
+        }
+    }
+    if let Some(tls_policy) = &service_config_vistor.tls_policy {
+        validate_tls_policy(tls_policy)?;
+    }
+    if let Some(acme) = &service_config_vistor.acme {
+        validate_acme_config(acme)?;
+    }
+    if let Some(responder_url) = service_config_vistor
+        .ocsp_stapling
+        .as_ref()
+        .and_then(|item| item.responder_url.as_ref())
+    {
+        responder_url
+            .parse::<http::Uri>()
+            .ok()
+            .filter(|uri| uri.scheme().is_some() && uri.host().is_some())
+            .ok_or(anyhow!(
+                "The ocsp responder url {} is invalid.",
+                responder_url
+            ))?;
     }
-    rw_app_config_write.api_service_config = res;
     Ok(())
 }
+/**
+*The http-01 challenge could not validate the wildcard domains.
+*/
+fn validate_acme_config(acme_config: &AcmeConfig) -> Result<(), anyhow::Error> {
+    if acme_config.domains.is_empty() {
+        return Err(anyhow!("The domains of the acme could not be empty."));
+    }
+    for domain in acme_config.domains.iter() {
+        if domain.contains('*') || rustls::ServerName::try_from(domain.as_str()).is_err() {
+            return Err(anyhow!("The domain {} of the acme is invalid.", domain));
+        }
+    }
+    if acme_config.contact_email.is_empty() {
+        return Err(anyhow!("The contact email of the acme could not be empty."));
+    }
+    if acme_config
+        .renew_before_days
+        .is_some_and(|renew_before_days| renew_before_days <= 0)
+    {
+        return Err(anyhow!(
+            "The renew_before_days of the acme should be positive."
+        ));
+    }
+    if let Some(directory_tls) = &acme_config.directory_tls {
+        UpstreamTls::new(directory_tls.clone())?;
+    }
+    Ok(())
+}
+/**
+*The private key could be in the pkcs8,the pkcs1 rsa or the sec1 ec pem,and it must match the
+ certificate.
+*/
+pub fn validate_tls_config(
+    cert_pem_option: Option<String>,
+    key_pem_option: Option<String>,
+    key_passphrase_option: Option<String>,
+) -> Result<(), anyhow::Error> {
+    match (cert_pem_option, key_pem_option) {
+        (Some(cert_pem), Some(key_pem)) => {
+            parse_certified_key(&cert_pem, &key_pem, key_passphrase_option.as_deref())?;
+            Ok(())
+        }
+        _ => Err(anyhow!("Cert or key is none")),
+    }
+}
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::configuration_service::config_propagation::get_config_generation;
+    use crate::vojo::app_config::AtomicLivenessStatus;
     use crate::vojo::app_config::LivenessStatus;
+    use crate::vojo::app_config::OcspStaplingConfig;
     use crate::vojo::app_config::Route;
     use crate::vojo::route::AnomalyDetectionStatus;
+    use crate::vojo::route::{AtomicAliveStatus, AtomicAnomalyDetectionStatus};
     use crate::vojo::route::{BaseRoute, LoadbalancerStrategy, RandomBaseRoute, RandomRoute};
     use serial_test::serial;
     use std::sync::Arc;
     use tokio::runtime::{Builder, Runtime};
-    use tokio::sync::RwLock;
     lazy_static! {
         pub static ref TOKIO_RUNTIME: Runtime = Builder::new_multi_thread()
             .worker_threads(4)
@@ -261,11 +782,16 @@ mod tests {
     async fn before_test() {
         let mut app_config = GLOBAL_APP_CONFIG.write().await;
         *app_config = Default::default();
-        GLOBAL_CONFIG_MAPPING.clear();
+        GLOBAL_CONFIG_MAPPING.store(HashMap::new());
+        set_config_store(create_config_store(Some(String::from("sqlite::memory:")), None).unwrap())
+            .await;
         env::remove_var("DATABASE_URL");
         env::remove_var("ADMIN_PORT");
         env::remove_var("ACCESS_LOG");
         env::remove_var("CONFIG_FILE_PATH");
+        env::remove_var("CONFIG_VERSION_RETENTION");
+        env::remove_var("ADMIN_BIND_ADDRESS");
+        env::remove_var("ADMIN_CORS_ORIGINS");
     }
     #[test]
     #[serial("test")]
@@ -278,6 +804,8 @@ mod tests {
             assert_eq!(current.static_config.admin_port, String::from("8870"));
             assert_eq!(current.static_config.database_url, None);
             assert_eq!(current.static_config.config_file_path, None);
+            assert_eq!(current.static_config.admin_bind_address, None);
+            assert_eq!(current.static_config.admin_cors_origins, None);
         });
     }
     #[test]
@@ -291,10 +819,16 @@ mod tests {
             let access_log = "/log/test.log";
             let config_path = "/root/config/config.yaml";
 
+            env::set_var("CONFIG_VERSION_RETENTION", "20");
             env::set_var("DATABASE_URL", database_url);
             env::set_var("ADMIN_PORT", port.to_string());
             env::set_var("ACCESS_LOG", access_log);
             env::set_var("CONFIG_FILE_PATH", config_path);
+            env::set_var("ADMIN_BIND_ADDRESS", "127.0.0.1");
+            env::set_var(
+                "ADMIN_CORS_ORIGINS",
+                "https://dashboard.example.com, not-an-origin,http://localhost:3000/",
+            );
             init_static_config().await;
             let current = GLOBAL_APP_CONFIG.read().await;
             assert_eq!(
@@ -310,6 +844,18 @@ mod tests {
                 current.static_config.config_file_path,
                 Some(String::from(config_path))
             );
+            assert_eq!(current.static_config.config_version_retention, Some(20));
+            assert_eq!(
+                current.static_config.admin_bind_address,
+                Some(String::from("127.0.0.1"))
+            );
+            assert_eq!(
+                current.static_config.admin_cors_origins,
+                Some(vec![
+                    String::from("https://dashboard.example.com"),
+                    String::from("http://localhost:3000")
+                ])
+            );
         });
     }
     #[test]
@@ -338,6 +884,279 @@ mod tests {
     }
     #[test]
     #[serial("test")]
+    fn test_reload_app_service_config_ok() {
+        TOKIO_RUNTIME.block_on(async move {
+            before_test().await;
+            let config_path = env::temp_dir().join("test_reload_app_service_config_ok.yaml");
+            let config_str = r#"
+- listen_port: 4486
+  service_config:
+    server_type: Http
+    routes:
+    - route_id: reload_route
+      matcher:
+        prefix: /
+        prefix_rewrite: /
+      route_cluster:
+        type: RandomRoute
+        routes:
+        - base_route:
+            endpoint: http://localhost:7777/
+            try_file: null
+"#;
+            std::fs::write(config_path.clone(), config_str).unwrap();
+            env::set_var("CONFIG_FILE_PATH", config_path.clone());
+            init_static_config().await;
+            assert!(init_app_service_config().await.is_ok());
+            let old_route = GLOBAL_APP_CONFIG.read().await.api_service_config[0]
+                .service_config
+                .routes[0]
+                .clone();
+            let old_base_route = old_route.route_cluster.get_all_route().await.unwrap();
+            old_base_route[0].is_alive.store(Some(false));
+
+            let new_config_str = config_str.replace(
+                "            try_file: null\n",
+                "            try_file: null\n        - base_route:\n            endpoint: http://localhost:8888/\n            try_file: null\n",
+            );
+            std::fs::write(config_path.clone(), new_config_str).unwrap();
+            assert!(reload_app_service_config().await.is_ok());
+            let new_route = GLOBAL_APP_CONFIG.read().await.api_service_config[0]
+                .service_config
+                .routes[0]
+                .clone();
+            let new_base_route = new_route.route_cluster.get_all_route().await.unwrap();
+            assert_eq!(new_base_route.len(), 2);
+            assert_eq!(new_base_route[0].is_alive.load(), Some(false));
+            assert_eq!(new_base_route[1].is_alive.load(), None);
+            let _ = std::fs::remove_file(config_path);
+        });
+    }
+    #[test]
+    #[serial("test")]
+    fn test_init_app_service_config_with_changed_file() {
+        TOKIO_RUNTIME.block_on(async move {
+            before_test().await;
+            let config_path =
+                env::temp_dir().join("test_init_app_service_config_with_changed_file.yaml");
+            let config_str = r#"
+- listen_port: 4486
+  service_config:
+    server_type: Http
+    routes:
+    - matcher:
+        prefix: /
+        prefix_rewrite: /
+      route_cluster:
+        type: RandomRoute
+        routes:
+        - base_route:
+            endpoint: http://localhost:7777/
+            try_file: null
+"#;
+            std::fs::write(config_path.clone(), config_str).unwrap();
+            env::set_var("CONFIG_FILE_PATH", config_path.clone());
+            init_static_config().await;
+            assert!(init_app_service_config().await.is_ok());
+            let versions = get_config_store().await.list_versions().await.unwrap();
+            assert_eq!(versions.len(), 1);
+
+            assert!(init_app_service_config().await.is_ok());
+            let versions = get_config_store().await.list_versions().await.unwrap();
+            assert_eq!(versions.len(), 1);
+
+            let get_endpoint = || async {
+                let route = GLOBAL_APP_CONFIG.read().await.api_service_config[0]
+                    .service_config
+                    .routes[0]
+                    .clone();
+                route.route_cluster.get_all_route().await.unwrap()[0]
+                    .endpoint
+                    .clone()
+            };
+            let admin_api_service_vistors: Vec<ApiServiceVistor> =
+                serde_yaml::from_str(&config_str.replace("7777", "9999")).unwrap();
+            let admin_api_services = from_api_service_vistors(admin_api_service_vistors)
+                .await
+                .unwrap();
+            save_api_services(admin_api_services, Some(String::from("Admin change")))
+                .await
+                .unwrap();
+            assert!(init_app_service_config().await.is_ok());
+            let versions = get_config_store().await.list_versions().await.unwrap();
+            assert_eq!(versions.len(), 2);
+            assert_eq!(get_endpoint().await, "http://localhost:9999/");
+
+            std::fs::write(config_path.clone(), config_str.replace("7777", "8888")).unwrap();
+            assert!(init_app_service_config().await.is_ok());
+            let versions = get_config_store().await.list_versions().await.unwrap();
+            assert_eq!(versions.len(), 3);
+            assert_eq!(get_endpoint().await, "http://localhost:8888/");
+
+            assert!(init_app_service_config().await.is_ok());
+            let versions = get_config_store().await.list_versions().await.unwrap();
+            assert_eq!(versions.len(), 3);
+            let _ = std::fs::remove_file(config_path);
+        });
+    }
+    #[test]
+    fn test_inherit_api_services_status_by_endpoints() {
+        TOKIO_RUNTIME.block_on(async move {
+            let config_str = r#"
+- listen_port: 4486
+  service_config:
+    server_type: Http
+    routes:
+    - matcher:
+        prefix: /
+        prefix_rewrite: /
+      route_cluster:
+        type: RandomRoute
+        routes:
+        - base_route:
+            endpoint: http://localhost:7777/
+            try_file: null
+"#;
+            let load_api_services = |config_str: String| async move {
+                let api_service_vistors: Vec<ApiServiceVistor> =
+                    serde_yaml::from_str(&config_str).unwrap();
+                from_api_service_vistors(api_service_vistors).await.unwrap()
+            };
+            let old_api_services = load_api_services(config_str.to_string()).await;
+            let old_route = &old_api_services[0].service_config.routes[0];
+            old_route.liveness_status.store(LivenessStatus {
+                current_liveness_count: 5,
+            });
+
+            let other_api_services = load_api_services(config_str.replace("7777", "8888")).await;
+            inherit_api_services_status(&other_api_services, &old_api_services)
+                .await
+                .unwrap();
+            let other_route = &other_api_services[0].service_config.routes[0];
+            assert_eq!(other_route.liveness_status.load().current_liveness_count, 1);
+
+            let same_api_services = load_api_services(config_str.to_string()).await;
+            inherit_api_services_status(&same_api_services, &old_api_services)
+                .await
+                .unwrap();
+            let same_route = &same_api_services[0].service_config.routes[0];
+            assert_eq!(same_route.liveness_status.load().current_liveness_count, 5);
+        });
+    }
+    #[test]
+    #[serial("test")]
+    fn test_reload_app_service_config_keep_old_config_with_error() {
+        TOKIO_RUNTIME.block_on(async move {
+            before_test().await;
+            let config_path = env::temp_dir()
+                .join("test_reload_app_service_config_keep_old_config_with_error.yaml");
+            let config_str = r#"
+- listen_port: 4486
+  service_config:
+    server_type: Http
+    routes:
+    - matcher:
+        prefix: /
+        prefix_rewrite: /
+      route_cluster:
+        type: RandomRoute
+        routes:
+        - base_route:
+            endpoint: http://localhost:7777/
+            try_file: null
+"#;
+            std::fs::write(config_path.clone(), config_str).unwrap();
+            env::set_var("CONFIG_FILE_PATH", config_path.clone());
+            init_static_config().await;
+            assert!(init_app_service_config().await.is_ok());
+
+            std::fs::write(config_path.clone(), format!("{}{}", config_str, config_str)).unwrap();
+            assert!(reload_app_service_config().await.is_err());
+            std::fs::write(config_path.clone(), "- listen_port: abc").unwrap();
+            assert!(reload_app_service_config().await.is_err());
+            let app_config = GLOBAL_APP_CONFIG.read().await.clone();
+            assert_eq!(app_config.api_service_config.len(), 1);
+            assert_eq!(app_config.api_service_config[0].listen_port, 4486);
+            let _ = std::fs::remove_file(config_path);
+        });
+    }
+    #[test]
+    #[serial("test")]
+    fn test_rollback_app_service_config_ok() {
+        TOKIO_RUNTIME.block_on(async move {
+            before_test().await;
+            let config_path = env::temp_dir().join("test_rollback_app_service_config_ok.yaml");
+            let config_str = r#"
+- listen_port: 4486
+  service_config:
+    server_type: Http
+    routes:
+    - route_id: rollback_route
+      matcher:
+        prefix: /
+        prefix_rewrite: /
+      route_cluster:
+        type: RandomRoute
+        routes:
+        - base_route:
+            endpoint: http://localhost:7777/
+            try_file: null
+"#;
+            std::fs::write(config_path.clone(), config_str).unwrap();
+            env::set_var("CONFIG_FILE_PATH", config_path.clone());
+            init_static_config().await;
+            assert!(init_app_service_config().await.is_ok());
+            let old_version = get_config_store()
+                .await
+                .list_versions()
+                .await
+                .unwrap()
+                .into_iter()
+                .find(|item| item.message == Some(String::from("Load the config file")))
+                .unwrap()
+                .version;
+            let old_route = GLOBAL_APP_CONFIG.read().await.api_service_config[0]
+                .service_config
+                .routes[0]
+                .clone();
+            let old_base_route = old_route.route_cluster.get_all_route().await.unwrap();
+            old_base_route[0].is_alive.store(Some(false));
+
+            let new_config_str = config_str.replace(
+                "            try_file: null\n",
+                "            try_file: null\n        - base_route:\n            endpoint: http://localhost:8888/\n            try_file: null\n",
+            );
+            std::fs::write(config_path.clone(), new_config_str).unwrap();
+            assert!(reload_app_service_config().await.is_ok());
+
+            let (new_version, generation) =
+                rollback_app_service_config(old_version).await.unwrap();
+            assert!(new_version > old_version);
+            assert_eq!(GLOBAL_APP_CONFIG.read().await.generation, generation);
+            assert!(get_config_generation().applied_generation >= generation);
+            let route = GLOBAL_APP_CONFIG.read().await.api_service_config[0]
+                .service_config
+                .routes[0]
+                .clone();
+            let base_route = route.route_cluster.get_all_route().await.unwrap();
+            assert_eq!(base_route.len(), 1);
+            assert_eq!(base_route[0].is_alive.load(), Some(false));
+            let snapshot = get_config_store()
+                .await
+                .load_version(new_version)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                snapshot.config_version.message,
+                Some(format!("Rollback to the version {}", old_version))
+            );
+            assert!(rollback_app_service_config(-1).await.is_err());
+            let _ = std::fs::remove_file(config_path);
+        });
+    }
+    #[test]
+    #[serial("test")]
     fn test_update_mapping_from_global_appconfig_with_default() {
         TOKIO_RUNTIME.block_on(async move {
             before_test().await;
@@ -346,7 +1165,7 @@ mod tests {
             assert!(res_init_app_service_config.is_ok());
             let res_update_config_mapping = update_mapping_from_global_appconfig().await;
             assert!(res_update_config_mapping.is_ok());
-            assert!(GLOBAL_CONFIG_MAPPING.len() < 4);
+            assert!(GLOBAL_CONFIG_MAPPING.load().len() < 4);
         });
     }
     #[test]
@@ -366,11 +1185,12 @@ mod tests {
             assert!(res_init_app_service_config.is_ok());
             let _res_update_mapping_from_global_appconfig =
                 update_mapping_from_global_appconfig().await;
-            assert!(GLOBAL_CONFIG_MAPPING.len() <= 5);
+            assert!(GLOBAL_CONFIG_MAPPING.load().len() <= 5);
             let api_service_manager_list = GLOBAL_CONFIG_MAPPING
-                .iter()
-                .map(|s| s.to_owned())
-                .collect::<Vec<ApiServiceManager>>();
+                .load()
+                .values()
+                .cloned()
+                .collect::<Vec<Arc<ApiServiceManager>>>();
             assert!(api_service_manager_list.len() <= 5);
             let api_service_manager = api_service_manager_list.first().unwrap();
             let routes = api_service_manager.service_config.routes.first().unwrap();
@@ -397,10 +1217,12 @@ mod tests {
                 base_route: BaseRoute {
                     endpoint: String::from("httpbin.org:80"),
                     try_file: None,
-                    is_alive: Arc::new(RwLock::new(None)),
-                    anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                        consecutive_5xx: 100,
-                    })),
+                    is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                    anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                        AnomalyDetectionStatus {
+                            consecutive_5xx: 100,
+                        },
+                    )),
                 },
             }],
         });
@@ -410,6 +1232,12 @@ mod tests {
             sender,
             service_config: ServiceConfig {
                 key_str: Some(private_key),
+                key_passphrase: None,
+                certificates: vec![],
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
                 server_type: crate::vojo::app_config::ServiceType::Https,
                 cert_str: Some(certificate),
                 routes: vec![Route {
@@ -420,12 +1248,18 @@ mod tests {
                     allow_deny_list: None,
                     authentication: None,
                     ratelimit: None,
+                    server_names: None,
+                    client_identities: None,
+                    upstream_tls: None,
+                    grpc_web: None,
+                    grpc_json_transcoder: None,
+                    grpc_matcher: None,
                     rewrite_headers: None,
 
                     health_check: None,
                     anomaly_detection: None,
                     liveness_config: None,
-                    liveness_status: Arc::new(RwLock::new(LivenessStatus {
+                    liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                         current_liveness_count: 0,
                     })),
                 }],
@@ -437,4 +1271,65 @@ mod tests {
                 start_proxy(2256, receiver, ServiceType::Https, String::from("test")).await;
         });
     }
+    #[test]
+    fn test_validate_service_tls_config_with_acme() {
+        let acme_config = AcmeConfig {
+            domains: vec![String::from("acme.example.com")],
+            contact_email: String::from("admin@example.com"),
+            ..Default::default()
+        };
+        let service_config_vistor = ServiceConfigVistor {
+            server_type: ServiceType::Https,
+            acme: Some(acme_config.clone()),
+            ..Default::default()
+        };
+        assert!(validate_service_tls_config(&service_config_vistor).is_ok());
+        for acme in [
+            AcmeConfig {
+                domains: vec![],
+                ..acme_config.clone()
+            },
+            AcmeConfig {
+                domains: vec![String::from("*.example.com")],
+                ..acme_config.clone()
+            },
+            AcmeConfig {
+                contact_email: String::new(),
+                ..acme_config.clone()
+            },
+            AcmeConfig {
+                renew_before_days: Some(0),
+                ..acme_config.clone()
+            },
+        ] {
+            let service_config_vistor = ServiceConfigVistor {
+                acme: Some(acme),
+                ..service_config_vistor.clone()
+            };
+            assert!(validate_service_tls_config(&service_config_vistor).is_err());
+        }
+        let service_config_vistor = ServiceConfigVistor {
+            acme: None,
+            ..service_config_vistor
+        };
+        assert!(validate_service_tls_config(&service_config_vistor).is_err());
+    }
+    #[test]
+    fn test_validate_service_tls_config_with_ocsp_stapling() {
+        let certificates_dir = env::current_dir().unwrap().join("config");
+        let mut service_config_vistor = ServiceConfigVistor {
+            server_type: ServiceType::Https,
+            cert_str: std::fs::read_to_string(certificates_dir.join("test_cert.pem")).ok(),
+            key_str: std::fs::read_to_string(certificates_dir.join("test_key.pem")).ok(),
+            ocsp_stapling: Some(OcspStaplingConfig {
+                responder_url: Some(String::from("http://127.0.0.1:8888/ocsp")),
+            }),
+            ..Default::default()
+        };
+        assert!(validate_service_tls_config(&service_config_vistor).is_ok());
+        service_config_vistor.ocsp_stapling = Some(OcspStaplingConfig {
+            responder_url: Some(String::from("/ocsp")),
+        });
+        assert!(validate_service_tls_config(&service_config_vistor).is_err());
+    }
 }
@@ -0,0 +1,131 @@
+use crate::configuration_service::app_config_service::reload_app_service_config;
+use crate::configuration_service::app_config_service::GLOBAL_APP_CONFIG;
+use notify::{Event, RecursiveMode, Watcher};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+use tokio::time::{sleep, Duration};
+const DEBOUNCE_MILLIS: u64 = 500;
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ReloadReason {
+    FileChanged,
+    Hangup,
+}
+/**
+*The parent directory is watched instead of the file,as most of the tools replace the file by
+ renaming a new one,which would drop the watch on the old inode.
+*/
+pub async fn start_config_file_watcher() -> Result<(), anyhow::Error> {
+    let config_file_path_option = GLOBAL_APP_CONFIG
+        .read()
+        .await
+        .static_config
+        .config_file_path
+        .clone();
+    let config_file_path = match config_file_path_option {
+        Some(config_file_path) => PathBuf::from(config_file_path),
+        None => return Ok(()),
+    };
+    let file_name = config_file_path
+        .file_name()
+        .ok_or(anyhow!(
+            "The config file path {} is not a file!",
+            config_file_path.display()
+        ))?
+        .to_os_string();
+    let parent_path = match config_file_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let (sender, mut receiver) = mpsc::channel(10);
+    let file_sender = sender.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+        Ok(event) => {
+            if is_config_file_event(&event, &file_name) {
+                let _ = file_sender.try_send(ReloadReason::FileChanged);
+            }
+        }
+        Err(err) => error!("Watch the config file error,the error is {}.", err),
+    })?;
+    watcher.watch(parent_path.as_path(), RecursiveMode::NonRecursive)?;
+    #[cfg(unix)]
+    tokio::spawn(forward_hangup_signal(sender));
+    info!(
+        "Start watching the config file {}.",
+        config_file_path.display()
+    );
+    let mut last_content = tokio::fs::read_to_string(config_file_path.as_path())
+        .await
+        .ok();
+    while let Some(reason) = receiver.recv().await {
+        if reason == ReloadReason::FileChanged {
+            sleep(Duration::from_millis(DEBOUNCE_MILLIS)).await;
+        }
+        let mut force = reason == ReloadReason::Hangup;
+        while let Ok(queued_reason) = receiver.try_recv() {
+            force = force || queued_reason == ReloadReason::Hangup;
+        }
+        let current_content = tokio::fs::read_to_string(config_file_path.as_path())
+            .await
+            .ok();
+        if !force && current_content == last_content {
+            continue;
+        }
+        match reload_app_service_config().await {
+            Ok(_) => last_content = current_content,
+            Err(err) => error!(
+                "Reload the config file error,the old config will be kept,the error is {}.",
+                err
+            ),
+        }
+    }
+    drop(watcher);
+    Ok(())
+}
+fn is_config_file_event(event: &Event, file_name: &OsString) -> bool {
+    if !(event.kind.is_create() || event.kind.is_modify()) {
+        return false;
+    }
+    event
+        .paths
+        .iter()
+        .any(|path| Path::new(path).file_name() == Some(file_name.as_os_str()))
+}
+#[cfg(unix)]
+async fn forward_hangup_signal(sender: mpsc::Sender<ReloadReason>) {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(err) => {
+            error!("Listen the SIGHUP signal error,the error is {}.", err);
+            return;
+        }
+    };
+    while hangup.recv().await.is_some() {
+        info!("Receive the SIGHUP signal,and the config file will be reloaded.");
+        if sender.send(ReloadReason::Hangup).await.is_err() {
+            return;
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, EventKind, ModifyKind, RemoveKind};
+    #[test]
+    fn test_is_config_file_event_ok() {
+        let file_name = OsString::from("app_config.yaml");
+        let event = Event::new(EventKind::Modify(ModifyKind::Any))
+            .add_path(PathBuf::from("/root/config/app_config.yaml"));
+        assert!(is_config_file_event(&event, &file_name));
+        let event = Event::new(EventKind::Create(CreateKind::File))
+            .add_path(PathBuf::from("/root/config/app_config.yaml"));
+        assert!(is_config_file_event(&event, &file_name));
+        let event = Event::new(EventKind::Modify(ModifyKind::Any))
+            .add_path(PathBuf::from("/root/config/other.yaml"));
+        assert!(!is_config_file_event(&event, &file_name));
+        let event = Event::new(EventKind::Remove(RemoveKind::File))
+            .add_path(PathBuf::from("/root/config/app_config.yaml"));
+        assert!(!is_config_file_event(&event, &file_name));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::configuration_service::app_config_service::update_mapping_from_global_appconfig;
+use crate::health_check::health_check_task::HealthCheck;
+use crate::vojo::config_generation::ConfigGeneration;
+use futures::FutureExt;
+use lazy_static::lazy_static;
+use std::time::Duration;
+use tokio::sync::{watch, Mutex};
+lazy_static! {
+    static ref GLOBAL_CONFIG_GENERATION: watch::Sender<ConfigGeneration> =
+        watch::channel(ConfigGeneration::default()).0;
+    static ref GLOBAL_HEALTH_CHECK: Mutex<HealthCheck> = Mutex::new(HealthCheck::new());
+}
+/**
+*Publish the generation of the change,which should be called under the write lock of the global
+ config,so the order of the generations is the same as the order of the changes.
+*/
+pub fn publish_config_generation(generation: u64) {
+    GLOBAL_CONFIG_GENERATION.send_modify(|item| item.generation = item.generation.max(generation));
+}
+pub fn get_config_generation() -> ConfigGeneration {
+    *GLOBAL_CONFIG_GENERATION.borrow()
+}
+/**
+*Reconcile the listeners,the routes and the health check tasks with the global config.The
+ reconciliations run one by one and every reconciliation applies all the changes before it,so the
+ generation which has been applied by a later reconciliation returns directly.
+*/
+pub async fn reconcile_config(generation: u64) -> Result<ConfigGeneration, anyhow::Error> {
+    let mut health_check = GLOBAL_HEALTH_CHECK.lock().await;
+    if get_config_generation().applied_generation >= generation {
+        return Ok(get_config_generation());
+    }
+    let applied_generation = std::panic::AssertUnwindSafe(async {
+        let applied_generation = update_mapping_from_global_appconfig().await?;
+        health_check.do_health_check().await?;
+        Ok::<u64, anyhow::Error>(applied_generation)
+    })
+    .catch_unwind()
+    .await
+    .map_err(|_| anyhow!("Reconcile the config generation {} panic!", generation))??;
+    GLOBAL_CONFIG_GENERATION.send_modify(|item| {
+        item.applied_generation = item.applied_generation.max(applied_generation)
+    });
+    debug!(
+        "The config generation {} has been applied.",
+        applied_generation
+    );
+    Ok(get_config_generation())
+}
+/**
+*The endpoints of the dns routes and the registry routes change without any config change,so the
+ discovery refreshes the health check tasks by itself.
+*/
+pub async fn reconcile_health_check() -> Result<(), anyhow::Error> {
+    GLOBAL_HEALTH_CHECK.lock().await.do_health_check().await
+}
+/**
+*Wait until the generation has been applied or the timeout,and the caller should check the
+ applied generation of the result.
+*/
+pub async fn wait_for_config_generation(generation: u64, timeout_seconds: u64) -> ConfigGeneration {
+    wait_for_generation(&GLOBAL_CONFIG_GENERATION, generation, timeout_seconds).await
+}
+async fn wait_for_generation(
+    sender: &watch::Sender<ConfigGeneration>,
+    generation: u64,
+    timeout_seconds: u64,
+) -> ConfigGeneration {
+    let mut receiver = sender.subscribe();
+    let wait_task = async move {
+        loop {
+            let applied_generation = receiver.borrow_and_update().applied_generation;
+            if applied_generation >= generation || receiver.changed().await.is_err() {
+                break;
+            }
+        }
+    };
+    let _ = tokio::time::timeout(Duration::from_secs(timeout_seconds), wait_task).await;
+    *sender.borrow()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    #[tokio::test]
+    async fn test_wait_for_config_generation_ok() {
+        let current = get_config_generation();
+        let result = wait_for_config_generation(current.applied_generation, 1).await;
+        assert!(result.applied_generation >= current.applied_generation);
+    }
+    #[tokio::test]
+    async fn test_wait_for_generation_ok() {
+        let sender = Arc::new(watch::channel(ConfigGeneration::default()).0);
+        let result = wait_for_generation(&sender, 0, 1).await;
+        assert_eq!(result.applied_generation, 0);
+        let result = wait_for_generation(&sender, 1, 0).await;
+        assert_eq!(result.applied_generation, 0);
+
+        let waiting_sender = sender.clone();
+        let waiter = tokio::spawn(async move { wait_for_generation(&waiting_sender, 2, 5).await });
+        sender.send_modify(|item| item.applied_generation = 1);
+        sender.send_modify(|item| item.applied_generation = 2);
+        let result = waiter.await.unwrap();
+        assert_eq!(result.applied_generation, 2);
+    }
+}
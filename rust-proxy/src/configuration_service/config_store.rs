@@ -0,0 +1,145 @@
+use crate::configuration_service::file_config_store::FileConfigStore;
+use crate::configuration_service::sqlite_config_store::SqliteConfigStore;
+use crate::constants::common_constants::DEFAULT_CONFIG_VERSION_RETENTION;
+use crate::control_plane::admin_auth::redact_secrets;
+use crate::vojo::app_config_vistor::ApiServiceVistor;
+use crate::vojo::config_version::ConfigVersion;
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use similar::TextDiff;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+lazy_static! {
+    static ref GLOBAL_CONFIG_STORE: RwLock<Arc<dyn ConfigStore>> =
+        RwLock::new(Arc::new(FileConfigStore::default()));
+}
+#[derive(Debug, Clone)]
+pub struct ConfigSnapshot {
+    pub config_version: ConfigVersion,
+    pub api_services: Vec<ApiServiceVistor>,
+}
+#[async_trait]
+pub trait ConfigStore: Send + Sync {
+    /**
+     *Load the latest version,and None means the store has nothing to load.
+     */
+    async fn load_latest(&self) -> Result<Option<ConfigSnapshot>, anyhow::Error>;
+    async fn load_version(&self, version: i64) -> Result<Option<ConfigSnapshot>, anyhow::Error>;
+    /**
+     *List the retained versions from the newest to the oldest.
+     */
+    async fn list_versions(&self) -> Result<Vec<ConfigVersion>, anyhow::Error>;
+    /**
+    *Save the api services as a new version in one transaction and return the new version,the
+     versions beyond the retention are removed in the same transaction.
+    */
+    async fn save(
+        &self,
+        api_services: Vec<ApiServiceVistor>,
+        message: Option<String>,
+    ) -> Result<i64, anyhow::Error>;
+    /**
+     *The hash of the config file which was imported last,and None means no file has been imported.
+     */
+    async fn load_file_hash(&self) -> Result<Option<String>, anyhow::Error>;
+    async fn save_file_hash(&self, file_hash: String) -> Result<(), anyhow::Error>;
+}
+/**
+*The file store is used without the DATABASE_URL,and the sqlite store is used with the url like
+ sqlite://silverwind.db or sqlite::memory:.
+*/
+pub fn create_config_store(
+    database_url_option: Option<String>,
+    retention_option: Option<usize>,
+) -> Result<Arc<dyn ConfigStore>, anyhow::Error> {
+    let retention = retention_option.unwrap_or(DEFAULT_CONFIG_VERSION_RETENTION);
+    if retention == 0 {
+        return Err(anyhow!(
+            "The retention of the config versions could not be zero!"
+        ));
+    }
+    let database_url = match database_url_option {
+        Some(database_url) => database_url,
+        None => {
+            return Ok(Arc::new(
+                FileConfigStore::default().with_retention(retention),
+            ))
+        }
+    };
+    if let Some(path) = database_url
+        .strip_prefix("sqlite://")
+        .or(database_url.strip_prefix("sqlite:"))
+    {
+        return Ok(Arc::new(SqliteConfigStore::new(path, retention)?));
+    }
+    Err(anyhow!(
+        "The database url {} is not supported,only the sqlite is supported now!",
+        database_url
+    ))
+}
+pub async fn get_config_store() -> Arc<dyn ConfigStore> {
+    GLOBAL_CONFIG_STORE.read().await.clone()
+}
+pub async fn set_config_store(config_store: Arc<dyn ConfigStore>) {
+    *GLOBAL_CONFIG_STORE.write().await = config_store;
+}
+/**
+*Diff the two versions in the yaml format,which is the same as the config file.
+*/
+/**
+*The redacted snapshots are diffed by the sorted keys,as they are converted to the json value first.
+*/
+pub async fn diff_config_versions(
+    from_version: i64,
+    to_version: i64,
+    is_redacted: bool,
+) -> Result<String, anyhow::Error> {
+    let config_store = get_config_store().await;
+    let mut config_strs = vec![];
+    for version in [from_version, to_version] {
+        let snapshot = config_store
+            .load_version(version)
+            .await?
+            .ok_or(anyhow!("Can not find the config version {}!", version))?;
+        let config_str = if is_redacted {
+            let mut value = serde_json::to_value(&snapshot.api_services)?;
+            redact_secrets(&mut value);
+            serde_yaml::to_string(&value)?
+        } else {
+            serde_yaml::to_string(&snapshot.api_services)?
+        };
+        config_strs.push(config_str);
+    }
+    let diff = TextDiff::from_lines(config_strs[0].as_str(), config_strs[1].as_str())
+        .unified_diff()
+        .header(
+            format!("version-{}", from_version).as_str(),
+            format!("version-{}", to_version).as_str(),
+        )
+        .to_string();
+    Ok(diff)
+}
+pub fn current_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|item| item.as_secs() as i64)
+        .unwrap_or_default()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[tokio::test]
+    async fn test_create_config_store_ok() {
+        assert!(create_config_store(None, None).is_ok());
+        assert!(create_config_store(None, Some(0)).is_err());
+        let config_store =
+            create_config_store(Some(String::from("sqlite::memory:")), Some(10)).unwrap();
+        assert!(config_store.load_latest().await.unwrap().is_none());
+        assert!(create_config_store(
+            Some(String::from("mysql://root@127.0.0.1/silverwind")),
+            None
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,254 @@
+use crate::configuration_service::config_store::{current_timestamp, ConfigSnapshot, ConfigStore};
+use crate::constants::common_constants::DEFAULT_CONFIG_VERSION_RETENTION;
+use crate::constants::common_constants::DEFAULT_TEMPORARY_DIR;
+use crate::vojo::app_config_vistor::ApiServiceVistor;
+use crate::vojo::config_version::ConfigVersion;
+use async_trait::async_trait;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+const INDEX_FILE_NAME: &str = "index.yml";
+const FILE_HASH_FILE_NAME: &str = "file_hash";
+/**
+*The file store keeps the latest config in the temporary dir,and every version is saved as one
+ file in the versions dir with an index file.
+*/
+pub struct FileConfigStore {
+    file_path: PathBuf,
+    version_dir: PathBuf,
+    retention: usize,
+    lock: Mutex<()>,
+}
+impl Default for FileConfigStore {
+    fn default() -> Self {
+        FileConfigStore::new(PathBuf::from(DEFAULT_TEMPORARY_DIR).join("new_silverwind_config.yml"))
+    }
+}
+impl FileConfigStore {
+    /**
+     *The versions are saved in the dir next to the file,for example new_silverwind_config_versions.
+     */
+    pub fn new(file_path: PathBuf) -> Self {
+        let file_stem = file_path
+            .file_stem()
+            .map(|item| item.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let version_dir = file_path.with_file_name(format!("{}_versions", file_stem));
+        FileConfigStore {
+            file_path,
+            version_dir,
+            retention: DEFAULT_CONFIG_VERSION_RETENTION,
+            lock: Mutex::new(()),
+        }
+    }
+    pub fn with_retention(mut self, retention: usize) -> Self {
+        self.retention = retention;
+        self
+    }
+    fn index_path(&self) -> PathBuf {
+        self.version_dir.join(INDEX_FILE_NAME)
+    }
+    fn version_path(&self, version: i64) -> PathBuf {
+        self.version_dir.join(format!("version_{}.yml", version))
+    }
+    /**
+     *The index lists the retained versions from the oldest to the newest.
+     */
+    async fn read_index(&self) -> Result<Vec<ConfigVersion>, anyhow::Error> {
+        match tokio::fs::read_to_string(self.index_path()).await {
+            Ok(index_str) => Ok(serde_yaml::from_str(&index_str)?),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(vec![]),
+            Err(err) => Err(anyhow!(err)),
+        }
+    }
+    async fn read_snapshot(
+        &self,
+        config_version: ConfigVersion,
+    ) -> Result<ConfigSnapshot, anyhow::Error> {
+        let api_service_str =
+            tokio::fs::read_to_string(self.version_path(config_version.version)).await?;
+        Ok(ConfigSnapshot {
+            config_version,
+            api_services: serde_yaml::from_str(&api_service_str)?,
+        })
+    }
+}
+/**
+*Write a temporary file and rename it,so the file is never left half written.
+*/
+async fn write_file(file_path: &Path, content: &str) -> Result<(), anyhow::Error> {
+    if let Some(parent) = file_path.parent() {
+        if !parent.as_os_str().is_empty() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+    }
+    let temporary_path = file_path.with_extension("tmp");
+    let mut f = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(temporary_path.as_path())
+        .await?;
+    f.write_all(content.as_bytes()).await?;
+    f.sync_all().await?;
+    tokio::fs::rename(temporary_path.as_path(), file_path).await?;
+    Ok(())
+}
+#[async_trait]
+impl ConfigStore for FileConfigStore {
+    async fn load_latest(&self) -> Result<Option<ConfigSnapshot>, anyhow::Error> {
+        let _lock = self.lock.lock().await;
+        match self.read_index().await?.pop() {
+            Some(config_version) => Ok(Some(self.read_snapshot(config_version).await?)),
+            None => Ok(None),
+        }
+    }
+    async fn load_version(&self, version: i64) -> Result<Option<ConfigSnapshot>, anyhow::Error> {
+        let _lock = self.lock.lock().await;
+        let config_version = match self
+            .read_index()
+            .await?
+            .into_iter()
+            .find(|item| item.version == version)
+        {
+            Some(config_version) => config_version,
+            None => return Ok(None),
+        };
+        Ok(Some(self.read_snapshot(config_version).await?))
+    }
+    async fn list_versions(&self) -> Result<Vec<ConfigVersion>, anyhow::Error> {
+        let _lock = self.lock.lock().await;
+        Ok(self.read_index().await?.into_iter().rev().collect())
+    }
+    /**
+    *The version file is written before the index,and the files of the versions beyond the
+    retention are removed after the index is written.
+    */
+    async fn save(
+        &self,
+        api_services: Vec<ApiServiceVistor>,
+        message: Option<String>,
+    ) -> Result<i64, anyhow::Error> {
+        let _lock = self.lock.lock().await;
+        let mut index = self.read_index().await?;
+        let version = index.last().map(|item| item.version + 1).unwrap_or(1);
+        let api_service_str = serde_yaml::to_string(&api_services)?;
+        write_file(self.version_path(version).as_path(), &api_service_str).await?;
+        write_file(self.file_path.as_path(), &api_service_str).await?;
+        index.push(ConfigVersion {
+            version,
+            create_time: current_timestamp(),
+            message,
+        });
+        let expired_count = index.len().saturating_sub(self.retention);
+        let expired_versions = index.drain(..expired_count).collect::<Vec<ConfigVersion>>();
+        write_file(self.index_path().as_path(), &serde_yaml::to_string(&index)?).await?;
+        for expired_version in expired_versions {
+            if let Err(err) =
+                tokio::fs::remove_file(self.version_path(expired_version.version)).await
+            {
+                if err.kind() != ErrorKind::NotFound {
+                    return Err(anyhow!(err));
+                }
+            }
+        }
+        Ok(version)
+    }
+    async fn load_file_hash(&self) -> Result<Option<String>, anyhow::Error> {
+        let _lock = self.lock.lock().await;
+        match tokio::fs::read_to_string(self.version_dir.join(FILE_HASH_FILE_NAME)).await {
+            Ok(file_hash) => Ok(Some(file_hash.trim().to_string())),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(anyhow!(err)),
+        }
+    }
+    async fn save_file_hash(&self, file_hash: String) -> Result<(), anyhow::Error> {
+        let _lock = self.lock.lock().await;
+        write_file(
+            self.version_dir.join(FILE_HASH_FILE_NAME).as_path(),
+            &file_hash,
+        )
+        .await
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[tokio::test]
+    async fn test_file_config_store_save_ok() {
+        let file_path = PathBuf::from(DEFAULT_TEMPORARY_DIR).join("test_file_config_store.yml");
+        let version_dir =
+            PathBuf::from(DEFAULT_TEMPORARY_DIR).join("test_file_config_store_versions");
+        let _ = std::fs::remove_dir_all(version_dir.as_path());
+        let file_config_store = FileConfigStore::new(file_path.clone()).with_retention(2);
+        assert!(file_config_store.load_latest().await.unwrap().is_none());
+        let api_services = vec![ApiServiceVistor {
+            listen_port: 4490,
+            ..Default::default()
+        }];
+        assert_eq!(
+            file_config_store
+                .save(api_services.clone(), None)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            file_config_store
+                .save(api_services.clone(), None)
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            file_config_store
+                .save(api_services, Some(String::from("third")))
+                .await
+                .unwrap(),
+            3
+        );
+        let snapshot = file_config_store.load_latest().await.unwrap().unwrap();
+        assert_eq!(snapshot.config_version.version, 3);
+        assert_eq!(snapshot.config_version.message, Some(String::from("third")));
+        assert_eq!(snapshot.api_services[0].listen_port, 4490);
+        let versions = file_config_store.list_versions().await.unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].version, 3);
+        assert_eq!(versions[0].message, Some(String::from("third")));
+        assert!(file_config_store.load_version(1).await.unwrap().is_none());
+        assert!(file_config_store.load_version(2).await.unwrap().is_some());
+        assert!(!version_dir.join("version_1.yml").exists());
+        assert!(version_dir.join("version_3.yml").exists());
+
+        let reopened_store = FileConfigStore::new(file_path.clone()).with_retention(2);
+        let versions = reopened_store.list_versions().await.unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].message, Some(String::from("third")));
+        let snapshot = reopened_store.load_version(3).await.unwrap().unwrap();
+        assert_eq!(snapshot.api_services[0].listen_port, 4490);
+
+        let config_str = std::fs::read_to_string(file_path.as_path()).unwrap();
+        let api_services: Vec<ApiServiceVistor> = serde_yaml::from_str(&config_str).unwrap();
+        assert_eq!(api_services[0].listen_port, 4490);
+        assert_eq!(reopened_store.save(vec![], None).await.unwrap(), 4);
+        let snapshot = reopened_store.load_latest().await.unwrap().unwrap();
+        assert_eq!(snapshot.config_version.version, 4);
+        assert!(snapshot.api_services.is_empty());
+
+        assert!(reopened_store.load_file_hash().await.unwrap().is_none());
+        reopened_store
+            .save_file_hash(String::from("hash-1"))
+            .await
+            .unwrap();
+        assert_eq!(
+            FileConfigStore::new(file_path.clone())
+                .load_file_hash()
+                .await
+                .unwrap(),
+            Some(String::from("hash-1"))
+        );
+        std::fs::remove_file(file_path).unwrap();
+        std::fs::remove_dir_all(version_dir).unwrap();
+    }
+}
@@ -1,4 +1,6 @@
 use crate::constants::common_constants::DEFAULT_LOGER_LEVEL;
+use crate::constants::common_constants::ENV_LOG_LEVEL;
+use log::LevelFilter;
 use log4rs::append::console::ConsoleAppender;
 use log4rs::append::rolling_file::policy::compound::roll::fixed_window::FixedWindowRoller;
 use log4rs::append::rolling_file::policy::compound::trigger::size::SizeTrigger;
@@ -6,7 +8,13 @@ use log4rs::append::rolling_file::policy::compound::CompoundPolicy;
 use log4rs::append::rolling_file::RollingFileAppender;
 use log4rs::config::{Appender, Config, Logger, Root};
 use log4rs::encode::pattern::PatternEncoder;
+use std::env;
+use std::str::FromStr;
 pub fn start_logger() {
+    let log_level = env::var(ENV_LOG_LEVEL)
+        .ok()
+        .and_then(|level| LevelFilter::from_str(level.as_str()).ok())
+        .unwrap_or(DEFAULT_LOGER_LEVEL);
     let stdout = ConsoleAppender::builder()
         .encoder(Box::new(PatternEncoder::new(
             "{d(%Y-%m-%d %H:%M:%S)(local)} - {h({l})}: {m}{n}",
@@ -48,13 +56,13 @@ pub fn start_logger() {
             Logger::builder()
                 .appender("app")
                 .additive(false)
-                .build("app", DEFAULT_LOGER_LEVEL),
+                .build("app", log_level),
         )
         .build(
             Root::builder()
                 .appender("stdout")
                 .appender("common")
-                .build(DEFAULT_LOGER_LEVEL),
+                .build(log_level),
         )
         .unwrap();
 
@@ -1,2 +1,7 @@
 pub mod app_config_service;
+pub mod config_file_watcher;
+pub mod config_propagation;
+pub mod config_store;
+pub mod file_config_store;
 pub mod logger;
+pub mod sqlite_config_store;
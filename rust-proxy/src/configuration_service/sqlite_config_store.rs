@@ -0,0 +1,367 @@
+use crate::configuration_service::config_store::{current_timestamp, ConfigSnapshot, ConfigStore};
+use crate::vojo::app_config_vistor::{ApiServiceVistor, RouteVistor, ServiceConfigVistor};
+use crate::vojo::config_version::ConfigVersion;
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::{Arc, Mutex};
+const CREATE_TABLE_SQL: &str = r#"
+CREATE TABLE IF NOT EXISTS config_version (
+    version INTEGER PRIMARY KEY AUTOINCREMENT,
+    create_time INTEGER NOT NULL,
+    message TEXT
+);
+CREATE TABLE IF NOT EXISTS api_service (
+    version INTEGER NOT NULL,
+    service_index INTEGER NOT NULL,
+    listen_port INTEGER NOT NULL,
+    api_service_id TEXT NOT NULL,
+    service_config TEXT NOT NULL,
+    PRIMARY KEY (version, service_index)
+);
+CREATE TABLE IF NOT EXISTS route (
+    version INTEGER NOT NULL,
+    service_index INTEGER NOT NULL,
+    route_index INTEGER NOT NULL,
+    route_id TEXT NOT NULL,
+    route_config TEXT NOT NULL,
+    PRIMARY KEY (version, service_index, route_index)
+);
+CREATE TABLE IF NOT EXISTS config_file (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    file_hash TEXT NOT NULL
+);
+"#;
+/**
+*Every version keeps the whole api services,and the routes are stored apart from the service
+ config of the api service.
+*/
+pub struct SqliteConfigStore {
+    connection: Arc<Mutex<Connection>>,
+    retention: usize,
+}
+impl SqliteConfigStore {
+    pub fn new(path: &str, retention: usize) -> Result<Self, anyhow::Error> {
+        let connection = if path == ":memory:" {
+            Connection::open_in_memory()?
+        } else {
+            Connection::open(path)?
+        };
+        connection.execute_batch(CREATE_TABLE_SQL)?;
+        info!("Open the sqlite config store {} successfully.", path);
+        Ok(SqliteConfigStore {
+            connection: Arc::new(Mutex::new(connection)),
+            retention,
+        })
+    }
+}
+fn to_config_version(row: &rusqlite::Row) -> Result<ConfigVersion, rusqlite::Error> {
+    Ok(ConfigVersion {
+        version: row.get(0)?,
+        create_time: row.get(1)?,
+        message: row.get(2)?,
+    })
+}
+fn list_config_versions(connection: &Connection) -> Result<Vec<ConfigVersion>, anyhow::Error> {
+    let mut statement = connection.prepare(
+        "SELECT version, create_time, message FROM config_version ORDER BY version DESC",
+    )?;
+    let config_versions = statement
+        .query_map([], to_config_version)?
+        .collect::<Result<Vec<ConfigVersion>, rusqlite::Error>>()?;
+    Ok(config_versions)
+}
+/**
+*Load the version,or the latest version if the version is None.
+*/
+fn load_config_version(
+    connection: &Connection,
+    version_option: Option<i64>,
+) -> Result<Option<ConfigSnapshot>, anyhow::Error> {
+    let config_version_option = match version_option {
+        Some(version) => connection
+            .query_row(
+                "SELECT version, create_time, message FROM config_version WHERE version = ?1",
+                params![version],
+                to_config_version,
+            )
+            .optional()?,
+        None => connection
+            .query_row(
+                "SELECT version, create_time, message FROM config_version
+                 ORDER BY version DESC LIMIT 1",
+                [],
+                to_config_version,
+            )
+            .optional()?,
+    };
+    let config_version = match config_version_option {
+        Some(config_version) => config_version,
+        None => return Ok(None),
+    };
+    let version = config_version.version;
+    let mut service_statement = connection.prepare(
+        "SELECT service_index, listen_port, api_service_id, service_config FROM api_service
+         WHERE version = ?1 ORDER BY service_index",
+    )?;
+    let service_rows = service_statement
+        .query_map(params![version], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i32>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+    let mut route_statement = connection.prepare(
+        "SELECT route_config FROM route WHERE version = ?1 AND service_index = ?2
+         ORDER BY route_index",
+    )?;
+    let mut api_services = vec![];
+    for (service_index, listen_port, api_service_id, service_config_str) in service_rows {
+        let mut service_config: ServiceConfigVistor =
+            serde_json::from_str(service_config_str.as_str())?;
+        let route_rows = route_statement
+            .query_map(params![version, service_index], |row| {
+                row.get::<_, String>(0)
+            })?
+            .collect::<Result<Vec<String>, rusqlite::Error>>()?;
+        for route_str in route_rows {
+            let route: RouteVistor = serde_json::from_str(route_str.as_str())?;
+            service_config.routes.push(route);
+        }
+        api_services.push(ApiServiceVistor {
+            listen_port,
+            api_service_id,
+            service_config,
+        });
+    }
+    Ok(Some(ConfigSnapshot {
+        config_version,
+        api_services,
+    }))
+}
+fn save_version(
+    connection: &mut Connection,
+    api_services: Vec<ApiServiceVistor>,
+    message: Option<String>,
+    retention: usize,
+) -> Result<i64, anyhow::Error> {
+    let transaction = connection.transaction()?;
+    transaction.execute(
+        "INSERT INTO config_version (create_time, message) VALUES (?1, ?2)",
+        params![current_timestamp(), message],
+    )?;
+    let version = transaction.last_insert_rowid();
+    for (service_index, api_service) in api_services.into_iter().enumerate() {
+        let mut service_config = api_service.service_config;
+        let routes = std::mem::take(&mut service_config.routes);
+        transaction.execute(
+            "INSERT INTO api_service (version, service_index, listen_port, api_service_id, service_config)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                version,
+                service_index as i64,
+                api_service.listen_port,
+                api_service.api_service_id,
+                serde_json::to_string(&service_config)?
+            ],
+        )?;
+        for (route_index, route) in routes.into_iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO route (version, service_index, route_index, route_id, route_config)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    version,
+                    service_index as i64,
+                    route_index as i64,
+                    route.route_id,
+                    serde_json::to_string(&route)?
+                ],
+            )?;
+        }
+    }
+    let oldest_version = version - retention as i64;
+    transaction.execute(
+        "DELETE FROM config_version WHERE version <= ?1",
+        params![oldest_version],
+    )?;
+    transaction.execute(
+        "DELETE FROM api_service WHERE version <= ?1",
+        params![oldest_version],
+    )?;
+    transaction.execute(
+        "DELETE FROM route WHERE version <= ?1",
+        params![oldest_version],
+    )?;
+    transaction.commit()?;
+    Ok(version)
+}
+#[async_trait]
+impl ConfigStore for SqliteConfigStore {
+    async fn load_latest(&self) -> Result<Option<ConfigSnapshot>, anyhow::Error> {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || {
+            let connection = connection.lock().map_err(|err| anyhow!(err.to_string()))?;
+            load_config_version(&connection, None)
+        })
+        .await?
+    }
+    async fn load_version(&self, version: i64) -> Result<Option<ConfigSnapshot>, anyhow::Error> {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || {
+            let connection = connection.lock().map_err(|err| anyhow!(err.to_string()))?;
+            load_config_version(&connection, Some(version))
+        })
+        .await?
+    }
+    async fn list_versions(&self) -> Result<Vec<ConfigVersion>, anyhow::Error> {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || {
+            let connection = connection.lock().map_err(|err| anyhow!(err.to_string()))?;
+            list_config_versions(&connection)
+        })
+        .await?
+    }
+    async fn save(
+        &self,
+        api_services: Vec<ApiServiceVistor>,
+        message: Option<String>,
+    ) -> Result<i64, anyhow::Error> {
+        let connection = self.connection.clone();
+        let retention = self.retention;
+        tokio::task::spawn_blocking(move || {
+            let mut connection = connection.lock().map_err(|err| anyhow!(err.to_string()))?;
+            save_version(&mut connection, api_services, message, retention)
+        })
+        .await?
+    }
+    async fn load_file_hash(&self) -> Result<Option<String>, anyhow::Error> {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || {
+            let connection = connection.lock().map_err(|err| anyhow!(err.to_string()))?;
+            let file_hash = connection
+                .query_row(
+                    "SELECT file_hash FROM config_file WHERE id = 0",
+                    [],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(file_hash)
+        })
+        .await?
+    }
+    async fn save_file_hash(&self, file_hash: String) -> Result<(), anyhow::Error> {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || {
+            let connection = connection.lock().map_err(|err| anyhow!(err.to_string()))?;
+            connection.execute(
+                "INSERT OR REPLACE INTO config_file (id, file_hash) VALUES (0, ?1)",
+                params![file_hash],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vojo::app_config::ServiceType;
+    fn get_api_service(listen_port: i32, route_ids: Vec<&str>) -> ApiServiceVistor {
+        ApiServiceVistor {
+            listen_port,
+            api_service_id: format!("api-service-{}", listen_port),
+            service_config: ServiceConfigVistor {
+                server_type: ServiceType::Http,
+                routes: route_ids
+                    .into_iter()
+                    .map(|route_id| RouteVistor {
+                        route_id: String::from(route_id),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+        }
+    }
+    #[tokio::test]
+    async fn test_sqlite_config_store_save_and_load_ok() {
+        let sqlite_config_store = SqliteConfigStore::new(":memory:", 10).unwrap();
+        assert!(sqlite_config_store.load_latest().await.unwrap().is_none());
+        let version = sqlite_config_store
+            .save(vec![get_api_service(4491, vec!["route-1"])], None)
+            .await
+            .unwrap();
+        assert_eq!(version, 1);
+        let version = sqlite_config_store
+            .save(
+                vec![
+                    get_api_service(4492, vec!["route-2", "route-3"]),
+                    get_api_service(4491, vec![]),
+                ],
+                Some(String::from("add the port 4492")),
+            )
+            .await
+            .unwrap();
+        assert_eq!(version, 2);
+
+        let snapshot = sqlite_config_store.load_latest().await.unwrap().unwrap();
+        assert_eq!(snapshot.config_version.version, 2);
+        assert_eq!(
+            snapshot.config_version.message,
+            Some(String::from("add the port 4492"))
+        );
+        assert_eq!(snapshot.api_services.len(), 2);
+        assert_eq!(snapshot.api_services[0].listen_port, 4492);
+        assert_eq!(snapshot.api_services[0].api_service_id, "api-service-4492");
+        let route_ids = snapshot.api_services[0]
+            .service_config
+            .routes
+            .iter()
+            .map(|item| item.route_id.clone())
+            .collect::<Vec<String>>();
+        assert_eq!(route_ids, vec!["route-2", "route-3"]);
+        assert!(snapshot.api_services[1].service_config.routes.is_empty());
+
+        let snapshot = sqlite_config_store.load_version(1).await.unwrap().unwrap();
+        assert_eq!(snapshot.api_services[0].listen_port, 4491);
+        assert_eq!(snapshot.config_version.message, None);
+        assert!(sqlite_config_store.load_version(3).await.unwrap().is_none());
+
+        assert!(sqlite_config_store
+            .load_file_hash()
+            .await
+            .unwrap()
+            .is_none());
+        for file_hash in ["hash-1", "hash-2"] {
+            sqlite_config_store
+                .save_file_hash(String::from(file_hash))
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            sqlite_config_store.load_file_hash().await.unwrap(),
+            Some(String::from("hash-2"))
+        );
+    }
+    #[tokio::test]
+    async fn test_sqlite_config_store_retention_ok() {
+        let sqlite_config_store = SqliteConfigStore::new(":memory:", 2).unwrap();
+        for listen_port in [4493, 4494, 4495] {
+            sqlite_config_store
+                .save(vec![get_api_service(listen_port, vec!["route"])], None)
+                .await
+                .unwrap();
+        }
+        let versions = sqlite_config_store
+            .list_versions()
+            .await
+            .unwrap()
+            .iter()
+            .map(|item| item.version)
+            .collect::<Vec<i64>>();
+        assert_eq!(versions, vec![3, 2]);
+        assert!(sqlite_config_store.load_version(1).await.unwrap().is_none());
+    }
+}
@@ -1,5 +1,7 @@
 use log::LevelFilter;
 pub const DEFAULT_ADMIN_PORT: &str = "8870";
+pub const DEFAULT_ADMIN_BIND_ADDRESS: &str = "127.0.0.1";
+pub const CONFIG_GENERATION_HEADER: &str = "x-config-generation";
 pub const DENY_RESPONSE: &str = r#"{
     "response_code": -1,
     "response_object": "The request has been blocked by the silverwind!"
@@ -10,12 +12,22 @@ pub const NOT_FOUND: &str = r#"{
 }"#;
 pub const DEFAULT_FIXEDWINDOW_MAP_SIZE: i32 = 3;
 pub const ENV_ADMIN_PORT: &str = "ADMIN_PORT";
+pub const ENV_ADMIN_BIND_ADDRESS: &str = "ADMIN_BIND_ADDRESS";
+pub const ENV_ADMIN_CORS_ORIGINS: &str = "ADMIN_CORS_ORIGINS";
+pub const ENV_ADMIN_TOKENS: &str = "ADMIN_TOKENS";
+pub const ENV_ADMIN_TLS_CERT_PATH: &str = "ADMIN_TLS_CERT_PATH";
+pub const ENV_ADMIN_TLS_KEY_PATH: &str = "ADMIN_TLS_KEY_PATH";
+pub const ENV_ADMIN_TLS_CLIENT_CA_PATH: &str = "ADMIN_TLS_CLIENT_CA_PATH";
+pub const ENV_ADMIN_TLS_CLIENT_ROLE: &str = "ADMIN_TLS_CLIENT_ROLE";
 pub const ENV_DATABASE_URL: &str = "DATABASE_URL";
 pub const ENV_ACCESS_LOG: &str = "ACCESS_LOG";
 pub const ENV_CONFIG_FILE_PATH: &str = "CONFIG_FILE_PATH";
+pub const ENV_LOG_LEVEL: &str = "LOG_LEVEL";
+pub const ENV_CONFIG_VERSION_RETENTION: &str = "CONFIG_VERSION_RETENTION";
 pub const TIMER_WAIT_SECONDS: u64 = 5;
 pub const DEFAULT_HTTP_TIMEOUT: u64 = 10;
+pub const DEFAULT_GRPC_MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
 pub const DEFAULT_TEMPORARY_DIR: &str = "temporary";
+pub const DEFAULT_CONFIG_VERSION_RETENTION: usize = 100;
 pub const DEFAULT_LOGER_LEVEL: LevelFilter = LevelFilter::Info;
 pub const GRPC_STATUS_HEADER: &str = "grpc-status";
-pub const GRPC_STATUS_OK: &str = "0";
@@ -1,2 +1,5 @@
+pub mod admin_auth;
 pub mod lets_encrypt;
+pub mod openapi;
 pub mod rest_api;
+pub mod service_api;
@@ -0,0 +1,1225 @@
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use warp::http::{Response, StatusCode};
+use warp::Filter;
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+/**
+*The siblings of the $ref are ignored in the openapi 3.0,so the nullable ref is wrapped by the
+ allOf.
+*/
+fn nullable_ref(name: &str) -> Value {
+    json!({ "allOf": [schema_ref(name)], "nullable": true })
+}
+fn nullable_string() -> Value {
+    json!({ "type": "string", "nullable": true })
+}
+fn array_of(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+fn object_schema(required: Vec<&str>, properties: Value) -> Value {
+    json!({ "type": "object", "required": required, "properties": properties })
+}
+/**
+*The typetag and the serde internally tagged enums write the variant name into the type field.
+*/
+fn tagged_schema(type_name: &str, required: Vec<&str>, properties: Value) -> Value {
+    let mut schema = object_schema(required, properties);
+    schema["required"]
+        .as_array_mut()
+        .unwrap()
+        .insert(0, json!("type"));
+    schema["properties"]["type"] = json!({ "type": "string", "enum": [type_name] });
+    schema
+}
+fn one_of(names: Vec<&str>) -> Value {
+    json!({ "oneOf": names.into_iter().map(schema_ref).collect::<Vec<Value>>() })
+}
+fn base_response(response_object: Value) -> Value {
+    object_schema(
+        vec!["response_code", "response_object"],
+        json!({
+            "response_code": { "type": "integer", "format": "int32" },
+            "response_object": response_object
+        }),
+    )
+}
+fn json_content(schema: Value) -> Value {
+    json!({ "application/json": { "schema": schema } })
+}
+fn ok_response(description: &str, response_object: Value) -> Value {
+    json!({ "description": description, "content": json_content(base_response(response_object)) })
+}
+/**
+*The mutation returns after the change has been applied,with the generation of the change.
+*/
+fn changed_response(description: &str, response_object: Value) -> Value {
+    let mut response = ok_response(description, response_object);
+    response["headers"] = json!({
+        "x-config-generation": {
+            "description": "The config generation of the change.",
+            "schema": { "type": "integer", "format": "int64" }
+        }
+    });
+    response
+}
+fn error_response(description: &str) -> Value {
+    json!({ "description": description, "content": json_content(schema_ref("ErrorResponse")) })
+}
+fn text_response(description: &str) -> Value {
+    json!({ "description": description, "content": { "text/plain": { "schema": { "type": "string" } } } })
+}
+fn path_parameter(name: &str, schema: Value) -> Value {
+    json!({ "name": name, "in": "path", "required": true, "schema": schema })
+}
+fn query_parameter(name: &str, required: bool, schema: Value) -> Value {
+    json!({ "name": name, "in": "query", "required": required, "schema": schema })
+}
+fn change_message_parameter() -> Value {
+    json!({
+        "name": "x-change-message",
+        "in": "header",
+        "required": false,
+        "description": "The message saved with the new config version.",
+        "schema": { "type": "string" }
+    })
+}
+fn port_parameter() -> Value {
+    path_parameter("port", json!({ "type": "integer", "format": "int32" }))
+}
+/**
+*Every operation could be rejected by the admin auth,so the 401 and the 403 are added here.
+*/
+fn operation(
+    operation_id: &str,
+    tag: &str,
+    summary: &str,
+    parameters: Vec<Value>,
+    request_body: Option<Value>,
+    mut responses: Value,
+) -> Value {
+    responses["401"] = json!({ "$ref": "#/components/responses/Unauthorized" });
+    responses["403"] = json!({ "$ref": "#/components/responses/Forbidden" });
+    let mut operation = json!({
+        "operationId": operation_id,
+        "tags": [tag],
+        "summary": summary,
+        "parameters": parameters,
+        "responses": responses
+    });
+    if let Some(schema) = request_body {
+        operation["requestBody"] = json!({ "required": true, "content": json_content(schema) });
+    }
+    operation
+}
+fn config_paths() -> Value {
+    json!({
+        "/appConfig": {
+            "get": operation(
+                "getAppConfig",
+                "config",
+                "Get the static config and all the api services.",
+                vec![],
+                None,
+                json!({
+                    "200": ok_response("The current config.", schema_ref("AppConfig")),
+                    "500": text_response("The config could not be read.")
+                }),
+            ),
+            "post": operation(
+                "postAppConfig",
+                "config",
+                "Add an api service,or replace the api service on the same port.",
+                vec![change_message_parameter()],
+                Some(schema_ref("ApiService")),
+                json!({
+                    "200": changed_response("The config has been applied.", json!({ "type": "integer" })),
+                    "500": text_response("The config is invalid or could not be saved.")
+                }),
+            )
+        },
+        "/route": {
+            "put": operation(
+                "putRoute",
+                "config",
+                "Update the route with the same route id.",
+                vec![change_message_parameter()],
+                Some(schema_ref("Route")),
+                json!({
+                    "200": changed_response("The route has been updated.", json!({ "type": "integer" })),
+                    "500": text_response("The route is invalid or could not be saved.")
+                }),
+            )
+        },
+        "/route/{route_id}": {
+            "delete": operation(
+                "deleteRoute",
+                "config",
+                "Delete the route by the route id.",
+                vec![
+                    path_parameter("route_id", json!({ "type": "string" })),
+                    change_message_parameter()
+                ],
+                None,
+                json!({
+                    "200": changed_response("The route has been deleted.", json!({ "type": "integer" })),
+                    "500": text_response("The route could not be deleted.")
+                }),
+            )
+        },
+        "/configVersion": {
+            "get": operation(
+                "listConfigVersions",
+                "configVersion",
+                "List the retained config versions from the newest to the oldest.",
+                vec![],
+                None,
+                json!({
+                    "200": ok_response("The config versions.", array_of(schema_ref("ConfigVersion"))),
+                    "500": text_response("The versions could not be loaded.")
+                }),
+            )
+        },
+        "/configVersion/diff": {
+            "get": operation(
+                "diffConfigVersions",
+                "configVersion",
+                "Diff two config versions in the yaml format.",
+                vec![
+                    query_parameter("from_version", true, json!({ "type": "integer", "format": "int64" })),
+                    query_parameter("to_version", true, json!({ "type": "integer", "format": "int64" }))
+                ],
+                None,
+                json!({
+                    "200": ok_response("The unified diff.", schema_ref("ConfigVersionDiff")),
+                    "404": text_response("The version could not be found.")
+                }),
+            )
+        },
+        "/configVersion/{version}/rollback": {
+            "post": operation(
+                "rollbackConfigVersion",
+                "configVersion",
+                "Apply the config of the version as a new version.",
+                vec![path_parameter("version", json!({ "type": "integer", "format": "int64" }))],
+                None,
+                json!({
+                    "200": changed_response("The new version.", json!({ "type": "integer", "format": "int64" })),
+                    "500": text_response("The version could not be rolled back.")
+                }),
+            )
+        },
+        "/configGeneration": {
+            "get": operation(
+                "getConfigGeneration",
+                "configGeneration",
+                "Get the latest config generation and the applied one.",
+                vec![],
+                None,
+                json!({
+                    "200": ok_response("The config generation.", schema_ref("ConfigGeneration"))
+                }),
+            )
+        },
+        "/configGeneration/wait": {
+            "get": operation(
+                "waitConfigGeneration",
+                "configGeneration",
+                "Wait until the config generation has been applied or the timeout.",
+                vec![
+                    query_parameter("generation", true, json!({ "type": "integer", "format": "int64" })),
+                    query_parameter("timeout", false, json!({ "type": "integer", "format": "int64", "default": 30 }))
+                ],
+                None,
+                json!({
+                    "200": ok_response("The config generation.", schema_ref("ConfigGeneration"))
+                }),
+            )
+        }
+    })
+}
+fn maintenance_paths() -> Value {
+    json!({
+        "/endpointStatus": {
+            "get": operation(
+                "listEndpointStatus",
+                "endpoint",
+                "List the endpoints which are draining or disabled.",
+                vec![],
+                None,
+                json!({
+                    "200": ok_response("The endpoint status.", array_of(schema_ref("EndpointStatusResponse")))
+                }),
+            ),
+            "put": operation(
+                "putEndpointStatus",
+                "endpoint",
+                "Drain,disable or activate an endpoint.",
+                vec![],
+                Some(schema_ref("EndpointStatus")),
+                json!({
+                    "200": ok_response("The endpoint status.", schema_ref("EndpointStatusResponse")),
+                    "404": text_response("The endpoint could not be found.")
+                }),
+            )
+        },
+        "/endpointStatus/drained": {
+            "get": operation(
+                "waitEndpointDrained",
+                "endpoint",
+                "Wait until the in-flight requests of the endpoint are finished or the timeout.",
+                vec![
+                    query_parameter("endpoint", true, json!({ "type": "string" })),
+                    query_parameter("timeout", false, json!({ "type": "integer", "format": "int64", "default": 30 }))
+                ],
+                None,
+                json!({
+                    "200": ok_response("The endpoint has been drained.", schema_ref("EndpointStatusResponse")),
+                    "408": ok_response("The in-flight requests are not finished before the timeout.", schema_ref("EndpointStatusResponse"))
+                }),
+            )
+        },
+        "/registry/{cluster_name}": {
+            "get": operation(
+                "listRegistryInstances",
+                "registry",
+                "List the instances of the cluster.",
+                vec![path_parameter("cluster_name", json!({ "type": "string" }))],
+                None,
+                json!({
+                    "200": ok_response("The instances.", array_of(schema_ref("RegistryInstance")))
+                }),
+            ),
+            "post": operation(
+                "registerInstance",
+                "registry",
+                "Register an instance into the cluster.",
+                vec![path_parameter("cluster_name", json!({ "type": "string" }))],
+                Some(schema_ref("RegistryInstance")),
+                json!({
+                    "200": ok_response("The registered instance.", schema_ref("RegistryInstance")),
+                    "400": error_response("The weight or the ttl of the instance is not positive.")
+                }),
+            )
+        },
+        "/registry/{cluster_name}/{instance_id}": {
+            "delete": operation(
+                "deregisterInstance",
+                "registry",
+                "Deregister the instance.",
+                vec![
+                    path_parameter("cluster_name", json!({ "type": "string" })),
+                    path_parameter("instance_id", json!({ "type": "string" }))
+                ],
+                None,
+                json!({
+                    "200": ok_response("The deregistered instance.", schema_ref("RegistryInstance")),
+                    "404": error_response("The instance could not be found.")
+                }),
+            )
+        },
+        "/registry/{cluster_name}/{instance_id}/heartbeat": {
+            "put": operation(
+                "heartbeatInstance",
+                "registry",
+                "Renew the ttl of the instance.",
+                vec![
+                    path_parameter("cluster_name", json!({ "type": "string" })),
+                    path_parameter("instance_id", json!({ "type": "string" }))
+                ],
+                None,
+                json!({
+                    "200": ok_response("The instance.", schema_ref("RegistryInstance")),
+                    "404": error_response("The instance could not be found.")
+                }),
+            )
+        },
+        "/metrics": {
+            "get": operation(
+                "getMetrics",
+                "metrics",
+                "Get the prometheus metrics.",
+                vec![],
+                None,
+                json!({ "200": text_response("The metrics in the prometheus text format.") }),
+            )
+        },
+        "/letsEncryptCertificate": {
+            "post": operation(
+                "requestLetsEncryptCertificate",
+                "letsEncrypt",
+                "Request a certificate from the let's encrypt.",
+                vec![],
+                Some(schema_ref("LetsEncryptRequest")),
+                json!({
+                    "200": ok_response("The certificate.", schema_ref("LetsEncryptResponse")),
+                    "500": text_response("The certificate could not be issued.")
+                }),
+            )
+        },
+        "/openapi.json": {
+            "get": operation(
+                "getOpenapi",
+                "openapi",
+                "Get this document.",
+                vec![],
+                None,
+                json!({
+                    "200": {
+                        "description": "The openapi document.",
+                        "content": { "application/json": { "schema": { "type": "object" } } }
+                    }
+                }),
+            )
+        }
+    })
+}
+fn service_paths() -> Value {
+    let service_errors = |mut responses: Value| {
+        responses["400"] = error_response("The request is invalid.");
+        responses["404"] = error_response("The service or the route could not be found.");
+        responses["409"] = error_response("The port or the route id has been used.");
+        responses["413"] = error_response("The request body is too large.");
+        responses["415"] = error_response("The content type is not application/json.");
+        responses["500"] = error_response("The config could not be saved.");
+        responses
+    };
+    json!({
+        "/services": {
+            "get": operation(
+                "listServices",
+                "service",
+                "List the api services.",
+                vec![],
+                None,
+                service_errors(json!({
+                    "200": ok_response("The api services.", array_of(schema_ref("ApiService")))
+                })),
+            ),
+            "post": operation(
+                "createService",
+                "service",
+                "Create an api service.",
+                vec![change_message_parameter()],
+                Some(schema_ref("ApiService")),
+                service_errors(json!({
+                    "201": changed_response("The created api service.", schema_ref("ApiService"))
+                })),
+            )
+        },
+        "/services/{port}": {
+            "get": operation(
+                "getService",
+                "service",
+                "Get the api service on the port.",
+                vec![port_parameter()],
+                None,
+                service_errors(json!({
+                    "200": ok_response("The api service.", schema_ref("ApiService"))
+                })),
+            ),
+            "put": operation(
+                "replaceService",
+                "service",
+                "Replace the api service on the port.",
+                vec![port_parameter(), change_message_parameter()],
+                Some(schema_ref("ApiService")),
+                service_errors(json!({
+                    "200": changed_response("The replaced api service.", schema_ref("ApiService"))
+                })),
+            ),
+            "delete": operation(
+                "deleteService",
+                "service",
+                "Delete the api service on the port.",
+                vec![port_parameter(), change_message_parameter()],
+                None,
+                service_errors(json!({
+                    "200": changed_response("The deleted api service.", schema_ref("ApiService"))
+                })),
+            )
+        },
+        "/services/{port}/routes": {
+            "get": operation(
+                "listRoutes",
+                "service",
+                "List the routes of the api service.",
+                vec![port_parameter()],
+                None,
+                service_errors(json!({
+                    "200": ok_response("The routes.", array_of(schema_ref("Route")))
+                })),
+            ),
+            "post": operation(
+                "createRoute",
+                "service",
+                "Add a route to the api service.",
+                vec![port_parameter(), change_message_parameter()],
+                Some(schema_ref("Route")),
+                service_errors(json!({
+                    "201": changed_response("The route id.", json!({ "type": "string" }))
+                })),
+            )
+        },
+        "/services/{port}/routes/{route_id}": {
+            "get": operation(
+                "getRoute",
+                "service",
+                "Get the route of the api service.",
+                vec![port_parameter(), path_parameter("route_id", json!({ "type": "string" }))],
+                None,
+                service_errors(json!({
+                    "200": ok_response("The route.", schema_ref("Route"))
+                })),
+            ),
+            "put": operation(
+                "replaceRoute",
+                "service",
+                "Replace the route of the api service.",
+                vec![
+                    port_parameter(),
+                    path_parameter("route_id", json!({ "type": "string" })),
+                    change_message_parameter()
+                ],
+                Some(schema_ref("Route")),
+                service_errors(json!({
+                    "200": changed_response("The route id.", json!({ "type": "string" }))
+                })),
+            ),
+            "delete": operation(
+                "deleteServiceRoute",
+                "service",
+                "Delete the route of the api service.",
+                vec![
+                    port_parameter(),
+                    path_parameter("route_id", json!({ "type": "string" })),
+                    change_message_parameter()
+                ],
+                None,
+                service_errors(json!({
+                    "200": changed_response("The route id.", json!({ "type": "string" }))
+                })),
+            )
+        }
+    })
+}
+fn route_schemas() -> Value {
+    json!({
+        "ApiService": object_schema(vec!["listen_port", "service_config"], json!({
+            "listen_port": { "type": "integer", "format": "int32" },
+            "api_service_id": { "type": "string", "description": "A uuid is generated if it is missing." },
+            "service_config": schema_ref("ServiceConfig")
+        })),
+        "ServiceConfig": object_schema(vec!["server_type", "routes"], json!({
+            "server_type": { "type": "string", "enum": ["Http", "Https", "Tcp", "Http2", "Http2Tls"] },
+            "cert_str": nullable_string(),
+            "key_str": nullable_string(),
+            "key_passphrase": {
+                "type": "string",
+                "nullable": true,
+                "description": "The passphrase of the encrypted private key."
+            },
+            "certificates": array_of(schema_ref("Certificate")),
+            "client_auth": nullable_ref("ClientAuth"),
+            "tls_policy": nullable_ref("TlsPolicy"),
+            "acme": nullable_ref("Acme"),
+            "ocsp_stapling": nullable_ref("OcspStapling"),
+            "routes": array_of(schema_ref("Route"))
+        })),
+        "ClientAuth": object_schema(vec!["ca_str"], json!({
+            "ca_str": { "type": "string", "description": "The ca bundle which verifies the client certificates." },
+            "mode": { "type": "string", "enum": ["Required", "Optional"] },
+            "identity_header": {
+                "type": "string",
+                "nullable": true,
+                "description": "The header which forwards the verified client identity to the upstreams."
+            }
+        })),
+        "TlsPolicy": object_schema(vec![], json!({
+            "min_version": { "type": "string", "enum": ["TLSv1.2", "TLSv1.3"], "nullable": true },
+            "max_version": { "type": "string", "enum": ["TLSv1.2", "TLSv1.3"], "nullable": true },
+            "cipher_suites": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "The allowed cipher suites like TLS13_AES_256_GCM_SHA384."
+            },
+            "alpn_protocols": { "type": "array", "items": { "type": "string" } },
+            "session_tickets": { "type": "boolean" },
+            "session_cache_size": {
+                "type": "integer",
+                "nullable": true,
+                "description": "The size of the session cache,0 disables the session resumption by id."
+            }
+        })),
+        "Acme": object_schema(vec!["domains", "contact_email"], json!({
+            "domains": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "The domains of the managed certificate,which are validated by the http-01 challenges."
+            },
+            "contact_email": { "type": "string" },
+            "directory_url": {
+                "type": "string",
+                "nullable": true,
+                "description": "The acme directory,which defaults to the lets encrypt one."
+            },
+            "directory_tls": nullable_ref("UpstreamTls"),
+            "renew_before_days": {
+                "type": "integer",
+                "nullable": true,
+                "description": "Renew the certificate the days before it expires,which defaults to 30."
+            }
+        })),
+        "OcspStapling": object_schema(vec![], json!({
+            "responder_url": {
+                "type": "string",
+                "nullable": true,
+                "description": "The ocsp responder which overrides the one in the certificate."
+            }
+        })),
+        "Certificate": object_schema(vec!["server_names", "cert_str", "key_str"], json!({
+            "server_names": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "The server names like example.com or *.example.com."
+            },
+            "cert_str": { "type": "string" },
+            "key_str": { "type": "string" },
+            "key_passphrase": nullable_string()
+        })),
+        "UpstreamTls": object_schema(vec![], json!({
+            "ca_str": {
+                "type": "string",
+                "nullable": true,
+                "description": "The ca bundle which verifies the upstream certificates instead of the webpki roots."
+            },
+            "cert_str": { "type": "string", "nullable": true, "description": "The client certificate for the upstream mtls." },
+            "key_str": { "type": "string", "nullable": true },
+            "server_name": {
+                "type": "string",
+                "nullable": true,
+                "description": "The sni and the name verified in the upstream certificate."
+            },
+            "insecure_skip_verify": { "type": "boolean", "description": "Skip the verification of the upstream certificate,only for development." },
+            "alpn_protocols": { "type": "array", "items": { "type": "string", "enum": ["h2", "http/1.1"] } }
+        })),
+        "Route": object_schema(vec!["route_cluster"], json!({
+            "route_id": { "type": "string", "description": "A uuid is generated if it is missing." },
+            "host_name": nullable_string(),
+            "matcher": nullable_ref("Matcher"),
+            "allow_deny_list": { "type": "array", "items": schema_ref("AllowDenyObject"), "nullable": true },
+            "authentication": nullable_ref("Authentication"),
+            "anomaly_detection": nullable_ref("AnomalyDetection"),
+            "rewrite_headers": {
+                "type": "object",
+                "additionalProperties": { "type": "string" },
+                "nullable": true
+            },
+            "liveness_config": nullable_ref("LivenessConfig"),
+            "health_check": nullable_ref("HealthCheck"),
+            "server_names": {
+                "type": "array",
+                "items": { "type": "string" },
+                "nullable": true,
+                "description": "The route only matches the tls requests with one of the server names."
+            },
+            "client_identities": {
+                "type": "array",
+                "items": { "type": "string" },
+                "nullable": true,
+                "description": "The subject,uri or dns name of the client certificate which is allowed by the route."
+            },
+            "upstream_tls": nullable_ref("UpstreamTls"),
+            "grpc_web": nullable_ref("GrpcWeb"),
+            "grpc_json_transcoder": nullable_ref("GrpcJsonTranscoder"),
+            "grpc_matcher": nullable_ref("GrpcMatcher"),
+            "ratelimit": nullable_ref("Ratelimit"),
+            "route_cluster": schema_ref("RouteCluster")
+        })),
+        "GrpcWeb": object_schema(vec![], json!({
+            "allowed_origins": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "The origins allowed in the cors requests,and all origins are allowed if it is empty."
+            },
+            "max_age_seconds": {
+                "type": "integer",
+                "minimum": 0,
+                "nullable": true,
+                "description": "The lifetime of the preflight result,which defaults to 86400."
+            },
+            "max_body_size": {
+                "type": "integer",
+                "minimum": 0,
+                "nullable": true,
+                "description": "The max size of the request body in bytes,which defaults to 4194304,and the larger body is answered with the grpc status 8."
+            },
+            "timeout_seconds": {
+                "type": "integer",
+                "minimum": 0,
+                "nullable": true,
+                "description": "The timeout of the upstream response,which defaults to 10,and it is answered with the grpc status 4."
+            }
+        })),
+        "GrpcMatcher": object_schema(vec!["service"], json!({
+            "service": {
+                "type": "string",
+                "description": "The full name of the grpc service like helloworld.Greeter."
+            },
+            "method": {
+                "type": "string",
+                "nullable": true,
+                "description": "The grpc method,and all methods of the service are matched if it is missing."
+            }
+        })),
+        "GrpcJsonTranscoder": object_schema(vec![], json!({
+            "proto_descriptor": {
+                "type": "string",
+                "nullable": true,
+                "description": "The path of the compiled descriptor set."
+            },
+            "proto_descriptor_bin": {
+                "type": "string",
+                "nullable": true,
+                "description": "The base64 of the compiled descriptor set,which is used when the proto_descriptor is missing."
+            },
+            "services": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "The full names of the transcoded services,and all services are transcoded if it is empty."
+            },
+            "max_message_size": {
+                "type": "integer",
+                "minimum": 0,
+                "nullable": true,
+                "description": "The max size of the request body and the upstream response in bytes,which defaults to 4194304,and the larger request is answered with 413."
+            },
+            "timeout_seconds": {
+                "type": "integer",
+                "minimum": 0,
+                "nullable": true,
+                "description": "The timeout of the upstream call,which defaults to 10,and it is answered with 504."
+            }
+        })),
+        "Matcher": object_schema(vec!["prefix", "prefix_rewrite"], json!({
+            "prefix": { "type": "string" },
+            "prefix_rewrite": { "type": "string" }
+        })),
+        "AllowDenyObject": object_schema(vec!["limit_type"], json!({
+            "limit_type": { "type": "string", "enum": ["AllowAll", "DenyAll", "Allow", "Deny"] },
+            "value": nullable_string()
+        })),
+        "LivenessConfig": object_schema(vec!["min_liveness_count"], json!({
+            "min_liveness_count": { "type": "integer", "format": "int32" }
+        })),
+        "Authentication": one_of(vec!["BasicAuth", "ApiKeyAuth"]),
+        "BasicAuth": tagged_schema("BasicAuth", vec!["credentials"], json!({
+            "credentials": { "type": "string", "description": "The base64 of the user:password." }
+        })),
+        "ApiKeyAuth": tagged_schema("ApiKeyAuth", vec!["key", "value"], json!({
+            "key": { "type": "string" },
+            "value": { "type": "string" }
+        })),
+        "Ratelimit": one_of(vec!["TokenBucketRateLimit", "FixedWindowRateLimit"]),
+        "TokenBucketRateLimit": tagged_schema(
+            "TokenBucketRateLimit",
+            vec!["rate_per_unit", "unit", "capacity", "limit_location"],
+            json!({
+                "rate_per_unit": { "type": "integer", "minimum": 0 },
+                "unit": schema_ref("TimeUnit"),
+                "capacity": { "type": "integer", "format": "int32" },
+                "limit_location": schema_ref("LimitLocation")
+            }),
+        ),
+        "FixedWindowRateLimit": tagged_schema(
+            "FixedWindowRateLimit",
+            vec!["rate_per_unit", "unit", "limit_location"],
+            json!({
+                "rate_per_unit": { "type": "integer", "minimum": 0 },
+                "unit": schema_ref("TimeUnit"),
+                "limit_location": schema_ref("LimitLocation")
+            }),
+        ),
+        "TimeUnit": object_schema(vec!["type"], json!({
+            "type": { "type": "string", "enum": ["MillionSecond", "Second", "Minute", "Hour", "Day"] }
+        })),
+        "LimitLocation": one_of(vec!["IpLimitLocation", "HeaderLimitLocation", "IprangeLimitLocation"]),
+        "IpLimitLocation": tagged_schema("IP", vec!["value"], json!({
+            "value": { "type": "string" }
+        })),
+        "HeaderLimitLocation": tagged_schema("Header", vec!["key", "value"], json!({
+            "key": { "type": "string" },
+            "value": { "type": "string" }
+        })),
+        "IprangeLimitLocation": tagged_schema("Iprange", vec!["value"], json!({
+            "value": { "type": "string", "description": "The ip range like 192.168.0.0/24." }
+        })),
+        "AnomalyDetection": one_of(vec!["HttpAnomalyDetection"]),
+        "HttpAnomalyDetection": tagged_schema(
+            "Http",
+            vec!["consecutive_5xx", "base_anomaly_detection_param"],
+            json!({
+                "consecutive_5xx": { "type": "integer", "format": "int32" },
+                "base_anomaly_detection_param": object_schema(vec!["ejection_second"], json!({
+                    "ejection_second": { "type": "integer", "format": "int64", "minimum": 0 }
+                }))
+            }),
+        ),
+        "HealthCheck": one_of(vec!["HttpGetHealthCheck", "RedisHealthCheck", "MysqlHealthCheck"]),
+        "BaseHealthCheckParam": object_schema(vec!["timeout", "interval"], json!({
+            "timeout": { "type": "integer", "format": "int32" },
+            "interval": { "type": "integer", "format": "int32" }
+        })),
+        "HttpGetHealthCheck": tagged_schema(
+            "HttpGet",
+            vec!["base_health_check_param", "path"],
+            json!({
+                "base_health_check_param": schema_ref("BaseHealthCheckParam"),
+                "path": { "type": "string" }
+            }),
+        ),
+        "RedisHealthCheck": tagged_schema("Redis", vec!["timeout", "interval"], json!({
+            "timeout": { "type": "integer", "format": "int32" },
+            "interval": { "type": "integer", "format": "int32" }
+        })),
+        "MysqlHealthCheck": tagged_schema("Mysql", vec!["timeout", "interval"], json!({
+            "timeout": { "type": "integer", "format": "int32" },
+            "interval": { "type": "integer", "format": "int32" }
+        }))
+    })
+}
+fn route_cluster_schemas() -> Value {
+    let weight = json!({ "type": "integer", "format": "int32", "default": 100 });
+    json!({
+        "RouteCluster": one_of(vec![
+            "PollRoute",
+            "HeaderBasedRoute",
+            "RandomRoute",
+            "WeightBasedRoute",
+            "DnsRoute",
+            "RegistryRoute"
+        ]),
+        "BaseRoute": object_schema(vec!["endpoint"], json!({
+            "endpoint": { "type": "string" },
+            "try_file": nullable_string(),
+            "is_alive": { "type": "boolean", "nullable": true }
+        })),
+        "BaseRouteItem": object_schema(vec!["base_route"], json!({
+            "base_route": schema_ref("BaseRoute")
+        })),
+        "WeightRouteItem": object_schema(vec!["base_route"], json!({
+            "base_route": schema_ref("BaseRoute"),
+            "weight": weight
+        })),
+        "HeaderRouteItem": object_schema(
+            vec!["base_route", "header_key", "header_value_mapping_type"],
+            json!({
+                "base_route": schema_ref("BaseRoute"),
+                "header_key": { "type": "string" },
+                "header_value_mapping_type": one_of(vec!["RegexMatch", "TextMatch", "SplitMatch"])
+            }),
+        ),
+        "RegexMatch": tagged_schema("Regex", vec!["value"], json!({
+            "value": { "type": "string" }
+        })),
+        "TextMatch": tagged_schema("Text", vec!["value"], json!({
+            "value": { "type": "string" }
+        })),
+        "SplitMatch": tagged_schema("Split", vec!["split_by", "split_list"], json!({
+            "split_by": { "type": "string" },
+            "split_list": array_of(json!({ "type": "string" }))
+        })),
+        "PollRoute": tagged_schema("PollRoute", vec!["routes"], json!({
+            "routes": array_of(schema_ref("BaseRouteItem"))
+        })),
+        "RandomRoute": tagged_schema("RandomRoute", vec!["routes"], json!({
+            "routes": array_of(schema_ref("BaseRouteItem"))
+        })),
+        "HeaderBasedRoute": tagged_schema("HeaderBasedRoute", vec!["routes"], json!({
+            "routes": array_of(schema_ref("HeaderRouteItem"))
+        })),
+        "WeightBasedRoute": tagged_schema("WeightBasedRoute", vec!["routes"], json!({
+            "routes": array_of(schema_ref("WeightRouteItem"))
+        })),
+        "DnsRoute": tagged_schema("DnsRoute", vec!["endpoint"], json!({
+            "endpoint": { "type": "string", "description": "The domain and the port like example.com:8080." },
+            "record_type": { "type": "string", "enum": ["A", "Srv"], "default": "A" },
+            "weight": weight,
+            "refresh_interval": { "type": "integer", "format": "int64", "default": 30 },
+            "routes": { "type": "array", "items": schema_ref("WeightRouteItem"), "readOnly": true }
+        })),
+        "RegistryRoute": tagged_schema("RegistryRoute", vec!["cluster_name"], json!({
+            "cluster_name": { "type": "string" },
+            "routes": { "type": "array", "items": schema_ref("WeightRouteItem"), "readOnly": true }
+        }))
+    })
+}
+fn admin_schemas() -> Value {
+    json!({
+        "ErrorResponse": base_response(json!({ "type": "string" })),
+        "StaticConfig": object_schema(vec!["admin_port"], json!({
+            "access_log": nullable_string(),
+            "database_url": nullable_string(),
+            "admin_port": { "type": "string" },
+            "admin_bind_address": nullable_string(),
+            "admin_cors_origins": { "type": "array", "items": { "type": "string" }, "nullable": true },
+            "config_file_path": nullable_string(),
+            "config_version_retention": { "type": "integer", "nullable": true }
+        })),
+        "AppConfig": object_schema(vec!["static_config", "api_service_config"], json!({
+            "static_config": schema_ref("StaticConfig"),
+            "api_service_config": array_of(schema_ref("ApiService"))
+        })),
+        "ConfigVersion": object_schema(vec!["version", "create_time"], json!({
+            "version": { "type": "integer", "format": "int64" },
+            "create_time": { "type": "integer", "format": "int64", "description": "The unix timestamp in seconds." },
+            "message": nullable_string()
+        })),
+        "ConfigGeneration": object_schema(vec!["generation", "applied_generation"], json!({
+            "generation": { "type": "integer", "format": "int64" },
+            "applied_generation": { "type": "integer", "format": "int64" }
+        })),
+        "ConfigVersionDiff": object_schema(vec!["from_version", "to_version", "diff"], json!({
+            "from_version": { "type": "integer", "format": "int64" },
+            "to_version": { "type": "integer", "format": "int64" },
+            "diff": { "type": "string" }
+        })),
+        "EndpointStatus": object_schema(vec!["endpoint", "status"], json!({
+            "endpoint": { "type": "string" },
+            "status": { "type": "string", "enum": ["Active", "Draining", "Disabled"] }
+        })),
+        "EndpointStatusResponse": object_schema(
+            vec!["endpoint", "status", "in_flight", "drained"],
+            json!({
+                "endpoint": { "type": "string" },
+                "status": { "type": "string", "enum": ["Active", "Draining", "Disabled"] },
+                "in_flight": { "type": "integer", "format": "int64" },
+                "drained": { "type": "boolean" }
+            }),
+        ),
+        "RegistryInstance": object_schema(vec!["endpoint"], json!({
+            "instance_id": { "type": "string", "description": "A uuid is generated if it is missing." },
+            "endpoint": { "type": "string" },
+            "weight": { "type": "integer", "format": "int32", "default": 100, "minimum": 1 },
+            "ttl": { "type": "integer", "format": "int64", "default": 30, "minimum": 1 }
+        })),
+        "LetsEncryptRequest": object_schema(vec!["mail_name", "domain_name"], json!({
+            "mail_name": { "type": "string" },
+            "domain_name": { "type": "string" }
+        })),
+        "LetsEncryptResponse": object_schema(vec!["key_perm", "certificate_perm"], json!({
+            "key_perm": { "type": "string" },
+            "certificate_perm": { "type": "string" }
+        }))
+    })
+}
+fn merge_objects(values: Vec<Value>) -> Value {
+    let mut result = serde_json::Map::new();
+    for value in values {
+        if let Value::Object(map) = value {
+            result.extend(map);
+        }
+    }
+    Value::Object(result)
+}
+pub fn openapi_value() -> Value {
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Silverwind admin api",
+            "description": "The bearer token is required when the ADMIN_TOKENS is set.",
+            "version": env!("CARGO_PKG_VERSION")
+        },
+        "paths": merge_objects(vec![config_paths(), maintenance_paths(), service_paths()]),
+        "components": {
+            "schemas": merge_objects(vec![route_schemas(), route_cluster_schemas(), admin_schemas()]),
+            "responses": {
+                "Unauthorized": error_response("The request is not authenticated."),
+                "Forbidden": error_response("The role is not allowed to do the request.")
+            },
+            "securitySchemes": {
+                "bearerAuth": { "type": "http", "scheme": "bearer", "bearerFormat": "token" }
+            }
+        }
+    })
+}
+/**
+*The document is checked by the oas3 model,so the fields out of the openapi are never served.
+*/
+pub fn openapi_spec() -> Result<oas3::Spec, anyhow::Error> {
+    let spec: oas3::Spec = serde_json::from_value(openapi_value())?;
+    spec.validate_version()
+        .map_err(|err| anyhow!(err.to_string()))?;
+    Ok(spec)
+}
+async fn get_openapi_json() -> Result<impl warp::Reply, Infallible> {
+    let json_result = openapi_spec()
+        .and_then(|spec| oas3::to_json(&spec).map_err(|err| anyhow!(err.to_string())));
+    let res = match json_result {
+        Ok(json_str) => Response::builder()
+            .header("content-type", "application/json")
+            .body(json_str)
+            .unwrap(),
+        Err(err) => Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(err.to_string())
+            .unwrap(),
+    };
+    Ok(res)
+}
+pub fn path() -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::get()
+        .and(warp::path("openapi.json"))
+        .and(warp::path::end())
+        .and_then(get_openapi_json)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::command::DEFAULT_CONFIG;
+    use crate::vojo::app_config_vistor::ApiServiceVistor;
+    use crate::vojo::config_generation::ConfigGeneration;
+    fn resolve<'a>(document: &'a Value, schema: &'a Value) -> &'a Value {
+        match schema["$ref"].as_str() {
+            Some(reference) => {
+                let name = reference.trim_start_matches("#/components/schemas/");
+                &document["components"]["schemas"][name]
+            }
+            None => schema,
+        }
+    }
+    /**
+    *Check the serialized value against the schema,so a field added to the vistors but missing
+     in the document fails the test.
+    */
+    fn is_match(document: &Value, schema: &Value, value: &Value) -> bool {
+        let schema = resolve(document, schema);
+        if value.is_null() {
+            return schema["nullable"].as_bool().unwrap_or(false);
+        }
+        if let Some(all_of) = schema["allOf"].as_array() {
+            return all_of.iter().all(|item| is_match(document, item, value));
+        }
+        if let Some(one_of) = schema["oneOf"].as_array() {
+            return one_of
+                .iter()
+                .filter(|item| is_match(document, item, value))
+                .count()
+                == 1;
+        }
+        if let Some(enum_values) = schema["enum"].as_array() {
+            if !enum_values.contains(value) {
+                return false;
+            }
+        }
+        match schema["type"].as_str() {
+            Some("object") => {
+                let object = match value.as_object() {
+                    Some(object) => object,
+                    None => return false,
+                };
+                let required_ok = schema["required"]
+                    .as_array()
+                    .map(|required| {
+                        required
+                            .iter()
+                            .all(|key| object.contains_key(key.as_str().unwrap()))
+                    })
+                    .unwrap_or(true);
+                required_ok
+                    && object
+                        .iter()
+                        .all(|(key, item)| match schema["properties"].get(key) {
+                            Some(property) => is_match(document, property, item),
+                            None => match schema.get("additionalProperties") {
+                                Some(additional) => is_match(document, additional, item),
+                                None => schema.get("properties").is_none(),
+                            },
+                        })
+            }
+            Some("array") => value
+                .as_array()
+                .map(|items| {
+                    items
+                        .iter()
+                        .all(|item| is_match(document, &schema["items"], item))
+                })
+                .unwrap_or(false),
+            Some("string") => value.is_string(),
+            Some("integer") => value.is_i64() || value.is_u64(),
+            Some("boolean") => value.is_boolean(),
+            _ => true,
+        }
+    }
+    #[test]
+    fn test_openapi_spec_ok() {
+        let spec = openapi_spec().unwrap();
+        assert_eq!(spec.operations().count(), 29);
+        assert!(spec
+            .paths
+            .contains_key("/services/{port}/routes/{route_id}"));
+        assert!(spec.paths.contains_key("/letsEncryptCertificate"));
+        let document = serde_json::to_value(&spec).unwrap();
+        let mut references = vec![];
+        let document_str = document.to_string();
+        for item in document_str.split("\"$ref\":\"").skip(1) {
+            references.push(item.split('"').next().unwrap().to_string());
+        }
+        for reference in references {
+            let path = reference.trim_start_matches("#/").split('/');
+            let mut current = &document;
+            for segment in path {
+                current = &current[segment];
+            }
+            assert!(!current.is_null(), "{} could not be resolved", reference);
+        }
+    }
+    #[test]
+    fn test_openapi_schema_match_the_vistors() {
+        let document = serde_json::to_value(openapi_spec().unwrap()).unwrap();
+        let config_str = r#"
+- listen_port: 4486
+  service_config:
+    server_type: Http
+    certificates:
+      - server_names: [a.example.com]
+        cert_str: cert
+        key_str: key
+        key_passphrase: secret
+    client_auth:
+      ca_str: ca
+      mode: Optional
+      identity_header: x-forwarded-client-cert
+    tls_policy:
+      min_version: TLSv1.3
+      cipher_suites: [TLS13_AES_256_GCM_SHA384]
+      alpn_protocols: [http/1.1]
+      session_tickets: true
+      session_cache_size: 1024
+    acme:
+      domains: [a.example.com]
+      contact_email: admin@example.com
+      directory_url: https://localhost:14000/dir
+      directory_tls:
+        ca_str: ca
+      renew_before_days: 30
+    ocsp_stapling:
+      responder_url: http://127.0.0.1:8888/ocsp
+    routes:
+      - route_id: route-1
+        matcher:
+          prefix: /
+          prefix_rewrite: /
+        allow_deny_list:
+          - limit_type: Allow
+            value: 192.168.0.1
+        authentication:
+          type: BasicAuth
+          credentials: YWRtaW46YWRtaW4=
+        anomaly_detection:
+          type: Http
+          consecutive_5xx: 3
+          base_anomaly_detection_param:
+            ejection_second: 10
+        rewrite_headers:
+          x-client: silverwind
+        liveness_config:
+          min_liveness_count: 1
+        health_check:
+          type: HttpGet
+          path: /health
+          base_health_check_param:
+            timeout: 3
+            interval: 5
+        ratelimit:
+          type: TokenBucketRateLimit
+          rate_per_unit: 3
+          unit:
+            type: Second
+          capacity: 10
+          limit_location:
+            type: Header
+            key: x-user
+            value: admin
+        route_cluster:
+          type: HeaderBasedRoute
+          routes:
+            - base_route:
+                endpoint: http://127.0.0.1:8080
+              header_key: x-version
+              header_value_mapping_type:
+                type: Split
+                split_by: ","
+                split_list: [a, b]
+      - route_id: route-2
+        authentication:
+          type: ApiKeyAuth
+          key: x-api-key
+          value: secret
+        health_check:
+          type: Redis
+          timeout: 3
+          interval: 5
+        ratelimit:
+          type: FixedWindowRateLimit
+          rate_per_unit: 3
+          unit:
+            type: Minute
+          limit_location:
+            type: Iprange
+            value: 192.168.0.0/24
+        route_cluster:
+          type: WeightBasedRoute
+          routes:
+            - base_route:
+                endpoint: http://127.0.0.1:8081
+              weight: 50
+      - route_id: route-3
+        server_names: [a.example.com, "*.b.example.com"]
+        client_identities: [spiffe://example.org/ns/default/*]
+        upstream_tls:
+          server_name: backend.internal
+          insecure_skip_verify: true
+          alpn_protocols: [h2, http/1.1]
+        grpc_web:
+          allowed_origins: [https://app.example.com]
+          max_age_seconds: 600
+        grpc_json_transcoder:
+          proto_descriptor: config/test_transcoding_descriptor.pb
+          services: [bookstore.Bookstore]
+        grpc_matcher:
+          service: helloworld.Greeter
+          method: SayHello
+        route_cluster:
+          type: DnsRoute
+          endpoint: example.com:80
+      - route_id: route-4
+        route_cluster:
+          type: RegistryRoute
+          cluster_name: backend
+"#;
+        for config in [config_str, DEFAULT_CONFIG] {
+            let api_services: Vec<ApiServiceVistor> = serde_yaml::from_str(config).unwrap();
+            let value = serde_json::to_value(api_services).unwrap();
+            let schema = json!({ "type": "array", "items": schema_ref("ApiService") });
+            assert!(is_match(&document, &schema, &value), "{}", value);
+        }
+        let value = json!({ "listen_port": 4486, "unknown": 1 });
+        assert!(!is_match(&document, &schema_ref("ApiService"), &value));
+        let value = serde_json::to_value(ConfigGeneration::default()).unwrap();
+        assert!(is_match(&document, &schema_ref("ConfigGeneration"), &value));
+    }
+    #[tokio::test]
+    async fn test_get_openapi_json_ok() {
+        let res = warp::test::request()
+            .method("GET")
+            .path("/openapi.json")
+            .reply(&path())
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let spec: oas3::Spec = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(spec.info.version, env!("CARGO_PKG_VERSION"));
+    }
+}
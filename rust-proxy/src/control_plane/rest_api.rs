@@ -1,22 +1,47 @@
-use crate::configuration_service::app_config_service::GLOBAL_APP_CONFIG;
-use crate::constants::common_constants::DEFAULT_TEMPORARY_DIR;
+use crate::configuration_service::app_config_service::{
+    commit_api_services, inherit_route_status, rollback_app_service_config, save_api_services,
+    validate_service_tls_config, GLOBAL_APP_CONFIG,
+};
+use crate::configuration_service::config_propagation::{
+    get_config_generation, wait_for_config_generation,
+};
+use crate::configuration_service::config_store::{diff_config_versions, get_config_store};
+use crate::constants::common_constants::CONFIG_GENERATION_HEADER;
+use crate::constants::common_constants::DEFAULT_ADMIN_BIND_ADDRESS;
+use crate::control_plane::admin_auth::{
+    handle_admin_auth_rejection, to_role_value, with_admin_auth, with_admin_role, AdminAuthConfig,
+    AdminRole,
+};
 use crate::control_plane::lets_encrypt::path;
+use crate::control_plane::openapi;
+use crate::control_plane::service_api;
+use crate::discovery::service_registry::{
+    deregister_instance, heartbeat_instance, list_instances, register_instance,
+    sync_registry_routes,
+};
+use crate::maintenance::endpoint_maintenance::{
+    get_endpoint_status_response, list_endpoint_status, set_endpoint_status, wait_for_drained,
+};
+use crate::proxy::http1::tls_acceptor::TlsAcceptor;
 use crate::vojo::app_config::ApiService;
 use crate::vojo::app_config::Route;
 use crate::vojo::app_config::ServiceType;
-use crate::vojo::app_config_vistor::from_api_service;
 use crate::vojo::app_config_vistor::ApiServiceVistor;
 use crate::vojo::app_config_vistor::AppConfigVistor;
 use crate::vojo::app_config_vistor::RouteVistor;
 use crate::vojo::base_response::BaseResponse;
-use crate::vojo::route::BaseRoute;
+use crate::vojo::config_generation::ConfigGenerationWaitQuery;
+use crate::vojo::config_version::{ConfigVersionDiff, ConfigVersionDiffQuery};
+use crate::vojo::maintenance::{EndpointStatusVistor, EndpointWaitQuery};
+use crate::vojo::service_registry::RegistryInstanceVistor;
+use arc_swap::ArcSwap;
+use hyper::server::conn::AddrIncoming;
+use hyper::service::make_service_fn;
+use hyper::Server;
 use prometheus::{Encoder, TextEncoder};
-use std::collections::HashMap;
 use std::convert::Infallible;
-use std::env;
-use std::net::SocketAddr;
-use std::path::Path;
-use tokio::io::AsyncWriteExt;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
 use warp::http::{Response, StatusCode};
 use warp::Filter;
 use warp::{reject, Rejection, Reply};
@@ -24,10 +49,12 @@ static INTERNAL_SERVER_ERROR: &str = "Internal Server Error";
 #[derive(Debug)]
 struct MethodError;
 impl reject::Reject for MethodError {}
-async fn get_app_config() -> Result<impl warp::Reply, Infallible> {
+async fn get_app_config(admin_role: AdminRole) -> Result<impl warp::Reply, Infallible> {
     let app_config = GLOBAL_APP_CONFIG.read().await;
 
-    let app_config_vistor_result = AppConfigVistor::from(app_config.clone()).await;
+    let app_config_vistor_result = AppConfigVistor::from(app_config.clone())
+        .await
+        .and_then(|app_config_vistor| to_role_value(admin_role, &app_config_vistor));
     if app_config_vistor_result.is_err() {
         return Ok(Response::builder()
             .status(StatusCode::INTERNAL_SERVER_ERROR)
@@ -63,8 +90,9 @@ async fn get_prometheus_metrics() -> Result<impl warp::Reply, Infallible> {
 }
 async fn post_app_config(
     api_services_vistor: ApiServiceVistor,
+    change_message: Option<String>,
 ) -> Result<impl warp::Reply, Infallible> {
-    match post_app_config_with_error(api_services_vistor).await {
+    match post_app_config_with_error(api_services_vistor, change_message).await {
         Ok(r) => Ok(r),
         Err(err) => Ok(Response::builder()
             .status(StatusCode::INTERNAL_SERVER_ERROR)
@@ -74,18 +102,16 @@ async fn post_app_config(
 }
 async fn post_app_config_with_error(
     api_services_vistor: ApiServiceVistor,
+    change_message: Option<String>,
 ) -> Result<Response<String>, anyhow::Error> {
     let current_type = api_services_vistor.service_config.server_type.clone();
     if current_type == ServiceType::Https || current_type == ServiceType::Http2Tls {
-        validate_tls_config(
-            api_services_vistor.service_config.cert_str.clone(),
-            api_services_vistor.service_config.key_str.clone(),
-        )?;
+        validate_service_tls_config(&api_services_vistor.service_config)?;
     }
     let api_service = ApiService::from(api_services_vistor).await?;
-    let mut rw_global_lock = GLOBAL_APP_CONFIG.write().await;
-    match rw_global_lock
-        .api_service_config
+    let rw_global_lock = GLOBAL_APP_CONFIG.write().await;
+    let mut api_services = rw_global_lock.api_service_config.clone();
+    match api_services
         .iter_mut()
         .find(|item| item.listen_port == api_service.listen_port)
     {
@@ -97,26 +123,45 @@ async fn post_app_config_with_error(
                 .ok_or(anyhow!("The route is empty!"))?
                 .clone(),
         ),
-        None => rw_global_lock.api_service_config.push(api_service),
+        None => api_services.push(api_service),
     };
-    tokio::spawn(async {
-        if let Err(err) = save_config_to_file().await {
-            error!("Save file error,the error is {}!", err);
-        }
-    });
+    save_api_services(api_services.clone(), change_message).await?;
+    let generation = commit_api_services(rw_global_lock, api_services).await?;
     let data = BaseResponse {
         response_code: 0,
         response_object: 0,
     };
     let json_str = serde_json::to_string(&data).unwrap();
-    Ok(Response::builder()
+    Ok(config_changed_response(json_str, generation))
+}
+/**
+*The generation of the change is returned in the header,so the client could wait on it.
+*/
+pub fn config_changed_response(json_str: String, generation: u64) -> Response<String> {
+    Response::builder()
         .status(StatusCode::OK)
         .header("content-type", "application/json")
+        .header(CONFIG_GENERATION_HEADER, generation.to_string())
         .body(json_str)
-        .unwrap())
+        .unwrap()
 }
-async fn delete_route(route_id: String) -> Result<impl warp::Reply, Infallible> {
-    let mut rw_global_lock = GLOBAL_APP_CONFIG.write().await;
+async fn delete_route(
+    route_id: String,
+    change_message: Option<String>,
+) -> Result<impl warp::Reply, Infallible> {
+    match delete_route_with_error(route_id, change_message).await {
+        Ok((r, generation)) => Ok(config_changed_response(r, generation)),
+        Err(e) => Ok(Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(e.to_string())
+            .unwrap()),
+    }
+}
+async fn delete_route_with_error(
+    route_id: String,
+    change_message: Option<String>,
+) -> Result<(String, u64), anyhow::Error> {
+    let rw_global_lock = GLOBAL_APP_CONFIG.write().await;
     let mut api_services = vec![];
     for mut api_service in rw_global_lock.clone().api_service_config {
         api_service
@@ -127,32 +172,22 @@ async fn delete_route(route_id: String) -> Result<impl warp::Reply, Infallible>
             api_services.push(api_service);
         }
     }
-    rw_global_lock.api_service_config = api_services;
-    tokio::spawn(async {
-        if let Err(err) = save_config_to_file().await {
-            error!("Save file error,the error is {}!", err);
-        }
-    });
+    save_api_services(api_services.clone(), change_message).await?;
+    let generation = commit_api_services(rw_global_lock, api_services).await?;
 
     let data = BaseResponse {
         response_code: 0,
         response_object: 0,
     };
-    let json_str = serde_json::to_string(&data).unwrap();
-    Ok(Response::builder()
-        .status(StatusCode::OK)
-        .header("content-type", "application/json")
-        .body(json_str)
-        .unwrap())
+    Ok((serde_json::to_string(&data).unwrap(), generation))
 }
 
-async fn put_route(route_vistor: RouteVistor) -> Result<impl warp::Reply, Infallible> {
-    match post_route_with_error(route_vistor).await {
-        Ok(r) => Ok(Response::builder()
-            .status(StatusCode::OK)
-            .header("content-type", "application/json")
-            .body(r)
-            .unwrap()),
+async fn put_route(
+    route_vistor: RouteVistor,
+    change_message: Option<String>,
+) -> Result<impl warp::Reply, Infallible> {
+    match post_route_with_error(route_vistor, change_message).await {
+        Ok((r, generation)) => Ok(config_changed_response(r, generation)),
         Err(e) => Ok(Response::builder()
             .status(StatusCode::INTERNAL_SERVER_ERROR)
             .header("content-type", "application/json")
@@ -160,7 +195,10 @@ async fn put_route(route_vistor: RouteVistor) -> Result<impl warp::Reply, Infall
             .unwrap()),
     }
 }
-async fn post_route_with_error(route_vistor: RouteVistor) -> Result<String, anyhow::Error> {
+async fn post_route_with_error(
+    route_vistor: RouteVistor,
+    change_message: Option<String>,
+) -> Result<(String, u64), anyhow::Error> {
     let mut rw_global_lock = GLOBAL_APP_CONFIG.write().await;
 
     let old_route = rw_global_lock
@@ -170,97 +208,310 @@ async fn post_route_with_error(route_vistor: RouteVistor) -> Result<String, anyh
         .find(|item| item.route_id == route_vistor.route_id)
         .ok_or(anyhow!("Can not find the route by route id!"))?;
 
-    let mut new_route = Route::from(route_vistor.clone()).await?;
-    let mut new_liveness_status = new_route.liveness_status.write().await;
-    *new_liveness_status = old_route.liveness_status.write().await.clone();
-
-    let old_base_clusters = old_route.clone().route_cluster.get_all_route().await?;
-    let hashmap = old_base_clusters
-        .iter()
-        .map(|item| (item.endpoint.clone(), item.clone()))
-        .collect::<HashMap<String, BaseRoute>>();
-    let mut new_routes = new_route.route_cluster.get_all_route().await?;
-    for new_base_route in new_routes.iter_mut() {
-        if hashmap.clone().contains_key(&new_base_route.endpoint) {
-            let old_base_route = hashmap.get(&new_base_route.endpoint).unwrap();
-            let mut alive = new_base_route.is_alive.write().await;
-            *alive = *old_base_route.is_alive.write().await;
-            let mut anomaly_detection_status =
-                new_base_route.anomaly_detection_status.write().await;
-            *anomaly_detection_status = old_base_route
-                .anomaly_detection_status
-                .write()
-                .await
-                .clone();
-        }
-    }
-    for api_service in rw_global_lock.api_service_config.iter_mut() {
+    let new_route = Route::from(route_vistor.clone()).await?;
+    inherit_route_status(&new_route, &old_route).await?;
+    let mut api_services = rw_global_lock.api_service_config.clone();
+    for api_service in api_services.iter_mut() {
         for route in api_service.service_config.routes.iter_mut() {
             if route.route_id == route_vistor.route_id {
                 *route = new_route.clone();
             }
         }
     }
-    tokio::spawn(async {
-        if let Err(err) = save_config_to_file().await {
-            error!("Save file error,the error is {}!", err);
-        }
-    });
+    save_api_services(api_services.clone(), change_message).await?;
+    let generation = commit_api_services(rw_global_lock, api_services).await?;
     let data = BaseResponse {
         response_code: 0,
         response_object: 0,
     };
+    Ok((serde_json::to_string(&data).unwrap(), generation))
+}
+async fn get_config_versions() -> Result<impl warp::Reply, Infallible> {
+    match get_config_store().await.list_versions().await {
+        Ok(config_versions) => {
+            let data = BaseResponse {
+                response_code: 0,
+                response_object: config_versions,
+            };
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&data).unwrap())
+                .unwrap())
+        }
+        Err(e) => Ok(Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(e.to_string())
+            .unwrap()),
+    }
+}
+async fn get_config_version_diff(
+    diff_query: ConfigVersionDiffQuery,
+    admin_role: AdminRole,
+) -> Result<impl warp::Reply, Infallible> {
+    match diff_config_versions(
+        diff_query.from_version,
+        diff_query.to_version,
+        admin_role == AdminRole::ReadOnly,
+    )
+    .await
+    {
+        Ok(diff) => {
+            let data = BaseResponse {
+                response_code: 0,
+                response_object: ConfigVersionDiff {
+                    from_version: diff_query.from_version,
+                    to_version: diff_query.to_version,
+                    diff,
+                },
+            };
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&data).unwrap())
+                .unwrap())
+        }
+        Err(e) => Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .header("content-type", "application/json")
+            .body(e.to_string())
+            .unwrap()),
+    }
+}
+async fn post_config_version_rollback(version: i64) -> Result<impl warp::Reply, Infallible> {
+    match rollback_app_service_config(version).await {
+        Ok((new_version, generation)) => {
+            let data = BaseResponse {
+                response_code: 0,
+                response_object: new_version,
+            };
+            Ok(config_changed_response(
+                serde_json::to_string(&data).unwrap(),
+                generation,
+            ))
+        }
+        Err(e) => Ok(Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(e.to_string())
+            .unwrap()),
+    }
+}
+async fn get_config_generation_status() -> Result<impl warp::Reply, Infallible> {
+    let data = BaseResponse {
+        response_code: 0,
+        response_object: get_config_generation(),
+    };
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", "application/json")
+        .body(serde_json::to_string(&data).unwrap())
+        .unwrap())
+}
+async fn wait_config_generation(
+    config_generation_wait_query: ConfigGenerationWaitQuery,
+) -> Result<impl warp::Reply, Infallible> {
+    let data = BaseResponse {
+        response_code: 0,
+        response_object: wait_for_config_generation(
+            config_generation_wait_query.generation,
+            config_generation_wait_query.timeout,
+        )
+        .await,
+    };
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", "application/json")
+        .body(serde_json::to_string(&data).unwrap())
+        .unwrap())
+}
+async fn put_endpoint_status(
+    endpoint_status_vistor: EndpointStatusVistor,
+) -> Result<impl warp::Reply, Infallible> {
+    match put_endpoint_status_with_error(endpoint_status_vistor).await {
+        Ok(r) => Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header("content-type", "application/json")
+            .body(r)
+            .unwrap()),
+        Err(e) => Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .header("content-type", "application/json")
+            .body(e.to_string())
+            .unwrap()),
+    }
+}
+async fn put_endpoint_status_with_error(
+    endpoint_status_vistor: EndpointStatusVistor,
+) -> Result<String, anyhow::Error> {
+    let app_config = GLOBAL_APP_CONFIG.read().await.clone();
+    let mut is_found = false;
+    for route in app_config
+        .api_service_config
+        .into_iter()
+        .flat_map(|item| item.service_config.routes)
+    {
+        let base_routes = route.route_cluster.clone().get_all_route().await?;
+        if base_routes
+            .iter()
+            .any(|item| item.endpoint == endpoint_status_vistor.endpoint)
+        {
+            is_found = true;
+            break;
+        }
+    }
+    if !is_found {
+        return Err(anyhow!(
+            "Can not find the endpoint {} in the routes!",
+            endpoint_status_vistor.endpoint
+        ));
+    }
+    set_endpoint_status(
+        endpoint_status_vistor.endpoint.clone(),
+        endpoint_status_vistor.status,
+    );
+    let data = BaseResponse {
+        response_code: 0,
+        response_object: get_endpoint_status_response(endpoint_status_vistor.endpoint),
+    };
     Ok(serde_json::to_string(&data).unwrap())
 }
-async fn save_config_to_file() -> Result<(), anyhow::Error> {
-    let read_global_lock = GLOBAL_APP_CONFIG.read().await;
-    let data = read_global_lock.clone();
-    drop(read_global_lock);
-    let api_services_vistor = from_api_service(data.api_service_config.clone()).await?;
-    let result: bool = Path::new(DEFAULT_TEMPORARY_DIR).is_dir();
-    if !result {
-        let path = env::current_dir()?;
-        let absolute_path = path.join(DEFAULT_TEMPORARY_DIR);
-        std::fs::create_dir_all(absolute_path)?;
+async fn get_endpoint_status() -> Result<impl warp::Reply, Infallible> {
+    let data = BaseResponse {
+        response_code: 0,
+        response_object: list_endpoint_status(),
+    };
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", "application/json")
+        .body(serde_json::to_string(&data).unwrap())
+        .unwrap())
+}
+async fn wait_endpoint_drained(
+    endpoint_wait_query: EndpointWaitQuery,
+) -> Result<impl warp::Reply, Infallible> {
+    let drained = wait_for_drained(
+        endpoint_wait_query.endpoint.as_str(),
+        endpoint_wait_query.timeout,
+    )
+    .await;
+    let mut endpoint_status_response = get_endpoint_status_response(endpoint_wait_query.endpoint);
+    endpoint_status_response.drained = drained;
+    let (response_code, status) = if drained {
+        (0, StatusCode::OK)
+    } else {
+        (-1, StatusCode::REQUEST_TIMEOUT)
+    };
+    let data = BaseResponse {
+        response_code,
+        response_object: endpoint_status_response,
+    };
+    Ok(Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(serde_json::to_string(&data).unwrap())
+        .unwrap())
+}
+async fn post_registry_instance(
+    cluster_name: String,
+    registry_instance_vistor: RegistryInstanceVistor,
+) -> Result<impl warp::Reply, Infallible> {
+    let instance = match register_instance(cluster_name.as_str(), registry_instance_vistor) {
+        Ok(instance) => instance,
+        Err(e) => return Ok(error_response(StatusCode::BAD_REQUEST, e.to_string())),
+    };
+    sync_registry_routes().await;
+    let data = BaseResponse {
+        response_code: 0,
+        response_object: instance,
+    };
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", "application/json")
+        .body(serde_json::to_string(&data).unwrap())
+        .unwrap())
+}
+async fn put_registry_heartbeat(
+    cluster_name: String,
+    instance_id: String,
+) -> Result<impl warp::Reply, Infallible> {
+    match heartbeat_instance(cluster_name.as_str(), instance_id.as_str()) {
+        Ok(instance) => {
+            let data = BaseResponse {
+                response_code: 0,
+                response_object: instance,
+            };
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&data).unwrap())
+                .unwrap())
+        }
+        Err(e) => Ok(error_response(StatusCode::NOT_FOUND, e.to_string())),
     }
-
-    let mut f = tokio::fs::OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open("temporary/new_silverwind_config.yml")
-        .await?;
-    let api_service_str = serde_yaml::to_string(&api_services_vistor)?;
-    f.write_all(api_service_str.as_bytes()).await?;
-    Ok(())
-}
-fn validate_tls_config(
-    cert_pem_option: Option<String>,
-    key_pem_option: Option<String>,
-) -> Result<(), anyhow::Error> {
-    if cert_pem_option.is_none() || key_pem_option.is_none() {
-        return Err(anyhow!("Cert or key is none"));
-    }
-    let cert_pem = cert_pem_option.unwrap();
-    let mut cer_reader = std::io::BufReader::new(cert_pem.as_bytes());
-    let result_certs = rustls_pemfile::certs(&mut cer_reader);
-    if result_certs.is_err() || result_certs.unwrap().is_empty() {
-        return Err(anyhow!("Can not parse the certs pem."));
-    }
-    let key_pem = key_pem_option.unwrap();
-    let key_pem_result = pkcs8::PrivateKeyDocument::from_pem(key_pem.as_str());
-    if key_pem_result.is_err() {
-        return Err(anyhow!("Can not parse the key pem."));
-    }
-    Ok(())
 }
-
+async fn delete_registry_instance(
+    cluster_name: String,
+    instance_id: String,
+) -> Result<impl warp::Reply, Infallible> {
+    match deregister_instance(cluster_name.as_str(), instance_id.as_str()) {
+        Ok(instance) => {
+            sync_registry_routes().await;
+            let data = BaseResponse {
+                response_code: 0,
+                response_object: instance,
+            };
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&data).unwrap())
+                .unwrap())
+        }
+        Err(e) => Ok(error_response(StatusCode::NOT_FOUND, e.to_string())),
+    }
+}
+async fn get_registry_instances(cluster_name: String) -> Result<impl warp::Reply, Infallible> {
+    let data = BaseResponse {
+        response_code: 0,
+        response_object: list_instances(cluster_name.as_str()),
+    };
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", "application/json")
+        .body(serde_json::to_string(&data).unwrap())
+        .unwrap())
+}
+/**
+*The error response whose body is the base response with the error message.
+*/
+fn error_response(status_code: StatusCode, message: String) -> Response<String> {
+    let data = BaseResponse {
+        response_code: -1,
+        response_object: message,
+    };
+    Response::builder()
+        .status(status_code)
+        .header("content-type", "application/json")
+        .body(serde_json::to_string(&data).unwrap())
+        .unwrap()
+}
 fn json_body() -> impl Filter<Extract = (ApiServiceVistor,), Error = warp::Rejection> + Clone {
     warp::body::content_length_limit(1024 * 16).and(warp::body::json())
 }
 fn route_json_body() -> impl Filter<Extract = (RouteVistor,), Error = warp::Rejection> + Clone {
     warp::body::content_length_limit(1024 * 16).and(warp::body::json())
 }
+pub fn change_message_header(
+) -> impl Filter<Extract = (Option<String>,), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("x-change-message")
+}
+fn endpoint_status_json_body(
+) -> impl Filter<Extract = (EndpointStatusVistor,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
+fn registry_instance_json_body(
+) -> impl Filter<Extract = (RegistryInstanceVistor,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
 
 pub async fn handle_not_found(reject: Rejection) -> Result<impl Reply, Rejection> {
     if reject.is_not_found() {
@@ -277,64 +528,232 @@ pub async fn _handle_custom(reject: Rejection) -> Result<impl Reply, Rejection>
     }
 }
 
-pub async fn start_control_plane(port: i32) {
+/**
+*The cors headers are only sent for the configured origins.
+*/
+fn admin_cors(admin_cors_origins: Vec<String>) -> warp::cors::Builder {
+    warp::cors()
+        .allow_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS", "HEAD"])
+        .allow_credentials(true)
+        .allow_headers(vec![
+            "access-control-allow-methods",
+            "access-control-allow-origin",
+            "useragent",
+            "content-type",
+            "x-custom-header",
+            "x-change-message",
+            "authorization",
+        ])
+        .expose_headers(vec![CONFIG_GENERATION_HEADER])
+        .allow_origins(admin_cors_origins.iter().map(|item| item.as_str()))
+}
+/**
+*All the routes of the admin api,the openapi document should describe every one of them.
+*/
+fn admin_routes(
+    admin_auth_config: Arc<AdminAuthConfig>,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     let post_app_config = warp::path("appConfig")
         .and(warp::path::end())
         .and(json_body())
+        .and(change_message_header())
         .and_then(post_app_config);
     let put_route = warp::path("route")
         .and(warp::path::end())
         .and(route_json_body())
+        .and(change_message_header())
         .and_then(put_route);
     let delete_route = warp::path("route")
         .and(warp::path::param::<String>())
         .and(warp::path::end())
+        .and(change_message_header())
         .and_then(delete_route);
-    let get_app_config = warp::path("appConfig").and_then(get_app_config);
+    let get_config_versions = warp::path("configVersion")
+        .and(warp::path::end())
+        .and_then(get_config_versions);
+    let get_config_version_diff = warp::path("configVersion")
+        .and(warp::path("diff"))
+        .and(warp::path::end())
+        .and(warp::query::<ConfigVersionDiffQuery>())
+        .and(with_admin_role(admin_auth_config.clone()))
+        .and_then(get_config_version_diff);
+    let post_config_version_rollback = warp::path("configVersion")
+        .and(warp::path::param::<i64>())
+        .and(warp::path("rollback"))
+        .and(warp::path::end())
+        .and_then(post_config_version_rollback);
+    let get_config_generation_status = warp::path("configGeneration")
+        .and(warp::path::end())
+        .and_then(get_config_generation_status);
+    let wait_config_generation = warp::path("configGeneration")
+        .and(warp::path("wait"))
+        .and(warp::path::end())
+        .and(warp::query::<ConfigGenerationWaitQuery>())
+        .and_then(wait_config_generation);
+    let put_endpoint_status = warp::path("endpointStatus")
+        .and(warp::path::end())
+        .and(endpoint_status_json_body())
+        .and_then(put_endpoint_status);
+    let get_app_config = warp::path("appConfig")
+        .and(with_admin_role(admin_auth_config.clone()))
+        .and_then(get_app_config);
 
     let get_prometheus_metrics = warp::path("metrics").and_then(get_prometheus_metrics);
+    let get_endpoint_status = warp::path("endpointStatus")
+        .and(warp::path::end())
+        .and_then(get_endpoint_status);
+    let wait_endpoint_drained = warp::path("endpointStatus")
+        .and(warp::path("drained"))
+        .and(warp::path::end())
+        .and(warp::query::<EndpointWaitQuery>())
+        .and_then(wait_endpoint_drained);
+    let post_registry_instance = warp::path("registry")
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(registry_instance_json_body())
+        .and_then(post_registry_instance);
+    let put_registry_heartbeat = warp::path("registry")
+        .and(warp::path::param::<String>())
+        .and(warp::path::param::<String>())
+        .and(warp::path("heartbeat"))
+        .and(warp::path::end())
+        .and_then(put_registry_heartbeat);
+    let delete_registry_instance = warp::path("registry")
+        .and(warp::path::param::<String>())
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and_then(delete_registry_instance);
+    let get_registry_instances = warp::path("registry")
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and_then(get_registry_instances);
 
-    let get_request = warp::get().and(get_app_config.or(get_prometheus_metrics));
-    let post_request = warp::post().and(path().or(post_app_config));
-    let put_request = warp::put().and(put_route);
-    let delete_request = warp::delete().and(delete_route);
+    let get_request = warp::get().and(
+        get_app_config
+            .or(get_prometheus_metrics)
+            .or(get_endpoint_status)
+            .or(wait_endpoint_drained)
+            .or(get_registry_instances)
+            .or(get_config_versions)
+            .or(get_config_version_diff)
+            .or(get_config_generation_status)
+            .or(wait_config_generation),
+    );
+    let post_request = warp::post().and(
+        path()
+            .or(post_app_config)
+            .or(post_registry_instance)
+            .or(post_config_version_rollback),
+    );
+    let put_request = warp::put().and(put_route.or(put_endpoint_status).or(put_registry_heartbeat));
+    let delete_request = warp::delete().and(delete_route.or(delete_registry_instance));
 
     // let put_request = warp::put().and(path()).recover(handle_not_found);
-
+    post_request
+        .or(get_request)
+        .or(put_request)
+        .or(delete_request)
+        .or(service_api::path(admin_auth_config))
+        .or(openapi::path())
+}
+pub async fn start_control_plane(port: i32) {
+    let static_config = GLOBAL_APP_CONFIG.read().await.static_config.clone();
+    let bind_address = static_config
+        .admin_bind_address
+        .unwrap_or(String::from(DEFAULT_ADMIN_BIND_ADDRESS));
+    let ip_addr = match bind_address.parse::<IpAddr>() {
+        Ok(ip_addr) => ip_addr,
+        Err(err) => {
+            error!(
+                "The admin bind address {} is invalid,the admin api will not be started,the error is {}.",
+                bind_address, err
+            );
+            return;
+        }
+    };
+    let addr = SocketAddr::new(ip_addr, port as u16);
+    let admin_auth_config = match AdminAuthConfig::from_env() {
+        Ok(admin_auth_config) => admin_auth_config,
+        Err(err) => {
+            error!(
+                "Load the admin auth config error,the admin api will not be started,the error is {}.",
+                err
+            );
+            return;
+        }
+    };
+    if let Err(err) = admin_auth_config.check_bind_address(ip_addr) {
+        error!("The admin api will not be started,the error is {}", err);
+        return;
+    }
+    if !admin_auth_config.is_enabled() {
+        warn!("The admin api is started without the authentication,set the ADMIN_TOKENS or the ADMIN_TLS_CLIENT_CA_PATH to enable it.");
+    }
+    let tls_config_option = admin_auth_config.tls_config.clone();
+    let admin_auth_config = Arc::new(admin_auth_config);
     let log = warp::log("dashbaord-svc");
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], port as u16));
-
-    let cors = warp::cors()
-        .allow_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS", "HEAD"])
-        .allow_credentials(true)
-        .allow_headers(vec![
-            "access-control-allow-methods",
-            "access-control-allow-origin",
-            "useragent",
-            "content-type",
-            "x-custom-header",
-        ])
-        .allow_any_origin();
-    warp::serve(
-        post_request
-            .or(get_request)
-            .or(put_request)
-            .or(delete_request)
-            .with(cors)
-            .with(log)
-            .recover(handle_not_found),
-    )
-    .run(addr)
-    .await;
+    let api_routes = with_admin_auth(admin_auth_config.clone())
+        .and(admin_routes(admin_auth_config))
+        .recover(handle_admin_auth_rejection);
+    let api_routes = match static_config.admin_cors_origins {
+        Some(admin_cors_origins) if !admin_cors_origins.is_empty() => api_routes
+            .with(admin_cors(admin_cors_origins))
+            .map(|reply| Box::new(reply) as Box<dyn Reply>)
+            .boxed(),
+        _ => api_routes
+            .map(|reply| Box::new(reply) as Box<dyn Reply>)
+            .boxed(),
+    };
+    let routes = api_routes.with(log).recover(handle_not_found);
+    match tls_config_option {
+        Some(tls_config) => {
+            let incoming = match AddrIncoming::bind(&addr) {
+                Ok(incoming) => incoming,
+                Err(err) => {
+                    error!(
+                        "Cause error when binding the socket,the addr is {},the error is {}.",
+                        addr, err
+                    );
+                    return;
+                }
+            };
+            let service = warp::service(routes);
+            let make_service = make_service_fn(move |_| {
+                let service = service.clone();
+                async move { Ok::<_, Infallible>(service) }
+            });
+            info!("Listening on https://{}", addr);
+            if let Err(err) = Server::builder(TlsAcceptor::new(
+                Arc::new(ArcSwap::from(tls_config)),
+                incoming,
+            ))
+            .serve(make_service)
+            .await
+            {
+                error!("The admin api has received error: {}", err);
+            }
+        }
+        None => warp::serve(routes).run(addr).await,
+    }
 }
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::configuration_service::app_config_service::validate_tls_config;
+    use crate::control_plane::admin_auth::{parse_admin_tokens, REDACTED_SECRET};
+    use crate::maintenance::endpoint_maintenance::track_in_flight;
+    use crate::utils::uuid::get_uuid;
+    use crate::vojo::app_config::{CertificateConfig, ServiceConfig};
+    use crate::vojo::config_generation::ConfigGeneration;
+    use crate::vojo::maintenance::EndpointStatusResponse;
     use http::StatusCode;
     use lazy_static::lazy_static;
     use std::env;
     use tokio::runtime::{Builder, Runtime};
+    use warp::body::BodyDeserializeError;
+    use warp::http::HeaderValue;
+    use warp::reject::{InvalidHeader, InvalidQuery, LengthRequired};
     lazy_static! {
         pub static ref TOKIO_RUNTIME: Runtime = Builder::new_multi_thread()
             .worker_threads(4)
@@ -347,7 +766,7 @@ mod tests {
     #[test]
     fn test_api_get_response_ok() {
         TOKIO_RUNTIME.block_on(async {
-            let res = get_app_config().await.unwrap();
+            let res = get_app_config(AdminRole::Operator).await.unwrap();
             assert_eq!(res.into_response().status(), StatusCode::OK);
         })
     }
@@ -358,6 +777,7 @@ mod tests {
                 .and(warp::path("appConfig"))
                 .and(warp::path::end())
                 .and(json_body())
+                .and(change_message_header())
                 .and_then(post_app_config)
                 .recover(handle_not_found);
             let res = warp::test::request()
@@ -403,6 +823,7 @@ mod tests {
                 .and(warp::path("appConfig"))
                 .and(warp::path::end())
                 .and(json_body())
+                .and(change_message_header())
                 .and_then(post_app_config)
                 .recover(handle_not_found);
             let res = warp::test::request()
@@ -433,7 +854,7 @@ mod tests {
             .join("test_cert.pem");
         let certificate = std::fs::read_to_string(certificate_path).unwrap();
 
-        let validation_res = validate_tls_config(Some(certificate), Some(private_key));
+        let validation_res = validate_tls_config(Some(certificate), Some(private_key), None);
         assert!(validation_res.is_ok());
     }
     #[test]
@@ -445,7 +866,7 @@ mod tests {
         let certificate = std::fs::read_to_string(certificate_path).unwrap();
 
         let private_key = String::from("private key");
-        let validation_res = validate_tls_config(Some(certificate), Some(private_key));
+        let validation_res = validate_tls_config(Some(certificate), Some(private_key), None);
         assert!(validation_res.is_err());
     }
     #[test]
@@ -457,7 +878,7 @@ mod tests {
         let private_key = std::fs::read_to_string(private_key_path).unwrap();
         let certificate = String::from("test");
 
-        let validation_res = validate_tls_config(Some(certificate), Some(private_key));
+        let validation_res = validate_tls_config(Some(certificate), Some(private_key), None);
         assert!(validation_res.is_err());
     }
     #[test]
@@ -467,6 +888,7 @@ mod tests {
                 .and(warp::path("appConfig"))
                 .and(warp::path::end())
                 .and(json_body())
+                .and(change_message_header())
                 .and_then(post_app_config)
                 .recover(handle_not_found);
             let res = warp::test::request()
@@ -510,6 +932,7 @@ mod tests {
                 .and(warp::path("appConfig"))
                 .and(warp::path::end())
                 .and(json_body())
+                .and(change_message_header())
                 .and_then(post_app_config)
                 .recover(handle_not_found);
             let res = warp::test::request()
@@ -531,6 +954,7 @@ mod tests {
             let get_app_config = warp::get()
                 .and(warp::path("appConfig"))
                 .and(warp::path::end())
+                .and(warp::any().map(|| AdminRole::Operator))
                 .and_then(get_app_config)
                 .recover(handle_not_found);
             let res = warp::test::request()
@@ -542,6 +966,129 @@ mod tests {
         })
     }
     #[tokio::test]
+    async fn test_get_app_config_with_read_only_role() {
+        let admin_auth_config = Arc::new(AdminAuthConfig {
+            tokens: parse_admin_tokens("operator-token:operator,read-token:read_only").unwrap(),
+            ..Default::default()
+        });
+        let get_app_config = warp::path("appConfig")
+            .and(with_admin_role(admin_auth_config))
+            .and_then(get_app_config);
+        let api_service_id = get_uuid();
+        GLOBAL_APP_CONFIG
+            .write()
+            .await
+            .api_service_config
+            .push(ApiService {
+                listen_port: 4496,
+                api_service_id: api_service_id.clone(),
+                service_config: ServiceConfig {
+                    server_type: ServiceType::Https,
+                    key_str: Some(String::from("the-private-key")),
+                    key_passphrase: Some(String::from("the-passphrase")),
+                    certificates: vec![CertificateConfig {
+                        key_str: String::from("the-certificate-key"),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            });
+        let read_only_res = warp::test::request()
+            .method("GET")
+            .path("/appConfig")
+            .header("authorization", "Bearer read-token")
+            .reply(&get_app_config)
+            .await;
+        let operator_res = warp::test::request()
+            .method("GET")
+            .path("/appConfig")
+            .header("authorization", "Bearer operator-token")
+            .reply(&get_app_config)
+            .await;
+        GLOBAL_APP_CONFIG
+            .write()
+            .await
+            .api_service_config
+            .retain(|item| item.api_service_id != api_service_id);
+        assert_eq!(read_only_res.status(), StatusCode::OK);
+        let read_only_body = String::from_utf8(read_only_res.body().to_vec()).unwrap();
+        assert!(!read_only_body.contains("the-private-key"));
+        assert!(!read_only_body.contains("the-passphrase"));
+        assert!(!read_only_body.contains("the-certificate-key"));
+        assert!(read_only_body.contains(REDACTED_SECRET));
+        let operator_body = String::from_utf8(operator_res.body().to_vec()).unwrap();
+        assert!(operator_body.contains("the-private-key"));
+        assert!(operator_body.contains("the-certificate-key"));
+    }
+    #[tokio::test]
+    async fn test_admin_routes_match_the_openapi() {
+        let routes = admin_routes(Arc::new(AdminAuthConfig::default()));
+        let document = openapi::openapi_value();
+        let spec_paths = document["paths"].as_object().unwrap();
+        for (path, path_item) in spec_paths.iter() {
+            let request_path = path
+                .replace("{port}", "4497")
+                .replace("{version}", "-1")
+                .replace("{route_id}", "openapi-probe")
+                .replace("{cluster_name}", "openapi-probe")
+                .replace("{instance_id}", "openapi-probe");
+            for method in ["get", "post", "put", "delete"] {
+                // The invalid change message rejects the changes before the handlers are called.
+                let result = warp::test::request()
+                    .method(method.to_uppercase().as_str())
+                    .path(request_path.as_str())
+                    .header(
+                        "x-change-message",
+                        HeaderValue::from_bytes(b"\xff").unwrap(),
+                    )
+                    .filter(&routes)
+                    .await;
+                let is_routed = match result {
+                    Ok(reply) => {
+                        let res = reply.into_response();
+                        let status = res.status();
+                        let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+                        let body = String::from_utf8_lossy(&body);
+                        status != StatusCode::METHOD_NOT_ALLOWED
+                            && !body.contains("Can not find the path")
+                    }
+                    Err(rejection) => {
+                        rejection.find::<InvalidHeader>().is_some()
+                            || rejection.find::<InvalidQuery>().is_some()
+                            || rejection.find::<LengthRequired>().is_some()
+                            || rejection.find::<BodyDeserializeError>().is_some()
+                    }
+                };
+                assert_eq!(
+                    is_routed,
+                    path_item.get(method).is_some(),
+                    "{} {} is not the same in the routes and the openapi",
+                    method,
+                    path
+                );
+            }
+        }
+        let spec_segments = spec_paths
+            .keys()
+            .flat_map(|path| path.split('/'))
+            .collect::<Vec<&str>>();
+        for source in [
+            include_str!("rest_api.rs"),
+            include_str!("service_api.rs"),
+            include_str!("lets_encrypt.rs"),
+            include_str!("openapi.rs"),
+        ] {
+            for item in source.split("warp::path(\"").skip(1) {
+                let segment = item.split('"').next().unwrap();
+                assert!(
+                    spec_segments.contains(&segment),
+                    "{} is not in the openapi",
+                    segment
+                );
+            }
+        }
+    }
+    #[tokio::test]
     async fn test_put_route_ok() {
         let body = r#"{
             "route_id": "90c66439-5c87-4902-aebb-1c2c9443c154",
@@ -573,6 +1120,7 @@ mod tests {
         let put_route = warp::path("route")
             .and(warp::path::end())
             .and(route_json_body())
+            .and(change_message_header())
             .and_then(put_route);
         let res = warp::test::request()
             .method("PUT")
@@ -587,10 +1135,176 @@ mod tests {
         // assert_eq!(base_response.response_object, 0);
     }
     #[tokio::test]
+    async fn test_put_endpoint_status_not_found() {
+        let body = r#"{
+            "endpoint": "http://127.0.0.1:19010",
+            "status": "Draining"
+        }"#;
+        let put_endpoint_status = warp::path("endpointStatus")
+            .and(warp::path::end())
+            .and(endpoint_status_json_body())
+            .and_then(put_endpoint_status);
+        let res = warp::test::request()
+            .method("PUT")
+            .path("/endpointStatus")
+            .body(body)
+            .reply(&put_endpoint_status)
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+    #[tokio::test]
+    async fn test_wait_endpoint_drained_ok() {
+        let wait_endpoint_drained = warp::path("endpointStatus")
+            .and(warp::path("drained"))
+            .and(warp::path::end())
+            .and(warp::query::<EndpointWaitQuery>())
+            .and_then(wait_endpoint_drained);
+        let res = warp::test::request()
+            .method("GET")
+            .path("/endpointStatus/drained?endpoint=http://127.0.0.1:19011&timeout=1")
+            .reply(&wait_endpoint_drained)
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let base_response: BaseResponse<EndpointStatusResponse> =
+            serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(base_response.response_object.in_flight, 0);
+        assert!(base_response.response_object.drained);
+
+        let _in_flight_guard = track_in_flight("http://127.0.0.1:19012");
+        let res = warp::test::request()
+            .method("GET")
+            .path("/endpointStatus/drained?endpoint=http://127.0.0.1:19012&timeout=1")
+            .reply(&wait_endpoint_drained)
+            .await;
+        assert_eq!(res.status(), StatusCode::REQUEST_TIMEOUT);
+        let base_response: BaseResponse<EndpointStatusResponse> =
+            serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(base_response.response_code, -1);
+        assert_eq!(base_response.response_object.in_flight, 1);
+        assert!(!base_response.response_object.drained);
+    }
+    #[tokio::test]
+    async fn test_registry_instance_ok() {
+        let body = r#"{
+            "instance_id": "worker-1",
+            "endpoint": "http://127.0.0.1:19040",
+            "ttl": 30
+        }"#;
+        let post_registry_instance = warp::path("registry")
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(registry_instance_json_body())
+            .and_then(post_registry_instance);
+        let res = warp::test::request()
+            .method("POST")
+            .path("/registry/test_registry_instance_ok")
+            .body(body)
+            .reply(&post_registry_instance)
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let base_response: BaseResponse<RegistryInstanceVistor> =
+            serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(base_response.response_object.weight, 100);
+        let res = warp::test::request()
+            .method("POST")
+            .path("/registry/test_registry_instance_ok")
+            .body(r#"{"instance_id":"worker-2","endpoint":"http://127.0.0.1:19041","weight":0}"#)
+            .reply(&post_registry_instance)
+            .await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        let base_response: BaseResponse<String> = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(base_response.response_code, -1);
+
+        let put_registry_heartbeat = warp::path("registry")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path("heartbeat"))
+            .and(warp::path::end())
+            .and_then(put_registry_heartbeat);
+        let res = warp::test::request()
+            .method("PUT")
+            .path("/registry/test_registry_instance_ok/worker-1/heartbeat")
+            .reply(&put_registry_heartbeat)
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let delete_registry_instance = warp::path("registry")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and_then(delete_registry_instance);
+        let res = warp::test::request()
+            .method("DELETE")
+            .path("/registry/test_registry_instance_ok/worker-1")
+            .reply(&delete_registry_instance)
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = warp::test::request()
+            .method("PUT")
+            .path("/registry/test_registry_instance_ok/worker-1/heartbeat")
+            .reply(&put_registry_heartbeat)
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        let base_response: BaseResponse<String> = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(base_response.response_code, -1);
+    }
+    #[tokio::test]
+    async fn test_get_config_version_diff_not_found() {
+        let get_config_version_diff = warp::path("configVersion")
+            .and(warp::path("diff"))
+            .and(warp::path::end())
+            .and(warp::query::<ConfigVersionDiffQuery>())
+            .and(warp::any().map(|| AdminRole::ReadOnly))
+            .and_then(get_config_version_diff);
+        let res = warp::test::request()
+            .method("GET")
+            .path("/configVersion/diff?from_version=-1&to_version=-2")
+            .reply(&get_config_version_diff)
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+    #[tokio::test]
+    async fn test_wait_config_generation_ok() {
+        let get_config_generation_status = warp::path("configGeneration")
+            .and(warp::path::end())
+            .and_then(get_config_generation_status);
+        let res = warp::test::request()
+            .method("GET")
+            .path("/configGeneration")
+            .reply(&get_config_generation_status)
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let base_response: BaseResponse<ConfigGeneration> =
+            serde_json::from_slice(res.body()).unwrap();
+        let next_generation = base_response.response_object.generation + 1000;
+
+        let wait_config_generation = warp::path("configGeneration")
+            .and(warp::path("wait"))
+            .and(warp::path::end())
+            .and(warp::query::<ConfigGenerationWaitQuery>())
+            .and_then(wait_config_generation);
+        let res = warp::test::request()
+            .method("GET")
+            .path(
+                format!(
+                    "/configGeneration/wait?generation={}&timeout=1",
+                    next_generation
+                )
+                .as_str(),
+            )
+            .reply(&wait_config_generation)
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let base_response: BaseResponse<ConfigGeneration> =
+            serde_json::from_slice(res.body()).unwrap();
+        assert!(base_response.response_object.applied_generation < next_generation);
+    }
+    #[tokio::test]
     async fn test_delete_route_ok() {
         let delete_route = warp::path("route")
             .and(warp::path::param::<String>())
             .and(warp::path::end())
+            .and(change_message_header())
             .and_then(delete_route);
         let res = warp::test::request()
             .method("DELETE")
@@ -599,6 +1313,12 @@ mod tests {
             .reply(&delete_route)
             .await;
         assert_eq!(res.status(), StatusCode::OK);
+        let generation: u64 = res.headers()[CONFIG_GENERATION_HEADER]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(get_config_generation().applied_generation >= generation);
         // let body_bytes = res.body();
         // let base_response: BaseResponse<i32> = serde_json::from_slice(body_bytes).unwrap();
         // assert_eq!(base_response.response_code, 0);
@@ -0,0 +1,763 @@
+use crate::configuration_service::app_config_service::{
+    commit_api_services, from_api_service_vistors, inherit_api_services_status,
+    inherit_route_status, save_api_services, GLOBAL_APP_CONFIG,
+};
+use crate::constants::common_constants::CONFIG_GENERATION_HEADER;
+use crate::control_plane::admin_auth::{
+    to_role_value, with_admin_role, AdminAuthConfig, AdminRole,
+};
+use crate::control_plane::rest_api::change_message_header;
+use crate::vojo::app_config::{ApiService, AppConfig, Route};
+use crate::vojo::app_config_vistor::{ApiServiceVistor, RouteVistor};
+use crate::vojo::base_response::BaseResponse;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::RwLockWriteGuard;
+use warp::body::BodyDeserializeError;
+use warp::http::{HeaderValue, Response, StatusCode};
+use warp::reject::{
+    InvalidHeader, LengthRequired, MethodNotAllowed, PayloadTooLarge, UnsupportedMediaType,
+};
+use warp::Filter;
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ServiceApiError {
+    BadRequest(String),
+    NotFound(String),
+    Conflict(String),
+    Internal(String),
+    Rejected(StatusCode, String),
+}
+impl ServiceApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ServiceApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ServiceApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ServiceApiError::Conflict(_) => StatusCode::CONFLICT,
+            ServiceApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ServiceApiError::Rejected(status_code, _) => *status_code,
+        }
+    }
+    fn message(self) -> String {
+        match self {
+            ServiceApiError::BadRequest(message)
+            | ServiceApiError::NotFound(message)
+            | ServiceApiError::Conflict(message)
+            | ServiceApiError::Internal(message)
+            | ServiceApiError::Rejected(_, message) => message,
+        }
+    }
+}
+impl From<anyhow::Error> for ServiceApiError {
+    fn from(err: anyhow::Error) -> Self {
+        ServiceApiError::Internal(err.to_string())
+    }
+}
+/**
+*The error is returned as the json with the response code -1,so the clients could parse the
+ success and the error in the same way.
+*/
+fn to_response<T: Serialize>(
+    status_code: StatusCode,
+    result: Result<T, ServiceApiError>,
+) -> Response<String> {
+    let (status_code, json_str) = match result {
+        Ok(response_object) => {
+            let data = BaseResponse {
+                response_code: 0,
+                response_object,
+            };
+            (status_code, serde_json::to_string(&data).unwrap())
+        }
+        Err(err) => {
+            let error_status_code = err.status_code();
+            let data = BaseResponse {
+                response_code: -1,
+                response_object: err.message(),
+            };
+            (error_status_code, serde_json::to_string(&data).unwrap())
+        }
+    };
+    Response::builder()
+        .status(status_code)
+        .header("content-type", "application/json")
+        .body(json_str)
+        .unwrap()
+}
+/**
+*The generation of the change is returned in the header,so the client could wait on it.
+*/
+fn to_changed_response<T: Serialize>(
+    status_code: StatusCode,
+    result: Result<(T, u64), ServiceApiError>,
+) -> Response<String> {
+    match result {
+        Ok((response_object, generation)) => {
+            let mut response = to_response(status_code, Ok(response_object));
+            response
+                .headers_mut()
+                .insert(CONFIG_GENERATION_HEADER, HeaderValue::from(generation));
+            response
+        }
+        Err(err) => to_response::<T>(status_code, Err(err)),
+    }
+}
+fn service_not_found(port: i32) -> ServiceApiError {
+    ServiceApiError::NotFound(format!("Can not find the service on the port {}!", port))
+}
+fn route_not_found(port: i32, route_id: &str) -> ServiceApiError {
+    ServiceApiError::NotFound(format!(
+        "Can not find the route {} on the port {}!",
+        route_id, port
+    ))
+}
+/**
+*The route id is unique in all the services,as the route is also found by the route id only.
+*/
+fn check_route_ids(
+    api_services: &[ApiService],
+    route_ids: Vec<String>,
+) -> Result<(), ServiceApiError> {
+    let mut existing_route_ids = api_services
+        .iter()
+        .flat_map(|item| item.service_config.routes.iter())
+        .map(|item| item.route_id.clone())
+        .collect::<HashSet<String>>();
+    for route_id in route_ids {
+        if !existing_route_ids.insert(route_id.clone()) {
+            return Err(ServiceApiError::Conflict(format!(
+                "The route {} already exists!",
+                route_id
+            )));
+        }
+    }
+    Ok(())
+}
+async fn to_api_service(
+    api_service_vistor: ApiServiceVistor,
+) -> Result<ApiService, ServiceApiError> {
+    let mut api_services = from_api_service_vistors(vec![api_service_vistor])
+        .await
+        .map_err(|errors| ServiceApiError::BadRequest(errors.join("\n")))?;
+    Ok(api_services.remove(0))
+}
+async fn apply_api_services(
+    rw_global_lock: RwLockWriteGuard<'static, AppConfig>,
+    api_services: Vec<ApiService>,
+    change_message: Option<String>,
+) -> Result<u64, ServiceApiError> {
+    save_api_services(api_services.clone(), change_message).await?;
+    Ok(commit_api_services(rw_global_lock, api_services).await?)
+}
+async fn list_services_with_error() -> Result<Vec<ApiServiceVistor>, ServiceApiError> {
+    let api_services = GLOBAL_APP_CONFIG.read().await.api_service_config.clone();
+    let mut result = vec![];
+    for api_service in api_services {
+        result.push(ApiServiceVistor::from(api_service).await?);
+    }
+    Ok(result)
+}
+async fn get_service_with_error(port: i32) -> Result<ApiServiceVistor, ServiceApiError> {
+    let api_service = GLOBAL_APP_CONFIG
+        .read()
+        .await
+        .api_service_config
+        .iter()
+        .find(|item| item.listen_port == port)
+        .cloned()
+        .ok_or(service_not_found(port))?;
+    Ok(ApiServiceVistor::from(api_service).await?)
+}
+async fn post_service_with_error(
+    api_service_vistor: ApiServiceVistor,
+    change_message: Option<String>,
+) -> Result<(ApiServiceVistor, u64), ServiceApiError> {
+    let port = api_service_vistor.listen_port;
+    let route_ids = api_service_vistor
+        .service_config
+        .routes
+        .iter()
+        .map(|item| item.route_id.clone())
+        .collect::<Vec<String>>();
+    let api_service = to_api_service(api_service_vistor).await?;
+    let rw_global_lock = GLOBAL_APP_CONFIG.write().await;
+    let mut api_services = rw_global_lock.api_service_config.clone();
+    if api_services.iter().any(|item| item.listen_port == port) {
+        return Err(ServiceApiError::Conflict(format!(
+            "The service on the port {} already exists!",
+            port
+        )));
+    }
+    check_route_ids(&api_services, route_ids)?;
+    api_services.push(api_service.clone());
+    let generation = apply_api_services(rw_global_lock, api_services, change_message).await?;
+    Ok((ApiServiceVistor::from(api_service).await?, generation))
+}
+async fn put_service_with_error(
+    port: i32,
+    api_service_vistor: ApiServiceVistor,
+    change_message: Option<String>,
+) -> Result<(ApiServiceVistor, u64), ServiceApiError> {
+    if api_service_vistor.listen_port != port {
+        return Err(ServiceApiError::BadRequest(format!(
+            "The listen port {} is different from the port {} in the path!",
+            api_service_vistor.listen_port, port
+        )));
+    }
+    let route_ids = api_service_vistor
+        .service_config
+        .routes
+        .iter()
+        .map(|item| item.route_id.clone())
+        .collect::<Vec<String>>();
+    let api_service = to_api_service(api_service_vistor).await?;
+    let rw_global_lock = GLOBAL_APP_CONFIG.write().await;
+    let mut api_services = rw_global_lock.api_service_config.clone();
+    let index = api_services
+        .iter()
+        .position(|item| item.listen_port == port)
+        .ok_or(service_not_found(port))?;
+    let old_api_service = api_services.remove(index);
+    check_route_ids(&api_services, route_ids)?;
+    inherit_api_services_status(std::slice::from_ref(&api_service), &[old_api_service]).await?;
+    api_services.insert(index, api_service.clone());
+    let generation = apply_api_services(rw_global_lock, api_services, change_message).await?;
+    Ok((ApiServiceVistor::from(api_service).await?, generation))
+}
+async fn delete_service_with_error(
+    port: i32,
+    change_message: Option<String>,
+) -> Result<(ApiServiceVistor, u64), ServiceApiError> {
+    let rw_global_lock = GLOBAL_APP_CONFIG.write().await;
+    let mut api_services = rw_global_lock.api_service_config.clone();
+    let index = api_services
+        .iter()
+        .position(|item| item.listen_port == port)
+        .ok_or(service_not_found(port))?;
+    let old_api_service = api_services.remove(index);
+    let generation = apply_api_services(rw_global_lock, api_services, change_message).await?;
+    Ok((ApiServiceVistor::from(old_api_service).await?, generation))
+}
+async fn list_routes_with_error(port: i32) -> Result<Vec<RouteVistor>, ServiceApiError> {
+    Ok(get_service_with_error(port).await?.service_config.routes)
+}
+async fn get_route_with_error(port: i32, route_id: String) -> Result<RouteVistor, ServiceApiError> {
+    get_service_with_error(port)
+        .await?
+        .service_config
+        .routes
+        .into_iter()
+        .find(|item| item.route_id == route_id)
+        .ok_or(route_not_found(port, route_id.as_str()))
+}
+async fn post_route_with_error(
+    port: i32,
+    route_vistor: RouteVistor,
+    change_message: Option<String>,
+) -> Result<(String, u64), ServiceApiError> {
+    let route_id = route_vistor.route_id.clone();
+    let route = Route::from(route_vistor)
+        .await
+        .map_err(|err| ServiceApiError::BadRequest(err.to_string()))?;
+    let rw_global_lock = GLOBAL_APP_CONFIG.write().await;
+    let mut api_services = rw_global_lock.api_service_config.clone();
+    let index = api_services
+        .iter()
+        .position(|item| item.listen_port == port)
+        .ok_or(service_not_found(port))?;
+    check_route_ids(&api_services, vec![route_id.clone()])?;
+    api_services[index].service_config.routes.push(route);
+    let generation = apply_api_services(rw_global_lock, api_services, change_message).await?;
+    Ok((route_id, generation))
+}
+async fn put_route_with_error(
+    port: i32,
+    route_id: String,
+    mut route_vistor: RouteVistor,
+    change_message: Option<String>,
+) -> Result<(String, u64), ServiceApiError> {
+    route_vistor.route_id = route_id.clone();
+    let route = Route::from(route_vistor)
+        .await
+        .map_err(|err| ServiceApiError::BadRequest(err.to_string()))?;
+    let rw_global_lock = GLOBAL_APP_CONFIG.write().await;
+    let mut api_services = rw_global_lock.api_service_config.clone();
+    let old_route = api_services
+        .iter_mut()
+        .find(|item| item.listen_port == port)
+        .ok_or(service_not_found(port))?
+        .service_config
+        .routes
+        .iter_mut()
+        .find(|item| item.route_id == route_id)
+        .ok_or(route_not_found(port, route_id.as_str()))?;
+    inherit_route_status(&route, old_route).await?;
+    *old_route = route;
+    let generation = apply_api_services(rw_global_lock, api_services, change_message).await?;
+    Ok((route_id, generation))
+}
+/**
+*The last route could not be deleted,as the service without any route is invalid in the config
+ file,and the service should be deleted instead.
+*/
+async fn delete_route_with_error(
+    port: i32,
+    route_id: String,
+    change_message: Option<String>,
+) -> Result<(String, u64), ServiceApiError> {
+    let rw_global_lock = GLOBAL_APP_CONFIG.write().await;
+    let mut api_services = rw_global_lock.api_service_config.clone();
+    let routes = &mut api_services
+        .iter_mut()
+        .find(|item| item.listen_port == port)
+        .ok_or(service_not_found(port))?
+        .service_config
+        .routes;
+    let index = routes
+        .iter()
+        .position(|item| item.route_id == route_id)
+        .ok_or(route_not_found(port, route_id.as_str()))?;
+    if routes.len() == 1 {
+        return Err(ServiceApiError::Conflict(format!(
+            "The route {} is the last route on the port {},delete the service instead!",
+            route_id, port
+        )));
+    }
+    routes.remove(index);
+    let generation = apply_api_services(rw_global_lock, api_services, change_message).await?;
+    Ok((route_id, generation))
+}
+/**
+*The secrets in the response are redacted for the read only role.
+*/
+fn to_role_response<T: Serialize>(
+    admin_role: AdminRole,
+    result: Result<T, ServiceApiError>,
+) -> Response<String> {
+    let result = result.and_then(|response_object| {
+        to_role_value(admin_role, &response_object).map_err(ServiceApiError::from)
+    });
+    to_response(StatusCode::OK, result)
+}
+async fn list_services(admin_role: AdminRole) -> Result<impl warp::Reply, Infallible> {
+    Ok(to_role_response(
+        admin_role,
+        list_services_with_error().await,
+    ))
+}
+async fn get_service(port: i32, admin_role: AdminRole) -> Result<impl warp::Reply, Infallible> {
+    Ok(to_role_response(
+        admin_role,
+        get_service_with_error(port).await,
+    ))
+}
+async fn post_service(
+    api_service_vistor: ApiServiceVistor,
+    change_message: Option<String>,
+) -> Result<impl warp::Reply, Infallible> {
+    Ok(to_changed_response(
+        StatusCode::CREATED,
+        post_service_with_error(api_service_vistor, change_message).await,
+    ))
+}
+async fn put_service(
+    port: i32,
+    api_service_vistor: ApiServiceVistor,
+    change_message: Option<String>,
+) -> Result<impl warp::Reply, Infallible> {
+    Ok(to_changed_response(
+        StatusCode::OK,
+        put_service_with_error(port, api_service_vistor, change_message).await,
+    ))
+}
+async fn delete_service(
+    port: i32,
+    change_message: Option<String>,
+) -> Result<impl warp::Reply, Infallible> {
+    Ok(to_changed_response(
+        StatusCode::OK,
+        delete_service_with_error(port, change_message).await,
+    ))
+}
+async fn list_routes(port: i32, admin_role: AdminRole) -> Result<impl warp::Reply, Infallible> {
+    Ok(to_role_response(
+        admin_role,
+        list_routes_with_error(port).await,
+    ))
+}
+async fn get_route(
+    port: i32,
+    route_id: String,
+    admin_role: AdminRole,
+) -> Result<impl warp::Reply, Infallible> {
+    Ok(to_role_response(
+        admin_role,
+        get_route_with_error(port, route_id).await,
+    ))
+}
+async fn post_route(
+    port: i32,
+    route_vistor: RouteVistor,
+    change_message: Option<String>,
+) -> Result<impl warp::Reply, Infallible> {
+    Ok(to_changed_response(
+        StatusCode::CREATED,
+        post_route_with_error(port, route_vistor, change_message).await,
+    ))
+}
+async fn put_route(
+    port: i32,
+    route_id: String,
+    route_vistor: RouteVistor,
+    change_message: Option<String>,
+) -> Result<impl warp::Reply, Infallible> {
+    Ok(to_changed_response(
+        StatusCode::OK,
+        put_route_with_error(port, route_id, route_vistor, change_message).await,
+    ))
+}
+async fn delete_route(
+    port: i32,
+    route_id: String,
+    change_message: Option<String>,
+) -> Result<impl warp::Reply, Infallible> {
+    Ok(to_changed_response(
+        StatusCode::OK,
+        delete_route_with_error(port, route_id, change_message).await,
+    ))
+}
+fn service_json_body() -> impl Filter<Extract = (ApiServiceVistor,), Error = warp::Rejection> + Clone
+{
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
+fn route_json_body() -> impl Filter<Extract = (RouteVistor,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
+/**
+*The rejections from warp are returned as the same json as the other errors,such as the malformed
+ json,the oversized body,the wrong content type and the unknown path.
+*/
+async fn handle_rejection(rejection: warp::Rejection) -> Result<Response<String>, Infallible> {
+    let err = if let Some(err) = rejection.find::<BodyDeserializeError>() {
+        ServiceApiError::BadRequest(format!("The request body is invalid,the error is {}!", err))
+    } else if rejection.find::<PayloadTooLarge>().is_some() {
+        ServiceApiError::Rejected(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            String::from("The request body is too large!"),
+        )
+    } else if rejection.find::<UnsupportedMediaType>().is_some() {
+        ServiceApiError::Rejected(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            String::from("The content type should be application/json!"),
+        )
+    } else if rejection.find::<LengthRequired>().is_some() {
+        ServiceApiError::Rejected(
+            StatusCode::LENGTH_REQUIRED,
+            String::from("The content length is required!"),
+        )
+    } else if let Some(err) = rejection.find::<InvalidHeader>() {
+        ServiceApiError::BadRequest(format!("The header is invalid,the error is {}!", err))
+    } else if rejection.find::<MethodNotAllowed>().is_some() {
+        ServiceApiError::Rejected(
+            StatusCode::METHOD_NOT_ALLOWED,
+            String::from("The method is not allowed!"),
+        )
+    } else {
+        ServiceApiError::NotFound(String::from(
+            "Can not find the path,and the port should be a number!",
+        ))
+    };
+    Ok(to_response::<()>(StatusCode::OK, Err(err)))
+}
+/**
+*GET|POST /services
+ GET|PUT|DELETE /services/{port}
+ GET|POST /services/{port}/routes
+ GET|PUT|DELETE /services/{port}/routes/{route_id}
+ The path is matched before the method,so an unknown path is always answered with 404.
+*/
+pub fn path(
+    admin_auth_config: Arc<AdminAuthConfig>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let services = warp::path::end();
+    let service = warp::path::param::<i32>().and(warp::path::end());
+    let routes = warp::path::param::<i32>()
+        .and(warp::path("routes"))
+        .and(warp::path::end());
+    let route = warp::path::param::<i32>()
+        .and(warp::path("routes"))
+        .and(warp::path::param::<String>())
+        .and(warp::path::end());
+
+    let admin_role = with_admin_role(admin_auth_config);
+    let list_services = services
+        .and(warp::get())
+        .and(admin_role.clone())
+        .and_then(list_services);
+    let post_service = services
+        .and(warp::post())
+        .and(service_json_body())
+        .and(change_message_header())
+        .and_then(post_service);
+    let get_service = service
+        .and(warp::get())
+        .and(admin_role.clone())
+        .and_then(get_service);
+    let put_service = service
+        .and(warp::put())
+        .and(service_json_body())
+        .and(change_message_header())
+        .and_then(put_service);
+    let delete_service = service
+        .and(warp::delete())
+        .and(change_message_header())
+        .and_then(delete_service);
+    let list_routes = routes
+        .and(warp::get())
+        .and(admin_role.clone())
+        .and_then(list_routes);
+    let post_route = routes
+        .and(warp::post())
+        .and(route_json_body())
+        .and(change_message_header())
+        .and_then(post_route);
+    let get_route = route.and(warp::get()).and(admin_role).and_then(get_route);
+    let put_route = route
+        .and(warp::put())
+        .and(route_json_body())
+        .and(change_message_header())
+        .and_then(put_route);
+    let delete_route = route
+        .and(warp::delete())
+        .and(change_message_header())
+        .and_then(delete_route);
+    warp::path("services").and(
+        list_services
+            .or(post_service)
+            .or(get_service)
+            .or(put_service)
+            .or(delete_service)
+            .or(list_routes)
+            .or(post_route)
+            .or(get_route)
+            .or(put_route)
+            .or(delete_route)
+            .recover(handle_rejection),
+    )
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use warp::hyper::body::Bytes;
+    fn get_route_body(route_id: &str) -> String {
+        format!(
+            r#"{{
+                "route_id": "{}",
+                "matcher": {{
+                    "prefix": "/",
+                    "prefix_rewrite": "/"
+                }},
+                "route_cluster": {{
+                    "type": "RandomRoute",
+                    "routes": [
+                        {{
+                            "base_route": {{
+                                "endpoint": "http://127.0.0.1:10000",
+                                "try_file": null
+                            }}
+                        }}
+                    ]
+                }}
+            }}"#,
+            route_id
+        )
+    }
+    fn get_service_body(port: i32, route_ids: Vec<&str>) -> String {
+        let routes = route_ids
+            .into_iter()
+            .map(get_route_body)
+            .collect::<Vec<String>>()
+            .join(",");
+        format!(
+            r#"{{
+                "listen_port": {},
+                "service_config": {{
+                    "server_type": "Http",
+                    "routes": [{}]
+                }}
+            }}"#,
+            port, routes
+        )
+    }
+    fn get_error_message(res: &Response<Bytes>) -> String {
+        let base_response: BaseResponse<String> = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(base_response.response_code, -1);
+        base_response.response_object
+    }
+    #[tokio::test]
+    #[serial("test")]
+    async fn test_service_api_ok() {
+        let filter = path(Arc::new(AdminAuthConfig::default()));
+        let res = warp::test::request()
+            .method("POST")
+            .path("/services")
+            .body(get_service_body(4601, vec!["service-api-route-1"]))
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let res = warp::test::request()
+            .method("POST")
+            .path("/services")
+            .body(get_service_body(4601, vec!["service-api-route-2"]))
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+        assert!(get_error_message(&res).contains("4601"));
+
+        let res = warp::test::request()
+            .method("POST")
+            .path("/services/4601/routes")
+            .body(get_route_body("service-api-route-2"))
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let res = warp::test::request()
+            .method("GET")
+            .path("/services/4601/routes")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let base_response: BaseResponse<Vec<RouteVistor>> =
+            serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(base_response.response_object.len(), 2);
+
+        let res = warp::test::request()
+            .method("GET")
+            .path("/services/4601/routes/service-api-route-2")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = warp::test::request()
+            .method("DELETE")
+            .path("/services/4601/routes/service-api-route-2")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = warp::test::request()
+            .method("DELETE")
+            .path("/services/4601/routes/service-api-route-1")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+
+        let res = warp::test::request()
+            .method("PUT")
+            .path("/services/4601")
+            .body(get_service_body(4601, vec!["service-api-route-3"]))
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = warp::test::request()
+            .method("GET")
+            .path("/services/4601/routes/service-api-route-1")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        let res = warp::test::request()
+            .method("DELETE")
+            .path("/services/4601")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = warp::test::request()
+            .method("GET")
+            .path("/services/4601")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+    #[tokio::test]
+    async fn test_service_api_with_bad_request() {
+        let filter = path(Arc::new(AdminAuthConfig::default()));
+        let res = warp::test::request()
+            .method("PUT")
+            .path("/services/4602")
+            .body(get_service_body(4603, vec!["service-api-route-4"]))
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        let res = warp::test::request()
+            .method("POST")
+            .path("/services")
+            .body(get_service_body(4602, vec![]))
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert!(get_error_message(&res).contains("could not be empty"));
+        let res = warp::test::request()
+            .method("DELETE")
+            .path("/services/4602/routes/service-api-route-4")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+    #[tokio::test]
+    async fn test_service_api_with_rejection() {
+        let filter = path(Arc::new(AdminAuthConfig::default()));
+        let res = warp::test::request()
+            .method("POST")
+            .path("/services")
+            .body("{")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert!(get_error_message(&res).contains("invalid"));
+        let res = warp::test::request()
+            .method("POST")
+            .path("/services")
+            .body("a".repeat(1024 * 17))
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        get_error_message(&res);
+        let res = warp::test::request()
+            .method("POST")
+            .path("/services")
+            .header("content-type", "text/plain")
+            .body(get_service_body(4604, vec!["service-api-route-5"]))
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        get_error_message(&res);
+        let res = warp::test::request()
+            .method("GET")
+            .path("/services/abc")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        get_error_message(&res);
+        let res = warp::test::request()
+            .method("GET")
+            .path("/services/4604/unknown")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        get_error_message(&res);
+        let res = warp::test::request()
+            .method("PATCH")
+            .path("/services/4604")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        get_error_message(&res);
+        assert!(warp::test::request()
+            .method("GET")
+            .path("/appConfig")
+            .filter(&filter)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::configuration_service::app_config_service::GLOBAL_CONFIG_MAPPING;
+use crate::configuration_service::config_propagation::reconcile_health_check;
+use crate::constants::common_constants::TIMER_WAIT_SECONDS;
+use crate::vojo::app_config::AtomicLivenessStatus;
+use crate::vojo::app_config::Route;
+use crate::vojo::route::DnsRecordType;
+use crate::vojo::route::DnsRoute;
+use crate::vojo::route::LoadbalancerStrategy;
+use futures::FutureExt;
+use lazy_static::lazy_static;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tokio::time::sleep;
+use trust_dns_resolver::config::{ResolverConfig, ResolverOpts};
+use trust_dns_resolver::TokioAsyncResolver;
+use url::Host;
+use url::Url;
+lazy_static! {
+    static ref GLOBAL_DNS_RESOLVER: TokioAsyncResolver =
+        TokioAsyncResolver::tokio_from_system_conf().unwrap_or_else(|err| {
+            error!(
+                "Read the system dns config error,the error is {},and the default config will be used.",
+                err
+            );
+            TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default()).unwrap()
+        });
+}
+pub async fn start_dns_discovery_loop() {
+    loop {
+        let async_result = std::panic::AssertUnwindSafe(do_dns_discovery())
+            .catch_unwind()
+            .await;
+        if async_result.is_err() {
+            error!("start_dns_discovery_loop catch panic successfully!");
+        }
+        sleep(std::time::Duration::from_secs(TIMER_WAIT_SECONDS)).await;
+    }
+}
+async fn do_dns_discovery() {
+    let route_list = GLOBAL_CONFIG_MAPPING
+        .load()
+        .values()
+        .flat_map(|item| item.service_config.routes.clone())
+        .collect::<Vec<Route>>();
+    let mut is_refreshed = false;
+    for route in route_list {
+        if let LoadbalancerStrategy::DnsBased(dns_route) = route.route_cluster {
+            if !dns_route.need_refresh().await {
+                continue;
+            }
+            match refresh_dns_route(&dns_route, route.liveness_status).await {
+                Ok(_) => is_refreshed = true,
+                Err(err) => error!(
+                    "Resolve the endpoint-{} error,the error is {}.",
+                    dns_route.endpoint, err
+                ),
+            }
+        }
+    }
+    if is_refreshed {
+        if let Err(err) = reconcile_health_check().await {
+            error!("Refresh the health check error,the error is {}.", err);
+        }
+    }
+}
+pub async fn refresh_dns_route(
+    dns_route: &DnsRoute,
+    liveness_status: Arc<AtomicLivenessStatus>,
+) -> Result<(), anyhow::Error> {
+    let resolved_endpoints = resolve_endpoints(dns_route).await?;
+    let liveness_count_change = dns_route.update_routes(resolved_endpoints).await;
+    if liveness_count_change != 0 {
+        liveness_status
+            .current_liveness_count
+            .fetch_add(liveness_count_change, Ordering::SeqCst);
+    }
+    Ok(())
+}
+/**
+*Resolve the endpoint to the list of (endpoint,weight).The A records(and AAAA records) share
+ the weight in the config,while the SRV records with the lowest priority use their own weight.
+*/
+pub async fn resolve_endpoints(dns_route: &DnsRoute) -> Result<Vec<(String, i32)>, anyhow::Error> {
+    let (host, port_option) = parse_endpoint(dns_route.endpoint.as_str())?;
+    let mut result: Vec<(String, i32)> = vec![];
+    match dns_route.record_type {
+        DnsRecordType::A => {
+            let port = port_option.ok_or(anyhow!(
+                "The port of the endpoint-{} could not be empty!",
+                dns_route.endpoint
+            ))?;
+            let lookup_ip = GLOBAL_DNS_RESOLVER.lookup_ip(host.as_str()).await?;
+            for ip in lookup_ip.iter() {
+                let endpoint = build_endpoint(dns_route.endpoint.as_str(), ip, port)?;
+                result.push((endpoint, dns_route.weight));
+            }
+        }
+        DnsRecordType::Srv => {
+            let srv_lookup = GLOBAL_DNS_RESOLVER.srv_lookup(host.as_str()).await?;
+            let min_priority = srv_lookup.iter().map(|item| item.priority()).min();
+            for srv in srv_lookup
+                .iter()
+                .filter(|item| Some(item.priority()) == min_priority)
+            {
+                let lookup_ip = GLOBAL_DNS_RESOLVER
+                    .lookup_ip(srv.target().to_utf8())
+                    .await?;
+                for ip in lookup_ip.iter() {
+                    let endpoint = build_endpoint(dns_route.endpoint.as_str(), ip, srv.port())?;
+                    result.push((endpoint, std::cmp::max(srv.weight() as i32, 1)));
+                }
+            }
+        }
+    }
+    if result.is_empty() {
+        return Err(anyhow!(
+            "Can not resolve any address from the endpoint-{}!",
+            dns_route.endpoint
+        ));
+    }
+    Ok(result)
+}
+fn parse_endpoint(endpoint: &str) -> Result<(String, Option<u16>), anyhow::Error> {
+    if endpoint.contains("://") {
+        let url = Url::parse(endpoint)?;
+        let host = match url.host() {
+            Some(Host::Domain(domain)) => domain.to_string(),
+            Some(Host::Ipv4(ip)) => ip.to_string(),
+            Some(Host::Ipv6(ip)) => ip.to_string(),
+            None => return Err(anyhow!("The endpoint-{} has no host!", endpoint)),
+        };
+        return Ok((host, url.port_or_known_default()));
+    }
+    match endpoint.rsplit_once(':') {
+        Some((host, port)) => Ok((host.to_string(), Some(port.parse::<u16>()?))),
+        None => Ok((endpoint.to_string(), None)),
+    }
+}
+fn build_endpoint(endpoint: &str, ip: IpAddr, port: u16) -> Result<String, anyhow::Error> {
+    if endpoint.contains("://") {
+        let mut url = Url::parse(endpoint)?;
+        url.set_ip_host(ip)
+            .map_err(|_| anyhow!("Can not set the ip of the endpoint-{}!", endpoint))?;
+        url.set_port(Some(port))
+            .map_err(|_| anyhow!("Can not set the port of the endpoint-{}!", endpoint))?;
+        return Ok(url.to_string());
+    }
+    Ok(SocketAddr::new(ip, port).to_string())
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vojo::app_config::LivenessStatus;
+    use std::net::Ipv6Addr;
+    #[test]
+    fn test_parse_endpoint_ok() {
+        let (host, port) = parse_endpoint("http://backend.internal:8080/api").unwrap();
+        assert_eq!(host, "backend.internal");
+        assert_eq!(port, Some(8080));
+        let (host, port) = parse_endpoint("https://backend.internal").unwrap();
+        assert_eq!(host, "backend.internal");
+        assert_eq!(port, Some(443));
+        let (host, port) = parse_endpoint("backend.internal:3306").unwrap();
+        assert_eq!(host, "backend.internal");
+        assert_eq!(port, Some(3306));
+        let (host, port) = parse_endpoint("_grpc._tcp.backend.internal").unwrap();
+        assert_eq!(host, "_grpc._tcp.backend.internal");
+        assert_eq!(port, None);
+    }
+    #[test]
+    fn test_build_endpoint_ok() {
+        let endpoint = build_endpoint(
+            "http://backend.internal:8080/api",
+            "10.0.0.1".parse().unwrap(),
+            9090,
+        )
+        .unwrap();
+        assert_eq!(endpoint, "http://10.0.0.1:9090/api");
+        let endpoint = build_endpoint(
+            "http://backend.internal:8080",
+            IpAddr::V6(Ipv6Addr::LOCALHOST),
+            8080,
+        )
+        .unwrap();
+        assert_eq!(endpoint, "http://[::1]:8080/");
+        let endpoint =
+            build_endpoint("backend.internal:3306", "10.0.0.1".parse().unwrap(), 3306).unwrap();
+        assert_eq!(endpoint, "10.0.0.1:3306");
+    }
+    #[tokio::test]
+    async fn test_refresh_dns_route_ok() {
+        let dns_route = DnsRoute {
+            endpoint: String::from("http://127.0.0.1:8080"),
+            weight: 50,
+            refresh_interval: 30,
+            ..Default::default()
+        };
+        let liveness_status = Arc::new(AtomicLivenessStatus::from(LivenessStatus {
+            current_liveness_count: 0,
+        }));
+        assert!(dns_route.need_refresh().await);
+        let res = refresh_dns_route(&dns_route, liveness_status.clone()).await;
+        assert!(res.is_ok());
+        assert!(!dns_route.need_refresh().await);
+        let routes = dns_route.cluster.routes.load();
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].base_route.endpoint, "http://127.0.0.1:8080/");
+        assert_eq!(routes[0].weight, 50);
+        assert_eq!(liveness_status.load().current_liveness_count, 1);
+    }
+    #[tokio::test]
+    async fn test_resolve_endpoints_without_port() {
+        let dns_route = DnsRoute {
+            endpoint: String::from("127.0.0.1"),
+            ..Default::default()
+        };
+        assert!(resolve_endpoints(&dns_route).await.is_err());
+    }
+}
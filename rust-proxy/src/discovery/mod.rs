@@ -0,0 +1,2 @@
+pub mod dns_discovery;
+pub mod service_registry;
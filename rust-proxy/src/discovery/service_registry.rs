@@ -0,0 +1,224 @@
+use crate::configuration_service::app_config_service::GLOBAL_CONFIG_MAPPING;
+use crate::configuration_service::config_propagation::reconcile_health_check;
+use crate::constants::common_constants::TIMER_WAIT_SECONDS;
+use crate::vojo::app_config::Route;
+use crate::vojo::route::LoadbalancerStrategy;
+use crate::vojo::service_registry::RegistryInstanceVistor;
+use dashmap::DashMap;
+use futures::FutureExt;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use tokio::time::{sleep, Duration, Instant};
+lazy_static! {
+    static ref GLOBAL_SERVICE_REGISTRY: DashMap<String, HashMap<String, RegistryInstance>> =
+        Default::default();
+}
+#[derive(Debug, Clone)]
+struct RegistryInstance {
+    instance: RegistryInstanceVistor,
+    expire_time: Instant,
+}
+impl RegistryInstance {
+    fn new(instance: RegistryInstanceVistor) -> Self {
+        let expire_time = Instant::now() + Duration::from_secs(instance.ttl);
+        RegistryInstance {
+            instance,
+            expire_time,
+        }
+    }
+}
+/**
+*Register the instance,whose weight and ttl should be positive,otherwise it could never be
+ selected or it would expire at once.
+*/
+pub fn register_instance(
+    cluster_name: &str,
+    instance: RegistryInstanceVistor,
+) -> Result<RegistryInstanceVistor, anyhow::Error> {
+    if instance.weight <= 0 {
+        return Err(anyhow!(
+            "The weight of the instance-{} should be greater than 0!",
+            instance.instance_id
+        ));
+    }
+    if instance.ttl == 0 {
+        return Err(anyhow!(
+            "The ttl of the instance-{} should be greater than 0!",
+            instance.instance_id
+        ));
+    }
+    info!(
+        "Register the instance-{} with the endpoint-{} into the cluster-{}.",
+        instance.instance_id, instance.endpoint, cluster_name
+    );
+    GLOBAL_SERVICE_REGISTRY
+        .entry(cluster_name.to_string())
+        .or_default()
+        .insert(
+            instance.instance_id.clone(),
+            RegistryInstance::new(instance.clone()),
+        );
+    Ok(instance)
+}
+pub fn heartbeat_instance(
+    cluster_name: &str,
+    instance_id: &str,
+) -> Result<RegistryInstanceVistor, anyhow::Error> {
+    let mut cluster = GLOBAL_SERVICE_REGISTRY
+        .get_mut(cluster_name)
+        .ok_or(anyhow!("Can not find the cluster-{}!", cluster_name))?;
+    let registry_instance = cluster.get_mut(instance_id).ok_or(anyhow!(
+        "Can not find the instance-{} in the cluster-{}!",
+        instance_id,
+        cluster_name
+    ))?;
+    *registry_instance = RegistryInstance::new(registry_instance.instance.clone());
+    Ok(registry_instance.instance.clone())
+}
+pub fn deregister_instance(
+    cluster_name: &str,
+    instance_id: &str,
+) -> Result<RegistryInstanceVistor, anyhow::Error> {
+    let mut cluster = GLOBAL_SERVICE_REGISTRY
+        .get_mut(cluster_name)
+        .ok_or(anyhow!("Can not find the cluster-{}!", cluster_name))?;
+    let registry_instance = cluster.remove(instance_id).ok_or(anyhow!(
+        "Can not find the instance-{} in the cluster-{}!",
+        instance_id,
+        cluster_name
+    ))?;
+    drop(cluster);
+    GLOBAL_SERVICE_REGISTRY.remove_if(cluster_name, |_, cluster| cluster.is_empty());
+    info!(
+        "Deregister the instance-{} from the cluster-{}.",
+        instance_id, cluster_name
+    );
+    Ok(registry_instance.instance)
+}
+pub fn list_instances(cluster_name: &str) -> Vec<RegistryInstanceVistor> {
+    let mut result = GLOBAL_SERVICE_REGISTRY
+        .get(cluster_name)
+        .map(|cluster| {
+            cluster
+                .values()
+                .map(|item| item.instance.clone())
+                .collect::<Vec<RegistryInstanceVistor>>()
+        })
+        .unwrap_or_default();
+    result.sort_by(|a, b| a.instance_id.cmp(&b.instance_id));
+    result
+}
+fn remove_expired_instances() {
+    let now = Instant::now();
+    for mut cluster in GLOBAL_SERVICE_REGISTRY.iter_mut() {
+        let cluster_name = cluster.key().clone();
+        cluster.value_mut().retain(|instance_id, item| {
+            if item.expire_time > now {
+                return true;
+            }
+            info!(
+                "The instance-{} in the cluster-{} has expired.",
+                instance_id, cluster_name
+            );
+            false
+        });
+    }
+    GLOBAL_SERVICE_REGISTRY.retain(|_, cluster| !cluster.is_empty());
+}
+/**
+*Update the routes which refer to the cluster by name with the instances in the registry.
+*/
+pub async fn sync_registry_routes() {
+    let route_list = GLOBAL_CONFIG_MAPPING
+        .load()
+        .values()
+        .flat_map(|item| item.service_config.routes.clone())
+        .collect::<Vec<Route>>();
+    let mut has_registry_route = false;
+    for route in route_list {
+        if let LoadbalancerStrategy::RegistryBased(registry_route) = route.route_cluster {
+            has_registry_route = true;
+            let endpoints = list_instances(registry_route.cluster_name.as_str())
+                .into_iter()
+                .map(|item| (item.endpoint, item.weight))
+                .collect::<Vec<(String, i32)>>();
+            let liveness_count_change = registry_route.cluster.update_routes(endpoints).await;
+            if liveness_count_change != 0 {
+                route
+                    .liveness_status
+                    .current_liveness_count
+                    .fetch_add(liveness_count_change, Ordering::SeqCst);
+            }
+        }
+    }
+    if has_registry_route {
+        if let Err(err) = reconcile_health_check().await {
+            error!("Refresh the health check error,the error is {}.", err);
+        }
+    }
+}
+pub async fn start_service_registry_loop() {
+    loop {
+        remove_expired_instances();
+        let async_result = std::panic::AssertUnwindSafe(sync_registry_routes())
+            .catch_unwind()
+            .await;
+        if async_result.is_err() {
+            error!("start_service_registry_loop catch panic successfully!");
+        }
+        sleep(std::time::Duration::from_secs(TIMER_WAIT_SECONDS)).await;
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn get_instance(instance_id: &str, ttl: u64) -> RegistryInstanceVistor {
+        RegistryInstanceVistor {
+            instance_id: String::from(instance_id),
+            endpoint: format!("http://127.0.0.1:190{}", instance_id),
+            weight: 100,
+            ttl,
+        }
+    }
+    #[tokio::test]
+    async fn test_register_instance_ok() {
+        let cluster_name = "test_register_instance_ok";
+        assert!(register_instance(cluster_name, get_instance("31", 30)).is_ok());
+        assert!(register_instance(cluster_name, get_instance("30", 30)).is_ok());
+        assert!(register_instance(cluster_name, get_instance("35", 0)).is_err());
+        let without_weight = RegistryInstanceVistor {
+            weight: 0,
+            ..get_instance("36", 30)
+        };
+        assert!(register_instance(cluster_name, without_weight).is_err());
+        let instances = list_instances(cluster_name);
+        assert_eq!(instances.len(), 2);
+        assert_eq!(instances[0].instance_id, "30");
+
+        assert!(heartbeat_instance(cluster_name, "30").is_ok());
+        assert!(heartbeat_instance(cluster_name, "32").is_err());
+
+        assert!(deregister_instance(cluster_name, "30").is_ok());
+        assert!(deregister_instance(cluster_name, "30").is_err());
+        assert!(deregister_instance(cluster_name, "31").is_ok());
+        assert!(list_instances(cluster_name).is_empty());
+        assert!(heartbeat_instance(cluster_name, "31").is_err());
+    }
+    #[tokio::test]
+    async fn test_remove_expired_instances_ok() {
+        let cluster_name = "test_remove_expired_instances_ok";
+        register_instance(cluster_name, get_instance("33", 30)).unwrap();
+        register_instance(cluster_name, get_instance("34", 30)).unwrap();
+        GLOBAL_SERVICE_REGISTRY
+            .get_mut(cluster_name)
+            .unwrap()
+            .get_mut("33")
+            .unwrap()
+            .expire_time = Instant::now();
+        remove_expired_instances();
+        let instances = list_instances(cluster_name);
+        assert_eq!(instances.len(), 1);
+        assert_eq!(instances[0].instance_id, "34");
+    }
+}
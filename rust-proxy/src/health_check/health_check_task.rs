@@ -1,5 +1,4 @@
 use crate::configuration_service::app_config_service::GLOBAL_CONFIG_MAPPING;
-use crate::constants::common_constants::TIMER_WAIT_SECONDS;
 use crate::proxy::http1::http_client::HttpClients;
 use crate::vojo::app_config::Route;
 use crate::vojo::health_check::HealthCheckType;
@@ -7,7 +6,6 @@ use crate::vojo::health_check::HttpHealthCheckParam;
 use delay_timer::prelude::*;
 use futures;
 use futures::future::join_all;
-use futures::FutureExt;
 use http::Request;
 use http::StatusCode;
 use hyper::Body;
@@ -18,7 +16,6 @@ use url::Url;
 
 use std::sync::Arc;
 use tokio::task::JoinSet;
-use tokio::time::sleep;
 
 #[derive(Clone)]
 pub struct HealthCheckClient {
@@ -53,7 +50,7 @@ impl TaskKey {
         }
     }
 }
-async fn get_endpoint_list(mut route: Route) -> Vec<String> {
+async fn get_endpoint_list(route: Route) -> Vec<String> {
     let mut result = vec![];
     let base_route_list = route.route_cluster.get_all_route().await.unwrap_or(vec![]);
     for item in base_route_list {
@@ -76,21 +73,13 @@ impl HealthCheck {
             current_id: Arc::new(AtomicU64::new(0)),
         }
     }
-    pub async fn start_health_check_loop(&mut self) {
-        loop {
-            let async_result = std::panic::AssertUnwindSafe(self.do_health_check())
-                .catch_unwind()
-                .await;
-            if async_result.is_err() {
-                error!("start_health_check_loop catch panic successfully!");
-            }
-            sleep(std::time::Duration::from_secs(TIMER_WAIT_SECONDS)).await;
-        }
-    }
-
-    async fn do_health_check(&mut self) -> Result<(), anyhow::Error> {
-        let handles = GLOBAL_CONFIG_MAPPING
-            .iter()
+    /**
+     *Sync the health check tasks with the routes in the global config mapping.
+     */
+    pub async fn do_health_check(&mut self) -> Result<(), anyhow::Error> {
+        let config_mapping = GLOBAL_CONFIG_MAPPING.load();
+        let handles = config_mapping
+            .values()
             .flat_map(|item| item.service_config.routes.clone())
             .filter(|item| item.health_check.is_some() && item.liveness_config.is_some())
             .map(|item| {
@@ -158,7 +147,7 @@ impl HealthCheck {
 
 async fn do_http_health_check(
     http_health_check_param: HttpHealthCheckParam,
-    mut route: Route,
+    route: Route,
     timeout_number: i32,
     http_health_check_client: HealthCheckClient,
 ) -> Result<(), anyhow::Error> {
@@ -277,6 +266,7 @@ fn submit_task(
 mod tests {
     use super::*;
     use crate::vojo::api_service_manager::ApiServiceManager;
+    use crate::vojo::app_config::AtomicLivenessStatus;
     use crate::vojo::app_config::LivenessConfig;
     use crate::vojo::app_config::LivenessStatus;
     use crate::vojo::app_config::Matcher;
@@ -284,13 +274,15 @@ mod tests {
     use crate::vojo::health_check::BaseHealthCheckParam;
     use crate::vojo::route::AnomalyDetectionStatus;
     use crate::vojo::route::LoadbalancerStrategy;
+    use crate::vojo::route::{AtomicAliveStatus, AtomicAnomalyDetectionStatus};
     use crate::vojo::route::{BaseRoute, WeightBasedRoute, WeightRoute};
+    use arc_swap::ArcSwap;
     use lazy_static::lazy_static;
     use std::sync::atomic::AtomicIsize;
     use std::sync::Arc;
     use std::time::Duration;
     use tokio::runtime::{Builder, Runtime};
-    use tokio::sync::RwLock;
+    use tokio::time::sleep;
 
     use uuid::Uuid;
     lazy_static! {
@@ -310,20 +302,22 @@ mod tests {
             host_name: None,
             route_id: id.to_string(),
             route_cluster: LoadbalancerStrategy::WeightBased(WeightBasedRoute {
-                routes: Arc::new(RwLock::new(vec![WeightRoute {
+                routes: Arc::new(ArcSwap::from_pointee(vec![WeightRoute {
                     base_route: BaseRoute {
                         endpoint: String::from("/"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     },
                     weight: 100,
                     index: Arc::new(AtomicIsize::new(0)),
                 }])),
             }),
-            liveness_status: Arc::new(RwLock::new(LivenessStatus {
+            liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                 current_liveness_count: 0,
             })),
             anomaly_detection: None,
@@ -334,6 +328,12 @@ mod tests {
 
             authentication: None,
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -350,14 +350,16 @@ mod tests {
             host_name: None,
             route_id: id.to_string(),
             route_cluster: LoadbalancerStrategy::WeightBased(WeightBasedRoute {
-                routes: Arc::new(RwLock::new(vec![WeightRoute {
+                routes: Arc::new(ArcSwap::from_pointee(vec![WeightRoute {
                     base_route: BaseRoute {
                         endpoint: String::from("/"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     },
                     weight: 100,
                     index: Arc::new(AtomicIsize::new(0)),
@@ -370,7 +372,7 @@ mod tests {
                 },
                 path: String::from("value"),
             })),
-            liveness_status: Arc::new(RwLock::new(LivenessStatus {
+            liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                 current_liveness_count: 0,
             })),
             anomaly_detection: None,
@@ -380,6 +382,12 @@ mod tests {
             allow_deny_list: None,
             authentication: None,
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -401,14 +409,16 @@ mod tests {
             host_name: None,
             route_id: id.to_string(),
             route_cluster: LoadbalancerStrategy::WeightBased(WeightBasedRoute {
-                routes: Arc::new(RwLock::new(vec![WeightRoute {
+                routes: Arc::new(ArcSwap::from_pointee(vec![WeightRoute {
                     base_route: BaseRoute {
                         endpoint: String::from("/"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     },
                     weight: 100,
                     index: Arc::new(AtomicIsize::new(0)),
@@ -425,12 +435,18 @@ mod tests {
             liveness_config: None,
             rewrite_headers: None,
 
-            liveness_status: Arc::new(RwLock::new(LivenessStatus {
+            liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                 current_liveness_count: 0,
             })),
             allow_deny_list: None,
             authentication: None,
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -440,6 +456,12 @@ mod tests {
             sender,
             service_config: ServiceConfig {
                 key_str: None,
+                key_passphrase: None,
+                certificates: vec![],
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
                 server_type: crate::vojo::app_config::ServiceType::Https,
                 cert_str: None,
                 routes: vec![route],
@@ -465,14 +487,16 @@ mod tests {
             host_name: None,
             route_id: id.to_string(),
             route_cluster: LoadbalancerStrategy::WeightBased(WeightBasedRoute {
-                routes: Arc::new(RwLock::new(vec![WeightRoute {
+                routes: Arc::new(ArcSwap::from_pointee(vec![WeightRoute {
                     base_route: BaseRoute {
                         endpoint: String::from("http://httpbin.org/"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     },
                     weight: 100,
                     index: Arc::new(AtomicIsize::new(0)),
@@ -490,11 +514,17 @@ mod tests {
             liveness_config: None,
             rewrite_headers: None,
 
-            liveness_status: Arc::new(RwLock::new(LivenessStatus {
+            liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                 current_liveness_count: 0,
             })),
             authentication: None,
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -504,6 +534,12 @@ mod tests {
             sender,
             service_config: ServiceConfig {
                 key_str: None,
+                key_passphrase: None,
+                certificates: vec![],
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
                 server_type: crate::vojo::app_config::ServiceType::Https,
                 cert_str: None,
                 routes: vec![route],
@@ -531,14 +567,16 @@ mod tests {
             host_name: None,
             route_id: id.to_string(),
             route_cluster: LoadbalancerStrategy::WeightBased(WeightBasedRoute {
-                routes: Arc::new(RwLock::new(vec![WeightRoute {
+                routes: Arc::new(ArcSwap::from_pointee(vec![WeightRoute {
                     base_route: BaseRoute {
                         endpoint: String::from("http://127.0.0.1:9394/"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     },
                     weight: 100,
                     index: Arc::new(AtomicIsize::new(0)),
@@ -558,11 +596,17 @@ mod tests {
             liveness_config: Some(LivenessConfig {
                 min_liveness_count: 3,
             }),
-            liveness_status: Arc::new(RwLock::new(LivenessStatus {
+            liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                 current_liveness_count: 0,
             })),
             authentication: None,
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -572,6 +616,12 @@ mod tests {
             sender,
             service_config: ServiceConfig {
                 key_str: None,
+                key_passphrase: None,
+                certificates: vec![],
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
                 server_type: crate::vojo::app_config::ServiceType::Https,
                 cert_str: None,
                 routes: vec![route],
@@ -605,14 +655,16 @@ mod tests {
             host_name: None,
             route_id: id.to_string(),
             route_cluster: LoadbalancerStrategy::WeightBased(WeightBasedRoute {
-                routes: Arc::new(RwLock::new(vec![WeightRoute {
+                routes: Arc::new(ArcSwap::from_pointee(vec![WeightRoute {
                     base_route: BaseRoute {
                         endpoint: String::from("/"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     },
                     weight: 100,
                     index: Arc::new(AtomicIsize::new(0)),
@@ -626,7 +678,7 @@ mod tests {
                 path: String::from("value"),
             })),
             liveness_config: None,
-            liveness_status: Arc::new(RwLock::new(LivenessStatus {
+            liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                 current_liveness_count: 0,
             })),
             rewrite_headers: None,
@@ -635,6 +687,12 @@ mod tests {
             allow_deny_list: None,
             authentication: None,
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -663,14 +721,16 @@ mod tests {
             host_name: None,
             route_id: id.to_string(),
             route_cluster: LoadbalancerStrategy::WeightBased(WeightBasedRoute {
-                routes: Arc::new(RwLock::new(vec![WeightRoute {
+                routes: Arc::new(ArcSwap::from_pointee(vec![WeightRoute {
                     base_route: BaseRoute {
                         endpoint: String::from("/"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     },
                     weight: 100,
                     index: Arc::new(AtomicIsize::new(0)),
@@ -689,10 +749,16 @@ mod tests {
             liveness_config: None,
             rewrite_headers: None,
 
-            liveness_status: Arc::new(RwLock::new(LivenessStatus {
+            liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                 current_liveness_count: 0,
             })),
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -721,14 +787,16 @@ mod tests {
             host_name: None,
             route_id: id.to_string(),
             route_cluster: LoadbalancerStrategy::WeightBased(WeightBasedRoute {
-                routes: Arc::new(RwLock::new(vec![WeightRoute {
+                routes: Arc::new(ArcSwap::from_pointee(vec![WeightRoute {
                     base_route: BaseRoute {
                         endpoint: String::from("/"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     },
                     weight: 100,
                     index: Arc::new(AtomicIsize::new(0)),
@@ -741,7 +809,7 @@ mod tests {
                 },
                 path: String::from("/"),
             })),
-            liveness_status: Arc::new(RwLock::new(LivenessStatus {
+            liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                 current_liveness_count: 0,
             })),
             anomaly_detection: None,
@@ -751,6 +819,12 @@ mod tests {
 
             liveness_config: None,
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -8,14 +8,19 @@ static GLOBAL: Jemalloc = Jemalloc;
 #[macro_use]
 extern crate anyhow;
 extern crate derive_builder;
+mod acme;
+mod cli;
 mod configuration_service;
 mod constants;
 mod control_plane;
+mod discovery;
 mod health_check;
+mod maintenance;
 mod monitor;
 mod proxy;
 mod utils;
 mod vojo;
+use crate::cli::command::{run_validate_command, Cli, Commands, DEFAULT_CONFIG};
 use crate::constants::common_constants::DEFAULT_ADMIN_PORT;
 use crate::constants::common_constants::ENV_ADMIN_PORT;
 use std::env;
@@ -23,9 +28,15 @@ use std::env;
 extern crate log;
 use crate::control_plane::rest_api::start_control_plane;
 
+use clap::Parser;
 use tokio::runtime;
 
 fn main() {
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or_default();
+    if let Commands::Run(run_args) = &command {
+        run_args.apply_to_env();
+    }
     let num = num_cpus::get();
     let rt = runtime::Builder::new_multi_thread()
         .worker_threads(num * 2)
@@ -33,13 +44,20 @@ fn main() {
         .build()
         .unwrap();
 
-    rt.block_on(async {
-        let admin_port: i32 = env::var(ENV_ADMIN_PORT)
-            .unwrap_or(String::from(DEFAULT_ADMIN_PORT))
-            .parse()
-            .unwrap();
-        start(admin_port).await
-    });
+    match command {
+        Commands::Run(_) => rt.block_on(async {
+            let admin_port: i32 = env::var(ENV_ADMIN_PORT)
+                .unwrap_or(String::from(DEFAULT_ADMIN_PORT))
+                .parse()
+                .unwrap();
+            start(admin_port).await
+        }),
+        Commands::Validate { file } => {
+            let exit_code = rt.block_on(run_validate_command(file));
+            std::process::exit(exit_code);
+        }
+        Commands::PrintDefaultConfig => print!("{}", DEFAULT_CONFIG),
+    }
 }
 async fn start(admin_port: i32) {
     configuration_service::app_config_service::init().await;
@@ -0,0 +1,156 @@
+use crate::vojo::maintenance::EndpointStatus;
+use crate::vojo::maintenance::EndpointStatusResponse;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tokio::time::{timeout_at, Duration, Instant};
+lazy_static! {
+    static ref GLOBAL_ENDPOINT_STATUS: DashMap<String, EndpointStatus> = Default::default();
+    static ref GLOBAL_IN_FLIGHT: DashMap<String, Arc<AtomicI64>> = Default::default();
+    static ref DRAINED_NOTIFY: Notify = Notify::new();
+}
+/**
+*The endpoint status is kept outside of the app config,so that it survives
+ every config reload until it is set back to active.
+*/
+pub fn set_endpoint_status(endpoint: String, status: EndpointStatus) {
+    info!("Set the status of endpoint-{} to {}.", endpoint, status);
+    if status == EndpointStatus::Active {
+        GLOBAL_ENDPOINT_STATUS.remove(&endpoint);
+    } else {
+        GLOBAL_ENDPOINT_STATUS.insert(endpoint, status);
+    }
+}
+pub fn get_endpoint_status(endpoint: &str) -> EndpointStatus {
+    GLOBAL_ENDPOINT_STATUS
+        .get(endpoint)
+        .map(|item| *item.value())
+        .unwrap_or_default()
+}
+pub fn is_accepting_traffic(endpoint: &str) -> bool {
+    if GLOBAL_ENDPOINT_STATUS.is_empty() {
+        return true;
+    }
+    !GLOBAL_ENDPOINT_STATUS.contains_key(endpoint)
+}
+pub fn get_in_flight(endpoint: &str) -> i64 {
+    GLOBAL_IN_FLIGHT
+        .get(endpoint)
+        .map(|item| item.load(Ordering::SeqCst))
+        .unwrap_or(0)
+}
+pub fn get_endpoint_status_response(endpoint: String) -> EndpointStatusResponse {
+    let status = get_endpoint_status(endpoint.as_str());
+    let in_flight = get_in_flight(endpoint.as_str());
+    EndpointStatusResponse {
+        drained: status != EndpointStatus::Active && in_flight == 0,
+        endpoint,
+        status,
+        in_flight,
+    }
+}
+pub fn list_endpoint_status() -> Vec<EndpointStatusResponse> {
+    let mut result = GLOBAL_ENDPOINT_STATUS
+        .iter()
+        .map(|item| item.key().clone())
+        .map(get_endpoint_status_response)
+        .collect::<Vec<EndpointStatusResponse>>();
+    result.sort_by(|a, b| a.endpoint.cmp(&b.endpoint));
+    result
+}
+pub struct InFlightGuard {
+    endpoint: String,
+    counter: Arc<AtomicI64>,
+}
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let old_value = self.counter.fetch_sub(1, Ordering::SeqCst);
+        if old_value == 1 && !is_accepting_traffic(self.endpoint.as_str()) {
+            info!(
+                "The in-flight work on endpoint-{} has reached zero.",
+                self.endpoint
+            );
+            DRAINED_NOTIFY.notify_waiters();
+        }
+    }
+}
+pub fn track_in_flight(endpoint: &str) -> InFlightGuard {
+    let counter = match GLOBAL_IN_FLIGHT.get(endpoint) {
+        Some(counter) => counter.clone(),
+        None => GLOBAL_IN_FLIGHT
+            .entry(endpoint.to_string())
+            .or_default()
+            .clone(),
+    };
+    counter.fetch_add(1, Ordering::SeqCst);
+    InFlightGuard {
+        endpoint: endpoint.to_string(),
+        counter,
+    }
+}
+pub async fn wait_for_drained(endpoint: &str, timeout_seconds: u64) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(timeout_seconds);
+    loop {
+        let notified = DRAINED_NOTIFY.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+        if get_in_flight(endpoint) == 0 {
+            return true;
+        }
+        if timeout_at(deadline, notified).await.is_err() {
+            return get_in_flight(endpoint) == 0;
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_set_endpoint_status_ok() {
+        let endpoint = String::from("http://127.0.0.1:19001");
+        assert!(is_accepting_traffic(endpoint.as_str()));
+        set_endpoint_status(endpoint.clone(), EndpointStatus::Draining);
+        assert!(!is_accepting_traffic(endpoint.as_str()));
+        assert_eq!(
+            get_endpoint_status(endpoint.as_str()),
+            EndpointStatus::Draining
+        );
+        set_endpoint_status(endpoint.clone(), EndpointStatus::Active);
+        assert!(is_accepting_traffic(endpoint.as_str()));
+    }
+    #[test]
+    fn test_track_in_flight_ok() {
+        let endpoint = "http://127.0.0.1:19002";
+        let guard1 = track_in_flight(endpoint);
+        let guard2 = track_in_flight(endpoint);
+        assert_eq!(get_in_flight(endpoint), 2);
+        drop(guard1);
+        assert_eq!(get_in_flight(endpoint), 1);
+        drop(guard2);
+        assert_eq!(get_in_flight(endpoint), 0);
+    }
+    #[tokio::test]
+    async fn test_wait_for_drained_ok() {
+        let endpoint = String::from("http://127.0.0.1:19003");
+        set_endpoint_status(endpoint.clone(), EndpointStatus::Draining);
+        let guard = track_in_flight(endpoint.as_str());
+        let response = get_endpoint_status_response(endpoint.clone());
+        assert_eq!(response.in_flight, 1);
+        assert!(!response.drained);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            drop(guard);
+        });
+        assert!(wait_for_drained(endpoint.as_str(), 5).await);
+        assert!(get_endpoint_status_response(endpoint.clone()).drained);
+        set_endpoint_status(endpoint, EndpointStatus::Active);
+    }
+    #[tokio::test]
+    async fn test_wait_for_drained_timeout() {
+        let endpoint = "http://127.0.0.1:19004";
+        let _guard = track_in_flight(endpoint);
+        assert!(!wait_for_drained(endpoint, 1).await);
+    }
+}
@@ -1,6 +1,8 @@
 use lazy_static::lazy_static;
-use prometheus::{labels, opts, register_counter_vec, register_gauge, register_histogram_vec};
-use prometheus::{CounterVec, Gauge, Histogram, HistogramVec};
+use prometheus::{
+    labels, opts, register_counter_vec, register_gauge, register_gauge_vec, register_histogram_vec,
+};
+use prometheus::{CounterVec, Gauge, GaugeVec, Histogram, HistogramVec};
 
 lazy_static! {
     static ref HTTP_COUNTER: CounterVec = register_counter_vec!(
@@ -23,6 +25,36 @@ lazy_static! {
         &["port", "request_path"]
     )
     .unwrap();
+    static ref TLS_HANDSHAKE_COUNTER: CounterVec = register_counter_vec!(
+        opts!(
+            "silverwind_tls_handshakes_total",
+            "Number of TLS handshakes by the negotiated protocol version and cipher suite.",
+        ),
+        &["port", "version", "cipher_suite"]
+    )
+    .unwrap();
+    static ref GRPC_COUNTER: CounterVec = register_counter_vec!(
+        opts!(
+            "silverwind_grpc_requests_total",
+            "Number of gRPC requests by the service,method and grpc-status.",
+        ),
+        &["port", "grpc_service", "grpc_method", "grpc_status"]
+    )
+    .unwrap();
+    static ref GRPC_REQ_HISTOGRAM: HistogramVec = register_histogram_vec!(
+        "silverwind_grpc_request_duration_seconds",
+        "The gRPC request latencies in seconds.",
+        &["port", "grpc_service", "grpc_method"]
+    )
+    .unwrap();
+    static ref CERTIFICATE_EXPIRY_GAUGE: GaugeVec = register_gauge_vec!(
+        opts!(
+            "silverwind_certificate_expiry_timestamp_seconds",
+            "The unix timestamp when the managed certificate expires.",
+        ),
+        &["domains"]
+    )
+    .unwrap();
 }
 pub fn inc(key: String, path: String, code: u16) {
     HTTP_COUNTER
@@ -40,3 +72,21 @@ pub fn get_timer_list(key: String, path: String) -> Vec<Histogram> {
         HTTP_REQ_HISTOGRAM.with_label_values(&["all", "all"]),
     ]
 }
+pub fn inc_tls_handshake(port: u16, version: &str, cipher_suite: &str) {
+    TLS_HANDSHAKE_COUNTER
+        .with_label_values(&[port.to_string().as_str(), version, cipher_suite])
+        .inc();
+}
+pub fn inc_grpc(key: &str, service: &str, method: &str, grpc_status: &str) {
+    GRPC_COUNTER
+        .with_label_values(&[key, service, method, grpc_status])
+        .inc();
+}
+pub fn get_grpc_timer(key: &str, service: &str, method: &str) -> Histogram {
+    GRPC_REQ_HISTOGRAM.with_label_values(&[key, service, method])
+}
+pub fn set_certificate_expiry(domains: &str, expiry_timestamp: i64) {
+    CERTIFICATE_EXPIRY_GAUGE
+        .with_label_values(&[domains])
+        .set(expiry_timestamp as f64);
+}
@@ -0,0 +1,356 @@
+/**
+*Compare the request path of the data plane against the design before the lock-free snapshots.
+ The legacy design cloned the api service from the DashMap for every request,and read the
+ cluster and the liveness of every endpoint through the RwLock.Run them with:
+ cargo test --release data_plane_benchmark -- --ignored --nocapture --test-threads=1
+*/
+use crate::vojo::api_service_manager::{ApiServiceManager, ConfigMapping};
+use crate::vojo::app_config::{
+    AtomicLivenessStatus, LivenessStatus, Matcher, Route, ServiceConfig, ServiceType,
+};
+use crate::vojo::route::{
+    AtomicAliveStatus, BaseRoute, LoadbalancerStrategy, WeightBasedRoute, WeightRoute,
+};
+use arc_swap::ArcSwap;
+use dashmap::DashMap;
+use http::{HeaderMap, HeaderValue};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::{Builder, Runtime};
+use tokio::sync::{mpsc, RwLock};
+
+const MAPPING_KEY: &str = "8084-HTTP";
+const REQUEST_PATH: &str = "/api7/users";
+const ROUTE_COUNT: usize = 8;
+const ENDPOINT_COUNT: usize = 3;
+const TASK_COUNT: usize = 8;
+const ITERATIONS_PER_TASK: usize = 50_000;
+
+#[derive(Clone)]
+struct LegacyWeightRoute {
+    endpoint: String,
+    weight: i32,
+    index: Arc<AtomicIsize>,
+    is_alive: Arc<RwLock<Option<bool>>>,
+}
+#[derive(Clone)]
+struct LegacyRoute {
+    route: Route,
+    cluster: Arc<RwLock<Vec<LegacyWeightRoute>>>,
+}
+#[derive(Clone)]
+struct LegacyApiServiceManager {
+    routes: Vec<LegacyRoute>,
+}
+
+fn endpoint(route_index: usize, endpoint_index: usize) -> String {
+    format!("http://10.0.{}.{}:8080/", route_index, endpoint_index)
+}
+fn new_route(route_index: usize) -> Route {
+    let routes = (0..ENDPOINT_COUNT)
+        .map(|endpoint_index| WeightRoute {
+            base_route: BaseRoute {
+                endpoint: endpoint(route_index, endpoint_index),
+                is_alive: Arc::new(AtomicAliveStatus::new(Some(true))),
+                ..Default::default()
+            },
+            weight: 100,
+            index: Arc::new(AtomicIsize::new(100)),
+        })
+        .collect::<Vec<WeightRoute>>();
+    Route {
+        route_id: format!("route-{}", route_index),
+        host_name: None,
+        matcher: Some(Matcher {
+            prefix: format!("/api{}/", route_index),
+            prefix_rewrite: String::from("/"),
+        }),
+        allow_deny_list: None,
+        authentication: None,
+        anomaly_detection: None,
+        liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
+            current_liveness_count: ENDPOINT_COUNT as i32,
+        })),
+        rewrite_headers: None,
+        liveness_config: None,
+        health_check: None,
+        ratelimit: None,
+        server_names: None,
+        client_identities: None,
+        upstream_tls: None,
+        grpc_web: None,
+        grpc_json_transcoder: None,
+        grpc_matcher: None,
+        route_cluster: LoadbalancerStrategy::WeightBased(WeightBasedRoute {
+            routes: Arc::new(ArcSwap::from_pointee(routes)),
+        }),
+    }
+}
+fn new_mapping() -> ConfigMapping {
+    let (sender, _) = mpsc::channel(1);
+    let config_mapping = ConfigMapping::default();
+    config_mapping.store(HashMap::from([(
+        String::from(MAPPING_KEY),
+        Arc::new(ApiServiceManager {
+            service_config: ServiceConfig {
+                server_type: ServiceType::Http,
+                cert_str: None,
+                key_str: None,
+                key_passphrase: None,
+                certificates: vec![],
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
+                routes: (0..ROUTE_COUNT).map(new_route).collect(),
+            },
+            sender,
+        }),
+    )]));
+    config_mapping
+}
+fn new_legacy_mapping() -> DashMap<String, LegacyApiServiceManager> {
+    let routes = (0..ROUTE_COUNT)
+        .map(|route_index| LegacyRoute {
+            route: new_route(route_index),
+            cluster: Arc::new(RwLock::new(
+                (0..ENDPOINT_COUNT)
+                    .map(|endpoint_index| LegacyWeightRoute {
+                        endpoint: endpoint(route_index, endpoint_index),
+                        weight: 100,
+                        index: Arc::new(AtomicIsize::new(100)),
+                        is_alive: Arc::new(RwLock::new(Some(true))),
+                    })
+                    .collect(),
+            )),
+        })
+        .collect();
+    let legacy_mapping = DashMap::new();
+    legacy_mapping.insert(
+        String::from(MAPPING_KEY),
+        LegacyApiServiceManager { routes },
+    );
+    legacy_mapping
+}
+async fn legacy_get_route(
+    cluster: &RwLock<Vec<LegacyWeightRoute>>,
+) -> Result<String, anyhow::Error> {
+    let cluster_read_lock = cluster.read().await;
+    let mut all_cluster_dead = true;
+    for item in cluster_read_lock.iter() {
+        if item.is_alive.read().await.unwrap_or(true) {
+            all_cluster_dead = false;
+            if item.index.fetch_sub(1, Ordering::SeqCst) > 0 {
+                return Ok(item.endpoint.clone());
+            }
+        }
+    }
+    drop(cluster_read_lock);
+    if all_cluster_dead {
+        return Err(anyhow!("There are no alive host!"));
+    }
+    let mut cluster_write_lock = cluster.write().await;
+    if !cluster_write_lock
+        .iter()
+        .any(|item| item.index.load(Ordering::SeqCst).is_positive())
+    {
+        cluster_write_lock.iter_mut().for_each(|item| {
+            item.index = Arc::new(AtomicIsize::new(item.weight as isize));
+        });
+    }
+    drop(cluster_write_lock);
+    let cluster_read_lock = cluster.read().await;
+    for item in cluster_read_lock.iter() {
+        if item.is_alive.read().await.unwrap_or(true)
+            && item.index.fetch_sub(1, Ordering::SeqCst) > 0
+        {
+            return Ok(item.endpoint.clone());
+        }
+    }
+    Err(anyhow!("WeightRoute get route error"))
+}
+async fn legacy_request(
+    legacy_mapping: &DashMap<String, LegacyApiServiceManager>,
+    headers: &HeaderMap<HeaderValue>,
+) -> Result<String, anyhow::Error> {
+    let api_service_manager = legacy_mapping
+        .get(MAPPING_KEY)
+        .ok_or(anyhow!("Can not find the config mapping!"))?
+        .clone();
+    for item in api_service_manager.routes {
+        let match_result = item
+            .route
+            .clone()
+            .is_matched(String::from(REQUEST_PATH), Some(headers.clone()))?;
+        if match_result.is_none() {
+            continue;
+        }
+        return legacy_get_route(&item.cluster).await;
+    }
+    Err(anyhow!("No route matched!"))
+}
+async fn snapshot_request(
+    config_mapping: &ConfigMapping,
+    headers: &HeaderMap<HeaderValue>,
+) -> Result<String, anyhow::Error> {
+    let api_service_manager = config_mapping
+        .get(MAPPING_KEY)
+        .ok_or(anyhow!("Can not find the config mapping!"))?;
+    for item in api_service_manager.service_config.routes.iter() {
+        let match_result = item.is_matched(String::from(REQUEST_PATH), Some(headers.clone()))?;
+        if match_result.is_none() {
+            continue;
+        }
+        let base_route = item.route_cluster.get_route(headers.clone()).await?;
+        return Ok(base_route.endpoint);
+    }
+    Err(anyhow!("No route matched!"))
+}
+/**
+*Return the elapsed time and the count of the failed requests,as the legacy design could fail to
+ get the route when the weight indexes are used up by the concurrent requests.
+*/
+async fn run_concurrently<F, Fut>(request: F) -> (Duration, usize)
+where
+    F: Fn() -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Result<String, anyhow::Error>> + Send,
+{
+    let start = Instant::now();
+    let handles = (0..TASK_COUNT)
+        .map(|_| {
+            let request = request.clone();
+            tokio::spawn(async move {
+                let mut failed_count = 0;
+                for _ in 0..ITERATIONS_PER_TASK {
+                    if request().await.is_err() {
+                        failed_count += 1;
+                    }
+                }
+                failed_count
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut failed_count = 0;
+    for handle in handles {
+        failed_count += handle.await.unwrap();
+    }
+    (start.elapsed(), failed_count)
+}
+fn report(name: &str, (elapsed, failed_count): (Duration, usize)) {
+    let requests = (TASK_COUNT * ITERATIONS_PER_TASK) as f64;
+    println!(
+        "{:<32} {:>8.0} ns/request {:>10.0} requests/s {:>6} failed",
+        name,
+        elapsed.as_nanos() as f64 / requests,
+        requests / elapsed.as_secs_f64(),
+        failed_count
+    );
+}
+fn new_runtime() -> Runtime {
+    Builder::new_multi_thread()
+        .worker_threads(4)
+        .enable_all()
+        .build()
+        .unwrap()
+}
+async fn run_legacy(with_health_updates: bool) -> (Duration, usize) {
+    let legacy_mapping = Arc::new(new_legacy_mapping());
+    let stop = Arc::new(AtomicBool::new(false));
+    if with_health_updates {
+        let legacy_mapping = legacy_mapping.clone();
+        let stop = stop.clone();
+        tokio::spawn(async move {
+            let routes = legacy_mapping.get(MAPPING_KEY).unwrap().routes.clone();
+            let mut is_alive = false;
+            while !stop.load(Ordering::SeqCst) {
+                for item in routes.iter() {
+                    let cluster = item.cluster.read().await;
+                    *cluster[0].is_alive.write().await = Some(is_alive);
+                }
+                is_alive = !is_alive;
+                tokio::task::yield_now().await;
+            }
+        });
+    }
+    let headers = Arc::new(HeaderMap::new());
+    let result = run_concurrently(move || {
+        let legacy_mapping = legacy_mapping.clone();
+        let headers = headers.clone();
+        async move { legacy_request(&legacy_mapping, &headers).await }
+    })
+    .await;
+    stop.store(true, Ordering::SeqCst);
+    result
+}
+async fn run_snapshot(with_health_updates: bool) -> (Duration, usize) {
+    let config_mapping = Arc::new(new_mapping());
+    let stop = Arc::new(AtomicBool::new(false));
+    if with_health_updates {
+        let config_mapping = config_mapping.clone();
+        let stop = stop.clone();
+        tokio::spawn(async move {
+            let api_service_manager = config_mapping.get(MAPPING_KEY).unwrap();
+            let mut is_alive = false;
+            while !stop.load(Ordering::SeqCst) {
+                for item in api_service_manager.service_config.routes.iter() {
+                    let base_routes = item.route_cluster.get_all_route().await.unwrap();
+                    base_routes[0].is_alive.store(Some(is_alive));
+                }
+                is_alive = !is_alive;
+                tokio::task::yield_now().await;
+            }
+        });
+    }
+    let headers = Arc::new(HeaderMap::new());
+    let result = run_concurrently(move || {
+        let config_mapping = config_mapping.clone();
+        let headers = headers.clone();
+        async move { snapshot_request(&config_mapping, &headers).await }
+    })
+    .await;
+    stop.store(true, Ordering::SeqCst);
+    result
+}
+
+#[test]
+fn test_legacy_and_snapshot_select_the_same_endpoints() {
+    new_runtime().block_on(async {
+        let legacy_mapping = new_legacy_mapping();
+        let config_mapping = new_mapping();
+        let headers = HeaderMap::new();
+        for _ in 0..(ENDPOINT_COUNT * 100 + 1) {
+            let legacy_endpoint = legacy_request(&legacy_mapping, &headers).await.unwrap();
+            let snapshot_endpoint = snapshot_request(&config_mapping, &headers).await.unwrap();
+            assert_eq!(legacy_endpoint, snapshot_endpoint);
+        }
+    });
+}
+#[test]
+#[ignore]
+fn bench_data_plane_request_path() {
+    let runtime = new_runtime();
+    report(
+        "legacy(DashMap clone + RwLock)",
+        runtime.block_on(run_legacy(false)),
+    );
+    report(
+        "snapshot(ArcSwap + atomics)",
+        runtime.block_on(run_snapshot(false)),
+    );
+}
+#[test]
+#[ignore]
+fn bench_data_plane_request_path_with_health_updates() {
+    let runtime = new_runtime();
+    report(
+        "legacy(DashMap clone + RwLock)",
+        runtime.block_on(run_legacy(true)),
+    );
+    report(
+        "snapshot(ArcSwap + atomics)",
+        runtime.block_on(run_snapshot(true)),
+    );
+}
@@ -0,0 +1,1109 @@
+use crate::constants::common_constants::{
+    DEFAULT_GRPC_MAX_MESSAGE_SIZE, DEFAULT_HTTP_TIMEOUT, GRPC_STATUS_HEADER,
+};
+use crate::maintenance::endpoint_maintenance::InFlightGuard;
+use crate::proxy::http1::grpc_web_proxy::{is_hop_by_hop_header, read_body_with_limit};
+use crate::proxy::http1::protobuf_json::{find_field, json_name, DescriptorPool};
+use crate::proxy::http2::grpc_connection_pool::GLOBAL_GRPC_CONNECTION_POOL;
+use crate::proxy::upstream_tls::UpstreamTls;
+use crate::vojo::app_config::GrpcJsonTranscoderConfig;
+use base64::{engine::general_purpose, Engine as _};
+use http::header::{CONTENT_TYPE, TE};
+use http::{HeaderMap, Method, StatusCode, Version};
+use hyper::body::Bytes;
+use hyper::{Body, Request, Response};
+use protobuf::descriptor::{FieldDescriptorProto_Label, FieldDescriptorProto_Type};
+use protobuf::{CodedInputStream, Message};
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+const HTTP_RULE_EXTENSION_NUMBER: u32 = 72295728;
+const GRPC_CONTENT_TYPE: &str = "application/grpc";
+const JSON_CONTENT_TYPE: &str = "application/json";
+const GRPC_MESSAGE_HEADER: &str = "grpc-message";
+const GRPC_STATUS_UNKNOWN: u32 = 2;
+const GRPC_STATUS_INVALID_ARGUMENT: u32 = 3;
+const GRPC_STATUS_DEADLINE_EXCEEDED: u32 = 4;
+const GRPC_STATUS_RESOURCE_EXHAUSTED: u32 = 8;
+const GRPC_STATUS_UNAVAILABLE: u32 = 14;
+#[derive(Debug, Clone, PartialEq)]
+enum TemplateSegment {
+    Literal(String),
+    Single,
+    Multi,
+}
+/**
+*The path template of the http rule like /v1/shelves/{shelf}/books:verb.The variable covers the
+ segments from the start to the end,and its value is set to the field path of the request.
+*/
+#[derive(Debug, Clone, PartialEq)]
+struct PathTemplate {
+    segments: Vec<TemplateSegment>,
+    variables: Vec<(String, usize, usize)>,
+    verb: Option<String>,
+}
+impl PathTemplate {
+    fn parse(template: &str) -> Result<Self, anyhow::Error> {
+        let invalid_template = || anyhow!("The path template {} is invalid.", template);
+        let mut rest = template.strip_prefix('/').ok_or_else(invalid_template)?;
+        let mut verb = None;
+        if let Some(index) = rest.rfind(':') {
+            if !rest[index..].contains('}') && !rest[index..].contains('/') {
+                verb = Some(rest[index + 1..].to_string());
+                rest = &rest[..index];
+            }
+        }
+        let mut segments = vec![];
+        let mut variables = vec![];
+        while !rest.is_empty() {
+            if let Some(variable) = rest.strip_prefix('{') {
+                let end = variable.find('}').ok_or_else(invalid_template)?;
+                let (field_path, pattern) = match variable[..end].split_once('=') {
+                    Some((field_path, pattern)) => (field_path, pattern),
+                    None => (&variable[..end], "*"),
+                };
+                let start = segments.len();
+                for item in pattern.split('/') {
+                    segments.push(parse_segment(item).ok_or_else(invalid_template)?);
+                }
+                variables.push((field_path.to_string(), start, segments.len()));
+                rest = &variable[end + 1..];
+            } else {
+                let end = rest.find('/').unwrap_or(rest.len());
+                segments.push(parse_segment(&rest[..end]).ok_or_else(invalid_template)?);
+                rest = &rest[end..];
+            }
+            rest = match rest.strip_prefix('/') {
+                Some(next) if !next.is_empty() => next,
+                None if rest.is_empty() => rest,
+                _ => return Err(invalid_template()),
+            };
+        }
+        Ok(PathTemplate {
+            segments,
+            variables,
+            verb,
+        })
+    }
+    /**
+     *Match the request path and return the field paths with the values of the variables.
+     */
+    fn match_path(&self, path: &str) -> Option<Vec<(String, String)>> {
+        let path = match &self.verb {
+            Some(verb) => path.strip_suffix(&format!(":{}", verb))?,
+            None => path,
+        };
+        let path_segments = path.strip_prefix('/')?.split('/').collect::<Vec<&str>>();
+        let mut ranges = vec![];
+        if !match_segments(&self.segments, &path_segments, &mut ranges) {
+            return None;
+        }
+        let variables = self
+            .variables
+            .iter()
+            .map(|(field_path, start, end)| {
+                let value = path_segments[ranges[*start].0..ranges[*end - 1].1]
+                    .iter()
+                    .map(|item| percent_decode(item))
+                    .collect::<Vec<String>>()
+                    .join("/");
+                (field_path.clone(), value)
+            })
+            .collect();
+        Some(variables)
+    }
+}
+fn parse_segment(segment: &str) -> Option<TemplateSegment> {
+    match segment {
+        "" => None,
+        "*" => Some(TemplateSegment::Single),
+        "**" => Some(TemplateSegment::Multi),
+        _ => Some(TemplateSegment::Literal(segment.to_string())),
+    }
+}
+fn match_segments(
+    segments: &[TemplateSegment],
+    path_segments: &[&str],
+    ranges: &mut Vec<(usize, usize)>,
+) -> bool {
+    let start = ranges.last().map(|item| item.1).unwrap_or(0);
+    let segment = match segments.get(ranges.len()) {
+        Some(segment) => segment,
+        None => return start == path_segments.len(),
+    };
+    let ends = match segment {
+        TemplateSegment::Literal(literal) => {
+            if path_segments.get(start) != Some(&literal.as_str()) {
+                return false;
+            }
+            vec![start + 1]
+        }
+        TemplateSegment::Single => {
+            if path_segments.get(start).is_none_or(|item| item.is_empty()) {
+                return false;
+            }
+            vec![start + 1]
+        }
+        TemplateSegment::Multi => (start + 1..=path_segments.len()).rev().collect(),
+    };
+    for end in ends {
+        ranges.push((start, end));
+        if match_segments(segments, path_segments, ranges) {
+            return true;
+        }
+        ranges.pop();
+    }
+    false
+}
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = vec![];
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes
+            .get(index + 1..index + 3)
+            .and_then(|item| std::str::from_utf8(item).ok())
+            .and_then(|item| u8::from_str_radix(item, 16).ok());
+        match hex {
+            Some(byte) if bytes[index] == b'%' => {
+                decoded.push(byte);
+                index += 3;
+            }
+            _ => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+/**
+*The http binding of the grpc method,and the method is None for the custom rule whose kind is *.
+*/
+#[derive(Debug, Clone)]
+struct HttpBinding {
+    method: Option<Method>,
+    template: PathTemplate,
+    body: String,
+    response_body: String,
+}
+fn parse_http_rule(bytes: &[u8]) -> Result<Vec<HttpBinding>, anyhow::Error> {
+    let mut input_stream = CodedInputStream::from_bytes(bytes);
+    let mut method_and_path = None;
+    let mut body = String::new();
+    let mut response_body = String::new();
+    let mut additional_bindings = vec![];
+    while !input_stream.eof()? {
+        let (number, wire_type) = input_stream.read_tag_unpack()?;
+        match number {
+            2 => method_and_path = Some((Some(Method::GET), input_stream.read_string()?)),
+            3 => method_and_path = Some((Some(Method::PUT), input_stream.read_string()?)),
+            4 => method_and_path = Some((Some(Method::POST), input_stream.read_string()?)),
+            5 => method_and_path = Some((Some(Method::DELETE), input_stream.read_string()?)),
+            6 => method_and_path = Some((Some(Method::PATCH), input_stream.read_string()?)),
+            7 => body = input_stream.read_string()?,
+            8 => method_and_path = Some(parse_custom_pattern(&input_stream.read_bytes()?)?),
+            11 => additional_bindings.extend(parse_http_rule(&input_stream.read_bytes()?)?),
+            12 => response_body = input_stream.read_string()?,
+            _ => input_stream.skip_field(wire_type)?,
+        }
+    }
+    let (method, path) = method_and_path.ok_or(anyhow!("The http rule has no pattern."))?;
+    let mut bindings = vec![HttpBinding {
+        method,
+        template: PathTemplate::parse(&path)?,
+        body,
+        response_body,
+    }];
+    bindings.extend(additional_bindings);
+    Ok(bindings)
+}
+fn parse_custom_pattern(bytes: &[u8]) -> Result<(Option<Method>, String), anyhow::Error> {
+    let mut input_stream = CodedInputStream::from_bytes(bytes);
+    let mut kind = String::new();
+    let mut path = String::new();
+    while !input_stream.eof()? {
+        let (number, wire_type) = input_stream.read_tag_unpack()?;
+        match number {
+            1 => kind = input_stream.read_string()?,
+            2 => path = input_stream.read_string()?,
+            _ => input_stream.skip_field(wire_type)?,
+        }
+    }
+    if kind == "*" {
+        return Ok((None, path));
+    }
+    Ok((Some(Method::from_bytes(kind.as_bytes())?), path))
+}
+#[derive(Debug, Clone)]
+struct TranscodedMethod {
+    service_name: String,
+    method_name: String,
+    input_type: String,
+    output_type: String,
+    server_streaming: bool,
+    bindings: Vec<HttpBinding>,
+}
+/**
+*The transcoder of the route,which maps the json requests to the grpc methods by the
+ google.api.http rules in the descriptor set.The method without the rule is bound to
+ POST /package.Service/Method with the whole body,and the client streaming methods are skipped.
+*/
+#[derive(Clone)]
+pub struct GrpcJsonTranscoder {
+    pub config: GrpcJsonTranscoderConfig,
+    descriptor_pool: Arc<DescriptorPool>,
+    methods: Arc<Vec<TranscodedMethod>>,
+}
+impl fmt::Debug for GrpcJsonTranscoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GrpcJsonTranscoder")
+            .field("proto_descriptor", &self.config.proto_descriptor)
+            .field("services", &self.config.services)
+            .finish()
+    }
+}
+impl GrpcJsonTranscoder {
+    pub fn new(config: GrpcJsonTranscoderConfig) -> Result<Self, anyhow::Error> {
+        let descriptor_bytes = match (&config.proto_descriptor, &config.proto_descriptor_bin) {
+            (Some(proto_descriptor), None) => std::fs::read(proto_descriptor).map_err(|e| {
+                anyhow!(
+                    "Read the proto_descriptor {} error,the error is {}.",
+                    proto_descriptor,
+                    e
+                )
+            })?,
+            (None, Some(proto_descriptor_bin)) => {
+                general_purpose::STANDARD.decode(proto_descriptor_bin)?
+            }
+            _ => {
+                return Err(anyhow!(
+                "Either the proto_descriptor or the proto_descriptor_bin of the grpc json transcoder should be set."
+            ))
+            }
+        };
+        let descriptor_pool = DescriptorPool::from_bytes(&descriptor_bytes)?;
+        for item in config.services.iter() {
+            if !descriptor_pool
+                .services()
+                .iter()
+                .any(|(service_name, _)| service_name == item)
+            {
+                return Err(anyhow!(
+                    "The service {} is not in the descriptor set.",
+                    item
+                ));
+            }
+        }
+        let mut methods = vec![];
+        for (service_name, service) in descriptor_pool.services() {
+            if !config.services.is_empty() && !config.services.contains(service_name) {
+                continue;
+            }
+            for method in service.get_method() {
+                if method.get_client_streaming() {
+                    continue;
+                }
+                let bindings = match method
+                    .get_options()
+                    .get_unknown_fields()
+                    .get(HTTP_RULE_EXTENSION_NUMBER)
+                    .and_then(|item| item.length_delimited.last())
+                {
+                    Some(http_rule) => parse_http_rule(http_rule)?,
+                    None => vec![HttpBinding {
+                        method: Some(Method::POST),
+                        template: PathTemplate {
+                            segments: vec![
+                                TemplateSegment::Literal(service_name.clone()),
+                                TemplateSegment::Literal(method.get_name().to_string()),
+                            ],
+                            variables: vec![],
+                            verb: None,
+                        },
+                        body: String::from("*"),
+                        response_body: String::new(),
+                    }],
+                };
+                let transcoded_method = TranscodedMethod {
+                    service_name: service_name.clone(),
+                    method_name: method.get_name().to_string(),
+                    input_type: method.get_input_type().to_string(),
+                    output_type: method.get_output_type().to_string(),
+                    server_streaming: method.get_server_streaming(),
+                    bindings,
+                };
+                validate_bindings(&descriptor_pool, &transcoded_method)?;
+                methods.push(transcoded_method);
+            }
+        }
+        Ok(GrpcJsonTranscoder {
+            config,
+            descriptor_pool: Arc::new(descriptor_pool),
+            methods: Arc::new(methods),
+        })
+    }
+    /**
+    *Find the method whose http binding matches the request,and the request which matches none of
+    them is proxied as before.
+    */
+    pub fn match_request(&self, method: &Method, path: &str) -> Option<TranscodingMatch<'_>> {
+        for transcoded_method in self.methods.iter() {
+            for binding in transcoded_method.bindings.iter() {
+                if binding.method.as_ref().is_some_and(|item| item != method) {
+                    continue;
+                }
+                if let Some(variables) = binding.template.match_path(path) {
+                    return Some(TranscodingMatch {
+                        config: &self.config,
+                        descriptor_pool: &self.descriptor_pool,
+                        method: transcoded_method,
+                        binding,
+                        variables,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+fn validate_bindings(
+    descriptor_pool: &DescriptorPool,
+    transcoded_method: &TranscodedMethod,
+) -> Result<(), anyhow::Error> {
+    for binding in transcoded_method.bindings.iter() {
+        let mut field_paths = binding
+            .template
+            .variables
+            .iter()
+            .map(|(field_path, _, _)| field_path.as_str())
+            .collect::<Vec<&str>>();
+        if binding.body != "*" && !binding.body.is_empty() {
+            field_paths.push(binding.body.as_str());
+        }
+        for field_path in field_paths {
+            if resolve_field_path(descriptor_pool, &transcoded_method.input_type, field_path)
+                .is_none()
+            {
+                return Err(anyhow!(
+                    "The field {} is not in the request of the method {}/{}.",
+                    field_path,
+                    transcoded_method.service_name,
+                    transcoded_method.method_name
+                ));
+            }
+        }
+        if !binding.response_body.is_empty()
+            && resolve_field_path(
+                descriptor_pool,
+                &transcoded_method.output_type,
+                &binding.response_body,
+            )
+            .is_none()
+        {
+            return Err(anyhow!(
+                "The field {} is not in the response of the method {}/{}.",
+                binding.response_body,
+                transcoded_method.service_name,
+                transcoded_method.method_name
+            ));
+        }
+    }
+    Ok(())
+}
+/**
+*Resolve the field path like book.id from the message,and return the json names on the path and
+ whether the last field is repeated.
+*/
+fn resolve_field_path(
+    descriptor_pool: &DescriptorPool,
+    type_name: &str,
+    field_path: &str,
+) -> Option<(Vec<String>, bool)> {
+    let mut type_name = type_name.to_string();
+    let mut json_names = vec![];
+    let mut is_repeated = false;
+    for item in field_path.split('.') {
+        let message = descriptor_pool.get_message(&type_name).ok()?;
+        let field = find_field(message, item)?;
+        json_names.push(json_name(field));
+        is_repeated = field.get_label() == FieldDescriptorProto_Label::LABEL_REPEATED;
+        type_name = if field.get_field_type() == FieldDescriptorProto_Type::TYPE_MESSAGE {
+            field.get_type_name().to_string()
+        } else {
+            String::new()
+        };
+    }
+    Some((json_names, is_repeated))
+}
+fn set_field(value: &mut Value, json_names: &[String], field_value: Value, is_repeated: bool) {
+    let mut current = value;
+    for item in json_names[..json_names.len() - 1].iter() {
+        if !current.get(item).is_some_and(|item| item.is_object()) {
+            current[item] = Value::Object(Map::new());
+        }
+        current = &mut current[item];
+    }
+    let last_name = &json_names[json_names.len() - 1];
+    if !is_repeated {
+        current[last_name] = field_value;
+        return;
+    }
+    match current.get_mut(last_name) {
+        Some(Value::Array(items)) => items.push(field_value),
+        _ => current[last_name] = Value::Array(vec![field_value]),
+    }
+}
+/**
+*The grpc status in the json body of the error,which is the google.rpc.Status in the json format.
+*/
+fn json_error_response(grpc_status: u32, message: &str) -> Response<Body> {
+    json_error_response_with_status(http_status_from_grpc(grpc_status), grpc_status, message)
+}
+fn json_error_response_with_status(
+    status: StatusCode,
+    grpc_status: u32,
+    message: &str,
+) -> Response<Body> {
+    let body = json!({
+        "code": grpc_status,
+        "message": message
+    });
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, JSON_CONTENT_TYPE)
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+/**
+*The http status of the grpc status,which follows the mapping of google.rpc.Code.
+*/
+fn http_status_from_grpc(grpc_status: u32) -> StatusCode {
+    match grpc_status {
+        0 => StatusCode::OK,
+        1 => StatusCode::from_u16(499).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+        3 | 9 | 11 => StatusCode::BAD_REQUEST,
+        4 => StatusCode::GATEWAY_TIMEOUT,
+        5 => StatusCode::NOT_FOUND,
+        6 | 10 => StatusCode::CONFLICT,
+        7 => StatusCode::FORBIDDEN,
+        8 => StatusCode::TOO_MANY_REQUESTS,
+        12 => StatusCode::NOT_IMPLEMENTED,
+        14 => StatusCode::SERVICE_UNAVAILABLE,
+        16 => StatusCode::UNAUTHORIZED,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+fn encode_grpc_frame(message: &[u8]) -> Bytes {
+    let mut frame = vec![0];
+    frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+    frame.extend_from_slice(message);
+    Bytes::from(frame)
+}
+fn decode_grpc_frames(body: &[u8]) -> Result<Vec<&[u8]>, anyhow::Error> {
+    let mut messages = vec![];
+    let mut rest = body;
+    while !rest.is_empty() {
+        if rest.len() < 5 {
+            return Err(anyhow!("The grpc frame is truncated."));
+        }
+        if rest[0] & 1 != 0 {
+            return Err(anyhow!("The compressed grpc message is not supported."));
+        }
+        let length = u32::from_be_bytes([rest[1], rest[2], rest[3], rest[4]]) as usize;
+        let message = rest
+            .get(5..5 + length)
+            .ok_or(anyhow!("The grpc frame is truncated."))?;
+        messages.push(message);
+        rest = &rest[5 + length..];
+    }
+    Ok(messages)
+}
+fn get_grpc_status(headers: &HeaderMap) -> Option<(u32, String)> {
+    let grpc_status = headers
+        .get(GRPC_STATUS_HEADER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()?;
+    let message = headers
+        .get(GRPC_MESSAGE_HEADER)
+        .and_then(|item| item.to_str().ok())
+        .map(percent_decode)
+        .unwrap_or_default();
+    Some((grpc_status, message))
+}
+/**
+*The matched method and binding of the request,with the values of the path variables.
+*/
+pub struct TranscodingMatch<'a> {
+    config: &'a GrpcJsonTranscoderConfig,
+    descriptor_pool: &'a DescriptorPool,
+    method: &'a TranscodedMethod,
+    binding: &'a HttpBinding,
+    variables: Vec<(String, String)>,
+}
+impl TranscodingMatch<'_> {
+    /**
+    *The request message built from the body,the path variables and the query parameters.The
+    query parameters which are not the fields of the request are ignored.
+    */
+    fn build_request_json(&self, query: Option<&str>, body: &[u8]) -> Result<Value, anyhow::Error> {
+        let body_value = if body.iter().all(|item| item.is_ascii_whitespace()) {
+            Value::Object(Map::new())
+        } else {
+            serde_json::from_slice(body)?
+        };
+        let input_type = &self.method.input_type;
+        let mut value = match self.binding.body.as_str() {
+            "*" => body_value,
+            "" => Value::Object(Map::new()),
+            field_path => {
+                let (json_names, _) =
+                    resolve_field_path(self.descriptor_pool, input_type, field_path)
+                        .ok_or(anyhow!("The body field {} is not found.", field_path))?;
+                let mut value = Value::Object(Map::new());
+                set_field(&mut value, &json_names, body_value, false);
+                value
+            }
+        };
+        if !value.is_object() {
+            return Err(anyhow!("The json body should be an object."));
+        }
+        for (field_path, field_value) in self.variables.iter() {
+            let (json_names, _) = resolve_field_path(self.descriptor_pool, input_type, field_path)
+                .ok_or(anyhow!("The path field {} is not found.", field_path))?;
+            set_field(
+                &mut value,
+                &json_names,
+                Value::from(field_value.clone()),
+                false,
+            );
+        }
+        if self.binding.body != "*" {
+            for (key, query_value) in
+                url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+            {
+                if let Some((json_names, is_repeated)) =
+                    resolve_field_path(self.descriptor_pool, input_type, &key)
+                {
+                    set_field(
+                        &mut value,
+                        &json_names,
+                        Value::from(query_value.to_string()),
+                        is_repeated,
+                    );
+                }
+            }
+        }
+        Ok(value)
+    }
+    fn build_response_json(&self, messages: Vec<&[u8]>) -> Result<Value, anyhow::Error> {
+        let mut values = vec![];
+        for message in messages {
+            let mut value = self
+                .descriptor_pool
+                .protobuf_to_json(&self.method.output_type, message)?;
+            if let Some((json_names, is_repeated)) = resolve_field_path(
+                self.descriptor_pool,
+                &self.method.output_type,
+                &self.binding.response_body,
+            )
+            .filter(|_| !self.binding.response_body.is_empty())
+            {
+                for item in json_names.iter() {
+                    value = value.get(item).cloned().unwrap_or(Value::Null);
+                }
+                if value.is_null() && is_repeated {
+                    value = Value::Array(vec![]);
+                }
+            }
+            values.push(value);
+        }
+        if self.method.server_streaming {
+            return Ok(Value::Array(values));
+        }
+        Ok(values
+            .into_iter()
+            .next()
+            .unwrap_or(Value::Object(Map::new())))
+    }
+    /**
+    *Call the grpc method of the upstream with the json request,and convert the response or the
+    grpc status back to json.The invalid request is answered with the invalid argument status,the
+    larger one with the resource exhausted status and 413,and the upstream which does not respond
+    in time with the deadline exceeded status.
+    */
+    pub async fn transcode(
+        &self,
+        req: Request<Body>,
+        endpoint: &str,
+        upstream_tls: Option<&UpstreamTls>,
+        in_flight_guard: InFlightGuard,
+    ) -> Result<Response<Body>, anyhow::Error> {
+        let max_message_size = self
+            .config
+            .max_message_size
+            .unwrap_or(DEFAULT_GRPC_MAX_MESSAGE_SIZE);
+        let timeout =
+            Duration::from_secs(self.config.timeout_seconds.unwrap_or(DEFAULT_HTTP_TIMEOUT));
+        let (parts, body) = req.into_parts();
+        let body = match read_body_with_limit(body, max_message_size).await? {
+            Some(body) => body,
+            None => {
+                return Ok(json_error_response_with_status(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    GRPC_STATUS_RESOURCE_EXHAUSTED,
+                    &format!(
+                        "The request body is larger than the limit of {} bytes.",
+                        max_message_size
+                    ),
+                ))
+            }
+        };
+        let message = match self
+            .build_request_json(parts.uri.query(), &body)
+            .and_then(|value| {
+                self.descriptor_pool
+                    .json_to_protobuf(&self.method.input_type, &value)
+            }) {
+            Ok(message) => message,
+            Err(err) => {
+                return Ok(json_error_response(
+                    GRPC_STATUS_INVALID_ARGUMENT,
+                    &err.to_string(),
+                ))
+            }
+        };
+        let result = tokio::time::timeout(
+            timeout,
+            self.call_upstream(
+                &parts.headers,
+                message,
+                endpoint,
+                upstream_tls,
+                max_message_size,
+            ),
+        )
+        .await;
+        drop(in_flight_guard);
+        match result {
+            Ok(Ok(response)) => Ok(response),
+            Err(_) => Ok(json_error_response(
+                GRPC_STATUS_DEADLINE_EXCEEDED,
+                &format!("The upstream does not respond in {:?}.", timeout),
+            )),
+            Ok(Err(err)) => {
+                error!("Grpc json transcoding error,the error is {}.", err);
+                Ok(json_error_response(
+                    GRPC_STATUS_UNAVAILABLE,
+                    &err.to_string(),
+                ))
+            }
+        }
+    }
+    async fn call_upstream(
+        &self,
+        headers: &HeaderMap,
+        message: Vec<u8>,
+        endpoint: &str,
+        upstream_tls: Option<&UpstreamTls>,
+        max_message_size: usize,
+    ) -> Result<Response<Body>, anyhow::Error> {
+        let url = Url::parse(endpoint)?.join(&format!(
+            "/{}/{}",
+            self.method.service_name, self.method.method_name
+        ))?;
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .version(Version::HTTP_2)
+            .uri(url.as_str())
+            .header(CONTENT_TYPE, GRPC_CONTENT_TYPE)
+            .header(TE, "trailers")
+            .body(())?;
+        for (name, value) in headers.iter() {
+            if !is_hop_by_hop_header(name) {
+                request.headers_mut().append(name, value.clone());
+            }
+        }
+        debug!("The grpc request transcoded from json is {:?}", request);
+        let (response_future, mut send_stream, pooled_stream) = GLOBAL_GRPC_CONNECTION_POOL
+            .send_request(&url, upstream_tls, request)
+            .await?;
+        send_stream.send_data(encode_grpc_frame(&message), true)?;
+        let (head, mut recv_stream) = response_future.await?.into_parts();
+        let mut body = vec![];
+        let mut flow_control = recv_stream.flow_control().clone();
+        while let Some(chunk_result) = recv_stream.data().await {
+            let chunk = chunk_result?;
+            flow_control.release_capacity(chunk.len())?;
+            if body.len() + chunk.len() > max_message_size {
+                return Ok(json_error_response_with_status(
+                    StatusCode::BAD_GATEWAY,
+                    GRPC_STATUS_RESOURCE_EXHAUSTED,
+                    &format!(
+                        "The upstream response is larger than the limit of {} bytes.",
+                        max_message_size
+                    ),
+                ));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let trailers = recv_stream.trailers().await?.unwrap_or_default();
+        drop(pooled_stream);
+
+        let (grpc_status, grpc_message) = get_grpc_status(&trailers)
+            .or_else(|| get_grpc_status(&head.headers))
+            .unwrap_or((
+                GRPC_STATUS_UNKNOWN,
+                format!(
+                    "The upstream responded without the grpc status,the http status is {}.",
+                    head.status
+                ),
+            ));
+        if grpc_status != 0 {
+            return Ok(json_error_response(grpc_status, &grpc_message));
+        }
+        let value = self.build_response_json(decode_grpc_frames(&body)?)?;
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, JSON_CONTENT_TYPE)
+            .body(Body::from(value.to_string()))?)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration_service::app_config_service::GLOBAL_CONFIG_MAPPING;
+    use crate::maintenance::endpoint_maintenance::track_in_flight;
+    use crate::proxy::http1::http_proxy::HttpProxy;
+    use crate::vojo::api_service_manager::ApiServiceManager;
+    use crate::vojo::app_config::{Route, ServiceConfig};
+    use crate::vojo::app_config_vistor::RouteVistor;
+    use http::HeaderValue;
+    use tokio::net::TcpListener;
+    use tokio::time::{sleep, Duration};
+    fn new_transcoder() -> GrpcJsonTranscoder {
+        GrpcJsonTranscoder::new(GrpcJsonTranscoderConfig {
+            proto_descriptor: Some(String::from("config/test_transcoding_descriptor.pb")),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+    async fn start_grpc_upstream(port: u16) {
+        let listener = TcpListener::bind(format!("127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        tokio::spawn(async move {
+            while let Ok((tcp_stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut connection = h2::server::handshake(tcp_stream).await.unwrap();
+                    while let Some(Ok((request, mut respond))) = connection.accept().await {
+                        let (parts, mut body) = request.into_parts();
+                        let mut message = vec![];
+                        while let Some(Ok(chunk)) = body.data().await {
+                            message.extend_from_slice(&chunk);
+                        }
+                        if parts.uri.path() == "/bookstore.Bookstore/GetShelf" {
+                            let response = Response::builder()
+                                .header(CONTENT_TYPE, GRPC_CONTENT_TYPE)
+                                .header(GRPC_STATUS_HEADER, "5")
+                                .header(GRPC_MESSAGE_HEADER, "shelf%20not%20found")
+                                .body(())
+                                .unwrap();
+                            respond.send_response(response, true).unwrap();
+                            continue;
+                        }
+                        let response = Response::builder()
+                            .header(CONTENT_TYPE, GRPC_CONTENT_TYPE)
+                            .body(())
+                            .unwrap();
+                        let mut send_stream = respond.send_response(response, false).unwrap();
+                        send_stream.send_data(Bytes::from(message), false).unwrap();
+                        let mut trailers = HeaderMap::new();
+                        trailers.insert(GRPC_STATUS_HEADER, HeaderValue::from_static("0"));
+                        send_stream.send_trailers(trailers).unwrap();
+                    }
+                });
+            }
+        });
+    }
+    #[test]
+    fn test_path_template_ok() {
+        let template = PathTemplate::parse("/v1/{parent=shelves/*}/books/{name=**}:move").unwrap();
+        assert_eq!(
+            template.match_path("/v1/shelves/1/books/a/b%20c:move"),
+            Some(vec![
+                (String::from("parent"), String::from("shelves/1")),
+                (String::from("name"), String::from("a/b c"))
+            ])
+        );
+        assert_eq!(template.match_path("/v1/shelves/1/books/a"), None);
+        assert_eq!(template.match_path("/v1/shelves//books/a:move"), None);
+        assert!(PathTemplate::parse("v1/shelves").is_err());
+        assert!(PathTemplate::parse("/v1/{shelf").is_err());
+        assert!(PathTemplate::parse("/v1//shelves").is_err());
+    }
+    #[test]
+    fn test_match_request_ok() {
+        let transcoder = new_transcoder();
+        let transcoding_match = transcoder
+            .match_request(&Method::GET, "/v1/shelves/1/books")
+            .unwrap();
+        assert_eq!(transcoding_match.method.method_name, "ListBooks");
+        let transcoding_match = transcoder
+            .match_request(&Method::PUT, "/v1/shelves/1:replace")
+            .unwrap();
+        assert_eq!(transcoding_match.method.method_name, "UpdateShelf");
+        let transcoding_match = transcoder
+            .match_request(&Method::POST, "/bookstore.Bookstore/WatchShelf")
+            .unwrap();
+        assert!(transcoding_match.method.server_streaming);
+        assert!(transcoder
+            .match_request(&Method::POST, "/v1/shelves/1")
+            .is_none());
+        assert!(GrpcJsonTranscoder::new(GrpcJsonTranscoderConfig {
+            proto_descriptor: Some(String::from("config/test_transcoding_descriptor.pb")),
+            services: vec![String::from("bookstore.Missing")],
+            ..Default::default()
+        })
+        .is_err());
+        assert!(GrpcJsonTranscoder::new(GrpcJsonTranscoderConfig::default()).is_err());
+    }
+    #[test]
+    fn test_build_request_json_ok() {
+        let transcoder = new_transcoder();
+        let transcoding_match = transcoder
+            .match_request(&Method::POST, "/v1/shelves/3/books")
+            .unwrap();
+        assert_eq!(
+            transcoding_match
+                .build_request_json(Some("book.title=Dune&unknown=1"), br#"{"id":"9"}"#)
+                .unwrap(),
+            json!({ "shelf": "3", "book": { "id": "9", "title": "Dune" } })
+        );
+        let transcoding_match = transcoder
+            .match_request(&Method::DELETE, "/v1/shelves/3/books/9")
+            .unwrap();
+        assert_eq!(
+            transcoding_match.build_request_json(None, b"").unwrap(),
+            json!({ "shelf": "3", "book": { "id": "9" } })
+        );
+        let transcoding_match = transcoder
+            .match_request(&Method::GET, "/v1/shelves/3/books")
+            .unwrap();
+        assert_eq!(
+            transcoding_match
+                .build_request_json(Some("pageSize=10"), b"")
+                .unwrap(),
+            json!({ "parent": "shelves/3", "pageSize": "10" })
+        );
+        let transcoding_match = transcoder
+            .match_request(&Method::PATCH, "/v1/shelves/3")
+            .unwrap();
+        assert!(transcoding_match.build_request_json(None, b"[1]").is_err());
+    }
+    #[test]
+    fn test_build_response_json_ok() {
+        let transcoder = new_transcoder();
+        let list_books_response = transcoder
+            .descriptor_pool
+            .json_to_protobuf(
+                ".bookstore.ListBooksResponse",
+                &json!({ "books": [{ "title": "Dune" }], "nextPageToken": "2" }),
+            )
+            .unwrap();
+        let transcoding_match = transcoder
+            .match_request(&Method::GET, "/v1/shelves/3/books")
+            .unwrap();
+        assert_eq!(
+            transcoding_match
+                .build_response_json(vec![&list_books_response])
+                .unwrap(),
+            json!([{ "title": "Dune" }])
+        );
+        let frames = [encode_grpc_frame(b""), encode_grpc_frame(b"")].concat();
+        let transcoding_match = transcoder
+            .match_request(&Method::POST, "/bookstore.Bookstore/WatchShelf")
+            .unwrap();
+        assert_eq!(
+            transcoding_match
+                .build_response_json(decode_grpc_frames(&frames).unwrap())
+                .unwrap(),
+            json!([{}, {}])
+        );
+        assert!(decode_grpc_frames(&frames[..6]).is_err());
+        assert_eq!(http_status_from_grpc(5), StatusCode::NOT_FOUND);
+        assert_eq!(http_status_from_grpc(16), StatusCode::UNAUTHORIZED);
+    }
+    #[tokio::test]
+    async fn test_grpc_json_transcoder_proxy_ok() {
+        start_grpc_upstream(4474).await;
+        let route_vistor: RouteVistor = serde_json::from_value(json!({
+            "matcher": { "prefix": "/", "prefix_rewrite": "/" },
+            "grpc_json_transcoder": { "proto_descriptor": "config/test_transcoding_descriptor.pb" },
+            "route_cluster": {
+                "type": "WeightBasedRoute",
+                "routes": [{ "base_route": { "endpoint": "http://127.0.0.1:4474" }, "weight": 100 }]
+            }
+        }))
+        .unwrap();
+        let (sender, _) = tokio::sync::mpsc::channel(1);
+        GLOBAL_CONFIG_MAPPING.insert(
+            String::from("4475-HTTP"),
+            ApiServiceManager {
+                service_config: ServiceConfig {
+                    routes: vec![Route::from(route_vistor).await.unwrap()],
+                    ..Default::default()
+                },
+                sender,
+            },
+        );
+        let (_sender, receiver) = tokio::sync::mpsc::channel(10);
+        tokio::spawn(async {
+            let mut http_proxy = HttpProxy {
+                port: 4475,
+                channel: receiver,
+                mapping_key: String::from("4475-HTTP"),
+            };
+            let _result = http_proxy.start_http_server().await;
+        });
+        sleep(Duration::from_millis(100)).await;
+
+        let client = hyper::Client::new();
+        let request = Request::patch("http://127.0.0.1:4475/v1/shelves/7")
+            .body(Body::from(r#"{"theme":"Travel","genre":1}"#))
+            .unwrap();
+        let response = client.request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], JSON_CONTENT_TYPE);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Value>(&body).unwrap(),
+            json!({ "id": "7", "theme": "Travel", "genre": "FICTION" })
+        );
+
+        let response = client
+            .get("http://127.0.0.1:4475/v1/shelves/8".parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Value>(&body).unwrap(),
+            json!({ "code": 5, "message": "shelf not found" })
+        );
+
+        let request = Request::patch("http://127.0.0.1:4475/v1/shelves/7")
+            .body(Body::from("{"))
+            .unwrap();
+        let response = client.request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+    #[tokio::test]
+    async fn test_grpc_json_transcoder_unavailable() {
+        let transcoder = new_transcoder();
+        let transcoding_match = transcoder
+            .match_request(&Method::GET, "/v1/shelves/1")
+            .unwrap();
+        let request = Request::get("/v1/shelves/1").body(Body::empty()).unwrap();
+        let response = transcoding_match
+            .transcode(
+                request,
+                "http://127.0.0.1:4476",
+                None,
+                track_in_flight("http://127.0.0.1:4476"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(serde_json::from_slice::<Value>(&body).unwrap()["code"], 14);
+    }
+    #[tokio::test]
+    async fn test_grpc_json_transcoder_message_too_large() {
+        start_grpc_upstream(4488).await;
+        let transcoder = GrpcJsonTranscoder::new(GrpcJsonTranscoderConfig {
+            proto_descriptor: Some(String::from("config/test_transcoding_descriptor.pb")),
+            max_message_size: Some(4),
+            ..Default::default()
+        })
+        .unwrap();
+        let transcoding_match = transcoder
+            .match_request(&Method::PUT, "/v1/shelves/1:replace")
+            .unwrap();
+        let request = Request::put("/v1/shelves/1:replace")
+            .body(Body::from(r#"{"theme":"Travel"}"#))
+            .unwrap();
+        let response = transcoding_match
+            .transcode(
+                request,
+                "http://127.0.0.1:4488",
+                None,
+                track_in_flight("http://127.0.0.1:4488"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(serde_json::from_slice::<Value>(&body).unwrap()["code"], 8);
+
+        let transcoding_match = transcoder
+            .match_request(&Method::GET, "/v1/shelves/1/books")
+            .unwrap();
+        let request = Request::get("/v1/shelves/1/books")
+            .body(Body::empty())
+            .unwrap();
+        let response = transcoding_match
+            .transcode(
+                request,
+                "http://127.0.0.1:4488",
+                None,
+                track_in_flight("http://127.0.0.1:4488"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(serde_json::from_slice::<Value>(&body).unwrap()["code"], 8);
+    }
+    #[tokio::test]
+    async fn test_grpc_json_transcoder_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:4489").await.unwrap();
+        tokio::spawn(async move {
+            while let Ok((tcp_stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut connection = h2::server::handshake(tcp_stream).await.unwrap();
+                    let mut pending_streams = vec![];
+                    while let Some(Ok(stream)) = connection.accept().await {
+                        pending_streams.push(stream);
+                    }
+                });
+            }
+        });
+        let transcoder = GrpcJsonTranscoder::new(GrpcJsonTranscoderConfig {
+            proto_descriptor: Some(String::from("config/test_transcoding_descriptor.pb")),
+            timeout_seconds: Some(1),
+            ..Default::default()
+        })
+        .unwrap();
+        let transcoding_match = transcoder
+            .match_request(&Method::GET, "/v1/shelves/1")
+            .unwrap();
+        let request = Request::get("/v1/shelves/1").body(Body::empty()).unwrap();
+        let response = transcoding_match
+            .transcode(
+                request,
+                "http://127.0.0.1:4489",
+                None,
+                track_in_flight("http://127.0.0.1:4489"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(serde_json::from_slice::<Value>(&body).unwrap()["code"], 4);
+    }
+}
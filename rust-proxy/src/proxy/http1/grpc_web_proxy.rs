@@ -0,0 +1,686 @@
+use crate::configuration_service::app_config_service::GLOBAL_CONFIG_MAPPING;
+use crate::constants::common_constants::{
+    DEFAULT_GRPC_MAX_MESSAGE_SIZE, DEFAULT_HTTP_TIMEOUT, GRPC_STATUS_HEADER,
+};
+use crate::maintenance::endpoint_maintenance::InFlightGuard;
+use crate::proxy::http2::grpc_connection_pool::GLOBAL_GRPC_CONNECTION_POOL;
+use crate::proxy::proxy_trait::{get_request_server_name, TlsConnectionInfo};
+use crate::proxy::upstream_tls::UpstreamTls;
+use crate::vojo::app_config::GrpcWebConfig;
+use base64::{engine::general_purpose, Engine as _};
+use h2::RecvStream;
+use http::header::{
+    HeaderName, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+    ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE,
+    ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, CONNECTION, CONTENT_LENGTH,
+    CONTENT_TYPE, HOST, ORIGIN, TE, TRANSFER_ENCODING, UPGRADE, VARY,
+};
+use http::{HeaderMap, HeaderValue, Method, StatusCode, Version};
+use hyper::body::{Bytes, HttpBody, Sender};
+use hyper::{Body, Request, Response};
+use std::time::Duration;
+use url::Url;
+const GRPC_CONTENT_TYPE: &str = "application/grpc";
+const GRPC_WEB_CONTENT_TYPE: &str = "application/grpc-web";
+const GRPC_WEB_TEXT_CONTENT_TYPE: &str = "application/grpc-web-text";
+const GRPC_MESSAGE_HEADER: &str = "grpc-message";
+const GRPC_STATUS_DEADLINE_EXCEEDED: &str = "4";
+const GRPC_STATUS_RESOURCE_EXHAUSTED: &str = "8";
+const GRPC_STATUS_UNAVAILABLE: &str = "14";
+const GRPC_WEB_ALLOWED_METHODS: &str = "POST, OPTIONS";
+const GRPC_WEB_EXPOSED_HEADERS: &str = "grpc-status,grpc-message,grpc-status-details-bin";
+const DEFAULT_MAX_AGE_SECONDS: u64 = 86400;
+const TRAILER_FRAME_FLAG: u8 = 0x80;
+pub fn is_grpc_web_request(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|item| item.to_str().ok())
+        .is_some_and(|item| item.starts_with(GRPC_WEB_CONTENT_TYPE))
+}
+fn is_grpc_web_text(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|item| item.to_str().ok())
+        .is_some_and(|item| item.starts_with(GRPC_WEB_TEXT_CONTENT_TYPE))
+}
+/**
+*The content type of the grpc request,which keeps the message format like +proto of the grpc-web
+ one.
+*/
+fn grpc_content_type(grpc_web_content_type: &str) -> String {
+    let format = grpc_web_content_type
+        .strip_prefix(GRPC_WEB_TEXT_CONTENT_TYPE)
+        .or_else(|| grpc_web_content_type.strip_prefix(GRPC_WEB_CONTENT_TYPE))
+        .unwrap_or_default();
+    format!("{}{}", GRPC_CONTENT_TYPE, format)
+}
+fn grpc_web_content_type(grpc_content_type: &str, is_text: bool) -> String {
+    let format = grpc_content_type
+        .strip_prefix(GRPC_CONTENT_TYPE)
+        .unwrap_or_default();
+    if is_text {
+        format!("{}{}", GRPC_WEB_TEXT_CONTENT_TYPE, format)
+    } else {
+        format!("{}{}", GRPC_WEB_CONTENT_TYPE, format)
+    }
+}
+/**
+*Decode the body of the grpc-web-text request.The base64 is decoded by the groups of four
+ characters,so the padding is allowed between the messages.
+*/
+fn decode_grpc_web_text(body: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+    let text = body
+        .iter()
+        .filter(|item| !item.is_ascii_whitespace())
+        .copied()
+        .collect::<Vec<u8>>();
+    let mut decoded = vec![];
+    for group in text.chunks(4) {
+        decoded.extend(general_purpose::STANDARD.decode(group)?);
+    }
+    Ok(decoded)
+}
+/**
+*The trailers are sent to the browser as the last frame of the body,whose flag is 0x80 and whose
+ payload is the trailers in the http/1.1 header format.
+*/
+fn encode_trailers(trailers: &HeaderMap) -> Vec<u8> {
+    let mut block = vec![];
+    for (name, value) in trailers.iter() {
+        block.extend_from_slice(name.as_str().as_bytes());
+        block.push(b':');
+        block.extend_from_slice(value.as_bytes());
+        block.extend_from_slice(b"\r\n");
+    }
+    let mut frame = vec![TRAILER_FRAME_FLAG];
+    frame.extend_from_slice(&(block.len() as u32).to_be_bytes());
+    frame.extend(block);
+    frame
+}
+fn encode_chunk(chunk: &[u8], is_text: bool) -> Bytes {
+    if is_text {
+        Bytes::from(general_purpose::STANDARD.encode(chunk))
+    } else {
+        Bytes::copy_from_slice(chunk)
+    }
+}
+fn percent_encode(message: &str) -> String {
+    message
+        .bytes()
+        .map(|item| {
+            if (0x20..0x7f).contains(&item) && item != b'%' {
+                (item as char).to_string()
+            } else {
+                format!("%{:02X}", item)
+            }
+        })
+        .collect()
+}
+pub fn is_hop_by_hop_header(name: &HeaderName) -> bool {
+    [
+        HOST,
+        CONNECTION,
+        CONTENT_LENGTH,
+        CONTENT_TYPE,
+        TRANSFER_ENCODING,
+        TE,
+        UPGRADE,
+    ]
+    .contains(name)
+        || name.as_str() == "keep-alive"
+}
+/**
+*Read the whole body unless it is larger than the limit,and None is returned for the larger body
+ without reading the rest of it.
+*/
+pub async fn read_body_with_limit(
+    mut body: Body,
+    limit: usize,
+) -> Result<Option<Bytes>, anyhow::Error> {
+    if body.size_hint().lower() > limit as u64 {
+        return Ok(None);
+    }
+    let mut buffer = vec![];
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if buffer.len() + chunk.len() > limit {
+            return Ok(None);
+        }
+        buffer.extend_from_slice(&chunk);
+    }
+    Ok(Some(Bytes::from(buffer)))
+}
+fn allowed_origin(grpc_web_config: &GrpcWebConfig, headers: &HeaderMap) -> Option<HeaderValue> {
+    let origin = headers.get(ORIGIN)?;
+    let is_allowed = grpc_web_config.allowed_origins.is_empty()
+        || grpc_web_config
+            .allowed_origins
+            .iter()
+            .any(|item| item == "*" || item.as_bytes() == origin.as_bytes());
+    is_allowed.then(|| origin.clone())
+}
+fn apply_cors_headers(headers: &mut HeaderMap, origin: HeaderValue) {
+    headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    headers.insert(
+        ACCESS_CONTROL_EXPOSE_HEADERS,
+        HeaderValue::from_static(GRPC_WEB_EXPOSED_HEADERS),
+    );
+    headers.append(VARY, HeaderValue::from_static("origin"));
+}
+fn forbidden_response() -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::FORBIDDEN)
+        .body(Body::empty())
+        .unwrap()
+}
+/**
+*The trailers-only response which carries the grpc status of the error in the headers.
+*/
+fn grpc_web_error_response(is_text: bool, grpc_status: &str, message: &str) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(
+            CONTENT_TYPE,
+            grpc_web_content_type(GRPC_CONTENT_TYPE, is_text),
+        )
+        .header(GRPC_STATUS_HEADER, grpc_status)
+        .header(GRPC_MESSAGE_HEADER, percent_encode(message))
+        .body(Body::empty())
+        .unwrap()
+}
+fn find_grpc_web_config(
+    mapping_key: &str,
+    req: &Request<Body>,
+) -> Result<Option<GrpcWebConfig>, anyhow::Error> {
+    let api_service_manager = match GLOBAL_CONFIG_MAPPING.get(mapping_key) {
+        Some(api_service_manager) => api_service_manager,
+        None => return Ok(None),
+    };
+    let path = req
+        .uri()
+        .path_and_query()
+        .map(|item| item.to_string())
+        .unwrap_or_default();
+    let tls_server_name = req
+        .extensions()
+        .get::<TlsConnectionInfo>()
+        .and_then(|item| item.server_name.clone());
+    let server_name = get_request_server_name(req.uri(), req.headers(), tls_server_name.as_deref());
+    for item in api_service_manager.service_config.routes.iter() {
+        if item
+            .is_matched(path.clone(), Some(req.headers().clone()))?
+            .is_none()
+            || !item.is_server_name_matched(server_name.as_deref())
+            || !item.is_grpc_matched(req.uri().path())
+        {
+            continue;
+        }
+        return Ok(item.grpc_web.clone());
+    }
+    Ok(None)
+}
+/**
+*Answer the cors preflight of the route with the grpc-web config,which is sent by the browsers
+ without the credentials before the grpc-web requests.
+*/
+pub fn grpc_web_preflight_response(
+    mapping_key: &str,
+    req: &Request<Body>,
+) -> Result<Option<Response<Body>>, anyhow::Error> {
+    if req.method() != Method::OPTIONS || !req.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+    {
+        return Ok(None);
+    }
+    let grpc_web_config = match find_grpc_web_config(mapping_key, req)? {
+        Some(grpc_web_config) => grpc_web_config,
+        None => return Ok(None),
+    };
+    let origin = match allowed_origin(&grpc_web_config, req.headers()) {
+        Some(origin) => origin,
+        None => return Ok(Some(forbidden_response())),
+    };
+    let max_age_seconds = grpc_web_config
+        .max_age_seconds
+        .unwrap_or(DEFAULT_MAX_AGE_SECONDS);
+    let mut response = Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .header(ACCESS_CONTROL_ALLOW_METHODS, GRPC_WEB_ALLOWED_METHODS)
+        .header(ACCESS_CONTROL_MAX_AGE, max_age_seconds)
+        .body(Body::empty())?;
+    if let Some(request_headers) = req.headers().get(ACCESS_CONTROL_REQUEST_HEADERS) {
+        response
+            .headers_mut()
+            .insert(ACCESS_CONTROL_ALLOW_HEADERS, request_headers.clone());
+    }
+    apply_cors_headers(response.headers_mut(), origin);
+    Ok(Some(response))
+}
+/**
+*Translate the grpc-web request into the grpc one to the upstream,and the response is translated
+ back with the trailers at the end of the body.The error before the response is returned as the
+ unavailable grpc status,the larger body as the resource exhausted one and the upstream which does
+ not respond in time as the deadline exceeded one.
+*/
+pub async fn grpc_web_proxy(
+    req: Request<Body>,
+    request_path: String,
+    upstream_tls: Option<&UpstreamTls>,
+    grpc_web_config: &GrpcWebConfig,
+    in_flight_guard: InFlightGuard,
+) -> Result<Response<Body>, anyhow::Error> {
+    let origin = match req.headers().get(ORIGIN) {
+        Some(_) => match allowed_origin(grpc_web_config, req.headers()) {
+            Some(origin) => Some(origin),
+            None => return Ok(forbidden_response()),
+        },
+        None => None,
+    };
+    let is_text = is_grpc_web_text(req.headers());
+    let max_body_size = grpc_web_config
+        .max_body_size
+        .unwrap_or(DEFAULT_GRPC_MAX_MESSAGE_SIZE);
+    let timeout = Duration::from_secs(
+        grpc_web_config
+            .timeout_seconds
+            .unwrap_or(DEFAULT_HTTP_TIMEOUT),
+    );
+    let (parts, body) = req.into_parts();
+    let mut response = match read_body_with_limit(body, max_body_size).await {
+        Ok(Some(body)) => {
+            let forward_future = forward_grpc_web(
+                parts,
+                body,
+                &request_path,
+                upstream_tls,
+                is_text,
+                in_flight_guard,
+            );
+            match tokio::time::timeout(timeout, forward_future).await {
+                Ok(Ok(response)) => response,
+                Ok(Err(err)) => {
+                    error!("Grpc-web request outbound error,the error is {}.", err);
+                    grpc_web_error_response(is_text, GRPC_STATUS_UNAVAILABLE, &err.to_string())
+                }
+                Err(_) => {
+                    let message = format!("The upstream does not respond in {:?}.", timeout);
+                    grpc_web_error_response(is_text, GRPC_STATUS_DEADLINE_EXCEEDED, &message)
+                }
+            }
+        }
+        Ok(None) => {
+            let message = format!(
+                "The request body is larger than the limit of {} bytes.",
+                max_body_size
+            );
+            grpc_web_error_response(is_text, GRPC_STATUS_RESOURCE_EXHAUSTED, &message)
+        }
+        Err(err) => {
+            error!("Read the grpc-web request error,the error is {}.", err);
+            grpc_web_error_response(is_text, GRPC_STATUS_UNAVAILABLE, &err.to_string())
+        }
+    };
+    if let Some(origin) = origin {
+        apply_cors_headers(response.headers_mut(), origin);
+    }
+    Ok(response)
+}
+async fn forward_grpc_web(
+    parts: http::request::Parts,
+    mut body: Bytes,
+    request_path: &str,
+    upstream_tls: Option<&UpstreamTls>,
+    is_text: bool,
+    in_flight_guard: InFlightGuard,
+) -> Result<Response<Body>, anyhow::Error> {
+    if is_text {
+        body = Bytes::from(decode_grpc_web_text(&body)?);
+    }
+    let url = Url::parse(request_path)?;
+    let content_type = parts
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|item| item.to_str().ok())
+        .unwrap_or(GRPC_WEB_CONTENT_TYPE);
+    let mut request = Request::builder()
+        .method(Method::POST)
+        .version(Version::HTTP_2)
+        .uri(url.as_str())
+        .header(CONTENT_TYPE, grpc_content_type(content_type))
+        .header(TE, "trailers")
+        .body(())?;
+    for (name, value) in parts.headers.iter() {
+        if !is_hop_by_hop_header(name) {
+            request.headers_mut().append(name, value.clone());
+        }
+    }
+    debug!("The grpc request translated from grpc-web is {:?}", request);
+    let (response_future, mut send_stream, pooled_stream) = GLOBAL_GRPC_CONNECTION_POOL
+        .send_request(&url, upstream_tls, request)
+        .await?;
+    send_stream.send_data(body, true)?;
+    let (head, recv_stream) = response_future.await?.into_parts();
+
+    let mut response = Response::builder()
+        .status(head.status)
+        .body(Body::empty())?;
+    for (name, value) in head.headers.iter() {
+        if !is_hop_by_hop_header(name) {
+            response.headers_mut().append(name, value.clone());
+        }
+    }
+    let upstream_content_type = head
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|item| item.to_str().ok())
+        .unwrap_or(GRPC_CONTENT_TYPE);
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_str(&grpc_web_content_type(upstream_content_type, is_text))?,
+    );
+    let (sender, body) = Body::channel();
+    tokio::spawn(async move {
+        if let Err(err) = copy_grpc_web_body(recv_stream, sender, is_text).await {
+            error!("Copy from grpc to grpc-web error,the error is {}", err);
+        }
+        drop(pooled_stream);
+        drop(in_flight_guard);
+    });
+    *response.body_mut() = body;
+    Ok(response)
+}
+async fn copy_grpc_web_body(
+    mut recv_stream: RecvStream,
+    mut sender: Sender,
+    is_text: bool,
+) -> Result<(), anyhow::Error> {
+    let mut flow_control = recv_stream.flow_control().clone();
+    while let Some(chunk_result) = recv_stream.data().await {
+        let chunk = chunk_result?;
+        flow_control.release_capacity(chunk.len())?;
+        sender.send_data(encode_chunk(&chunk, is_text)).await?;
+    }
+    if let Some(trailers) = recv_stream.trailers().await? {
+        sender
+            .send_data(encode_chunk(&encode_trailers(&trailers), is_text))
+            .await?;
+    }
+    Ok(())
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maintenance::endpoint_maintenance::track_in_flight;
+    use crate::proxy::http1::http_proxy::HttpProxy;
+    use crate::vojo::api_service_manager::ApiServiceManager;
+    use crate::vojo::app_config::{Route, ServiceConfig};
+    use crate::vojo::app_config_vistor::RouteVistor;
+    use serde_json::json;
+    use tokio::net::TcpListener;
+    use tokio::time::{sleep, Duration};
+    async fn start_grpc_upstream(port: u16) {
+        let listener = TcpListener::bind(format!("127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        tokio::spawn(async move {
+            while let Ok((tcp_stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut connection = h2::server::handshake(tcp_stream).await.unwrap();
+                    while let Some(Ok((request, mut respond))) = connection.accept().await {
+                        let (parts, mut body) = request.into_parts();
+                        let mut message = vec![];
+                        while let Some(Ok(chunk)) = body.data().await {
+                            message.extend_from_slice(&chunk);
+                        }
+                        let response = Response::builder()
+                            .header(CONTENT_TYPE, parts.headers[CONTENT_TYPE].clone())
+                            .header("x-path", parts.uri.path())
+                            .body(())
+                            .unwrap();
+                        let mut send_stream = respond.send_response(response, false).unwrap();
+                        send_stream.send_data(Bytes::from(message), false).unwrap();
+                        let mut trailers = HeaderMap::new();
+                        trailers.insert(GRPC_STATUS_HEADER, HeaderValue::from_static("0"));
+                        send_stream.send_trailers(trailers).unwrap();
+                    }
+                });
+            }
+        });
+    }
+    async fn insert_grpc_web_route(mapping_key: &str, endpoint: &str) {
+        let route_vistor: RouteVistor = serde_json::from_value(json!({
+            "matcher": { "prefix": "/", "prefix_rewrite": "/" },
+            "grpc_web": { "allowed_origins": ["https://app.example.com"] },
+            "route_cluster": {
+                "type": "WeightBasedRoute",
+                "routes": [{ "base_route": { "endpoint": endpoint }, "weight": 100 }]
+            }
+        }))
+        .unwrap();
+        let (sender, _) = tokio::sync::mpsc::channel(1);
+        GLOBAL_CONFIG_MAPPING.insert(
+            String::from(mapping_key),
+            ApiServiceManager {
+                service_config: ServiceConfig {
+                    routes: vec![Route::from(route_vistor).await.unwrap()],
+                    ..Default::default()
+                },
+                sender,
+            },
+        );
+    }
+    fn message_frame(message: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0];
+        frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        frame.extend_from_slice(message);
+        frame
+    }
+    #[test]
+    fn test_grpc_content_type_ok() {
+        assert_eq!(
+            grpc_content_type("application/grpc-web"),
+            "application/grpc"
+        );
+        assert_eq!(
+            grpc_content_type("application/grpc-web-text+proto"),
+            "application/grpc+proto"
+        );
+        assert_eq!(
+            grpc_web_content_type("application/grpc+proto", true),
+            "application/grpc-web-text+proto"
+        );
+        assert_eq!(
+            grpc_web_content_type("application/grpc", false),
+            "application/grpc-web"
+        );
+    }
+    #[test]
+    fn test_decode_grpc_web_text_ok() {
+        let first = general_purpose::STANDARD.encode(message_frame(b"a"));
+        let second = general_purpose::STANDARD.encode(message_frame(b"bc"));
+        let decoded = decode_grpc_web_text(format!("{}{}\r\n", first, second).as_bytes()).unwrap();
+        assert_eq!(
+            decoded,
+            [message_frame(b"a"), message_frame(b"bc")].concat()
+        );
+        assert!(decode_grpc_web_text(b"AAA*").is_err());
+    }
+    #[test]
+    fn test_encode_trailers_ok() {
+        let mut trailers = HeaderMap::new();
+        trailers.insert(GRPC_STATUS_HEADER, HeaderValue::from_static("0"));
+        assert_eq!(
+            encode_trailers(&trailers),
+            b"\x80\x00\x00\x00\x0fgrpc-status:0\r\n".to_vec()
+        );
+        assert_eq!(percent_encode("a%b\n"), "a%25b%0A");
+    }
+    #[tokio::test]
+    async fn test_grpc_web_preflight_response_ok() {
+        insert_grpc_web_route("4471-HTTP", "http://127.0.0.1:4469").await;
+        let preflight_request = |origin: &'static str| {
+            Request::builder()
+                .method(Method::OPTIONS)
+                .uri("/helloworld.Greeter/SayHello")
+                .header(ORIGIN, origin)
+                .header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                .header(ACCESS_CONTROL_REQUEST_HEADERS, "content-type,x-grpc-web")
+                .body(Body::empty())
+                .unwrap()
+        };
+        let response =
+            grpc_web_preflight_response("4471-HTTP", &preflight_request("https://app.example.com"))
+                .unwrap()
+                .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+        assert_eq!(
+            response.headers()[ACCESS_CONTROL_ALLOW_HEADERS],
+            "content-type,x-grpc-web"
+        );
+        assert_eq!(response.headers()[ACCESS_CONTROL_MAX_AGE], "86400");
+        let response = grpc_web_preflight_response(
+            "4471-HTTP",
+            &preflight_request("https://evil.example.com"),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/helloworld.Greeter/SayHello")
+            .body(Body::empty())
+            .unwrap();
+        assert!(grpc_web_preflight_response("4471-HTTP", &request)
+            .unwrap()
+            .is_none());
+    }
+    #[tokio::test]
+    async fn test_grpc_web_proxy_ok() {
+        start_grpc_upstream(4469).await;
+        insert_grpc_web_route("4470-HTTP", "http://127.0.0.1:4469").await;
+        let (_sender, receiver) = tokio::sync::mpsc::channel(10);
+        tokio::spawn(async {
+            let mut http_proxy = HttpProxy {
+                port: 4470,
+                channel: receiver,
+                mapping_key: String::from("4470-HTTP"),
+            };
+            let _result = http_proxy.start_http_server().await;
+        });
+        sleep(Duration::from_millis(100)).await;
+
+        let client = hyper::Client::new();
+        let request = Request::post("http://127.0.0.1:4470/helloworld.Greeter/SayHello")
+            .header(CONTENT_TYPE, "application/grpc-web-text+proto")
+            .header(ORIGIN, "https://app.example.com")
+            .header("x-grpc-web", "1")
+            .body(Body::from(
+                general_purpose::STANDARD.encode(message_frame(b"hello")),
+            ))
+            .unwrap();
+        let response = client.request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[CONTENT_TYPE],
+            "application/grpc-web-text+proto"
+        );
+        assert_eq!(
+            response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+        assert_eq!(response.headers()["x-path"], "/helloworld.Greeter/SayHello");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let mut trailers = HeaderMap::new();
+        trailers.insert(GRPC_STATUS_HEADER, HeaderValue::from_static("0"));
+        assert_eq!(
+            decode_grpc_web_text(&body).unwrap(),
+            [message_frame(b"hello"), encode_trailers(&trailers)].concat()
+        );
+
+        let request = Request::post("http://127.0.0.1:4470/helloworld.Greeter/SayHello")
+            .header(CONTENT_TYPE, "application/grpc-web+proto")
+            .header(ORIGIN, "https://evil.example.com")
+            .body(Body::from(message_frame(b"hello")))
+            .unwrap();
+        let response = client.request(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+    #[tokio::test]
+    async fn test_grpc_web_proxy_unavailable() {
+        let request = Request::post("/helloworld.Greeter/SayHello")
+            .header(CONTENT_TYPE, "application/grpc-web")
+            .body(Body::from(message_frame(b"hello")))
+            .unwrap();
+        let response = grpc_web_proxy(
+            request,
+            String::from("http://127.0.0.1:4473/helloworld.Greeter/SayHello"),
+            None,
+            &GrpcWebConfig::default(),
+            track_in_flight("http://127.0.0.1:4473"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[GRPC_STATUS_HEADER], "14");
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/grpc-web");
+    }
+    #[tokio::test]
+    async fn test_grpc_web_proxy_body_too_large() {
+        let request = Request::post("/helloworld.Greeter/SayHello")
+            .header(CONTENT_TYPE, "application/grpc-web")
+            .body(Body::from(message_frame(b"hello")))
+            .unwrap();
+        let grpc_web_config = GrpcWebConfig {
+            max_body_size: Some(8),
+            ..Default::default()
+        };
+        let response = grpc_web_proxy(
+            request,
+            String::from("http://127.0.0.1:4473/helloworld.Greeter/SayHello"),
+            None,
+            &grpc_web_config,
+            track_in_flight("http://127.0.0.1:4473"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[GRPC_STATUS_HEADER], "8");
+    }
+    #[tokio::test]
+    async fn test_grpc_web_proxy_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:4485").await.unwrap();
+        tokio::spawn(async move {
+            while let Ok((tcp_stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut connection = h2::server::handshake(tcp_stream).await.unwrap();
+                    let mut pending_streams = vec![];
+                    while let Some(Ok(stream)) = connection.accept().await {
+                        pending_streams.push(stream);
+                    }
+                });
+            }
+        });
+        let request = Request::post("/helloworld.Greeter/SayHello")
+            .header(CONTENT_TYPE, "application/grpc-web")
+            .body(Body::from(message_frame(b"hello")))
+            .unwrap();
+        let grpc_web_config = GrpcWebConfig {
+            timeout_seconds: Some(1),
+            ..Default::default()
+        };
+        let response = grpc_web_proxy(
+            request,
+            String::from("http://127.0.0.1:4485/helloworld.Greeter/SayHello"),
+            None,
+            &grpc_web_config,
+            track_in_flight("http://127.0.0.1:4485"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[GRPC_STATUS_HEADER], "4");
+    }
+}
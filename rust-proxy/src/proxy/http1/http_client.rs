@@ -1,3 +1,4 @@
+use crate::proxy::upstream_tls::UpstreamTls;
 use hyper::client::HttpConnector;
 use hyper::client::ResponseFuture;
 use hyper::Body;
@@ -46,8 +47,18 @@ impl HttpClients {
         let request_future = self.http_client.request(req);
         timeout(Duration::from_secs(time_out), request_future)
     }
-    pub fn request_https(&self, req: Request<Body>, time_out: u64) -> Timeout<ResponseFuture> {
-        let request_future = self.https_client.request(req);
-        timeout(Duration::from_secs(time_out), request_future)
+    /**
+     *Request the upstream with the https client of the route if it has the upstream tls settings.
+     */
+    pub fn request_https(
+        &self,
+        req: Request<Body>,
+        upstream_tls: Option<&UpstreamTls>,
+        time_out: u64,
+    ) -> Timeout<ResponseFuture> {
+        let https_client = upstream_tls
+            .map(|item| &item.https_client)
+            .unwrap_or(&self.https_client);
+        timeout(Duration::from_secs(time_out), https_client.request(req))
     }
 }
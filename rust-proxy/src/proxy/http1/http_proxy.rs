@@ -1,19 +1,28 @@
+use crate::acme::acme_client::acme_challenge_response;
 use crate::constants::common_constants;
 use crate::constants::common_constants::DEFAULT_HTTP_TIMEOUT;
+use crate::maintenance::endpoint_maintenance::{track_in_flight, InFlightGuard};
 use crate::monitor::prometheus_exporter::{get_timer_list, inc};
+use crate::proxy::certificate_resolver::build_server_config;
+use crate::proxy::http1::grpc_web_proxy::{
+    grpc_web_preflight_response, grpc_web_proxy, is_grpc_web_request,
+};
 use crate::proxy::http1::http_client::HttpClients;
 use crate::proxy::http1::tls_acceptor::TlsAcceptor;
 use crate::proxy::http1::tls_stream::TlsStream;
 use crate::vojo::anomaly_detection::AnomalyDetectionType;
-use crate::vojo::app_config::{LivenessConfig, LivenessStatus};
+use crate::vojo::app_config::{AtomicLivenessStatus, LivenessConfig};
 use crate::vojo::route::BaseRoute;
 use http::uri::InvalidUri;
-use http::{StatusCode, Uri};
-use hyper::header::{CONNECTION, SEC_WEBSOCKET_KEY};
+use http::{HeaderValue, StatusCode, Uri, Version};
+use hyper::header::{CONNECTION, HOST, SEC_WEBSOCKET_KEY};
 
 use crate::proxy::http1::websocket_proxy::server_upgrade;
 use crate::proxy::proxy_trait::CheckTrait;
 use crate::proxy::proxy_trait::CommonCheckRequest;
+use crate::proxy::proxy_trait::TlsConnectionInfo;
+use crate::vojo::app_config::ServiceConfig;
+use futures::StreamExt;
 use http::uri::PathAndQuery;
 use hyper::server::conn::AddrIncoming;
 use hyper::server::conn::AddrStream;
@@ -24,13 +33,12 @@ use log::Level;
 use prometheus::HistogramTimer;
 use serde_json::json;
 use std::convert::Infallible;
-use std::io::BufReader;
 use std::net::SocketAddr;
 use std::path::Path;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::SystemTime;
 use tokio::sync::mpsc;
-use tokio::sync::RwLock;
 #[derive(Debug)]
 pub struct HttpProxy {
     pub port: i32,
@@ -79,8 +87,7 @@ impl HttpProxy {
     }
     pub async fn start_https_server(
         &mut self,
-        pem_str: String,
-        key_str: String,
+        service_config: &ServiceConfig,
     ) -> Result<(), anyhow::Error> {
         let port_clone = self.port;
         let addr = SocketAddr::from(([0, 0, 0, 0], port_clone as u16));
@@ -91,31 +98,18 @@ impl HttpProxy {
             let client = client.clone();
             let mapping_key2 = mapping_key_clone1.clone();
             let remote_addr = socket.remote_addr();
+            let tls_connection_info = socket.tls_connection_info();
 
             async move {
-                Ok::<_, Infallible>(service_fn(move |req| {
+                Ok::<_, Infallible>(service_fn(move |mut req: Request<Body>| {
+                    if let Some(tls_connection_info) = tls_connection_info.get() {
+                        req.extensions_mut().insert(tls_connection_info.clone());
+                    }
                     proxy_adapter(client.clone(), req, mapping_key2.clone(), remote_addr)
                 }))
             }
         });
-        let mut cer_reader = BufReader::new(pem_str.as_bytes());
-        let certs = rustls_pemfile::certs(&mut cer_reader)
-            .unwrap()
-            .iter()
-            .map(|s| rustls::Certificate((*s).clone()))
-            .collect();
-
-        let doc = pkcs8::PrivateKeyDocument::from_pem(&key_str).unwrap();
-        let key_der = rustls::PrivateKey(doc.as_ref().to_owned());
-
-        let tls_cfg = {
-            let cfg = rustls::ServerConfig::builder()
-                .with_safe_defaults()
-                .with_no_client_auth()
-                .with_single_cert(certs, key_der)
-                .unwrap();
-            Arc::new(cfg)
-        };
+        let tls_cfg = build_server_config(self.mapping_key.clone(), service_config)?;
         let incoming = AddrIncoming::bind(&addr).map_err(|e| {
             anyhow!(
                 "Cause error when binding the socket,the addr is {},the error is {}.",
@@ -210,14 +204,23 @@ async fn proxy(
     if log_enabled!(Level::Debug) {
         debug!("req: {:?}", req);
     }
+    if let Some(key_authorization) = acme_challenge_response(req.uri().path()) {
+        return Ok(Response::new(Body::from(key_authorization)));
+    }
+    downgrade_http2_request(&mut req);
+    if let Some(response) = grpc_web_preflight_response(&mapping_key, &req)? {
+        return Ok(response);
+    }
     let inbound_headers = req.headers().clone();
     let uri = req.uri().clone();
+    let tls_connection_info = req.extensions().get::<TlsConnectionInfo>().cloned();
     let check_result = check_trait
         .check_before_request(
             mapping_key.clone(),
             inbound_headers.clone(),
             uri,
             remote_addr,
+            tls_connection_info,
         )
         .await?;
     if check_result.is_none() {
@@ -226,6 +229,12 @@ async fn proxy(
             .body(Body::from(common_constants::DENY_RESPONSE))
             .unwrap());
     }
+    if let Some(client_identity_header) = check_result
+        .as_ref()
+        .and_then(|item| item.client_identity_header.as_ref())
+    {
+        client_identity_header.apply(req.headers_mut())?;
+    }
     if inbound_headers.clone().contains_key(CONNECTION)
         && inbound_headers.contains_key(SEC_WEBSOCKET_KEY)
     {
@@ -240,6 +249,35 @@ async fn proxy(
         let request_path = check_request.request_path;
         let base_route = check_request.base_route;
         let route = check_request.route;
+        let in_flight_guard = track_in_flight(base_route.endpoint.as_str());
+        if let Some(grpc_web_config) = route
+            .grpc_web
+            .as_ref()
+            .filter(|_| is_grpc_web_request(req.headers()))
+        {
+            return grpc_web_proxy(
+                req,
+                request_path,
+                route.upstream_tls.as_ref(),
+                grpc_web_config,
+                in_flight_guard,
+            )
+            .await;
+        }
+        if let Some(transcoding_match) = route
+            .grpc_json_transcoder
+            .as_ref()
+            .and_then(|item| item.match_request(req.method(), req.uri().path()))
+        {
+            return transcoding_match
+                .transcode(
+                    req,
+                    base_route.endpoint.as_str(),
+                    route.upstream_tls.as_ref(),
+                    in_flight_guard,
+                )
+                .await;
+        }
         if !request_path.clone().contains("http") {
             let mut parts = req.uri().clone().into_parts();
             parts.path_and_query = Some(request_path.try_into().unwrap());
@@ -250,7 +288,7 @@ async fn proxy(
             .parse()
             .map_err(|err: InvalidUri| anyhow!(err.to_string()))?;
         let request_future = if request_path.contains("https") {
-            client.request_https(req, DEFAULT_HTTP_TIMEOUT)
+            client.request_https(req, route.upstream_tls.as_ref(), DEFAULT_HTTP_TIMEOUT)
         } else {
             client.request_http(req, DEFAULT_HTTP_TIMEOUT)
         };
@@ -269,10 +307,10 @@ async fn proxy(
                 }
                 Err(_) => true,
             };
-            let temporary_base_route = base_route.clone();
-            let anomaly_detection_status_lock =
-                temporary_base_route.anomaly_detection_status.read().await;
-            let consecutive_5xx = anomaly_detection_status_lock.consecutive_5xx;
+            let consecutive_5xx = base_route
+                .anomaly_detection_status
+                .consecutive_5xx
+                .load(Ordering::SeqCst);
             if is_5xx || consecutive_5xx > 0 {
                 if let Err(err) = trigger_anomaly_detection(
                     anomaly_detection,
@@ -287,16 +325,51 @@ async fn proxy(
                 }
             }
         }
-        return response_result;
+        return response_result
+            .map(|response| release_in_flight_after_body(response, in_flight_guard));
     }
     Ok(Response::builder()
         .status(StatusCode::NOT_FOUND)
         .body(Body::from(common_constants::NOT_FOUND))
         .unwrap())
 }
+/**
+*The h2 request is forwarded as the http/1.1 one,and its authority becomes the host header which
+ the http/1.1 clients send.
+*/
+fn downgrade_http2_request(req: &mut Request<Body>) {
+    if req.version() != Version::HTTP_2 {
+        return;
+    }
+    if !req.headers().contains_key(HOST) {
+        if let Some(authority) = req
+            .uri()
+            .authority()
+            .and_then(|item| HeaderValue::from_str(item.as_str()).ok())
+        {
+            req.headers_mut().insert(HOST, authority);
+        }
+    }
+    *req.version_mut() = Version::HTTP_11;
+}
+/**
+*The in-flight guard is moved into the body stream,so the endpoint is counted
+ as busy until the whole response has been sent to the client.
+*/
+fn release_in_flight_after_body(
+    response: Response<Body>,
+    in_flight_guard: InFlightGuard,
+) -> Response<Body> {
+    let (parts, body) = response.into_parts();
+    let body_stream = body.map(move |chunk| {
+        let _ = &in_flight_guard;
+        chunk
+    });
+    Response::from_parts(parts, Body::wrap_stream(body_stream))
+}
 async fn trigger_anomaly_detection(
     anomaly_detection: AnomalyDetectionType,
-    liveness_status_lock: Arc<RwLock<LivenessStatus>>,
+    liveness_status: Arc<AtomicLivenessStatus>,
     base_route: BaseRoute,
     is_5xx: bool,
     liveness_config: LivenessConfig,
@@ -305,7 +378,7 @@ async fn trigger_anomaly_detection(
     let res = base_route
         .trigger_http_anomaly_detection(
             http_anomaly_detection_param,
-            liveness_status_lock,
+            liveness_status,
             is_5xx,
             liveness_config,
         )
@@ -362,29 +435,40 @@ mod tests {
     use crate::vojo::allow_deny_ip::AllowType;
 
     use crate::configuration_service::app_config_service::GLOBAL_CONFIG_MAPPING;
+    use crate::proxy::certificate_resolver::update_server_config;
     use crate::utils::uuid::get_uuid;
     use crate::vojo::anomaly_detection::BaseAnomalyDetectionParam;
     use crate::vojo::anomaly_detection::HttpAnomalyDetectionParam;
     use crate::vojo::api_service_manager::ApiServiceManager;
     use crate::vojo::app_config::ApiService;
+    use crate::vojo::app_config::AtomicLivenessStatus;
+    use crate::vojo::app_config::CertificateConfig;
     use crate::vojo::app_config::LivenessStatus;
     use crate::vojo::app_config::Matcher;
     use crate::vojo::app_config::Route;
     use crate::vojo::app_config::ServiceConfig;
+    use crate::vojo::app_config::ServiceType;
+    use crate::vojo::app_config::{ClientAuthConfig, ClientAuthMode};
+    use crate::vojo::app_config::{TlsPolicyConfig, TlsVersion};
     use crate::vojo::base_response::BaseResponse;
     use crate::vojo::route::AnomalyDetectionStatus;
+    use crate::vojo::route::{AtomicAliveStatus, AtomicAnomalyDetectionStatus};
     use crate::vojo::route::{BaseRoute, LoadbalancerStrategy, RandomBaseRoute, RandomRoute};
     use lazy_static::lazy_static;
+    use openssl::nid::Nid;
+    use openssl::ssl::{
+        SslConnector, SslFiletype, SslMethod, SslStream, SslVerifyMode, SslVersion,
+    };
     use regex::Regex;
     use std::collections::HashMap;
     use std::env;
     use std::fs::File;
     use std::io::BufReader;
+    use std::io::{Read, Write};
     use std::net::{IpAddr, Ipv4Addr};
     use std::sync::Arc;
     use std::{thread, time};
     use tokio::runtime::{Builder, Runtime};
-    use tokio::sync::RwLock;
 
     lazy_static! {
         pub static ref TOKIO_RUNTIME: Runtime = Builder::new_multi_thread()
@@ -487,9 +571,13 @@ mod tests {
                 channel: receiver,
                 mapping_key: String::from("random key"),
             };
-            let _result = http_proxy
-                .start_https_server(ca_certificate, private_key)
-                .await;
+            let service_config = ServiceConfig {
+                server_type: ServiceType::Https,
+                cert_str: Some(ca_certificate),
+                key_str: Some(private_key),
+                ..Default::default()
+            };
+            let _result = http_proxy.start_https_server(&service_config).await;
         });
         let sleep_time = time::Duration::from_millis(100);
         thread::sleep(sleep_time);
@@ -499,7 +587,7 @@ mod tests {
                 .uri("https://localhost:4450/get")
                 .body(Body::empty())
                 .unwrap();
-            let response_result = client.request_https(request, 5).await;
+            let response_result = client.request_https(request, None, 5).await;
             assert!(response_result.is_ok());
             let response = response_result.unwrap().unwrap();
             assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
@@ -511,6 +599,295 @@ mod tests {
         let sleep_time2 = time::Duration::from_millis(100);
         thread::sleep(sleep_time2);
     }
+    fn get_peer_common_name(port: i32, server_name: &str) -> String {
+        let mut connector_builder = SslConnector::builder(SslMethod::tls()).unwrap();
+        connector_builder.set_verify(SslVerifyMode::NONE);
+        let stream = std::net::TcpStream::connect(format!("127.0.0.1:{}", port)).unwrap();
+        let ssl_stream = connector_builder
+            .build()
+            .connect(server_name, stream)
+            .unwrap();
+        let peer_certificate = ssl_stream.ssl().peer_certificate().unwrap();
+        let common_name = peer_certificate
+            .subject_name()
+            .entries_by_nid(Nid::COMMONNAME)
+            .next()
+            .unwrap()
+            .data()
+            .as_slice()
+            .to_vec();
+        String::from_utf8(common_name).unwrap()
+    }
+    #[test]
+    fn test_https_certificate_rotation_ok() {
+        let config_dir = env::current_dir().unwrap().join("config");
+        let cert_str = std::fs::read_to_string(config_dir.join("test_cert.pem")).unwrap();
+        let key_str = std::fs::read_to_string(config_dir.join("test_key.pem")).unwrap();
+        let rotated_cert_str =
+            std::fs::read_to_string(config_dir.join("test_rotated_cert.pem")).unwrap();
+        let rotated_key_str =
+            std::fs::read_to_string(config_dir.join("test_rotated_key.pem")).unwrap();
+        let (sender, receiver) = tokio::sync::mpsc::channel(10);
+        TOKIO_RUNTIME.spawn(async {
+            let mut http_proxy = HttpProxy {
+                port: 4451,
+                channel: receiver,
+                mapping_key: String::from("4451-HTTPS"),
+            };
+            let service_config = ServiceConfig {
+                server_type: ServiceType::Https,
+                cert_str: Some(cert_str),
+                key_str: Some(key_str),
+                ..Default::default()
+            };
+            let _result = http_proxy.start_https_server(&service_config).await;
+        });
+        thread::sleep(time::Duration::from_millis(100));
+        assert_eq!(get_peer_common_name(4451, "localhost"), "ss");
+
+        let rotated_service_config = ServiceConfig {
+            server_type: ServiceType::Https,
+            cert_str: Some(rotated_cert_str),
+            key_str: Some(rotated_key_str),
+            ..Default::default()
+        };
+        update_server_config("4451-HTTPS", &rotated_service_config).unwrap();
+        assert_eq!(get_peer_common_name(4451, "localhost"), "rotated");
+        TOKIO_RUNTIME.block_on(async {
+            sender.send(()).await.unwrap();
+        });
+    }
+    #[test]
+    fn test_https_server_name_certificate_ok() {
+        let config_dir = env::current_dir().unwrap().join("config");
+        let read_config_file =
+            |file_name: &str| std::fs::read_to_string(config_dir.join(file_name)).unwrap();
+        let service_config = ServiceConfig {
+            server_type: ServiceType::Https,
+            cert_str: Some(read_config_file("test_cert.pem")),
+            key_str: Some(read_config_file("test_key.pem")),
+            certificates: vec![
+                CertificateConfig {
+                    server_names: vec![String::from("a.example.com")],
+                    cert_str: read_config_file("test_sni_cert.pem"),
+                    key_str: read_config_file("test_sni_key.pem"),
+                    key_passphrase: None,
+                },
+                CertificateConfig {
+                    server_names: vec![String::from("*.b.example.com")],
+                    cert_str: read_config_file("test_rotated_cert.pem"),
+                    key_str: read_config_file("test_rotated_key.pem"),
+                    key_passphrase: None,
+                },
+            ],
+            ..Default::default()
+        };
+        let (sender, receiver) = tokio::sync::mpsc::channel(10);
+        TOKIO_RUNTIME.spawn(async move {
+            let mut http_proxy = HttpProxy {
+                port: 4452,
+                channel: receiver,
+                mapping_key: String::from("4452-HTTPS"),
+            };
+            let _result = http_proxy.start_https_server(&service_config).await;
+        });
+        thread::sleep(time::Duration::from_millis(100));
+        assert_eq!(get_peer_common_name(4452, "a.example.com"), "a.example.com");
+        assert_eq!(get_peer_common_name(4452, "www.b.example.com"), "rotated");
+        assert_eq!(get_peer_common_name(4452, "c.example.com"), "ss");
+        TOKIO_RUNTIME.block_on(async {
+            sender.send(()).await.unwrap();
+        });
+    }
+    fn request_with_client_certificate(
+        port: i32,
+        with_client_certificate: bool,
+    ) -> Result<String, anyhow::Error> {
+        let config_dir = env::current_dir().unwrap().join("config");
+        let mut connector_builder = SslConnector::builder(SslMethod::tls())?;
+        connector_builder.set_verify(SslVerifyMode::NONE);
+        if with_client_certificate {
+            connector_builder
+                .set_certificate_file(config_dir.join("test_client_cert.pem"), SslFiletype::PEM)?;
+            connector_builder
+                .set_private_key_file(config_dir.join("test_client_key.pem"), SslFiletype::PEM)?;
+        }
+        let stream = std::net::TcpStream::connect(format!("127.0.0.1:{}", port))?;
+        let mut ssl_stream = connector_builder.build().connect("localhost", stream)?;
+        ssl_stream
+            .write_all(b"GET /get HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")?;
+        let mut response = String::new();
+        ssl_stream.read_to_string(&mut response)?;
+        Ok(response)
+    }
+    #[test]
+    fn test_https_client_certificate_required_ok() {
+        let config_dir = env::current_dir().unwrap().join("config");
+        let read_config_file =
+            |file_name: &str| std::fs::read_to_string(config_dir.join(file_name)).unwrap();
+        let service_config = ServiceConfig {
+            server_type: ServiceType::Https,
+            cert_str: Some(read_config_file("test_cert.pem")),
+            key_str: Some(read_config_file("test_key.pem")),
+            client_auth: Some(ClientAuthConfig {
+                ca_str: read_config_file("test_client_ca_cert.pem"),
+                mode: ClientAuthMode::Required,
+                identity_header: None,
+            }),
+            ..Default::default()
+        };
+        let (sender, receiver) = tokio::sync::mpsc::channel(10);
+        TOKIO_RUNTIME.spawn(async move {
+            let mut http_proxy = HttpProxy {
+                port: 4453,
+                channel: receiver,
+                mapping_key: String::from("4453-HTTPS"),
+            };
+            let _result = http_proxy.start_https_server(&service_config).await;
+        });
+        thread::sleep(time::Duration::from_millis(100));
+        let response = request_with_client_certificate(4453, true).unwrap();
+        assert!(response.starts_with("HTTP/1.1 500"), "{}", response);
+        assert!(request_with_client_certificate(4453, false)
+            .unwrap_or_default()
+            .is_empty());
+        TOKIO_RUNTIME.block_on(async {
+            sender.send(()).await.unwrap();
+        });
+    }
+    fn connect_with_max_version(
+        port: i32,
+        max_version: Option<SslVersion>,
+    ) -> Result<SslStream<std::net::TcpStream>, anyhow::Error> {
+        let mut connector_builder = SslConnector::builder(SslMethod::tls())?;
+        connector_builder.set_verify(SslVerifyMode::NONE);
+        connector_builder.set_max_proto_version(max_version)?;
+        connector_builder.set_alpn_protos(b"\x02h2\x08http/1.1")?;
+        let stream = std::net::TcpStream::connect(format!("127.0.0.1:{}", port))?;
+        Ok(connector_builder.build().connect("localhost", stream)?)
+    }
+    #[test]
+    fn test_https_tls_policy_ok() {
+        let config_dir = env::current_dir().unwrap().join("config");
+        let service_config = ServiceConfig {
+            server_type: ServiceType::Https,
+            cert_str: Some(std::fs::read_to_string(config_dir.join("test_cert.pem")).unwrap()),
+            key_str: Some(std::fs::read_to_string(config_dir.join("test_key.pem")).unwrap()),
+            tls_policy: Some(TlsPolicyConfig {
+                min_version: Some(TlsVersion::Tls13),
+                alpn_protocols: vec![String::from("http/1.1")],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let (sender, receiver) = tokio::sync::mpsc::channel(10);
+        TOKIO_RUNTIME.spawn(async move {
+            let mut http_proxy = HttpProxy {
+                port: 4456,
+                channel: receiver,
+                mapping_key: String::from("4456-HTTPS"),
+            };
+            let _result = http_proxy.start_https_server(&service_config).await;
+        });
+        thread::sleep(time::Duration::from_millis(100));
+        assert!(connect_with_max_version(4456, Some(SslVersion::TLS1_2)).is_err());
+        let ssl_stream = connect_with_max_version(4456, None).unwrap();
+        assert_eq!(ssl_stream.ssl().version_str(), "TLSv1.3");
+        assert_eq!(
+            ssl_stream.ssl().selected_alpn_protocol(),
+            Some(b"http/1.1".as_slice())
+        );
+        drop(ssl_stream);
+        thread::sleep(time::Duration::from_millis(100));
+        let is_recorded = prometheus::gather()
+            .iter()
+            .filter(|item| item.get_name() == "silverwind_tls_handshakes_total")
+            .flat_map(|item| item.get_metric().iter())
+            .any(|metric| {
+                let labels = metric
+                    .get_label()
+                    .iter()
+                    .map(|label| (label.get_name(), label.get_value()))
+                    .collect::<Vec<(&str, &str)>>();
+                labels.contains(&("port", "4456")) && labels.contains(&("version", "TLSv1_3"))
+            });
+        assert!(is_recorded);
+        TOKIO_RUNTIME.block_on(async {
+            sender.send(()).await.unwrap();
+        });
+    }
+    #[test]
+    fn test_https_alpn_h2_ok() {
+        let config_dir = env::current_dir().unwrap().join("config");
+        let service_config = ServiceConfig {
+            server_type: ServiceType::Https,
+            cert_str: Some(std::fs::read_to_string(config_dir.join("test_cert.pem")).unwrap()),
+            key_str: Some(std::fs::read_to_string(config_dir.join("test_key.pem")).unwrap()),
+            ..Default::default()
+        };
+        let (sender, receiver) = tokio::sync::mpsc::channel(10);
+        TOKIO_RUNTIME.spawn(async move {
+            let mut http_proxy = HttpProxy {
+                port: 4465,
+                channel: receiver,
+                mapping_key: String::from("4465-HTTPS"),
+            };
+            let _result = http_proxy.start_https_server(&service_config).await;
+        });
+        thread::sleep(time::Duration::from_millis(100));
+        let ssl_stream = connect_with_max_version(4465, None).unwrap();
+        assert_eq!(
+            ssl_stream.ssl().selected_alpn_protocol(),
+            Some(b"h2".as_slice())
+        );
+        TOKIO_RUNTIME.block_on(async {
+            sender.send(()).await.unwrap();
+        });
+    }
+    #[test]
+    fn test_http_h2c_prior_knowledge_ok() {
+        let (sender, receiver) = tokio::sync::mpsc::channel(10);
+        TOKIO_RUNTIME.spawn(async {
+            let mut http_proxy = HttpProxy {
+                port: 4466,
+                channel: receiver,
+                mapping_key: String::from("4466-HTTP"),
+            };
+            let _result = http_proxy.start_http_server().await;
+        });
+        thread::sleep(time::Duration::from_millis(100));
+        TOKIO_RUNTIME.block_on(async {
+            let client = hyper::Client::builder()
+                .http2_only(true)
+                .build_http::<Body>();
+            let response = client
+                .get(Uri::from_static("http://127.0.0.1:4466/get"))
+                .await
+                .unwrap();
+            assert_eq!(response.version(), Version::HTTP_2);
+            sender.send(()).await.unwrap();
+        });
+    }
+    #[test]
+    fn test_downgrade_http2_request_ok() {
+        let mut request = Request::builder()
+            .version(Version::HTTP_2)
+            .uri("https://www.example.com:8443/get")
+            .body(Body::empty())
+            .unwrap();
+        downgrade_http2_request(&mut request);
+        assert_eq!(request.version(), Version::HTTP_11);
+        assert_eq!(request.headers()[HOST], "www.example.com:8443");
+
+        let mut request = Request::builder()
+            .version(Version::HTTP_2)
+            .uri("https://www.example.com/get")
+            .header(HOST, "api.example.com")
+            .body(Body::empty())
+            .unwrap();
+        downgrade_http2_request(&mut request);
+        assert_eq!(request.headers()[HOST], "api.example.com");
+    }
     #[test]
     fn test_proxy_adapter_error() {
         TOKIO_RUNTIME.spawn(async {
@@ -549,10 +926,12 @@ mod tests {
             let base_route = BaseRoute {
                 endpoint: String::from("not_found"),
                 try_file: None,
-                is_alive: Arc::new(RwLock::new(None)),
-                anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                    consecutive_5xx: 100,
-                })),
+                is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                    AnomalyDetectionStatus {
+                        consecutive_5xx: 100,
+                    },
+                )),
             };
             let res = route_file(base_route, request).await;
             assert!(res.is_err());
@@ -571,10 +950,12 @@ mod tests {
             let base_route = BaseRoute {
                 endpoint: String::from("config"),
                 try_file: None,
-                is_alive: Arc::new(RwLock::new(None)),
-                anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                    consecutive_5xx: 100,
-                })),
+                is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                    AnomalyDetectionStatus {
+                        consecutive_5xx: 100,
+                    },
+                )),
             };
             let res = route_file(base_route, request).await;
             assert!(res.is_ok());
@@ -590,10 +971,12 @@ mod tests {
             let base_route = BaseRoute {
                 endpoint: String::from("config"),
                 try_file: Some(String::from("app_config.yaml")),
-                is_alive: Arc::new(RwLock::new(None)),
-                anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                    consecutive_5xx: 100,
-                })),
+                is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                    AnomalyDetectionStatus {
+                        consecutive_5xx: 100,
+                    },
+                )),
             };
             let res = route_file(base_route, request).await;
             assert!(res.is_ok());
@@ -608,10 +991,12 @@ mod tests {
                     base_route: BaseRoute {
                         endpoint: String::from("http://httpbin.org:80"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     },
                 }],
             });
@@ -621,6 +1006,12 @@ mod tests {
                 sender,
                 service_config: ServiceConfig {
                     key_str: None,
+                    key_passphrase: None,
+                    certificates: vec![],
+                    client_auth: None,
+                    tls_policy: None,
+                    acme: None,
+                    ocsp_stapling: None,
                     server_type: crate::vojo::app_config::ServiceType::Http,
                     cert_str: None,
                     routes: vec![Route {
@@ -639,10 +1030,16 @@ mod tests {
                         authentication: None,
                         anomaly_detection: None,
                         liveness_config: None,
-                        liveness_status: Arc::new(RwLock::new(LivenessStatus {
+                        liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                             current_liveness_count: 0,
                         })),
                         ratelimit: None,
+                        server_names: None,
+                        client_identities: None,
+                        upstream_tls: None,
+                        grpc_web: None,
+                        grpc_json_transcoder: None,
+                        grpc_matcher: None,
                         health_check: None,
                     }],
                 },
@@ -679,10 +1076,12 @@ mod tests {
                     base_route: BaseRoute {
                         endpoint: String::from("httpbin.org:80"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     },
                 }],
             });
@@ -692,6 +1091,12 @@ mod tests {
                 sender,
                 service_config: ServiceConfig {
                     key_str: None,
+                    key_passphrase: None,
+                    certificates: vec![],
+                    client_auth: None,
+                    tls_policy: None,
+                    acme: None,
+                    ocsp_stapling: None,
                     server_type: crate::vojo::app_config::ServiceType::Tcp,
                     cert_str: None,
                     routes: vec![Route {
@@ -709,7 +1114,13 @@ mod tests {
                         }]),
                         authentication: None,
                         ratelimit: None,
-                        liveness_status: Arc::new(RwLock::new(LivenessStatus {
+                        server_names: None,
+                        client_identities: None,
+                        upstream_tls: None,
+                        grpc_web: None,
+                        grpc_json_transcoder: None,
+                        grpc_matcher: None,
+                        liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                             current_liveness_count: 0,
                         })),
                         health_check: None,
@@ -752,10 +1163,12 @@ mod tests {
                     base_route: BaseRoute {
                         endpoint: String::from("http://127.0.0.1:9851"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     },
                 }],
             });
@@ -765,6 +1178,12 @@ mod tests {
                 sender,
                 service_config: ServiceConfig {
                     key_str: None,
+                    key_passphrase: None,
+                    certificates: vec![],
+                    client_auth: None,
+                    tls_policy: None,
+                    acme: None,
+                    ocsp_stapling: None,
                     server_type: crate::vojo::app_config::ServiceType::Http,
                     cert_str: None,
                     routes: vec![Route {
@@ -789,10 +1208,16 @@ mod tests {
                         liveness_config: Some(LivenessConfig {
                             min_liveness_count: 1,
                         }),
-                        liveness_status: Arc::new(RwLock::new(LivenessStatus {
+                        liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                             current_liveness_count: 0,
                         })),
                         ratelimit: None,
+                        server_names: None,
+                        client_identities: None,
+                        upstream_tls: None,
+                        grpc_web: None,
+                        grpc_json_transcoder: None,
+                        grpc_matcher: None,
                         health_check: None,
                     }],
                 },
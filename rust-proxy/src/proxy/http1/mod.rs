@@ -1,6 +1,9 @@
+pub mod grpc_json_transcoder;
+pub mod grpc_web_proxy;
 pub mod http_client;
 
 pub mod http_proxy;
+pub mod protobuf_json;
 pub mod tls_acceptor;
 pub mod tls_stream;
 pub mod websocket_proxy;
@@ -0,0 +1,563 @@
+use base64::{engine::general_purpose, Engine as _};
+use protobuf::descriptor::{
+    DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FieldDescriptorProto_Label,
+    FieldDescriptorProto_Type, FileDescriptorSet, ServiceDescriptorProto,
+};
+use protobuf::wire_format::WireType;
+use protobuf::{CodedInputStream, CodedOutputStream, Message};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+const MAX_RECURSION_DEPTH: usize = 64;
+/**
+*The messages,enums and services of the compiled descriptor set,which are keyed by the full names
+ with the leading dot like .helloworld.HelloRequest.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct DescriptorPool {
+    messages: HashMap<String, DescriptorProto>,
+    enums: HashMap<String, EnumDescriptorProto>,
+    services: Vec<(String, ServiceDescriptorProto)>,
+}
+impl DescriptorPool {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        let file_descriptor_set = FileDescriptorSet::parse_from_bytes(bytes)
+            .map_err(|e| anyhow!("The descriptor set is invalid,the error is {}.", e))?;
+        let mut descriptor_pool = DescriptorPool::default();
+        for file in file_descriptor_set.get_file() {
+            let package = if file.get_package().is_empty() {
+                String::new()
+            } else {
+                format!(".{}", file.get_package())
+            };
+            for message in file.get_message_type() {
+                descriptor_pool.add_message(&package, message);
+            }
+            for enum_type in file.get_enum_type() {
+                descriptor_pool.enums.insert(
+                    format!("{}.{}", package, enum_type.get_name()),
+                    enum_type.clone(),
+                );
+            }
+            for service in file.get_service() {
+                descriptor_pool.services.push((
+                    format!("{}.{}", package, service.get_name())
+                        .trim_start_matches('.')
+                        .to_string(),
+                    service.clone(),
+                ));
+            }
+        }
+        Ok(descriptor_pool)
+    }
+    fn add_message(&mut self, scope: &str, message: &DescriptorProto) {
+        let full_name = format!("{}.{}", scope, message.get_name());
+        for nested_message in message.get_nested_type() {
+            self.add_message(&full_name, nested_message);
+        }
+        for enum_type in message.get_enum_type() {
+            self.enums.insert(
+                format!("{}.{}", full_name, enum_type.get_name()),
+                enum_type.clone(),
+            );
+        }
+        self.messages.insert(full_name, message.clone());
+    }
+    /**
+     *The services with the full names like helloworld.Greeter.
+     */
+    pub fn services(&self) -> &[(String, ServiceDescriptorProto)] {
+        &self.services
+    }
+    pub fn get_message(&self, type_name: &str) -> Result<&DescriptorProto, anyhow::Error> {
+        self.messages.get(type_name).ok_or(anyhow!(
+            "The message {} is not in the descriptor set.",
+            type_name
+        ))
+    }
+    fn get_enum(&self, type_name: &str) -> Result<&EnumDescriptorProto, anyhow::Error> {
+        self.enums.get(type_name).ok_or(anyhow!(
+            "The enum {} is not in the descriptor set.",
+            type_name
+        ))
+    }
+    /**
+    *Encode the json object as the message.The fields are found by the json names or the original
+    names,and the numbers and the booleans could be strings like the ones from the path and the
+    query.
+    */
+    pub fn json_to_protobuf(
+        &self,
+        type_name: &str,
+        value: &Value,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        self.encode_message(type_name, value, 0)
+    }
+    /**
+    *Decode the message as the json object with the json names,where the 64-bit integers are
+    strings,the enums are the names and the bytes are base64.
+    */
+    pub fn protobuf_to_json(&self, type_name: &str, bytes: &[u8]) -> Result<Value, anyhow::Error> {
+        self.decode_message(type_name, bytes, 0)
+    }
+    fn encode_message(
+        &self,
+        type_name: &str,
+        value: &Value,
+        depth: usize,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        if depth > MAX_RECURSION_DEPTH {
+            return Err(anyhow!("The message {} is nested too deeply.", type_name));
+        }
+        let message = self.get_message(type_name)?;
+        let object = value
+            .as_object()
+            .ok_or(anyhow!("The value of {} should be an object.", type_name))?;
+        let mut bytes = vec![];
+        let mut output_stream = CodedOutputStream::vec(&mut bytes);
+        for (key, item) in object.iter() {
+            let field = find_field(message, key).ok_or(anyhow!(
+                "The field {} is not in the message {}.",
+                key,
+                type_name
+            ))?;
+            if item.is_null() {
+                continue;
+            }
+            if field.get_label() != FieldDescriptorProto_Label::LABEL_REPEATED {
+                self.encode_field(&mut output_stream, field, item, depth)?;
+            } else if let Some(map_entry) = self.get_map_entry(field) {
+                let entries = item
+                    .as_object()
+                    .ok_or(anyhow!("The map {} should be an object.", key))?;
+                for (entry_key, entry_value) in entries.iter() {
+                    let entry_bytes =
+                        self.encode_map_entry(map_entry, entry_key, entry_value, depth)?;
+                    output_stream.write_bytes(field.get_number() as u32, &entry_bytes)?;
+                }
+            } else {
+                let items = match item {
+                    Value::Array(items) => items.clone(),
+                    other => vec![other.clone()],
+                };
+                for item in items.iter() {
+                    self.encode_field(&mut output_stream, field, item, depth)?;
+                }
+            }
+        }
+        output_stream.flush()?;
+        drop(output_stream);
+        Ok(bytes)
+    }
+    fn encode_map_entry(
+        &self,
+        map_entry: &DescriptorProto,
+        key: &str,
+        value: &Value,
+        depth: usize,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let mut bytes = vec![];
+        let mut output_stream = CodedOutputStream::vec(&mut bytes);
+        for field in map_entry.get_field() {
+            match field.get_number() {
+                1 => self.encode_field(&mut output_stream, field, &Value::from(key), depth)?,
+                _ => self.encode_field(&mut output_stream, field, value, depth)?,
+            }
+        }
+        output_stream.flush()?;
+        drop(output_stream);
+        Ok(bytes)
+    }
+    fn encode_field(
+        &self,
+        output_stream: &mut CodedOutputStream,
+        field: &FieldDescriptorProto,
+        value: &Value,
+        depth: usize,
+    ) -> Result<(), anyhow::Error> {
+        let number = field.get_number() as u32;
+        match field.get_field_type() {
+            FieldDescriptorProto_Type::TYPE_DOUBLE => {
+                output_stream.write_double(number, json_to_f64(field, value)?)?
+            }
+            FieldDescriptorProto_Type::TYPE_FLOAT => {
+                output_stream.write_float(number, json_to_f64(field, value)? as f32)?
+            }
+            FieldDescriptorProto_Type::TYPE_INT64 => {
+                output_stream.write_int64(number, json_to_i64(field, value)?)?
+            }
+            FieldDescriptorProto_Type::TYPE_SINT64 => {
+                output_stream.write_sint64(number, json_to_i64(field, value)?)?
+            }
+            FieldDescriptorProto_Type::TYPE_SFIXED64 => {
+                output_stream.write_sfixed64(number, json_to_i64(field, value)?)?
+            }
+            FieldDescriptorProto_Type::TYPE_UINT64 => {
+                output_stream.write_uint64(number, json_to_u64(field, value)?)?
+            }
+            FieldDescriptorProto_Type::TYPE_FIXED64 => {
+                output_stream.write_fixed64(number, json_to_u64(field, value)?)?
+            }
+            FieldDescriptorProto_Type::TYPE_INT32 => {
+                output_stream.write_int32(number, i32::try_from(json_to_i64(field, value)?)?)?
+            }
+            FieldDescriptorProto_Type::TYPE_SINT32 => {
+                output_stream.write_sint32(number, i32::try_from(json_to_i64(field, value)?)?)?
+            }
+            FieldDescriptorProto_Type::TYPE_SFIXED32 => {
+                output_stream.write_sfixed32(number, i32::try_from(json_to_i64(field, value)?)?)?
+            }
+            FieldDescriptorProto_Type::TYPE_UINT32 => {
+                output_stream.write_uint32(number, u32::try_from(json_to_u64(field, value)?)?)?
+            }
+            FieldDescriptorProto_Type::TYPE_FIXED32 => {
+                output_stream.write_fixed32(number, u32::try_from(json_to_u64(field, value)?)?)?
+            }
+            FieldDescriptorProto_Type::TYPE_BOOL => {
+                let bool_value = match value {
+                    Value::Bool(bool_value) => *bool_value,
+                    Value::String(string_value) => string_value.parse()?,
+                    _ => return Err(invalid_value(field, value)),
+                };
+                output_stream.write_bool(number, bool_value)?
+            }
+            FieldDescriptorProto_Type::TYPE_STRING => {
+                let string_value = value.as_str().ok_or(invalid_value(field, value))?;
+                output_stream.write_string(number, string_value)?
+            }
+            FieldDescriptorProto_Type::TYPE_BYTES => {
+                let string_value = value.as_str().ok_or(invalid_value(field, value))?;
+                output_stream.write_bytes(number, &decode_base64(string_value)?)?
+            }
+            FieldDescriptorProto_Type::TYPE_ENUM => {
+                let enum_type = self.get_enum(field.get_type_name())?;
+                let enum_number = match value {
+                    Value::String(name) => enum_type
+                        .get_value()
+                        .iter()
+                        .find(|item| item.get_name() == name)
+                        .map(|item| item.get_number())
+                        .or_else(|| name.parse().ok())
+                        .ok_or(invalid_value(field, value))?,
+                    _ => i32::try_from(json_to_i64(field, value)?)?,
+                };
+                output_stream.write_enum(number, enum_number)?
+            }
+            FieldDescriptorProto_Type::TYPE_MESSAGE => {
+                let message_bytes = self.encode_message(field.get_type_name(), value, depth + 1)?;
+                output_stream.write_bytes(number, &message_bytes)?
+            }
+            FieldDescriptorProto_Type::TYPE_GROUP => {
+                return Err(anyhow!(
+                    "The group field {} is not supported.",
+                    field.get_name()
+                ))
+            }
+        }
+        Ok(())
+    }
+    fn decode_message(
+        &self,
+        type_name: &str,
+        bytes: &[u8],
+        depth: usize,
+    ) -> Result<Value, anyhow::Error> {
+        if depth > MAX_RECURSION_DEPTH {
+            return Err(anyhow!("The message {} is nested too deeply.", type_name));
+        }
+        let message = self.get_message(type_name)?;
+        let mut object = Map::new();
+        let mut input_stream = CodedInputStream::from_bytes(bytes);
+        while !input_stream.eof()? {
+            let (number, wire_type) = input_stream.read_tag_unpack()?;
+            let field = match message
+                .get_field()
+                .iter()
+                .find(|item| item.get_number() as u32 == number)
+            {
+                Some(field) => field,
+                None => {
+                    input_stream.skip_field(wire_type)?;
+                    continue;
+                }
+            };
+            let key = json_name(field);
+            if field.get_label() != FieldDescriptorProto_Label::LABEL_REPEATED {
+                let value = self.decode_field(&mut input_stream, field, depth)?;
+                object.insert(key, value);
+            } else if let Some(map_entry) = self.get_map_entry(field) {
+                let entry_bytes = input_stream.read_bytes()?;
+                let (entry_key, entry_value) =
+                    self.decode_map_entry(map_entry, &entry_bytes, depth)?;
+                if let Value::Object(entries) =
+                    object.entry(key).or_insert(Value::Object(Map::new()))
+                {
+                    entries.insert(entry_key, entry_value);
+                }
+            } else {
+                let mut items = vec![];
+                if wire_type == WireType::WireTypeLengthDelimited && is_packable(field) {
+                    let packed_bytes = input_stream.read_bytes()?;
+                    let mut packed_stream = CodedInputStream::from_bytes(&packed_bytes);
+                    while !packed_stream.eof()? {
+                        items.push(self.decode_field(&mut packed_stream, field, depth)?);
+                    }
+                } else {
+                    items.push(self.decode_field(&mut input_stream, field, depth)?);
+                }
+                if let Value::Array(values) = object.entry(key).or_insert(Value::Array(vec![])) {
+                    values.extend(items);
+                }
+            }
+        }
+        Ok(Value::Object(object))
+    }
+    fn decode_map_entry(
+        &self,
+        map_entry: &DescriptorProto,
+        bytes: &[u8],
+        depth: usize,
+    ) -> Result<(String, Value), anyhow::Error> {
+        let mut entry_key = String::new();
+        let mut entry_value = Value::Null;
+        let mut input_stream = CodedInputStream::from_bytes(bytes);
+        while !input_stream.eof()? {
+            let (number, wire_type) = input_stream.read_tag_unpack()?;
+            let field = match map_entry
+                .get_field()
+                .iter()
+                .find(|item| item.get_number() as u32 == number)
+            {
+                Some(field) => field,
+                None => {
+                    input_stream.skip_field(wire_type)?;
+                    continue;
+                }
+            };
+            let value = self.decode_field(&mut input_stream, field, depth)?;
+            if number == 1 {
+                entry_key = match value {
+                    Value::String(string_value) => string_value,
+                    other => other.to_string(),
+                };
+            } else {
+                entry_value = value;
+            }
+        }
+        Ok((entry_key, entry_value))
+    }
+    fn decode_field(
+        &self,
+        input_stream: &mut CodedInputStream,
+        field: &FieldDescriptorProto,
+        depth: usize,
+    ) -> Result<Value, anyhow::Error> {
+        let value = match field.get_field_type() {
+            FieldDescriptorProto_Type::TYPE_DOUBLE => f64_to_json(input_stream.read_double()?),
+            FieldDescriptorProto_Type::TYPE_FLOAT => f64_to_json(input_stream.read_float()? as f64),
+            FieldDescriptorProto_Type::TYPE_INT64 => {
+                Value::from(input_stream.read_int64()?.to_string())
+            }
+            FieldDescriptorProto_Type::TYPE_SINT64 => {
+                Value::from(input_stream.read_sint64()?.to_string())
+            }
+            FieldDescriptorProto_Type::TYPE_SFIXED64 => {
+                Value::from(input_stream.read_sfixed64()?.to_string())
+            }
+            FieldDescriptorProto_Type::TYPE_UINT64 => {
+                Value::from(input_stream.read_uint64()?.to_string())
+            }
+            FieldDescriptorProto_Type::TYPE_FIXED64 => {
+                Value::from(input_stream.read_fixed64()?.to_string())
+            }
+            FieldDescriptorProto_Type::TYPE_INT32 => Value::from(input_stream.read_int32()?),
+            FieldDescriptorProto_Type::TYPE_SINT32 => Value::from(input_stream.read_sint32()?),
+            FieldDescriptorProto_Type::TYPE_SFIXED32 => Value::from(input_stream.read_sfixed32()?),
+            FieldDescriptorProto_Type::TYPE_UINT32 => Value::from(input_stream.read_uint32()?),
+            FieldDescriptorProto_Type::TYPE_FIXED32 => Value::from(input_stream.read_fixed32()?),
+            FieldDescriptorProto_Type::TYPE_BOOL => Value::from(input_stream.read_bool()?),
+            FieldDescriptorProto_Type::TYPE_STRING => Value::from(input_stream.read_string()?),
+            FieldDescriptorProto_Type::TYPE_BYTES => {
+                Value::from(general_purpose::STANDARD.encode(input_stream.read_bytes()?))
+            }
+            FieldDescriptorProto_Type::TYPE_ENUM => {
+                let enum_number = input_stream.read_int32()?;
+                self.get_enum(field.get_type_name())?
+                    .get_value()
+                    .iter()
+                    .find(|item| item.get_number() == enum_number)
+                    .map(|item| Value::from(item.get_name()))
+                    .unwrap_or(Value::from(enum_number))
+            }
+            FieldDescriptorProto_Type::TYPE_MESSAGE => {
+                let message_bytes = input_stream.read_bytes()?;
+                self.decode_message(field.get_type_name(), &message_bytes, depth + 1)?
+            }
+            FieldDescriptorProto_Type::TYPE_GROUP => {
+                return Err(anyhow!(
+                    "The group field {} is not supported.",
+                    field.get_name()
+                ))
+            }
+        };
+        Ok(value)
+    }
+    fn get_map_entry(&self, field: &FieldDescriptorProto) -> Option<&DescriptorProto> {
+        if field.get_field_type() != FieldDescriptorProto_Type::TYPE_MESSAGE {
+            return None;
+        }
+        self.messages
+            .get(field.get_type_name())
+            .filter(|item| item.get_options().get_map_entry())
+    }
+}
+/**
+*The json name of the field,which is the lower camel case of the name if protoc has not filled it.
+*/
+pub fn json_name(field: &FieldDescriptorProto) -> String {
+    if !field.get_json_name().is_empty() {
+        return field.get_json_name().to_string();
+    }
+    let mut result = String::new();
+    let mut is_upper = false;
+    for item in field.get_name().chars() {
+        if item == '_' {
+            is_upper = true;
+        } else if is_upper {
+            result.push(item.to_ascii_uppercase());
+            is_upper = false;
+        } else {
+            result.push(item);
+        }
+    }
+    result
+}
+pub fn find_field<'a>(message: &'a DescriptorProto, key: &str) -> Option<&'a FieldDescriptorProto> {
+    message
+        .get_field()
+        .iter()
+        .find(|item| item.get_name() == key || json_name(item) == key)
+}
+fn is_packable(field: &FieldDescriptorProto) -> bool {
+    !matches!(
+        field.get_field_type(),
+        FieldDescriptorProto_Type::TYPE_STRING
+            | FieldDescriptorProto_Type::TYPE_BYTES
+            | FieldDescriptorProto_Type::TYPE_MESSAGE
+            | FieldDescriptorProto_Type::TYPE_GROUP
+    )
+}
+fn invalid_value(field: &FieldDescriptorProto, value: &Value) -> anyhow::Error {
+    anyhow!(
+        "The value {} of the field {} is invalid.",
+        value,
+        field.get_name()
+    )
+}
+fn decode_base64(value: &str) -> Result<Vec<u8>, anyhow::Error> {
+    general_purpose::STANDARD
+        .decode(value)
+        .or_else(|_| general_purpose::URL_SAFE.decode(value))
+        .or_else(|_| general_purpose::STANDARD_NO_PAD.decode(value))
+        .or_else(|_| general_purpose::URL_SAFE_NO_PAD.decode(value))
+        .map_err(|e| anyhow!("The bytes {} is not base64,the error is {}.", value, e))
+}
+fn json_to_f64(field: &FieldDescriptorProto, value: &Value) -> Result<f64, anyhow::Error> {
+    match value {
+        Value::Number(number) => number.as_f64().ok_or(invalid_value(field, value)),
+        Value::String(string_value) => match string_value.as_str() {
+            "NaN" => Ok(f64::NAN),
+            "Infinity" => Ok(f64::INFINITY),
+            "-Infinity" => Ok(f64::NEG_INFINITY),
+            _ => string_value
+                .parse()
+                .map_err(|_| invalid_value(field, value)),
+        },
+        _ => Err(invalid_value(field, value)),
+    }
+}
+fn json_to_i64(field: &FieldDescriptorProto, value: &Value) -> Result<i64, anyhow::Error> {
+    match value {
+        Value::Number(number) => number.as_i64().ok_or(invalid_value(field, value)),
+        Value::String(string_value) => string_value
+            .parse()
+            .map_err(|_| invalid_value(field, value)),
+        _ => Err(invalid_value(field, value)),
+    }
+}
+fn json_to_u64(field: &FieldDescriptorProto, value: &Value) -> Result<u64, anyhow::Error> {
+    match value {
+        Value::Number(number) => number.as_u64().ok_or(invalid_value(field, value)),
+        Value::String(string_value) => string_value
+            .parse()
+            .map_err(|_| invalid_value(field, value)),
+        _ => Err(invalid_value(field, value)),
+    }
+}
+fn f64_to_json(value: f64) -> Value {
+    if value.is_nan() {
+        Value::from("NaN")
+    } else if value.is_infinite() && value > 0.0 {
+        Value::from("Infinity")
+    } else if value.is_infinite() {
+        Value::from("-Infinity")
+    } else {
+        Number::from_f64(value)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    fn descriptor_pool() -> DescriptorPool {
+        let bytes = std::fs::read("config/test_transcoding_descriptor.pb").unwrap();
+        DescriptorPool::from_bytes(&bytes).unwrap()
+    }
+    #[test]
+    fn test_protobuf_json_round_trip_ok() {
+        let descriptor_pool = descriptor_pool();
+        let shelf = json!({
+            "id": "1234567890123",
+            "theme": "Travel",
+            "genre": "HISTORY",
+            "labels": { "floor": "2" },
+            "ratings": [5, 4, 3]
+        });
+        let bytes = descriptor_pool
+            .json_to_protobuf(".bookstore.Shelf", &shelf)
+            .unwrap();
+        assert_eq!(
+            descriptor_pool
+                .protobuf_to_json(".bookstore.Shelf", &bytes)
+                .unwrap(),
+            shelf
+        );
+        let book = json!({ "title": "Dune", "author_name": "Herbert", "cover": "AQI=" });
+        let bytes = descriptor_pool
+            .json_to_protobuf(".bookstore.Book", &book)
+            .unwrap();
+        assert_eq!(
+            descriptor_pool
+                .protobuf_to_json(".bookstore.Book", &bytes)
+                .unwrap(),
+            json!({ "title": "Dune", "authorName": "Herbert", "cover": "AQI=" })
+        );
+    }
+    #[test]
+    fn test_json_to_protobuf_error() {
+        let descriptor_pool = descriptor_pool();
+        assert!(descriptor_pool
+            .json_to_protobuf(".bookstore.Shelf", &json!({ "id": "abc" }))
+            .is_err());
+        assert!(descriptor_pool
+            .json_to_protobuf(".bookstore.Shelf", &json!({ "genre": "POETRY" }))
+            .is_err());
+        assert!(descriptor_pool
+            .json_to_protobuf(".bookstore.Shelf", &json!({ "unknown": 1 }))
+            .is_err());
+        assert!(descriptor_pool
+            .json_to_protobuf(".bookstore.Missing", &json!({}))
+            .is_err());
+    }
+}
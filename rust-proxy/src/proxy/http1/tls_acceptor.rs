@@ -7,15 +7,16 @@ use std::io;
 use std::pin::Pin;
 use std::sync::Arc;
 
+use arc_swap::ArcSwap;
 use tokio_rustls::rustls::ServerConfig;
 
 pub struct TlsAcceptor {
-    config: Arc<ServerConfig>,
+    config: Arc<ArcSwap<ServerConfig>>,
     incoming: AddrIncoming,
 }
 
 impl TlsAcceptor {
-    pub fn new(config: Arc<ServerConfig>, incoming: AddrIncoming) -> TlsAcceptor {
+    pub fn new(config: Arc<ArcSwap<ServerConfig>>, incoming: AddrIncoming) -> TlsAcceptor {
         TlsAcceptor { config, incoming }
     }
 }
@@ -30,7 +31,7 @@ impl Accept for TlsAcceptor {
     ) -> Poll<Option<Result<Self::Conn, Self::Error>>> {
         let pin = self.get_mut();
         match ready!(Pin::new(&mut pin.incoming).poll_accept(cx)) {
-            Some(Ok(sock)) => Poll::Ready(Some(Ok(TlsStream::new(sock, pin.config.clone())))),
+            Some(Ok(sock)) => Poll::Ready(Some(Ok(TlsStream::new(sock, pin.config.load_full())))),
             Some(Err(e)) => Poll::Ready(Some(Err(e))),
             None => Poll::Ready(None),
         }
@@ -94,7 +95,7 @@ mod tests {
             let addr = SocketAddr::from(([127, 0, 0, 1], 7000));
             let listener = TcpListener::bind(&addr).await.unwrap();
             let tls_acceptor = TlsAcceptor {
-                config: tls_cfg,
+                config: Arc::new(ArcSwap::from(tls_cfg)),
                 incoming: AddrIncoming::from_listener(listener).unwrap(),
             };
 
@@ -1,9 +1,11 @@
+use crate::proxy::certificate_resolver::record_tls_handshake;
+use crate::proxy::proxy_trait::TlsConnectionInfo;
 use core::task::{Context, Poll};
 use futures_util::ready;
 use hyper::server::conn::AddrStream;
 use std::io;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::{future::Future, net::SocketAddr};
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use tokio_rustls::rustls::ServerConfig;
@@ -15,6 +17,7 @@ enum State {
 
 pub struct TlsStream {
     state: State,
+    tls_connection_info: Arc<OnceLock<TlsConnectionInfo>>,
 }
 
 impl TlsStream {
@@ -22,8 +25,16 @@ impl TlsStream {
         let accept = tokio_rustls::TlsAcceptor::from(config).accept(stream);
         TlsStream {
             state: State::Handshaking(accept),
+            tls_connection_info: Default::default(),
         }
     }
+    /**
+    *The server name and the client identity of the tls handshake,which are set once the
+    handshake has completed.
+    */
+    pub fn tls_connection_info(&self) -> Arc<OnceLock<TlsConnectionInfo>> {
+        self.tls_connection_info.clone()
+    }
     pub fn remote_addr(&self) -> SocketAddr {
         match &self.state {
             State::Handshaking(accept) => {
@@ -37,6 +48,14 @@ impl TlsStream {
         }
     }
 }
+fn set_tls_connection_info(
+    tls_connection_info: &OnceLock<TlsConnectionInfo>,
+    stream: &tokio_rustls::server::TlsStream<AddrStream>,
+) {
+    let (addr_stream, connection) = stream.get_ref();
+    record_tls_handshake(addr_stream.local_addr().port(), connection);
+    let _ = tls_connection_info.set(TlsConnectionInfo::from(connection));
+}
 impl AsyncRead for TlsStream {
     fn poll_read(
         self: Pin<&mut Self>,
@@ -47,6 +66,7 @@ impl AsyncRead for TlsStream {
         match pin.state {
             State::Handshaking(ref mut accept) => match ready!(Pin::new(accept).poll(cx)) {
                 Ok(mut stream) => {
+                    set_tls_connection_info(&pin.tls_connection_info, &stream);
                     let result = Pin::new(&mut stream).poll_read(cx, buf);
                     pin.state = State::Streaming(stream);
                     result
@@ -68,6 +88,7 @@ impl AsyncWrite for TlsStream {
         match pin.state {
             State::Handshaking(ref mut accept) => match ready!(Pin::new(accept).poll(cx)) {
                 Ok(mut stream) => {
+                    set_tls_connection_info(&pin.tls_connection_info, &stream);
                     let result = Pin::new(&mut stream).poll_write(cx, buf);
                     pin.state = State::Streaming(stream);
                     result
@@ -149,8 +170,10 @@ mod tests {
             };
             let addr = SocketAddr::from(([127, 0, 0, 1], 7000));
             let listener = TcpListener::bind(&addr).await.unwrap();
-            let tls_acceptor =
-                TlsAcceptor::new(tls_cfg, AddrIncoming::from_listener(listener).unwrap());
+            let tls_acceptor = TlsAcceptor::new(
+                Arc::new(arc_swap::ArcSwap::from(tls_cfg)),
+                AddrIncoming::from_listener(listener).unwrap(),
+            );
 
             let make_svc = make_service_fn(|_| async {
                 Ok::<_, hyper::Error>(service_fn(|_req| async {
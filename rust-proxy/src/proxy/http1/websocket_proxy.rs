@@ -1,6 +1,7 @@
 use tokio::io;
 
 use crate::constants::common_constants::DEFAULT_HTTP_TIMEOUT;
+use crate::maintenance::endpoint_maintenance::track_in_flight;
 use crate::proxy::http1::http_client::HttpClients;
 
 use base64::{engine::general_purpose, Engine as _};
@@ -58,7 +59,9 @@ pub async fn server_upgrade(
         .to_str()?
         .to_string();
 
-    let request_path = check_result.unwrap().request_path;
+    let check_result = check_result.ok_or(anyhow!("The route could not be found!"))?;
+    let in_flight_guard = track_in_flight(check_result.base_route.endpoint.as_str());
+    let request_path = check_result.request_path;
     let mut new_request = Request::builder()
         .method(req.method().clone())
         .uri(request_path.clone())
@@ -71,7 +74,11 @@ pub async fn server_upgrade(
     debug!("The new request is:{:?}", new_request);
 
     let request_future = if new_request.uri().to_string().contains("https") {
-        http_client.request_https(new_request, DEFAULT_HTTP_TIMEOUT)
+        http_client.request_https(
+            new_request,
+            check_result.route.upstream_tls.as_ref(),
+            DEFAULT_HTTP_TIMEOUT,
+        )
     } else {
         http_client.request_http(new_request, DEFAULT_HTTP_TIMEOUT)
     };
@@ -87,6 +94,7 @@ pub async fn server_upgrade(
         if let Err(err) = res {
             error!("{}", err);
         }
+        drop(in_flight_guard);
     });
     let web_socket_value = format!("{}258EAFA5-E914-47DA-95CA-C5AB0DC85B11", sec_websocke_key);
     let mut hasher = Sha1::new();
@@ -0,0 +1,527 @@
+use crate::constants::common_constants::DEFAULT_HTTP_TIMEOUT;
+use crate::proxy::upstream_tls::UpstreamTls;
+use crate::vojo::app_config::UpstreamTlsConfig;
+use dashmap::DashMap;
+use futures::future::{poll_fn, BoxFuture, Shared};
+use futures::FutureExt;
+use h2::client::{self, ResponseFuture, SendRequest};
+use h2::SendStream;
+use http::Request;
+use hyper::body::Bytes;
+use lazy_static::lazy_static;
+use std::collections::hash_map::DefaultHasher;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{lookup_host, TcpStream};
+use tokio_rustls::{rustls, TlsConnector};
+use url::Url;
+lazy_static! {
+    pub static ref GLOBAL_GRPC_CONNECTION_POOL: GrpcConnectionPool = Default::default();
+    static ref DEFAULT_CLIENT_CONFIG: Arc<rustls::ClientConfig> =
+        UpstreamTls::new(UpstreamTlsConfig::default())
+            .map(|item| item.client_config)
+            .unwrap();
+}
+/**
+*The h2 connection to the upstream,which carries the streams until the max_concurrent_streams of
+ the upstream is reached.The connection is closed when the upstream sends the goaway or the
+ connection task ends.
+*/
+#[derive(Clone)]
+struct PooledConnection {
+    send_request: SendRequest<Bytes>,
+    active_streams: Arc<AtomicUsize>,
+    max_streams: Arc<AtomicUsize>,
+    is_closed: Arc<AtomicBool>,
+}
+impl PooledConnection {
+    fn is_closed(&self) -> bool {
+        self.is_closed.load(Ordering::SeqCst)
+    }
+    fn is_available(&self) -> bool {
+        !self.is_closed()
+            && self.active_streams.load(Ordering::SeqCst) < self.max_streams.load(Ordering::SeqCst)
+    }
+}
+/**
+*The stream reserved on the pooled connection,which should be kept until the response has been
+ copied,and the reservation is released when it is dropped.
+*/
+pub struct PooledStream {
+    active_streams: Arc<AtomicUsize>,
+}
+impl Drop for PooledStream {
+    fn drop(&mut self) {
+        self.active_streams.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+type ConnectFuture = Shared<BoxFuture<'static, Result<PooledConnection, String>>>;
+/**
+*The connections of one endpoint,and the pending connect which is shared by the requests waiting
+ for a new connection,so the lock is never held while connecting.
+*/
+#[derive(Default)]
+struct EndpointConnections {
+    connections: Vec<PooledConnection>,
+    connecting: Option<ConnectFuture>,
+}
+impl EndpointConnections {
+    fn is_idle(&mut self) -> bool {
+        self.connections.retain(|item| !item.is_closed());
+        self.connections.is_empty() && self.connecting.is_none()
+    }
+    /**
+    *The stream is reserved under the lock,so the max_streams of the connection is never exceeded
+    by the concurrent requests.
+    */
+    fn reserve_stream(&mut self) -> Option<(PooledConnection, PooledStream)> {
+        self.connections.retain(|item| !item.is_closed());
+        self.connections
+            .iter()
+            .find(|item| item.is_available())
+            .cloned()
+            .map(reserve_stream)
+    }
+}
+/**
+*The h2 connections of the grpc upstreams,which are grouped by the endpoint and the upstream tls
+ settings.
+*/
+pub struct GrpcConnectionPool {
+    endpoints: DashMap<String, Arc<Mutex<EndpointConnections>>>,
+    connect_timeout: Duration,
+}
+impl Default for GrpcConnectionPool {
+    fn default() -> Self {
+        GrpcConnectionPool {
+            endpoints: Default::default(),
+            connect_timeout: Duration::from_secs(DEFAULT_HTTP_TIMEOUT),
+        }
+    }
+}
+impl GrpcConnectionPool {
+    /**
+    *Send the request on the pooled connection.If the connection has been closed by the goaway
+    before the stream is opened,the request is sent once more on a new connection.
+    */
+    pub async fn send_request(
+        &self,
+        url: &Url,
+        upstream_tls: Option<&UpstreamTls>,
+        request: Request<()>,
+    ) -> Result<(ResponseFuture, SendStream<Bytes>, PooledStream), anyhow::Error> {
+        let (connection, pooled_stream) = self.get_connection(url, upstream_tls).await?;
+        match send_on_connection(&connection, clone_request(&request)).await {
+            Ok((response, send_stream)) => Ok((response, send_stream, pooled_stream)),
+            Err(err) => {
+                debug!(
+                    "The pooled grpc connection is unusable,the error is {}.",
+                    err
+                );
+                connection.is_closed.store(true, Ordering::SeqCst);
+                drop(pooled_stream);
+                let (connection, pooled_stream) = self.get_connection(url, upstream_tls).await?;
+                let (response, send_stream) = send_on_connection(&connection, request).await?;
+                Ok((response, send_stream, pooled_stream))
+            }
+        }
+    }
+    async fn get_connection(
+        &self,
+        url: &Url,
+        upstream_tls: Option<&UpstreamTls>,
+    ) -> Result<(PooledConnection, PooledStream), anyhow::Error> {
+        loop {
+            let endpoint = self
+                .endpoints
+                .entry(pool_key(url, upstream_tls))
+                .or_default()
+                .value()
+                .clone();
+            let connect_future = {
+                let mut endpoint_connections = endpoint.lock().unwrap();
+                if let Some(reserved) = endpoint_connections.reserve_stream() {
+                    return Ok(reserved);
+                }
+                match &endpoint_connections.connecting {
+                    Some(connect_future) => connect_future.clone(),
+                    None => {
+                        let connect_future =
+                            self.new_connect_future(url.clone(), upstream_tls.cloned());
+                        endpoint_connections.connecting = Some(connect_future.clone());
+                        connect_future
+                    }
+                }
+            };
+            let connect_result = connect_future.clone().await;
+            let reserved_option = {
+                let mut endpoint_connections = endpoint.lock().unwrap();
+                let is_current = endpoint_connections
+                    .connecting
+                    .as_ref()
+                    .map(|item| item.ptr_eq(&connect_future))
+                    .unwrap_or(false);
+                if is_current {
+                    endpoint_connections.connecting = None;
+                    if let Ok(connection) = &connect_result {
+                        endpoint_connections.connections.push(connection.clone());
+                    }
+                }
+                endpoint_connections.reserve_stream()
+            };
+            drop(endpoint);
+            self.evict_idle_endpoints();
+            if let Some(reserved) = reserved_option {
+                return Ok(reserved);
+            }
+            // The new connection has been filled up by the other requests waiting for it.
+            connect_result.map_err(|err| anyhow!(err))?;
+        }
+    }
+    fn new_connect_future(&self, url: Url, upstream_tls: Option<UpstreamTls>) -> ConnectFuture {
+        let connect_timeout = self.connect_timeout;
+        async move {
+            match tokio::time::timeout(connect_timeout, connect(&url, upstream_tls.as_ref())).await
+            {
+                Ok(Ok(connection)) => Ok(connection),
+                Ok(Err(err)) => Err(format!("Connect to {} error,the error is {}.", url, err)),
+                Err(_) => Err(format!(
+                    "Connect to {} timeout after {:?}.",
+                    url, connect_timeout
+                )),
+            }
+        }
+        .boxed()
+        .shared()
+    }
+    /**
+    *Remove the endpoints which have neither the live connections nor the pending connect.The
+    endpoint held by a request is kept,as the request may add the connection to it.
+    */
+    fn evict_idle_endpoints(&self) {
+        self.endpoints.retain(|_, endpoint| {
+            Arc::strong_count(endpoint) > 1 || !endpoint.lock().unwrap().is_idle()
+        });
+    }
+}
+fn reserve_stream(connection: PooledConnection) -> (PooledConnection, PooledStream) {
+    connection.active_streams.fetch_add(1, Ordering::SeqCst);
+    let pooled_stream = PooledStream {
+        active_streams: connection.active_streams.clone(),
+    };
+    (connection, pooled_stream)
+}
+fn pool_key(url: &Url, upstream_tls: Option<&UpstreamTls>) -> String {
+    let mut hasher = DefaultHasher::new();
+    upstream_tls
+        .map(|item| (&item.config, &item.default_server_name))
+        .hash(&mut hasher);
+    format!(
+        "{}://{}:{}#{:x}",
+        url.scheme(),
+        url.host_str().unwrap_or_default(),
+        url.port_or_known_default().unwrap_or_default(),
+        hasher.finish()
+    )
+}
+fn clone_request(request: &Request<()>) -> Request<()> {
+    let mut cloned_request = Request::new(());
+    *cloned_request.method_mut() = request.method().clone();
+    *cloned_request.uri_mut() = request.uri().clone();
+    *cloned_request.version_mut() = request.version();
+    *cloned_request.headers_mut() = request.headers().clone();
+    cloned_request
+}
+async fn send_on_connection(
+    connection: &PooledConnection,
+    request: Request<()>,
+) -> Result<(ResponseFuture, SendStream<Bytes>), anyhow::Error> {
+    let mut send_request = connection.send_request.clone().ready().await?;
+    Ok(send_request.send_request(request, false)?)
+}
+async fn connect(
+    url: &Url,
+    upstream_tls: Option<&UpstreamTls>,
+) -> Result<PooledConnection, anyhow::Error> {
+    let host = url.host_str().ok_or(anyhow!("Parse host error!"))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or(anyhow!("Parse port error!"))?;
+    let addr = lookup_host((host, port))
+        .await?
+        .next()
+        .ok_or(anyhow!("Parse the domain error!"))?;
+    debug!("The addr is {}", addr);
+    let tcp_stream = TcpStream::connect(addr).await?;
+    if url.scheme() != "https" {
+        return handshake(tcp_stream).await;
+    }
+    let (client_config, server_name) = match upstream_tls {
+        Some(upstream_tls) => (
+            upstream_tls.client_config.clone(),
+            upstream_tls.server_name(host)?,
+        ),
+        None => (
+            DEFAULT_CLIENT_CONFIG.clone(),
+            rustls::ServerName::try_from(host)?,
+        ),
+    };
+    let tls_stream = TlsConnector::from(client_config)
+        .connect(server_name, tcp_stream)
+        .await?;
+    handshake(tls_stream).await
+}
+async fn handshake<T>(stream: T) -> Result<PooledConnection, anyhow::Error>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (send_request, mut connection) = client::handshake(stream).await?;
+    let max_streams = Arc::new(AtomicUsize::new(connection.max_concurrent_send_streams()));
+    let is_closed = Arc::new(AtomicBool::new(false));
+    let max_streams_cloned = max_streams.clone();
+    let is_closed_cloned = is_closed.clone();
+    tokio::spawn(async move {
+        let connection_result = poll_fn(|cx| {
+            let poll_result = Pin::new(&mut connection).poll(cx);
+            max_streams_cloned.store(connection.max_concurrent_send_streams(), Ordering::SeqCst);
+            poll_result
+        })
+        .await;
+        is_closed_cloned.store(true, Ordering::SeqCst);
+        if let Err(err) = connection_result {
+            error!(
+                "Cause error in grpc upstream connection,the error is {}.",
+                err
+            );
+        } else {
+            debug!("The connection has closed!");
+        }
+    });
+    Ok(PooledConnection {
+        send_request,
+        active_streams: Default::default(),
+        max_streams,
+        is_closed,
+    })
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::Response;
+    use tokio::net::TcpListener;
+    async fn start_upstream(
+        port: u16,
+        max_concurrent_streams: u32,
+        is_goaway: bool,
+    ) -> Arc<AtomicUsize> {
+        let connection_count = Arc::new(AtomicUsize::new(0));
+        let connection_count_cloned = connection_count.clone();
+        let listener = TcpListener::bind(format!("127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        tokio::spawn(async move {
+            while let Ok((tcp_stream, _)) = listener.accept().await {
+                connection_count_cloned.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut connection = h2::server::Builder::new()
+                        .max_concurrent_streams(max_concurrent_streams)
+                        .handshake::<_, Bytes>(tcp_stream)
+                        .await
+                        .unwrap();
+                    while let Some(Ok((_, mut respond))) = connection.accept().await {
+                        let mut send_stream =
+                            respond.send_response(Response::new(()), false).unwrap();
+                        send_stream
+                            .send_data(Bytes::from("upstream"), true)
+                            .unwrap();
+                        if is_goaway {
+                            connection.graceful_shutdown();
+                        }
+                    }
+                });
+            }
+        });
+        connection_count
+    }
+    fn new_request(url: &Url) -> Request<()> {
+        Request::post(url.as_str()).body(()).unwrap()
+    }
+    #[tokio::test]
+    async fn test_grpc_connection_pool_multiplex_ok() {
+        let connection_count = start_upstream(4467, 1, false).await;
+        let url = Url::parse("http://localhost:4467/helloworld.Greeter/SayHello").unwrap();
+        let pool = GrpcConnectionPool::default();
+        let (first_response, _, first_stream) = pool
+            .send_request(&url, None, new_request(&url))
+            .await
+            .unwrap();
+        assert!(first_response.await.unwrap().status().is_success());
+        let (second_response, _, second_stream) = pool
+            .send_request(&url, None, new_request(&url))
+            .await
+            .unwrap();
+        assert!(second_response.await.unwrap().status().is_success());
+        assert_eq!(connection_count.load(Ordering::SeqCst), 2);
+
+        drop(first_stream);
+        drop(second_stream);
+        for _ in 0..3 {
+            let (response, _, _) = pool
+                .send_request(&url, None, new_request(&url))
+                .await
+                .unwrap();
+            assert!(response.await.unwrap().status().is_success());
+        }
+        assert_eq!(connection_count.load(Ordering::SeqCst), 2);
+    }
+    #[tokio::test]
+    async fn test_grpc_connection_pool_goaway_ok() {
+        let connection_count = start_upstream(4468, 100, true).await;
+        let url = Url::parse("http://127.0.0.1:4468/helloworld.Greeter/SayHello").unwrap();
+        let pool = GrpcConnectionPool::default();
+        for _ in 0..2 {
+            let (response, _, _) = pool
+                .send_request(&url, None, new_request(&url))
+                .await
+                .unwrap();
+            assert!(response.await.unwrap().status().is_success());
+        }
+        assert_eq!(connection_count.load(Ordering::SeqCst), 2);
+    }
+    #[tokio::test]
+    async fn test_grpc_connection_pool_share_connect_ok() {
+        let connection_count = start_upstream(4481, 100, false).await;
+        let url = Url::parse("http://127.0.0.1:4481/helloworld.Greeter/SayHello").unwrap();
+        let pool = Arc::new(GrpcConnectionPool::default());
+        let mut handles = vec![];
+        for _ in 0..10 {
+            let pool_cloned = pool.clone();
+            let url_cloned = url.clone();
+            handles.push(tokio::spawn(async move {
+                let (response, _, _) = pool_cloned
+                    .send_request(&url_cloned, None, new_request(&url_cloned))
+                    .await
+                    .unwrap();
+                assert!(response.await.unwrap().status().is_success());
+            }));
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert_eq!(connection_count.load(Ordering::SeqCst), 1);
+    }
+    #[tokio::test]
+    async fn test_grpc_connection_pool_connect_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:4482").await.unwrap();
+        let _accepted = tokio::spawn(async move {
+            let mut tcp_streams = vec![];
+            while let Ok((tcp_stream, _)) = listener.accept().await {
+                tcp_streams.push(tcp_stream);
+            }
+        });
+        let url = Url::parse("https://127.0.0.1:4482/helloworld.Greeter/SayHello").unwrap();
+        let pool = GrpcConnectionPool {
+            connect_timeout: Duration::from_millis(200),
+            ..Default::default()
+        };
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            pool.send_request(&url, None, new_request(&url)),
+        )
+        .await
+        .unwrap();
+        assert!(result.is_err());
+        assert!(pool.endpoints.is_empty());
+    }
+    #[tokio::test]
+    async fn test_grpc_connection_pool_evict_closed_endpoint() {
+        let connection_count = start_upstream(4483, 100, true).await;
+        let url = Url::parse("http://127.0.0.1:4483/helloworld.Greeter/SayHello").unwrap();
+        let pool = GrpcConnectionPool::default();
+        let (response, _, _) = pool
+            .send_request(&url, None, new_request(&url))
+            .await
+            .unwrap();
+        assert!(response.await.unwrap().status().is_success());
+        let failed_url = Url::parse("http://127.0.0.1:4484/helloworld.Greeter/SayHello").unwrap();
+        for _ in 0..50 {
+            if pool
+                .send_request(&failed_url, None, new_request(&failed_url))
+                .await
+                .is_err()
+                && pool.endpoints.is_empty()
+            {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert_eq!(connection_count.load(Ordering::SeqCst), 1);
+        assert!(pool.endpoints.is_empty());
+    }
+    #[tokio::test]
+    async fn test_grpc_connection_pool_shared_connect_max_streams() {
+        let connection_count = start_upstream(4496, 1, false).await;
+        let url = Url::parse("http://127.0.0.1:4496/helloworld.Greeter/SayHello").unwrap();
+        let connection = connect(&url, None).await.unwrap();
+        let (response, _) = send_on_connection(&connection, new_request(&url))
+            .await
+            .unwrap();
+        assert!(response.await.unwrap().status().is_success());
+        assert_eq!(connection.max_streams.load(Ordering::SeqCst), 1);
+
+        let pool = Arc::new(GrpcConnectionPool::default());
+        let connect_future = futures::future::ready(Ok(connection.clone()))
+            .boxed()
+            .shared();
+        pool.endpoints.insert(
+            pool_key(&url, None),
+            Arc::new(Mutex::new(EndpointConnections {
+                connections: vec![],
+                connecting: Some(connect_future),
+            })),
+        );
+        let mut handles = vec![];
+        for _ in 0..3 {
+            let pool_cloned = pool.clone();
+            let url_cloned = url.clone();
+            handles.push(tokio::spawn(async move {
+                pool_cloned.get_connection(&url_cloned, None).await.unwrap()
+            }));
+        }
+        let mut reserved = vec![];
+        for handle in handles {
+            reserved.push(handle.await.unwrap());
+        }
+        assert_eq!(reserved.len(), 3);
+        assert_eq!(connection.active_streams.load(Ordering::SeqCst), 1);
+        assert!(connection_count.load(Ordering::SeqCst) >= 2);
+    }
+    #[tokio::test]
+    async fn test_grpc_connection_pool_keep_held_endpoint() {
+        let pool = GrpcConnectionPool::default();
+        let endpoint = Arc::new(Mutex::new(EndpointConnections::default()));
+        pool.endpoints
+            .insert(String::from("held-endpoint"), endpoint.clone());
+        pool.evict_idle_endpoints();
+        assert_eq!(pool.endpoints.len(), 1);
+        drop(endpoint);
+        pool.evict_idle_endpoints();
+        assert!(pool.endpoints.is_empty());
+    }
+    #[test]
+    fn test_pool_key_ok() {
+        let url = Url::parse("https://example.com/helloworld.Greeter/SayHello").unwrap();
+        let upstream_tls = UpstreamTls::new(UpstreamTlsConfig {
+            server_name: Some(String::from("backend.internal")),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(pool_key(&url, None).starts_with("https://example.com:443#"));
+        assert_ne!(pool_key(&url, None), pool_key(&url, Some(&upstream_tls)));
+    }
+}
@@ -1,29 +1,44 @@
+use crate::configuration_service::app_config_service::GLOBAL_CONFIG_MAPPING;
 use crate::constants::common_constants::GRPC_STATUS_HEADER;
-use crate::constants::common_constants::GRPC_STATUS_OK;
+use crate::maintenance::endpoint_maintenance::track_in_flight;
+use crate::monitor::prometheus_exporter::{get_grpc_timer, inc_grpc};
+use crate::proxy::certificate_resolver::build_server_config;
+use crate::proxy::certificate_resolver::record_tls_handshake;
+use crate::proxy::http1::grpc_web_proxy::is_hop_by_hop_header;
+use crate::proxy::http2::grpc_connection_pool::GLOBAL_GRPC_CONNECTION_POOL;
+use crate::proxy::proxy_trait::get_request_server_name;
 use crate::proxy::proxy_trait::CheckTrait;
 use crate::proxy::proxy_trait::CommonCheckRequest;
-use h2::client;
+use crate::proxy::proxy_trait::TlsConnectionInfo;
+use crate::vojo::app_config::ServiceConfig;
 use h2::server;
 use h2::server::SendResponse;
 use h2::RecvStream;
 use h2::SendStream;
+use http::header::{CONTENT_TYPE, TE};
 use http::version::Version;
 use http::Response;
-use http::{Method, Request};
+use http::{HeaderMap, HeaderValue, Request, Uri};
 use hyper::body::Bytes;
 
-use std::io::BufReader;
+use std::future::Future;
 use std::net::SocketAddr;
-use std::net::ToSocketAddrs;
-use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::net::TcpStream;
 use tokio::sync::mpsc;
-use tokio_rustls::rustls::OwnedTrustAnchor;
+use tokio::time::{error::Elapsed, timeout_at, Instant};
 use tokio_rustls::server::TlsStream;
-use tokio_rustls::TlsConnector;
-use tokio_rustls::{rustls, TlsAcceptor};
+use tokio_rustls::TlsAcceptor;
 use url::Url;
+const GRPC_CONTENT_TYPE: &str = "application/grpc";
+const GRPC_TIMEOUT_HEADER: &str = "grpc-timeout";
+const GRPC_MESSAGE_HEADER: &str = "grpc-message";
+const GRPC_STATUS_UNKNOWN: &str = "2";
+const GRPC_STATUS_DEADLINE_EXCEEDED: &str = "4";
+const GRPC_STATUS_PERMISSION_DENIED: &str = "7";
+const GRPC_STATUS_UNIMPLEMENTED: &str = "12";
+const GRPC_STATUS_UNAVAILABLE: &str = "14";
 pub struct GrpcProxy {
     pub port: i32,
     pub channel: mpsc::Receiver<()>,
@@ -57,9 +72,15 @@ pub async fn start_tls_task(
     mapping_key: String,
     peer_addr: SocketAddr,
 ) -> Result<(), anyhow::Error> {
+    let (tcp, connection) = tcp_stream.get_ref();
+    if let Ok(local_addr) = tcp.local_addr() {
+        record_tls_handshake(local_addr.port(), connection);
+    }
+    let tls_connection_info = TlsConnectionInfo::from(connection);
     let mut connection = server::handshake(tcp_stream).await?;
     while let Some(request_result) = connection.accept().await {
-        if let Ok((request, respond)) = request_result {
+        if let Ok((mut request, respond)) = request_result {
+            request.extensions_mut().insert(tls_connection_info.clone());
             let mapping_key_cloned = mapping_key.clone();
             tokio::spawn(async move {
                 let result =
@@ -116,30 +137,11 @@ impl GrpcProxy {
     }
     pub async fn start_tls_proxy(
         &mut self,
-        pem_str: String,
-        key_str: String,
+        service_config: &ServiceConfig,
     ) -> Result<(), anyhow::Error> {
         let port_clone = self.port;
         let addr = SocketAddr::from(([0, 0, 0, 0], port_clone as u16));
-        let mut cer_reader = BufReader::new(pem_str.as_bytes());
-        let certs = rustls_pemfile::certs(&mut cer_reader)
-            .unwrap()
-            .iter()
-            .map(|s| rustls::Certificate((*s).clone()))
-            .collect();
-
-        let doc = pkcs8::PrivateKeyDocument::from_pem(&key_str).unwrap();
-        let key_der = rustls::PrivateKey(doc.as_ref().to_owned());
-
-        let tls_cfg = {
-            let cfg = rustls::ServerConfig::builder()
-                .with_safe_defaults()
-                .with_no_client_auth()
-                .with_single_cert(certs, key_der)
-                .unwrap();
-            Arc::new(cfg)
-        };
-        let tls_acceptor = TlsAcceptor::from(tls_cfg);
+        let tls_cfg = build_server_config(self.mapping_key.clone(), service_config)?;
 
         info!("Listening on grpc with tls://{}", addr);
         let listener = TcpListener::bind(addr).await.unwrap();
@@ -151,6 +153,7 @@ impl GrpcProxy {
             tokio::select! {
                accept_result=accept_future=>{
                 if let Ok((tcp_stream, peer_addr))=accept_result{
+                    let tls_acceptor = TlsAcceptor::from(tls_cfg.load_full());
                     if let Ok(tls_streams) = tls_acceptor.accept(tcp_stream).await {
                         tokio::spawn(start_tls_task(tls_streams, mapping_key.clone(), peer_addr));
                     }
@@ -165,10 +168,139 @@ impl GrpcProxy {
     }
 }
 
+/**
+*Split the grpc path like /helloworld.Greeter/SayHello into the service and the method.
+*/
+pub fn split_grpc_path(path: &str) -> Option<(&str, &str)> {
+    let (service, method) = path.strip_prefix('/')?.split_once('/')?;
+    if service.is_empty() || method.is_empty() || method.contains('/') {
+        return None;
+    }
+    Some((service, method))
+}
+/**
+*Parse the grpc-timeout like 100m,whose value has at most 8 digits and whose unit is one of H,M,S,
+ m,u and n.
+*/
+fn parse_grpc_timeout(value: &HeaderValue) -> Option<Duration> {
+    let value = value.to_str().ok()?;
+    if value.len() < 2 || value.len() > 9 {
+        return None;
+    }
+    let (amount, unit) = value.split_at(value.len() - 1);
+    if !amount.bytes().all(|item| item.is_ascii_digit()) {
+        return None;
+    }
+    let amount = amount.parse::<u64>().ok()?;
+    match unit {
+        "H" => Some(Duration::from_secs(amount * 3600)),
+        "M" => Some(Duration::from_secs(amount * 60)),
+        "S" => Some(Duration::from_secs(amount)),
+        "m" => Some(Duration::from_millis(amount)),
+        "u" => Some(Duration::from_micros(amount)),
+        "n" => Some(Duration::from_nanos(amount)),
+        _ => None,
+    }
+}
+/**
+*Whether any route of the listener matches the grpc request,which tells the request without the
+ route from the denied one.
+*/
+fn is_route_found(
+    mapping_key: &str,
+    headers: &HeaderMap,
+    uri: &Uri,
+    tls_server_name: Option<&str>,
+) -> Result<bool, anyhow::Error> {
+    let api_service_manager = match GLOBAL_CONFIG_MAPPING.get(mapping_key) {
+        Some(api_service_manager) => api_service_manager,
+        None => return Ok(false),
+    };
+    let path = uri
+        .path_and_query()
+        .map(|item| item.to_string())
+        .unwrap_or_default();
+    let server_name = get_request_server_name(uri, headers, tls_server_name);
+    for item in api_service_manager.service_config.routes.iter() {
+        if item
+            .is_matched(path.clone(), Some(headers.clone()))?
+            .is_some()
+            && item.is_server_name_matched(server_name.as_deref())
+            && item.is_grpc_matched(uri.path())
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+async fn with_deadline<F: Future>(
+    deadline: Option<Instant>,
+    future: F,
+) -> Result<F::Output, Elapsed> {
+    match deadline {
+        Some(deadline) => timeout_at(deadline, future).await,
+        None => Ok(future.await),
+    }
+}
+fn grpc_status_headers(grpc_status: &'static str, grpc_message: &'static str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(GRPC_STATUS_HEADER, HeaderValue::from_static(grpc_status));
+    headers.insert(GRPC_MESSAGE_HEADER, HeaderValue::from_static(grpc_message));
+    headers
+}
+/**
+*The labels of the grpc call,and the metrics are recorded once the grpc status is known.
+*/
+struct GrpcCallMetrics {
+    mapping_key: String,
+    service: String,
+    method: String,
+    start_time: Instant,
+}
+impl GrpcCallMetrics {
+    fn new(mapping_key: &str, path: &str) -> Self {
+        let (service, method) = split_grpc_path(path).unwrap_or(("unknown", "unknown"));
+        GrpcCallMetrics {
+            mapping_key: mapping_key.to_string(),
+            service: service.to_string(),
+            method: method.to_string(),
+            start_time: Instant::now(),
+        }
+    }
+    fn record(self, grpc_status: &str) {
+        inc_grpc(&self.mapping_key, &self.service, &self.method, grpc_status);
+        get_grpc_timer(&self.mapping_key, &self.service, &self.method)
+            .observe(self.start_time.elapsed().as_secs_f64());
+    }
+}
+/**
+*Answer the inbound request with the trailers-only response carrying the grpc status.
+*/
+fn respond_with_status(
+    mut inbound_respond: SendResponse<Bytes>,
+    grpc_call_metrics: GrpcCallMetrics,
+    grpc_status: &'static str,
+    grpc_message: &'static str,
+) -> Result<(), anyhow::Error> {
+    grpc_call_metrics.record(grpc_status);
+    let mut response = Response::new(());
+    *response.headers_mut() = grpc_status_headers(grpc_status, grpc_message);
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(GRPC_CONTENT_TYPE));
+    inbound_respond
+        .send_response(response, true)
+        .map_err(|e| anyhow!(e.to_string()))?;
+    Ok(())
+}
+/**
+*Copy the data and the trailers,and return the grpc status of the trailers.The stream is ended
+ with an empty data frame if there are no trailers.
+*/
 async fn copy_io(
-    mut send_stream: SendStream<Bytes>,
+    send_stream: &mut SendStream<Bytes>,
     mut recv_stream: RecvStream,
-) -> Result<(), anyhow::Error> {
+) -> Result<Option<String>, anyhow::Error> {
     let mut flow_control = recv_stream.flow_control().clone();
     while let Some(chunk_result) = recv_stream.data().await {
         let chunk_bytes = chunk_result?;
@@ -176,11 +308,27 @@ async fn copy_io(
         send_stream.send_data(chunk_bytes.clone(), false)?;
         flow_control.release_capacity(chunk_bytes.len())?;
     }
-    if let Ok(Some(header)) = recv_stream.trailers().await {
-        send_stream.send_trailers(header)?;
+    match recv_stream.trailers().await? {
+        Some(header) => {
+            let grpc_status = header
+                .get(GRPC_STATUS_HEADER)
+                .and_then(|item| item.to_str().ok())
+                .map(|item| item.to_string());
+            send_stream.send_trailers(header)?;
+            Ok(grpc_status)
+        }
+        None => {
+            send_stream.send_data(Bytes::new(), true)?;
+            Ok(None)
+        }
     }
-    Ok(())
 }
+/**
+*Proxy the grpc call with the inbound method and metadata.The deadline of the grpc-timeout is
+ enforced on the upstream call,and the call which exceeds it is cancelled and answered with the
+ grpc status 4.The call without the route is answered with the grpc status 12,and the denied one
+ with 7.
+*/
 async fn request_outbound(
     inbount_request: Request<RecvStream>,
     mut inbound_respond: SendResponse<Bytes>,
@@ -193,120 +341,193 @@ async fn request_outbound(
 
     let inbound_headers = inbound_parts.headers.clone();
     let uri = inbound_parts.uri.clone();
+    let tls_connection_info = inbound_parts.extensions.get::<TlsConnectionInfo>().cloned();
+    let tls_server_name = tls_connection_info
+        .as_ref()
+        .and_then(|item| item.server_name.clone());
+    let grpc_call_metrics = GrpcCallMetrics::new(&mapping_key, inbound_parts.uri.path());
     let check_result = check_trait
-        .check_before_request(mapping_key.clone(), inbound_headers, uri, peer_addr)
-        .await?;
-    if check_result.is_none() {
-        return Err(anyhow!("The request has been denied by the proxy!"));
-    }
-    let request_path = check_result.unwrap().request_path;
-    let url = Url::parse(&request_path)?;
-    let cloned_url = url.clone();
-    let host = cloned_url.host().ok_or(anyhow!("Parse host error!"))?;
-    let port = cloned_url.port().ok_or(anyhow!("Parse host error!"))?;
-    debug!("The host is {}", host);
-
-    let addr = format!("{}:{}", host, port)
-        .to_socket_addrs()?
-        .next()
-        .ok_or(anyhow!("Parse the domain error!"))?;
-    debug!("The addr is {}", addr);
-
-    let send_request_poll = if request_path.clone().contains("https") {
-        let mut root_cert_store = rustls::RootCertStore::empty();
-        root_cert_store.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(
-            |ta| {
-                OwnedTrustAnchor::from_subject_spki_name_constraints(
-                    ta.subject,
-                    ta.spki,
-                    ta.name_constraints,
+        .check_before_request(
+            mapping_key.clone(),
+            inbound_headers,
+            uri,
+            peer_addr,
+            tls_connection_info,
+        )
+        .await;
+    let check_request = match check_result {
+        Ok(Some(check_request)) => check_request,
+        Ok(None) => {
+            let is_route_found = is_route_found(
+                &mapping_key,
+                &inbound_parts.headers,
+                &inbound_parts.uri,
+                tls_server_name.as_deref(),
+            )?;
+            return if is_route_found {
+                respond_with_status(
+                    inbound_respond,
+                    grpc_call_metrics,
+                    GRPC_STATUS_PERMISSION_DENIED,
+                    "The request has been denied by the proxy.",
                 )
-            },
-        ));
-        let mut config = rustls::ClientConfig::builder()
-            .with_safe_defaults()
-            .with_root_certificates(root_cert_store)
-            .with_no_client_auth();
-        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
-        let tls_connector = TlsConnector::from(Arc::new(config));
-        let stream = TcpStream::connect(&addr).await?;
-        let domain = rustls::ServerName::try_from(host.to_string().as_str())?;
-        debug!("The domain name is {}", host);
-        let stream = tls_connector.connect(domain, stream).await?;
-        let (send_request, connection) = client::handshake(stream).await?;
-        tokio::spawn(async move {
-            let connection_result = connection.await;
-            if let Err(err) = connection_result {
-                error!("Cause error in grpc https connection,the error is {}.", err);
             } else {
-                debug!("The connection has closed!");
-            }
-        });
-        send_request
-    } else {
-        let tcpstream = TcpStream::connect(addr).await?;
-        let (send_request, connection) = client::handshake(tcpstream).await?;
-        tokio::spawn(async move {
-            connection.await.unwrap();
-            debug!("The connection has closed!");
-        });
-        send_request
+                respond_with_status(
+                    inbound_respond,
+                    grpc_call_metrics,
+                    GRPC_STATUS_UNIMPLEMENTED,
+                    "The route could not be found in the proxy.",
+                )
+            };
+        }
+        Err(err) => {
+            error!("Check the grpc request error,the error is {}", err);
+            return respond_with_status(
+                inbound_respond,
+                grpc_call_metrics,
+                GRPC_STATUS_UNAVAILABLE,
+                "The upstream is unavailable.",
+            );
+        }
     };
-
+    let deadline = inbound_parts
+        .headers
+        .get(GRPC_TIMEOUT_HEADER)
+        .and_then(parse_grpc_timeout)
+        .map(|item| Instant::now() + item);
+    let in_flight_guard = track_in_flight(check_request.base_route.endpoint.as_str());
+    let request_path = check_request.request_path;
+    let upstream_tls = check_request.route.upstream_tls;
+    let url = Url::parse(&request_path)?;
     debug!("request path is {}", url.to_string());
-    let mut send_request = send_request_poll.ready().await?;
-    let request = Request::builder()
-        .method(Method::POST)
+    let content_type = inbound_parts
+        .headers
+        .get(CONTENT_TYPE)
+        .cloned()
+        .unwrap_or(HeaderValue::from_static(GRPC_CONTENT_TYPE));
+    let mut request = Request::builder()
+        .method(inbound_parts.method.clone())
         .version(Version::HTTP_2)
         .uri(url.to_string())
-        .header("content-type", "application/grpc")
-        .header("te", "trailers")
-        .body(())
-        .unwrap();
+        .header(CONTENT_TYPE, content_type)
+        .header(TE, "trailers")
+        .body(())?;
+    for (name, value) in inbound_parts.headers.iter() {
+        if !is_hop_by_hop_header(name) {
+            request.headers_mut().append(name, value.clone());
+        }
+    }
+    if let Some(client_identity_header) = check_request.client_identity_header {
+        client_identity_header.apply(request.headers_mut())?;
+    }
     debug!("Our bound request is {:?}", request);
-    let (response, outbound_send_stream) = send_request.send_request(request, false)?;
-    tokio::spawn(async {
-        if let Err(err) = copy_io(outbound_send_stream, inbound_body).await {
+    let send_result = with_deadline(
+        deadline,
+        GLOBAL_GRPC_CONNECTION_POOL.send_request(&url, upstream_tls.as_ref(), request),
+    )
+    .await;
+    let (response, mut outbound_send_stream, pooled_stream) = match send_result {
+        Ok(Ok(item)) => item,
+        Ok(Err(err)) => {
+            error!("Connect to the grpc upstream error,the error is {}", err);
+            return respond_with_status(
+                inbound_respond,
+                grpc_call_metrics,
+                GRPC_STATUS_UNAVAILABLE,
+                "The upstream is unavailable.",
+            );
+        }
+        Err(_) => {
+            return respond_with_status(
+                inbound_respond,
+                grpc_call_metrics,
+                GRPC_STATUS_DEADLINE_EXCEEDED,
+                "Deadline exceeded.",
+            )
+        }
+    };
+    tokio::spawn(async move {
+        let copy_future = copy_io(&mut outbound_send_stream, inbound_body);
+        if let Ok(Err(err)) = with_deadline(deadline, copy_future).await {
             error!("Copy from inbound to outboud error,the error is {}", err);
         }
     });
 
-    let (head, outboud_response_body) = response
-        .await
-        .map_err(|e| anyhow!(e.to_string()))?
-        .into_parts();
+    let (head, outboud_response_body) = match with_deadline(deadline, response).await {
+        Ok(Ok(response)) => response.into_parts(),
+        Ok(Err(err)) => {
+            error!("Receive from the grpc upstream error,the error is {}", err);
+            return respond_with_status(
+                inbound_respond,
+                grpc_call_metrics,
+                GRPC_STATUS_UNAVAILABLE,
+                "The upstream is unavailable.",
+            );
+        }
+        Err(_) => {
+            return respond_with_status(
+                inbound_respond,
+                grpc_call_metrics,
+                GRPC_STATUS_DEADLINE_EXCEEDED,
+                "Deadline exceeded.",
+            )
+        }
+    };
 
     debug!("Received response: {:?}", head);
 
-    let header_map = head.headers.clone();
-    let is_grpc_status_ok = header_map
+    let trailers_only_status = head
+        .headers
         .get(GRPC_STATUS_HEADER)
-        .map(|item| item.to_str().unwrap_or_default() != GRPC_STATUS_OK)
-        .unwrap_or(false);
+        .map(|item| item.to_str().unwrap_or(GRPC_STATUS_UNKNOWN).to_string());
     let inbound_response = Response::from_parts(head, ());
-
-    let send_stream = inbound_respond
-        .send_response(inbound_response, is_grpc_status_ok)
+    let mut send_stream = inbound_respond
+        .send_response(inbound_response, trailers_only_status.is_some())
         .map_err(|e| anyhow!(e.to_string()))?;
+    if let Some(grpc_status) = trailers_only_status {
+        grpc_call_metrics.record(&grpc_status);
+        return Ok(());
+    }
 
-    tokio::spawn(async {
-        if let Err(err) = copy_io(send_stream, outboud_response_body).await {
-            error!("Copy from outbound to inbound error,the error is {}", err);
-        }
+    tokio::spawn(async move {
+        let copy_future = copy_io(&mut send_stream, outboud_response_body);
+        let grpc_status = match with_deadline(deadline, copy_future).await {
+            Ok(Ok(grpc_status)) => grpc_status.unwrap_or(String::from(GRPC_STATUS_UNKNOWN)),
+            Ok(Err(err)) => {
+                error!("Copy from outbound to inbound error,the error is {}", err);
+                String::from(GRPC_STATUS_UNAVAILABLE)
+            }
+            Err(_) => {
+                let trailers =
+                    grpc_status_headers(GRPC_STATUS_DEADLINE_EXCEEDED, "Deadline exceeded.");
+                if let Err(err) = send_stream.send_trailers(trailers) {
+                    error!("Send the deadline trailers error,the error is {}", err);
+                }
+                String::from(GRPC_STATUS_DEADLINE_EXCEEDED)
+            }
+        };
+        grpc_call_metrics.record(&grpc_status);
+        drop(pooled_stream);
+        drop(in_flight_guard);
     });
     Ok(())
 }
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::configuration_service::app_config_service::GLOBAL_CONFIG_MAPPING;
     use crate::proxy::http1::http_client::HttpClients;
     use crate::proxy::proxy_trait::CheckResult;
+    use crate::vojo::api_service_manager::ApiServiceManager;
     use crate::vojo::app_config::Route;
+    use crate::vojo::app_config::ServiceType;
+    use crate::vojo::app_config_vistor::RouteVistor;
     use async_trait::async_trait;
     use hyper::HeaderMap;
     use hyper::Uri;
 
     use hyper::Body;
+    use hyper::Method;
     use hyper::StatusCode;
     use lazy_static::lazy_static;
     use std::env;
@@ -322,12 +543,14 @@ mod tests {
             _headers: HeaderMap,
             _uri: Uri,
             _peer_addr: SocketAddr,
+            _tls_connection_info: Option<TlsConnectionInfo>,
         ) -> Result<Option<CheckResult>, anyhow::Error> {
             let route = Route::from(Default::default()).await?;
             Ok(Some(CheckResult {
                 request_path: String::from("http://127.0.0.1:50051"),
                 route,
                 base_route: Default::default(),
+                client_identity_header: None,
             }))
         }
     }
@@ -394,9 +617,13 @@ mod tests {
                 channel: receiver,
                 mapping_key: String::from("random key"),
             };
-            let _result = http_proxy
-                .start_tls_proxy(ca_certificate, private_key)
-                .await;
+            let service_config = ServiceConfig {
+                server_type: ServiceType::Http2Tls,
+                cert_str: Some(ca_certificate),
+                key_str: Some(private_key),
+                ..Default::default()
+            };
+            let _result = http_proxy.start_tls_proxy(&service_config).await;
         });
         sleep(Duration::from_millis(100)).await;
 
@@ -414,4 +641,182 @@ mod tests {
             assert_eq!(response.status(), StatusCode::OK);
         }
     }
+    async fn start_grpc_upstream(port: u16) {
+        let listener = TcpListener::bind(format!("127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        tokio::spawn(async move {
+            while let Ok((tcp_stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut connection = h2::server::handshake(tcp_stream).await.unwrap();
+                    while let Some(Ok((request, mut respond))) = connection.accept().await {
+                        tokio::spawn(async move {
+                            if request.uri().path() == "/test.Echo/Slow" {
+                                sleep(Duration::from_millis(500)).await;
+                            }
+                            let mut response = Response::builder()
+                                .header(CONTENT_TYPE, GRPC_CONTENT_TYPE)
+                                .header("x-method", request.method().as_str())
+                                .body(())
+                                .unwrap();
+                            for name in ["x-request-id", GRPC_TIMEOUT_HEADER] {
+                                if let Some(value) = request.headers().get(name) {
+                                    response.headers_mut().insert(name, value.clone());
+                                }
+                            }
+                            let mut send_stream = respond.send_response(response, false).unwrap();
+                            let _ = send_stream.send_data(Bytes::from("echo"), false);
+                            let mut trailers = HeaderMap::new();
+                            trailers.insert(GRPC_STATUS_HEADER, HeaderValue::from_static("0"));
+                            let _ = send_stream.send_trailers(trailers);
+                        });
+                    }
+                });
+            }
+        });
+    }
+    #[test]
+    fn test_split_grpc_path_ok() {
+        assert_eq!(
+            split_grpc_path("/helloworld.Greeter/SayHello"),
+            Some(("helloworld.Greeter", "SayHello"))
+        );
+        assert_eq!(split_grpc_path("/helloworld.Greeter"), None);
+        assert_eq!(split_grpc_path("/helloworld.Greeter/SayHello/a"), None);
+        assert_eq!(split_grpc_path("//SayHello"), None);
+    }
+    #[test]
+    fn test_parse_grpc_timeout_ok() {
+        let parse = |value: &'static str| parse_grpc_timeout(&HeaderValue::from_static(value));
+        assert_eq!(parse("1H"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse("2M"), Some(Duration::from_secs(120)));
+        assert_eq!(parse("3S"), Some(Duration::from_secs(3)));
+        assert_eq!(parse("100m"), Some(Duration::from_millis(100)));
+        assert_eq!(parse("5u"), Some(Duration::from_micros(5)));
+        assert_eq!(parse("99999999n"), Some(Duration::from_nanos(99999999)));
+        assert_eq!(parse("100000000n"), None);
+        assert_eq!(parse("m"), None);
+        assert_eq!(parse("-1S"), None);
+        assert_eq!(parse("10x"), None);
+    }
+    #[tokio::test]
+    async fn test_grpc_proxy_metadata_and_deadline_ok() {
+        start_grpc_upstream(4477).await;
+        let route_vistor: RouteVistor = serde_json::from_value(serde_json::json!({
+            "matcher": { "prefix": "/", "prefix_rewrite": "/" },
+            "grpc_matcher": { "service": "test.Echo" },
+            "route_cluster": {
+                "type": "WeightBasedRoute",
+                "routes": [{ "base_route": { "endpoint": "http://127.0.0.1:4477" }, "weight": 100 }]
+            }
+        }))
+        .unwrap();
+        let denied_route_vistor: RouteVistor = serde_json::from_value(serde_json::json!({
+            "matcher": { "prefix": "/", "prefix_rewrite": "/" },
+            "grpc_matcher": { "service": "test.Denied" },
+            "allow_deny_list": [{ "limit_type": "DenyAll" }],
+            "route_cluster": {
+                "type": "WeightBasedRoute",
+                "routes": [{ "base_route": { "endpoint": "http://127.0.0.1:4477" }, "weight": 100 }]
+            }
+        }))
+        .unwrap();
+        let (sender, _) = tokio::sync::mpsc::channel(1);
+        GLOBAL_CONFIG_MAPPING.insert(
+            String::from("4478-GRPC"),
+            ApiServiceManager {
+                service_config: ServiceConfig {
+                    routes: vec![
+                        Route::from(route_vistor).await.unwrap(),
+                        Route::from(denied_route_vistor).await.unwrap(),
+                    ],
+                    ..Default::default()
+                },
+                sender,
+            },
+        );
+        let (_sender, receiver) = tokio::sync::mpsc::channel(10);
+        tokio::spawn(async {
+            let mut grpc_proxy = GrpcProxy {
+                port: 4478,
+                channel: receiver,
+                mapping_key: String::from("4478-GRPC"),
+            };
+            let _result = grpc_proxy.start_proxy().await;
+        });
+        sleep(Duration::from_millis(100)).await;
+
+        let tcp_stream = TcpStream::connect("127.0.0.1:4478").await.unwrap();
+        let (send_request, connection) = h2::client::handshake(tcp_stream).await.unwrap();
+        tokio::spawn(connection);
+        let new_request = |path: &str, grpc_timeout: &'static str| {
+            Request::post(format!("http://127.0.0.1:4478{}", path))
+                .header(CONTENT_TYPE, "application/grpc+proto")
+                .header("x-request-id", "42")
+                .header(GRPC_TIMEOUT_HEADER, grpc_timeout)
+                .body(())
+                .unwrap()
+        };
+
+        let mut client = send_request.clone().ready().await.unwrap();
+        let (response, _) = client
+            .send_request(new_request("/test.Echo/Say", "5S"), true)
+            .unwrap();
+        let (head, mut body) = response.await.unwrap().into_parts();
+        assert_eq!(head.headers["x-method"], "POST");
+        assert_eq!(head.headers["x-request-id"], "42");
+        assert_eq!(head.headers[GRPC_TIMEOUT_HEADER], "5S");
+        assert_eq!(body.data().await.unwrap().unwrap(), Bytes::from("echo"));
+        assert!(body.data().await.is_none());
+        let trailers = body.trailers().await.unwrap().unwrap();
+        assert_eq!(trailers[GRPC_STATUS_HEADER], "0");
+
+        let mut client = send_request.clone().ready().await.unwrap();
+        let (response, _) = client
+            .send_request(new_request("/test.Echo/Slow", "100m"), true)
+            .unwrap();
+        let head = response.await.unwrap().into_parts().0;
+        assert_eq!(head.headers[GRPC_STATUS_HEADER], "4");
+
+        let mut client = send_request.clone().ready().await.unwrap();
+        let (response, _) = client
+            .send_request(new_request("/test.Other/Say", "5S"), true)
+            .unwrap();
+        let head = response.await.unwrap().into_parts().0;
+        assert_eq!(head.headers[GRPC_STATUS_HEADER], "12");
+
+        let mut client = send_request.clone().ready().await.unwrap();
+        let (response, _) = client
+            .send_request(new_request("/test.Denied/Say", "5S"), true)
+            .unwrap();
+        let head = response.await.unwrap().into_parts().0;
+        assert_eq!(head.headers[GRPC_STATUS_HEADER], "7");
+
+        let metric_families = prometheus::gather();
+        let grpc_counter = metric_families
+            .iter()
+            .find(|item| item.get_name() == "silverwind_grpc_requests_total")
+            .unwrap();
+        let count = |service: &str, method: &str, grpc_status: &str| {
+            grpc_counter
+                .get_metric()
+                .iter()
+                .find(|metric| {
+                    let labels = metric
+                        .get_label()
+                        .iter()
+                        .map(|item| (item.get_name(), item.get_value()))
+                        .collect::<Vec<(&str, &str)>>();
+                    labels.contains(&("port", "4478-GRPC"))
+                        && labels.contains(&("grpc_service", service))
+                        && labels.contains(&("grpc_method", method))
+                        && labels.contains(&("grpc_status", grpc_status))
+                })
+                .map(|metric| metric.get_counter().get_value())
+        };
+        assert_eq!(count("test.Echo", "Say", "0"), Some(1.0));
+        assert_eq!(count("test.Echo", "Slow", "4"), Some(1.0));
+        assert_eq!(count("test.Other", "Say", "12"), Some(1.0));
+        assert_eq!(count("test.Denied", "Say", "7"), Some(1.0));
+    }
 }
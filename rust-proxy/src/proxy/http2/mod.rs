@@ -1 +1,2 @@
+pub mod grpc_connection_pool;
 pub mod grpc_proxy;
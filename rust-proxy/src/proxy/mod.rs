@@ -1,4 +1,10 @@
+pub mod certificate_resolver;
 pub mod http1;
 pub mod http2;
+pub mod ocsp_stapling;
 pub mod proxy_trait;
 pub mod tcp;
+pub mod upstream_tls;
+
+#[cfg(test)]
+mod data_plane_benchmark;
@@ -0,0 +1,402 @@
+use crate::acme::acme_manager::get_acme_certificate;
+use crate::configuration_service::app_config_service::GLOBAL_CONFIG_MAPPING;
+use crate::constants::common_constants::TIMER_WAIT_SECONDS;
+use crate::proxy::certificate_resolver::update_server_config;
+use crate::proxy::upstream_tls::UpstreamTls;
+use crate::vojo::app_config::{OcspStaplingConfig, ServiceConfig, UpstreamTlsConfig};
+use dashmap::DashMap;
+use futures::FutureExt;
+use http::header::CONTENT_TYPE;
+use hyper::{Body, Request};
+use lazy_static::lazy_static;
+use openssl::asn1::{Asn1GeneralizedTimeRef, Asn1Time, Asn1TimeRef};
+use openssl::hash::MessageDigest;
+use openssl::ocsp::{
+    OcspCertId, OcspCertStatus, OcspFlag, OcspRequest, OcspResponse, OcspResponseStatus,
+};
+use openssl::sha::sha256;
+use openssl::stack::Stack;
+use openssl::x509::store::X509StoreBuilder;
+use openssl::x509::verify::X509VerifyFlags;
+use openssl::x509::X509;
+use std::io::BufReader;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::time::{sleep, timeout, Duration};
+const OCSP_REQUEST_TIMEOUT_SECONDS: u64 = 10;
+const OCSP_MAX_CLOCK_SKEW_SECONDS: u32 = 300;
+const OCSP_REFRESH_SECONDS: i64 = 3600;
+#[derive(Debug, Clone)]
+struct OcspStaple {
+    response: Vec<u8>,
+    refresh_timestamp: i64,
+    next_update_timestamp: i64,
+}
+lazy_static! {
+    static ref GLOBAL_OCSP_STAPLES: DashMap<Vec<u8>, OcspStaple> = Default::default();
+    static ref GLOBAL_ATTACHED_STAPLES: DashMap<String, Vec<Option<Vec<u8>>>> = Default::default();
+}
+fn now_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|item| item.as_secs() as i64)
+        .unwrap_or_default()
+}
+/**
+*The cached ocsp response of the certificate,which is not stapled any more after its next update.
+*/
+pub fn get_ocsp_response(cert_der: &[u8]) -> Option<Vec<u8>> {
+    let ocsp_staple = GLOBAL_OCSP_STAPLES.get(sha256(cert_der).as_slice())?;
+    (ocsp_staple.next_update_timestamp > now_timestamp()).then(|| ocsp_staple.response.clone())
+}
+/**
+*The generalized time is an ASN1_TIME of the generalized type,so it is diffed with the unix epoch
+ as the asn1 time directly instead of parsing the printed string.
+*/
+fn get_timestamp(time: &Asn1GeneralizedTimeRef) -> Result<i64, anyhow::Error> {
+    // SAFETY: both of the references point to the same ASN1_STRING of openssl,and the
+    // ASN1_TIME_diff accepts the generalized time as well as the utc time.
+    let asn1_time = unsafe { &*(time as *const Asn1GeneralizedTimeRef as *const Asn1TimeRef) };
+    let time_diff = Asn1Time::from_unix(0)?.diff(asn1_time)?;
+    Ok(time_diff.days as i64 * 86400 + time_diff.secs as i64)
+}
+fn parse_chain(cert_str: &str) -> Result<Vec<X509>, anyhow::Error> {
+    let mut cer_reader = BufReader::new(cert_str.as_bytes());
+    let mut chain = vec![];
+    for cert_der in rustls_pemfile::certs(&mut cer_reader)? {
+        chain.push(X509::from_der(&cert_der)?);
+    }
+    Ok(chain)
+}
+/**
+*Request the ocsp response of the first certificate of the chain,which is issued by the second
+ one.The response must be signed by the issuer or the responder delegated by it.
+*/
+async fn fetch_ocsp_staple(
+    chain: &[X509],
+    responder_url_option: Option<&str>,
+) -> Result<OcspStaple, anyhow::Error> {
+    let (certificate, issuer) = match chain {
+        [certificate, issuer, ..] => (certificate, issuer),
+        _ => return Err(anyhow!("The issuer certificate is missing in the chain.")),
+    };
+    let responder_url = match responder_url_option {
+        Some(responder_url) => responder_url.to_string(),
+        None => certificate
+            .ocsp_responders()?
+            .iter()
+            .next()
+            .map(|item| item.to_string())
+            .ok_or(anyhow!("There is no ocsp responder in the certificate."))?,
+    };
+    let mut ocsp_request = OcspRequest::new()?;
+    ocsp_request.add_id(OcspCertId::from_cert(
+        MessageDigest::sha1(),
+        certificate,
+        issuer,
+    )?)?;
+    let request = Request::post(responder_url.as_str())
+        .header(CONTENT_TYPE, "application/ocsp-request")
+        .body(Body::from(ocsp_request.to_der()?))?;
+    let upstream_tls = UpstreamTls::new(UpstreamTlsConfig::default())?;
+    let response = timeout(
+        Duration::from_secs(OCSP_REQUEST_TIMEOUT_SECONDS),
+        upstream_tls.https_client.request(request),
+    )
+    .await
+    .map_err(|_| anyhow!("Request the ocsp responder {} timeout.", responder_url))??;
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Request the ocsp responder {} error,the status is {}.",
+            responder_url,
+            response.status()
+        ));
+    }
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    let ocsp_response = OcspResponse::from_der(&body)?;
+    if ocsp_response.status() != OcspResponseStatus::SUCCESSFUL {
+        return Err(anyhow!(
+            "The ocsp response status is {}.",
+            ocsp_response.status().as_raw()
+        ));
+    }
+    let basic_response = ocsp_response.basic()?;
+    let mut store_builder = X509StoreBuilder::new()?;
+    store_builder.add_cert(issuer.clone())?;
+    store_builder.set_flags(X509VerifyFlags::PARTIAL_CHAIN)?;
+    let mut certs = Stack::new()?;
+    certs.push(issuer.clone())?;
+    basic_response
+        .verify(&certs, &store_builder.build(), OcspFlag::empty())
+        .map_err(|e| anyhow!("Verify the ocsp response error,the error is {}.", e))?;
+    let cert_id = OcspCertId::from_cert(MessageDigest::sha1(), certificate, issuer)?;
+    let status = basic_response.find_status(&cert_id).ok_or(anyhow!(
+        "The ocsp response does not contain the certificate."
+    ))?;
+    status
+        .check_validity(OCSP_MAX_CLOCK_SKEW_SECONDS, None)
+        .map_err(|e| anyhow!("The ocsp response is out of date,the error is {}.", e))?;
+    if status.status == OcspCertStatus::REVOKED {
+        warn!(
+            "The certificate {:?} is revoked!",
+            certificate.subject_name()
+        );
+    }
+    let this_update_timestamp = get_timestamp(status.this_update)?;
+    let (refresh_timestamp, next_update_timestamp) = match status.next_update() {
+        Some(next_update) => {
+            let next_update_timestamp = get_timestamp(next_update)?;
+            (
+                this_update_timestamp + (next_update_timestamp - this_update_timestamp) / 2,
+                next_update_timestamp,
+            )
+        }
+        None => (now_timestamp() + OCSP_REFRESH_SECONDS, i64::MAX),
+    };
+    Ok(OcspStaple {
+        response: body.to_vec(),
+        refresh_timestamp,
+        next_update_timestamp,
+    })
+}
+fn get_certificate_chains(service_config: &ServiceConfig) -> Vec<String> {
+    let acme_certificate = service_config.acme.as_ref().and_then(get_acme_certificate);
+    service_config
+        .cert_str
+        .iter()
+        .cloned()
+        .chain(
+            service_config
+                .certificates
+                .iter()
+                .chain(acme_certificate.iter())
+                .map(|item| item.cert_str.clone()),
+        )
+        .collect()
+}
+/**
+*Refresh the ocsp responses which are missing or have passed the half of their validity,and
+ update the server config if the stapled responses change.The failed request is retried in the
+ next round,and the current response is kept until its next update.
+*/
+async fn sync_ocsp_staple(
+    mapping_key: &str,
+    service_config: &ServiceConfig,
+    ocsp_stapling_config: &OcspStaplingConfig,
+) -> Result<(), anyhow::Error> {
+    let mut ocsp_responses = vec![];
+    for cert_str in get_certificate_chains(service_config) {
+        let chain = parse_chain(&cert_str)?;
+        let cert_der = match chain.first() {
+            Some(certificate) => certificate.to_der()?,
+            None => continue,
+        };
+        let cert_key = sha256(&cert_der).to_vec();
+        let is_refresh_time = GLOBAL_OCSP_STAPLES
+            .get(&cert_key)
+            .is_none_or(|item| item.refresh_timestamp <= now_timestamp());
+        if is_refresh_time {
+            match fetch_ocsp_staple(&chain, ocsp_stapling_config.responder_url.as_deref()).await {
+                Ok(ocsp_staple) => {
+                    GLOBAL_OCSP_STAPLES.insert(cert_key, ocsp_staple);
+                }
+                Err(err) => warn!(
+                    "Fetch the ocsp response of the listener-{} error,the error is {}.",
+                    mapping_key, err
+                ),
+            }
+        }
+        ocsp_responses.push(get_ocsp_response(&cert_der));
+    }
+    let is_attached = GLOBAL_ATTACHED_STAPLES
+        .get(mapping_key)
+        .is_some_and(|item| *item.value() == ocsp_responses);
+    if !is_attached {
+        update_server_config(mapping_key, service_config)?;
+        GLOBAL_ATTACHED_STAPLES.insert(mapping_key.to_string(), ocsp_responses);
+    }
+    Ok(())
+}
+pub async fn sync_ocsp_staples() {
+    for (mapping_key, api_service_manager) in GLOBAL_CONFIG_MAPPING.load().iter() {
+        let service_config = &api_service_manager.service_config;
+        if let Some(ocsp_stapling_config) = &service_config.ocsp_stapling {
+            if let Err(err) =
+                sync_ocsp_staple(mapping_key, service_config, ocsp_stapling_config).await
+            {
+                error!(
+                    "Sync the ocsp staples of the listener-{} error,the error is {}.",
+                    mapping_key, err
+                );
+            }
+        }
+    }
+}
+pub async fn start_ocsp_stapling_loop() {
+    loop {
+        let async_result = std::panic::AssertUnwindSafe(sync_ocsp_staples())
+            .catch_unwind()
+            .await;
+        if async_result.is_err() {
+            error!("start_ocsp_stapling_loop catch panic successfully!");
+        }
+        sleep(std::time::Duration::from_secs(TIMER_WAIT_SECONDS)).await;
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::certificate_resolver::{build_server_config, CertificateResolver};
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Response, Server};
+    use openssl::asn1::Asn1GeneralizedTime;
+    use openssl::ssl::{SslConnector, SslMethod, SslVerifyMode, StatusType};
+    use std::convert::Infallible;
+    use std::env;
+    use std::net::SocketAddr;
+    use std::sync::{Arc, Mutex};
+    use tokio::net::TcpListener;
+    use tokio_rustls::TlsAcceptor;
+    fn read_config_file(file_name: &str) -> String {
+        let path = env::current_dir().unwrap().join("config").join(file_name);
+        std::fs::read_to_string(path).unwrap()
+    }
+    fn read_ocsp_response() -> Vec<u8> {
+        let path = env::current_dir()
+            .unwrap()
+            .join("config")
+            .join("test_ocsp_response.der");
+        std::fs::read(path).unwrap()
+    }
+    async fn start_responder(port: u16) {
+        let make_service = make_service_fn(|_| async {
+            Ok::<_, Infallible>(service_fn(|req: Request<Body>| async move {
+                assert_eq!(
+                    req.headers().get(CONTENT_TYPE).unwrap(),
+                    "application/ocsp-request"
+                );
+                let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+                assert!(OcspRequest::from_der(&body).is_ok());
+                Ok::<_, Infallible>(Response::new(Body::from(read_ocsp_response())))
+            }))
+        });
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], port))).serve(make_service);
+        tokio::spawn(server);
+    }
+    fn new_service_config(responder_url: Option<String>) -> ServiceConfig {
+        ServiceConfig {
+            cert_str: Some(read_config_file("test_ocsp_cert.pem")),
+            key_str: Some(read_config_file("test_ocsp_key.pem")),
+            ocsp_stapling: Some(OcspStaplingConfig { responder_url }),
+            ..Default::default()
+        }
+    }
+    #[tokio::test]
+    async fn test_fetch_ocsp_staple_ok() {
+        start_responder(4460).await;
+        let chain = parse_chain(&read_config_file("test_ocsp_cert.pem")).unwrap();
+        let ocsp_staple = fetch_ocsp_staple(&chain, None).await.unwrap();
+        assert_eq!(ocsp_staple.response, read_ocsp_response());
+        assert_eq!(ocsp_staple.next_update_timestamp, 4945991455);
+        assert_eq!(
+            ocsp_staple.refresh_timestamp,
+            1792391455 + (4945991455 - 1792391455) / 2
+        );
+    }
+    #[test]
+    fn test_get_timestamp_ok() {
+        let time = Asn1GeneralizedTime::from_str("20260905063055Z").unwrap();
+        assert_eq!(get_timestamp(&time).unwrap(), 1788589855);
+        let time = Asn1GeneralizedTime::from_str("21260925063055Z").unwrap();
+        assert_eq!(get_timestamp(&time).unwrap(), 4945991455);
+    }
+    #[tokio::test]
+    async fn test_fetch_ocsp_staple_error() {
+        let chain = parse_chain(&read_config_file("test_ocsp_cert.pem")).unwrap();
+        let result = fetch_ocsp_staple(&chain, Some("http://127.0.0.1:4461/")).await;
+        assert!(result.is_err());
+        let result = fetch_ocsp_staple(&chain[..1], None).await;
+        assert!(result.is_err());
+        let other_issuer = parse_chain(&read_config_file("test_upstream_ca_cert.pem")).unwrap();
+        let chain = vec![chain[0].clone(), other_issuer[0].clone()];
+        start_responder(4463).await;
+        let result = fetch_ocsp_staple(&chain, Some("http://127.0.0.1:4463/")).await;
+        assert!(result.is_err());
+    }
+    #[tokio::test]
+    async fn test_sync_ocsp_staple_ok() {
+        start_responder(4462).await;
+        let service_config = new_service_config(Some(String::from("http://127.0.0.1:4462/")));
+        let certificate_resolver = CertificateResolver::new(&service_config).unwrap();
+        assert!(certificate_resolver
+            .resolve_server_name(None)
+            .unwrap()
+            .ocsp
+            .is_none());
+        let result = sync_ocsp_staple(
+            "ocsp-sync-test",
+            &service_config,
+            service_config.ocsp_stapling.as_ref().unwrap(),
+        )
+        .await;
+        assert!(result.is_ok());
+        let certificate_resolver = CertificateResolver::new(&service_config).unwrap();
+        assert_eq!(
+            certificate_resolver.resolve_server_name(None).unwrap().ocsp,
+            Some(read_ocsp_response())
+        );
+
+        let server_config = build_server_config(String::from("ocsp-sync-test"), &service_config)
+            .unwrap()
+            .load_full();
+        let tls_acceptor = TlsAcceptor::from(server_config);
+        let listener = TcpListener::bind("127.0.0.1:4464").await.unwrap();
+        tokio::spawn(async move {
+            while let Ok((tcp_stream, _)) = listener.accept().await {
+                let _ = tls_acceptor.accept(tcp_stream).await;
+            }
+        });
+        let stapled_response = tokio::task::spawn_blocking(|| {
+            let stapled_response = Arc::new(Mutex::new(None));
+            let stapled_response_clone = stapled_response.clone();
+            let mut connector_builder = SslConnector::builder(SslMethod::tls()).unwrap();
+            connector_builder.set_verify(SslVerifyMode::NONE);
+            connector_builder
+                .set_status_callback(move |ssl| {
+                    *stapled_response_clone.lock().unwrap() = ssl.ocsp_status().map(Vec::from);
+                    Ok(true)
+                })
+                .unwrap();
+            let mut configuration = connector_builder.build().configure().unwrap();
+            configuration.set_status_type(StatusType::OCSP).unwrap();
+            let stream = std::net::TcpStream::connect("127.0.0.1:4464").unwrap();
+            let _ssl_stream = configuration.connect("ocsp.example.com", stream).unwrap();
+            let result = stapled_response.lock().unwrap().clone();
+            result
+        })
+        .await
+        .unwrap();
+        assert_eq!(stapled_response, Some(read_ocsp_response()));
+    }
+    #[tokio::test]
+    async fn test_sync_ocsp_staple_without_issuer() {
+        let service_config = ServiceConfig {
+            cert_str: Some(read_config_file("test_cert.pem")),
+            key_str: Some(read_config_file("test_key.pem")),
+            ocsp_stapling: Some(OcspStaplingConfig::default()),
+            ..Default::default()
+        };
+        let result = sync_ocsp_staple(
+            "ocsp-without-issuer-test",
+            &service_config,
+            service_config.ocsp_stapling.as_ref().unwrap(),
+        )
+        .await;
+        assert!(result.is_ok());
+        let certificate_resolver = CertificateResolver::new(&service_config).unwrap();
+        assert!(certificate_resolver
+            .resolve_server_name(None)
+            .unwrap()
+            .ocsp
+            .is_none());
+    }
+}
@@ -1,9 +1,13 @@
 use crate::configuration_service::app_config_service::GLOBAL_CONFIG_MAPPING;
 use crate::vojo::app_config::Route;
+use crate::vojo::client_identity::ClientIdentity;
 use crate::vojo::route::BaseRoute;
 use async_trait::async_trait;
+use http::header::{HeaderName, HeaderValue, HOST};
+use http::uri::Authority;
 use http::HeaderMap;
 use hyper::Uri;
+use rustls::ServerConnection;
 use std::net::SocketAddr;
 use std::path::Path;
 use url::Url;
@@ -15,8 +19,80 @@ pub trait CheckTrait {
         headers: HeaderMap,
         uri: Uri,
         peer_addr: SocketAddr,
+        tls_connection_info: Option<TlsConnectionInfo>,
     ) -> Result<Option<CheckResult>, anyhow::Error>;
 }
+/**
+*The server name and the verified client identity of the tls handshake,which are carried in the
+ extensions of the request.
+*/
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TlsConnectionInfo {
+    pub server_name: Option<String>,
+    pub client_identity: Option<ClientIdentity>,
+}
+impl TlsConnectionInfo {
+    pub fn from(server_connection: &ServerConnection) -> Self {
+        let client_identity = server_connection
+            .peer_certificates()
+            .and_then(|certificates| certificates.first())
+            .and_then(
+                |certificate| match ClientIdentity::from_der(&certificate.0) {
+                    Ok(client_identity) => Some(client_identity),
+                    Err(err) => {
+                        error!("{}", err);
+                        None
+                    }
+                },
+            );
+        TlsConnectionInfo {
+            server_name: server_connection.sni_hostname().map(String::from),
+            client_identity,
+        }
+    }
+}
+/**
+*The server names of the routes are matched against the host of the request,which is the
+ :authority of h2 or the host header without the port.The h2 connection could be reused for the
+ other hosts covered by the certificate,so the server name of the tls handshake is only used when
+ the request has no host.
+*/
+pub fn get_request_server_name(
+    uri: &Uri,
+    headers: &HeaderMap,
+    tls_server_name: Option<&str>,
+) -> Option<String> {
+    let authority_option = match uri.authority() {
+        Some(authority) => Some(authority.clone()),
+        None => headers
+            .get(HOST)
+            .and_then(|item| item.to_str().ok())
+            .and_then(|item| item.parse::<Authority>().ok()),
+    };
+    match authority_option {
+        Some(authority) => Some(String::from(authority.host())),
+        None => tls_server_name.map(String::from),
+    }
+}
+/**
+*The header which forwards the verified client identity to the upstream.The header from the
+ client is removed even if there is no client identity,so it could not be forged.
+*/
+#[derive(Debug, Clone)]
+pub struct ClientIdentityHeader {
+    pub name: String,
+    pub value: Option<String>,
+}
+impl ClientIdentityHeader {
+    pub fn apply(&self, headers: &mut HeaderMap) -> Result<(), anyhow::Error> {
+        let header_name = HeaderName::from_bytes(self.name.as_bytes())?;
+        headers.remove(&header_name);
+        if let Some(value) = &self.value {
+            headers.insert(header_name, HeaderValue::from_str(value)?);
+        }
+        Ok(())
+    }
+}
 pub struct CommonCheckRequest;
 impl CommonCheckRequest {
     pub fn new() -> Self {
@@ -28,6 +104,7 @@ pub struct CheckResult {
     pub request_path: String,
     pub route: Route,
     pub base_route: BaseRoute,
+    pub client_identity_header: Option<ClientIdentityHeader>,
 }
 
 #[async_trait]
@@ -38,33 +115,50 @@ impl CheckTrait for CommonCheckRequest {
         headers: HeaderMap,
         uri: Uri,
         peer_addr: SocketAddr,
+        tls_connection_info: Option<TlsConnectionInfo>,
     ) -> Result<Option<CheckResult>, anyhow::Error> {
         let backend_path = uri.path_and_query().ok_or(anyhow!(""))?.to_string();
-        let api_service_manager = GLOBAL_CONFIG_MAPPING
-            .get(&mapping_key)
-            .ok_or(anyhow!(format!(
-                "Can not find the config mapping on the key {}!",
-                mapping_key.clone()
-            )))?
-            .clone();
+        let api_service_manager =
+            GLOBAL_CONFIG_MAPPING
+                .get(&mapping_key)
+                .ok_or(anyhow!(format!(
+                    "Can not find the config mapping on the key {}!",
+                    mapping_key.clone()
+                )))?;
         let addr_string = peer_addr.ip().to_string();
-        for item in api_service_manager.service_config.routes {
+        let tls_connection_info = tls_connection_info.unwrap_or_default();
+        let server_name =
+            get_request_server_name(&uri, &headers, tls_connection_info.server_name.as_deref());
+        let client_identity_header = api_service_manager
+            .service_config
+            .client_auth
+            .as_ref()
+            .and_then(|client_auth| client_auth.identity_header.clone())
+            .map(|name| ClientIdentityHeader {
+                name,
+                value: tls_connection_info
+                    .client_identity
+                    .as_ref()
+                    .map(|client_identity| client_identity.to_header_value()),
+            });
+        for item in api_service_manager.service_config.routes.iter() {
             let back_path_clone = backend_path.clone();
             let match_result = item.is_matched(back_path_clone, Some(headers.clone()))?;
-            if match_result.clone().is_none() {
+            if match_result.clone().is_none()
+                || !item.is_server_name_matched(server_name.as_deref())
+                || !item.is_grpc_matched(uri.path())
+            {
                 continue;
             }
             let is_allowed = item
                 .is_allowed(addr_string.clone(), Some(headers.clone()))
                 .await?;
-            if !is_allowed {
+            if !is_allowed
+                || !item.is_client_identity_allowed(tls_connection_info.client_identity.as_ref())
+            {
                 return Ok(None);
             }
-            let base_route = item
-                .route_cluster
-                .clone()
-                .get_route(headers.clone())
-                .await?;
+            let base_route = item.route_cluster.get_route(headers.clone()).await?;
             let endpoint = base_route.endpoint.clone();
             debug!("The endpoint is {}", endpoint);
             if endpoint.contains("http") {
@@ -74,8 +168,9 @@ impl CheckTrait for CommonCheckRequest {
                 let request_path = host.join(rest_path.as_str())?.to_string();
                 return Ok(Some(CheckResult {
                     request_path,
-                    route: item,
+                    route: item.clone(),
                     base_route,
+                    client_identity_header,
                 }));
             } else {
                 let path = Path::new(&endpoint);
@@ -83,8 +178,9 @@ impl CheckTrait for CommonCheckRequest {
                 let request_path = path.join(rest_path);
                 return Ok(Some(CheckResult {
                     request_path: String::from(request_path.to_str().unwrap_or_default()),
-                    route: item,
+                    route: item.clone(),
                     base_route,
+                    client_identity_header,
                 }));
             }
         }
@@ -94,9 +190,173 @@ impl CheckTrait for CommonCheckRequest {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::vojo::api_service_manager::ApiServiceManager;
+    use crate::vojo::app_config::{ClientAuthConfig, ClientAuthMode, ServiceConfig, ServiceType};
+    use crate::vojo::app_config_vistor::RouteVistor;
+    use serde_json::json;
+    use std::net::{IpAddr, Ipv4Addr};
     #[test]
     fn test_url_parse() {
         let host = Url::parse("http://127.0.0.1:8080");
         assert!(host.is_ok());
     }
+    #[test]
+    fn test_client_identity_header_apply_ok() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-client-cert", "forged".parse().unwrap());
+        let mut client_identity_header = ClientIdentityHeader {
+            name: String::from("x-forwarded-client-cert"),
+            value: None,
+        };
+        client_identity_header.apply(&mut headers).unwrap();
+        assert!(!headers.contains_key("x-forwarded-client-cert"));
+
+        client_identity_header.value = Some(String::from("Subject=\"CN=client\""));
+        client_identity_header.apply(&mut headers).unwrap();
+        assert_eq!(
+            headers.get("x-forwarded-client-cert").unwrap(),
+            "Subject=\"CN=client\""
+        );
+    }
+    #[tokio::test]
+    async fn test_check_before_request_client_identity_ok() {
+        let route_vistor: RouteVistor = serde_json::from_value(json!({
+            "matcher": { "prefix": "/", "prefix_rewrite": "/" },
+            "client_identities": ["spiffe://example.org/ns/default/*"],
+            "route_cluster": {
+                "type": "WeightBasedRoute",
+                "routes": [{ "base_route": { "endpoint": "http://127.0.0.1:8080" }, "weight": 100 }]
+            }
+        }))
+        .unwrap();
+        let mapping_key = String::from("4454-HTTPS");
+        let (sender, _) = tokio::sync::mpsc::channel(1);
+        GLOBAL_CONFIG_MAPPING.insert(
+            mapping_key.clone(),
+            ApiServiceManager {
+                service_config: ServiceConfig {
+                    server_type: ServiceType::Https,
+                    client_auth: Some(ClientAuthConfig {
+                        ca_str: String::new(),
+                        mode: ClientAuthMode::Optional,
+                        identity_header: Some(String::from("x-forwarded-client-cert")),
+                    }),
+                    routes: vec![Route::from(route_vistor).await.unwrap()],
+                    ..Default::default()
+                },
+                sender,
+            },
+        );
+        let client_identity = ClientIdentity {
+            subject: String::from("CN=client"),
+            uris: vec![String::from("spiffe://example.org/ns/default/sa/client")],
+            dns_names: vec![],
+        };
+        let common_check_request = CommonCheckRequest::new();
+        let check_request = |client_identity: Option<ClientIdentity>| {
+            common_check_request.check_before_request(
+                mapping_key.clone(),
+                HeaderMap::new(),
+                Uri::from_static("/get"),
+                SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080),
+                Some(TlsConnectionInfo {
+                    server_name: None,
+                    client_identity,
+                }),
+            )
+        };
+
+        let check_result = check_request(Some(client_identity.clone()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(check_result.request_path, "http://127.0.0.1:8080/get");
+        assert_eq!(
+            check_result.client_identity_header.unwrap().value,
+            Some(client_identity.to_header_value())
+        );
+        let other_client_identity = ClientIdentity {
+            uris: vec![String::from("spiffe://example.org/ns/payments/sa/client")],
+            ..client_identity
+        };
+        assert!(check_request(Some(other_client_identity))
+            .await
+            .unwrap()
+            .is_none());
+        assert!(check_request(None).await.unwrap().is_none());
+    }
+    #[test]
+    fn test_get_request_server_name_ok() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            get_request_server_name(
+                &Uri::from_static("https://B.example.com:8443/get"),
+                &headers,
+                Some("a.example.com")
+            ),
+            Some(String::from("B.example.com"))
+        );
+        headers.insert(HOST, HeaderValue::from_static("b.example.com:8443"));
+        assert_eq!(
+            get_request_server_name(&Uri::from_static("/get"), &headers, Some("a.example.com")),
+            Some(String::from("b.example.com"))
+        );
+        assert_eq!(
+            get_request_server_name(
+                &Uri::from_static("/get"),
+                &HeaderMap::new(),
+                Some("a.example.com")
+            ),
+            Some(String::from("a.example.com"))
+        );
+        assert_eq!(
+            get_request_server_name(&Uri::from_static("/get"), &HeaderMap::new(), None),
+            None
+        );
+    }
+    #[tokio::test]
+    async fn test_check_before_request_with_reused_connection() {
+        let new_route = |server_name: &str, port: i32| {
+            serde_json::from_value::<RouteVistor>(json!({
+                "server_names": [server_name],
+                "matcher": { "prefix": "/", "prefix_rewrite": "/" },
+                "route_cluster": {
+                    "type": "WeightBasedRoute",
+                    "routes": [{ "base_route": { "endpoint": format!("http://127.0.0.1:{}", port) }, "weight": 100 }]
+                }
+            }))
+            .unwrap()
+        };
+        let mapping_key = String::from("4455-HTTPS");
+        let (sender, _) = tokio::sync::mpsc::channel(1);
+        GLOBAL_CONFIG_MAPPING.insert(
+            mapping_key.clone(),
+            ApiServiceManager {
+                service_config: ServiceConfig {
+                    server_type: ServiceType::Https,
+                    routes: vec![
+                        Route::from(new_route("a.example.com", 8080)).await.unwrap(),
+                        Route::from(new_route("b.example.com", 8081)).await.unwrap(),
+                    ],
+                    ..Default::default()
+                },
+                sender,
+            },
+        );
+        let check_result = CommonCheckRequest::new()
+            .check_before_request(
+                mapping_key.clone(),
+                HeaderMap::new(),
+                Uri::from_static("https://b.example.com/get"),
+                SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080),
+                Some(TlsConnectionInfo {
+                    server_name: Some(String::from("a.example.com")),
+                    client_identity: None,
+                }),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(check_result.request_path, "http://127.0.0.1:8081/get");
+    }
 }
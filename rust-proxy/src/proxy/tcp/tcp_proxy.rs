@@ -1,4 +1,5 @@
 use crate::configuration_service::app_config_service::GLOBAL_CONFIG_MAPPING;
+use crate::maintenance::endpoint_maintenance::track_in_flight;
 use futures::FutureExt;
 use http::HeaderMap;
 use std::net::SocketAddr;
@@ -43,6 +44,7 @@ impl TcpProxy {
 
 async fn transfer(mut inbound: TcpStream, mapping_key: String) -> Result<(), anyhow::Error> {
     let proxy_addr = get_route_cluster(mapping_key).await?;
+    let _in_flight_guard = track_in_flight(proxy_addr.as_str());
     let mut outbound = TcpStream::connect(proxy_addr)
         .await
         .map_err(|err| anyhow!(err.to_string()))?;
@@ -72,16 +74,12 @@ async fn check(mapping_key: String, remote_addr: SocketAddr) -> Result<bool, any
         .get(&mapping_key)
         .ok_or("Can not get apiservice from global_mapping")
         .map_err(|err| anyhow!(err.to_string()))?;
-    let service_config = &value.service_config.routes.clone();
-    let service_config_clone = service_config.clone();
-    if service_config_clone.is_empty() {
-        return Err(anyhow!("The len of routes is 0"));
-    }
-    let route = service_config_clone.first().unwrap();
-    let is_allowed = route
-        .clone()
-        .is_allowed(remote_addr.ip().to_string(), None)
-        .await?;
+    let route = value
+        .service_config
+        .routes
+        .first()
+        .ok_or(anyhow!("The len of routes is 0"))?;
+    let is_allowed = route.is_allowed(remote_addr.ip().to_string(), None).await?;
     Ok(is_allowed)
 }
 async fn get_route_cluster(mapping_key: String) -> Result<String, anyhow::Error> {
@@ -89,13 +87,16 @@ async fn get_route_cluster(mapping_key: String) -> Result<String, anyhow::Error>
         .get(&mapping_key)
         .ok_or("Can not get apiservice from global_mapping")
         .map_err(|err| anyhow!(err.to_string()))?;
-    let service_config = &value.service_config.routes.clone();
-    let service_config_clone = service_config.clone();
-    if service_config_clone.is_empty() {
-        return Err(anyhow!("The len of routes is 0"));
-    }
-    let mut route = service_config_clone.first().unwrap().route_cluster.clone();
-    route.get_route(HeaderMap::new()).await.map(|s| s.endpoint)
+    let route = value
+        .service_config
+        .routes
+        .first()
+        .ok_or(anyhow!("The len of routes is 0"))?;
+    route
+        .route_cluster
+        .get_route(HeaderMap::new())
+        .await
+        .map(|s| s.endpoint)
 }
 
 #[cfg(test)]
@@ -107,17 +108,18 @@ mod tests {
     use crate::vojo::allow_deny_ip::AllowType;
     use crate::vojo::api_service_manager::ApiServiceManager;
     use crate::vojo::app_config::ApiService;
+    use crate::vojo::app_config::AtomicLivenessStatus;
     use crate::vojo::app_config::LivenessStatus;
     use crate::vojo::app_config::Matcher;
     use crate::vojo::app_config::{Route, ServiceConfig};
     use crate::vojo::route::AnomalyDetectionStatus;
+    use crate::vojo::route::{AtomicAliveStatus, AtomicAnomalyDetectionStatus};
     use crate::vojo::route::{BaseRoute, LoadbalancerStrategy, RandomBaseRoute, RandomRoute};
     use lazy_static::lazy_static;
     use std::net::TcpListener;
     use std::net::{IpAddr, Ipv4Addr};
     use std::sync::Arc;
     use std::{thread, time, vec};
-    use tokio::sync::RwLock;
 
     use tokio::runtime::{Builder, Runtime};
 
@@ -167,10 +169,12 @@ mod tests {
                 base_route: BaseRoute {
                     endpoint: String::from("httpbin.org:80"),
                     try_file: None,
-                    is_alive: Arc::new(RwLock::new(None)),
-                    anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                        consecutive_5xx: 100,
-                    })),
+                    is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                    anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                        AnomalyDetectionStatus {
+                            consecutive_5xx: 100,
+                        },
+                    )),
                 },
             }],
         });
@@ -181,6 +185,12 @@ mod tests {
                 sender,
                 service_config: ServiceConfig {
                     key_str: None,
+                    key_passphrase: None,
+                    certificates: vec![],
+                    client_auth: None,
+                    tls_policy: None,
+                    acme: None,
+                    ocsp_stapling: None,
                     server_type: crate::vojo::app_config::ServiceType::Tcp,
                     cert_str: None,
                     routes: vec![Route {
@@ -191,12 +201,18 @@ mod tests {
                         allow_deny_list: None,
                         authentication: None,
                         ratelimit: None,
+                        server_names: None,
+                        client_identities: None,
+                        upstream_tls: None,
+                        grpc_web: None,
+                        grpc_json_transcoder: None,
+                        grpc_matcher: None,
                         health_check: None,
                         anomaly_detection: None,
                         rewrite_headers: None,
 
                         liveness_config: None,
-                        liveness_status: Arc::new(RwLock::new(LivenessStatus {
+                        liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                             current_liveness_count: 0,
                         })),
                     }],
@@ -223,10 +239,12 @@ mod tests {
                 base_route: BaseRoute {
                     endpoint: String::from("httpbin.org:80"),
                     try_file: None,
-                    is_alive: Arc::new(RwLock::new(None)),
-                    anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                        consecutive_5xx: 100,
-                    })),
+                    is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                    anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                        AnomalyDetectionStatus {
+                            consecutive_5xx: 100,
+                        },
+                    )),
                 },
             }],
         });
@@ -236,6 +254,12 @@ mod tests {
             sender,
             service_config: ServiceConfig {
                 key_str: None,
+                key_passphrase: None,
+                certificates: vec![],
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
                 server_type: crate::vojo::app_config::ServiceType::Tcp,
                 cert_str: None,
                 routes: vec![Route {
@@ -252,11 +276,17 @@ mod tests {
                     }]),
                     authentication: None,
                     ratelimit: None,
+                    server_names: None,
+                    client_identities: None,
+                    upstream_tls: None,
+                    grpc_web: None,
+                    grpc_json_transcoder: None,
+                    grpc_matcher: None,
                     health_check: None,
                     rewrite_headers: None,
 
                     anomaly_detection: None,
-                    liveness_status: Arc::new(RwLock::new(LivenessStatus {
+                    liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                         current_liveness_count: 0,
                     })),
                     liveness_config: None,
@@ -282,10 +312,12 @@ mod tests {
                 base_route: BaseRoute {
                     endpoint: String::from("httpbin.org:80"),
                     try_file: None,
-                    is_alive: Arc::new(RwLock::new(None)),
-                    anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                        consecutive_5xx: 100,
-                    })),
+                    is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                    anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                        AnomalyDetectionStatus {
+                            consecutive_5xx: 100,
+                        },
+                    )),
                 },
             }],
         });
@@ -295,6 +327,12 @@ mod tests {
             sender,
             service_config: ServiceConfig {
                 key_str: None,
+                key_passphrase: None,
+                certificates: vec![],
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
                 server_type: crate::vojo::app_config::ServiceType::Tcp,
                 cert_str: None,
                 routes: vec![Route {
@@ -312,10 +350,16 @@ mod tests {
                     authentication: None,
                     health_check: None,
                     ratelimit: None,
+                    server_names: None,
+                    client_identities: None,
+                    upstream_tls: None,
+                    grpc_web: None,
+                    grpc_json_transcoder: None,
+                    grpc_matcher: None,
                     anomaly_detection: None,
                     rewrite_headers: None,
                     liveness_config: None,
-                    liveness_status: Arc::new(RwLock::new(LivenessStatus {
+                    liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                         current_liveness_count: 0,
                     })),
                 }],
@@ -0,0 +1,355 @@
+use crate::proxy::certificate_resolver::{parse_ca_certificates, parse_certified_key};
+use crate::vojo::app_config::UpstreamTlsConfig;
+use hyper::client::HttpConnector;
+use hyper::Client;
+use hyper_rustls::HttpsConnector;
+use rustls::client::{ResolvesClientCert, ServerCertVerified, ServerCertVerifier, WebPkiVerifier};
+use rustls::sign::CertifiedKey;
+use rustls::{OwnedTrustAnchor, RootCertStore};
+use std::fmt;
+use std::sync::Arc;
+use std::time::SystemTime;
+pub const ALPN_H2: &str = "h2";
+pub const ALPN_HTTP1: &str = "http/1.1";
+/**
+*Accept any certificate of the upstream,which is only used if the insecure_skip_verify is set.
+*/
+struct InsecureServerCertVerifier;
+impl ServerCertVerifier for InsecureServerCertVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+struct ClientCertResolver {
+    certified_key: Arc<CertifiedKey>,
+}
+impl ResolvesClientCert for ClientCertResolver {
+    fn resolve(
+        &self,
+        _acceptable_issuers: &[&[u8]],
+        _sigschemes: &[rustls::SignatureScheme],
+    ) -> Option<Arc<CertifiedKey>> {
+        Some(self.certified_key.clone())
+    }
+    fn has_certs(&self) -> bool {
+        true
+    }
+}
+/**
+*The clients which are built from the upstream tls settings of the route.The https client keeps
+ the connection pool of the route,and the client config is used by the grpc proxy to set up the
+ tls connections itself.
+*/
+#[derive(Clone)]
+pub struct UpstreamTls {
+    pub config: UpstreamTlsConfig,
+    pub default_server_name: Option<String>,
+    pub client_config: Arc<rustls::ClientConfig>,
+    pub https_client: Client<HttpsConnector<HttpConnector>>,
+}
+impl fmt::Debug for UpstreamTls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpstreamTls")
+            .field("server_name", &self.config.server_name)
+            .field("default_server_name", &self.default_server_name)
+            .field("insecure_skip_verify", &self.config.insecure_skip_verify)
+            .field("alpn_protocols", &self.config.alpn_protocols)
+            .finish()
+    }
+}
+impl UpstreamTls {
+    pub fn new(config: UpstreamTlsConfig) -> Result<Self, anyhow::Error> {
+        UpstreamTls::with_default_server_name(config, None)
+    }
+    /**
+    *The default server name is used if the server name is not configured,which is the host name
+    of the dns route whose endpoints have been resolved to the ip addresses.
+    */
+    pub fn with_default_server_name(
+        config: UpstreamTlsConfig,
+        default_server_name: Option<String>,
+    ) -> Result<Self, anyhow::Error> {
+        for item in config.alpn_protocols.iter() {
+            if item != ALPN_H2 && item != ALPN_HTTP1 {
+                return Err(anyhow!("The alpn protocol {} is not supported.", item));
+            }
+        }
+        let client_config = new_client_config(&config)?;
+        let builder = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_tls_config(client_config.clone())
+            .https_or_http();
+        let builder = match config.server_name.as_ref().or(default_server_name.as_ref()) {
+            Some(server_name) => builder.with_server_name(server_name.clone()),
+            None => builder,
+        };
+        let enable_h2 = config.alpn_protocols.iter().any(|item| item == ALPN_H2);
+        let enable_http1 = config.alpn_protocols.is_empty()
+            || config.alpn_protocols.iter().any(|item| item == ALPN_HTTP1);
+        let https = match (enable_http1, enable_h2) {
+            (true, true) => builder.enable_http1().enable_http2().build(),
+            (false, true) => builder.enable_http2().build(),
+            _ => builder.enable_http1().build(),
+        };
+        let https_client = Client::builder().build::<_, hyper::Body>(https);
+
+        let mut client_config = client_config;
+        client_config.alpn_protocols = if config.alpn_protocols.is_empty() {
+            vec![ALPN_H2.as_bytes().to_vec(), ALPN_HTTP1.as_bytes().to_vec()]
+        } else {
+            config
+                .alpn_protocols
+                .iter()
+                .map(|item| item.as_bytes().to_vec())
+                .collect()
+        };
+        Ok(UpstreamTls {
+            config,
+            default_server_name,
+            client_config: Arc::new(client_config),
+            https_client,
+        })
+    }
+    /**
+     *The server name for the handshake,which is the overridden one or the host of the upstream.
+     */
+    pub fn server_name(&self, host: &str) -> Result<rustls::ServerName, anyhow::Error> {
+        let server_name = self
+            .config
+            .server_name
+            .as_deref()
+            .or(self.default_server_name.as_deref())
+            .unwrap_or(host);
+        rustls::ServerName::try_from(server_name).map_err(|e| {
+            anyhow!(
+                "The server name {} is invalid,the error is {}.",
+                server_name,
+                e
+            )
+        })
+    }
+}
+fn new_client_config(config: &UpstreamTlsConfig) -> Result<rustls::ClientConfig, anyhow::Error> {
+    let verifier: Arc<dyn ServerCertVerifier> = if config.insecure_skip_verify {
+        warn!("The upstream certificate will not be verified,which is only for development!");
+        Arc::new(InsecureServerCertVerifier)
+    } else if let Some(ca_str) = &config.ca_str {
+        Arc::new(WebPkiVerifier::new(parse_ca_certificates(ca_str)?, None))
+    } else {
+        let mut root_cert_store = RootCertStore::empty();
+        root_cert_store.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(
+            |ta| {
+                OwnedTrustAnchor::from_subject_spki_name_constraints(
+                    ta.subject,
+                    ta.spki,
+                    ta.name_constraints,
+                )
+            },
+        ));
+        Arc::new(WebPkiVerifier::new(root_cert_store, None))
+    };
+    let builder = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(verifier);
+    let client_config = match (&config.cert_str, &config.key_str) {
+        (Some(cert_str), Some(key_str)) => {
+            builder.with_client_cert_resolver(Arc::new(ClientCertResolver {
+                certified_key: Arc::new(parse_certified_key(cert_str, key_str, None)?),
+            }))
+        }
+        (None, None) => builder.with_no_client_auth(),
+        _ => {
+            return Err(anyhow!(
+                "The cert_str and the key_str of the upstream tls should be set together."
+            ))
+        }
+    };
+    Ok(client_config)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::service::service_fn;
+    use hyper::{Body, Request, Response};
+    use rustls::server::AllowAnyAuthenticatedClient;
+    use std::convert::Infallible;
+    use std::env;
+    use std::io::BufReader;
+    use tokio::net::TcpListener;
+    use tokio_rustls::TlsAcceptor;
+    fn read_config_file(name: &str) -> String {
+        let path = env::current_dir().unwrap().join("config").join(name);
+        std::fs::read_to_string(path).unwrap()
+    }
+    async fn start_upstream(port: u16) {
+        let cert_str = read_config_file("test_upstream_cert.pem");
+        let certs = rustls_pemfile::certs(&mut BufReader::new(cert_str.as_bytes()))
+            .unwrap()
+            .into_iter()
+            .map(rustls::Certificate)
+            .collect();
+        let key_str = read_config_file("test_upstream_key.pem");
+        let key = rustls_pemfile::pkcs8_private_keys(&mut BufReader::new(key_str.as_bytes()))
+            .unwrap()
+            .remove(0);
+        let client_ca =
+            parse_ca_certificates(&read_config_file("test_client_ca_cert.pem")).unwrap();
+        let server_config = rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_client_cert_verifier(AllowAnyAuthenticatedClient::new(client_ca))
+            .with_single_cert(certs, rustls::PrivateKey(key))
+            .unwrap();
+        let tls_acceptor = TlsAcceptor::from(Arc::new(server_config));
+        let listener = TcpListener::bind(format!("127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        tokio::spawn(async move {
+            while let Ok((tcp_stream, _)) = listener.accept().await {
+                let tls_acceptor = tls_acceptor.clone();
+                tokio::spawn(async move {
+                    if let Ok(tls_stream) = tls_acceptor.accept(tcp_stream).await {
+                        let _ = hyper::server::conn::Http::new()
+                            .serve_connection(
+                                tls_stream,
+                                service_fn(|_| async {
+                                    Ok::<_, Infallible>(Response::new(Body::from("upstream")))
+                                }),
+                            )
+                            .await;
+                    }
+                });
+            }
+        });
+    }
+    async fn request_upstream(port: u16, config: UpstreamTlsConfig) -> Result<(), anyhow::Error> {
+        let upstream_tls = UpstreamTls::new(config)?;
+        let request = Request::get(format!("https://127.0.0.1:{}/", port)).body(Body::empty())?;
+        let response = upstream_tls.https_client.request(request).await?;
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        assert_eq!(body, "upstream");
+        Ok(())
+    }
+    #[tokio::test]
+    async fn test_upstream_tls_request_ok() {
+        start_upstream(4455).await;
+        let config = UpstreamTlsConfig {
+            ca_str: Some(read_config_file("test_upstream_ca_cert.pem")),
+            cert_str: Some(read_config_file("test_client_cert.pem")),
+            key_str: Some(read_config_file("test_client_key.pem")),
+            server_name: Some(String::from("backend.internal")),
+            ..Default::default()
+        };
+        assert!(request_upstream(4455, config.clone()).await.is_ok());
+        let without_server_name = UpstreamTlsConfig {
+            server_name: None,
+            ..config.clone()
+        };
+        assert!(request_upstream(4455, without_server_name).await.is_err());
+        let without_client_certificate = UpstreamTlsConfig {
+            cert_str: None,
+            key_str: None,
+            ..config.clone()
+        };
+        assert!(request_upstream(4455, without_client_certificate)
+            .await
+            .is_err());
+        let insecure_skip_verify = UpstreamTlsConfig {
+            ca_str: None,
+            server_name: None,
+            insecure_skip_verify: true,
+            ..config
+        };
+        assert!(request_upstream(4455, insecure_skip_verify).await.is_ok());
+    }
+    #[tokio::test]
+    async fn test_upstream_tls_default_server_name_ok() {
+        start_upstream(4479).await;
+        let config = UpstreamTlsConfig {
+            ca_str: Some(read_config_file("test_upstream_ca_cert.pem")),
+            cert_str: Some(read_config_file("test_client_cert.pem")),
+            key_str: Some(read_config_file("test_client_key.pem")),
+            ..Default::default()
+        };
+        let upstream_tls = UpstreamTls::with_default_server_name(
+            config.clone(),
+            Some(String::from("backend.internal")),
+        )
+        .unwrap();
+        let request = Request::get("https://127.0.0.1:4479/")
+            .body(Body::empty())
+            .unwrap();
+        let response = upstream_tls.https_client.request(request).await.unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, "upstream");
+        assert_eq!(
+            upstream_tls.server_name("127.0.0.1").unwrap(),
+            rustls::ServerName::try_from("backend.internal").unwrap()
+        );
+        let upstream_tls = UpstreamTls::with_default_server_name(
+            UpstreamTlsConfig {
+                server_name: Some(String::from("override.internal")),
+                ..config
+            },
+            Some(String::from("backend.internal")),
+        )
+        .unwrap();
+        assert_eq!(
+            upstream_tls.server_name("127.0.0.1").unwrap(),
+            rustls::ServerName::try_from("override.internal").unwrap()
+        );
+    }
+    #[test]
+    fn test_upstream_tls_new_ok() {
+        let upstream_tls = UpstreamTls::new(UpstreamTlsConfig {
+            ca_str: Some(read_config_file("test_cert.pem")),
+            cert_str: Some(read_config_file("test_client_cert.pem")),
+            key_str: Some(read_config_file("test_client_key.pem")),
+            server_name: Some(String::from("backend.internal")),
+            insecure_skip_verify: false,
+            alpn_protocols: vec![String::from("http/1.1")],
+        })
+        .unwrap();
+        assert_eq!(
+            upstream_tls.client_config.alpn_protocols,
+            vec![b"http/1.1".to_vec()]
+        );
+        assert!(upstream_tls
+            .client_config
+            .client_auth_cert_resolver
+            .has_certs());
+        assert_eq!(
+            upstream_tls.server_name("127.0.0.1").unwrap(),
+            rustls::ServerName::try_from("backend.internal").unwrap()
+        );
+        let upstream_tls = UpstreamTls::new(UpstreamTlsConfig::default()).unwrap();
+        assert_eq!(
+            upstream_tls.client_config.alpn_protocols,
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+        );
+    }
+    #[test]
+    fn test_upstream_tls_new_error() {
+        let result = UpstreamTls::new(UpstreamTlsConfig {
+            cert_str: Some(read_config_file("test_client_cert.pem")),
+            ..Default::default()
+        });
+        assert!(result.is_err());
+        let result = UpstreamTls::new(UpstreamTlsConfig {
+            ca_str: Some(String::from("test")),
+            ..Default::default()
+        });
+        assert!(result.is_err());
+        let result = UpstreamTls::new(UpstreamTlsConfig {
+            alpn_protocols: vec![String::from("h3")],
+            ..Default::default()
+        });
+        assert!(result.is_err());
+    }
+}
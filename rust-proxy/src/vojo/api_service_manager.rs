@@ -1,7 +1,40 @@
 use crate::vojo::app_config::ServiceConfig;
+use arc_swap::ArcSwap;
+use std::collections::HashMap;
+use std::sync::Arc;
 use tokio::sync::mpsc;
 #[derive(Clone)]
 pub struct ApiServiceManager {
     pub service_config: ServiceConfig,
     pub sender: mpsc::Sender<()>,
 }
+/**
+*The snapshot of the api service managers keyed by the mapping key.The request reads the
+ current snapshot without any lock,and the control plane swaps a new snapshot atomically.
+*/
+#[derive(Default)]
+pub struct ConfigMapping {
+    snapshot: ArcSwap<HashMap<String, Arc<ApiServiceManager>>>,
+}
+impl ConfigMapping {
+    pub fn load(&self) -> Arc<HashMap<String, Arc<ApiServiceManager>>> {
+        self.snapshot.load_full()
+    }
+    pub fn store(&self, mapping: HashMap<String, Arc<ApiServiceManager>>) {
+        self.snapshot.store(Arc::new(mapping));
+    }
+    pub fn get(&self, key: &str) -> Option<Arc<ApiServiceManager>> {
+        self.snapshot.load().get(key).cloned()
+    }
+}
+#[cfg(test)]
+impl ConfigMapping {
+    pub fn insert(&self, key: String, api_service_manager: ApiServiceManager) {
+        let api_service_manager = Arc::new(api_service_manager);
+        self.snapshot.rcu(|mapping| {
+            let mut mapping = HashMap::clone(mapping);
+            mapping.insert(key.clone(), api_service_manager.clone());
+            mapping
+        });
+    }
+}
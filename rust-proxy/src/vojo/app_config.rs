@@ -1,11 +1,16 @@
 use super::allow_deny_ip::AllowResult;
 use super::app_config_vistor::ApiServiceVistor;
 use super::app_config_vistor::ServiceConfigVistor;
+use crate::proxy::http1::grpc_json_transcoder::GrpcJsonTranscoder;
+use crate::proxy::http2::grpc_proxy::split_grpc_path;
+use crate::proxy::upstream_tls::UpstreamTls;
 use crate::vojo::allow_deny_ip::AllowDenyObject;
 use crate::vojo::anomaly_detection::AnomalyDetectionType;
 use crate::vojo::app_config_vistor::from_loadbalancer_strategy_vistor;
+use crate::vojo::app_config_vistor::LoadbalancerStrategyVistor;
 use crate::vojo::app_config_vistor::RouteVistor;
 use crate::vojo::authentication::AuthenticationStrategy;
+use crate::vojo::client_identity::ClientIdentity;
 use crate::vojo::health_check::HealthCheckType;
 use crate::vojo::rate_limit::RatelimitStrategy;
 use crate::vojo::route::LoadbalancerStrategy;
@@ -14,8 +19,9 @@ use http::HeaderValue;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicI32, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use url::{Host, Url};
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct Matcher {
     pub prefix: String,
@@ -29,6 +35,28 @@ pub struct LivenessConfig {
 pub struct LivenessStatus {
     pub current_liveness_count: i32,
 }
+#[derive(Debug, Default)]
+pub struct AtomicLivenessStatus {
+    pub current_liveness_count: AtomicI32,
+}
+impl AtomicLivenessStatus {
+    pub fn load(&self) -> LivenessStatus {
+        LivenessStatus {
+            current_liveness_count: self.current_liveness_count.load(Ordering::SeqCst),
+        }
+    }
+    pub fn store(&self, liveness_status: LivenessStatus) {
+        self.current_liveness_count
+            .store(liveness_status.current_liveness_count, Ordering::SeqCst);
+    }
+}
+impl From<LivenessStatus> for AtomicLivenessStatus {
+    fn from(liveness_status: LivenessStatus) -> Self {
+        AtomicLivenessStatus {
+            current_liveness_count: AtomicI32::new(liveness_status.current_liveness_count),
+        }
+    }
+}
 #[derive(Debug, Clone)]
 pub struct Route {
     pub route_id: String,
@@ -37,11 +65,17 @@ pub struct Route {
     pub allow_deny_list: Option<Vec<AllowDenyObject>>,
     pub authentication: Option<Box<dyn AuthenticationStrategy>>,
     pub anomaly_detection: Option<AnomalyDetectionType>,
-    pub liveness_status: Arc<RwLock<LivenessStatus>>,
+    pub liveness_status: Arc<AtomicLivenessStatus>,
     pub rewrite_headers: Option<HashMap<String, String>>,
     pub liveness_config: Option<LivenessConfig>,
     pub health_check: Option<HealthCheckType>,
     pub ratelimit: Option<Box<dyn RatelimitStrategy>>,
+    pub server_names: Option<Vec<String>>,
+    pub client_identities: Option<Vec<String>>,
+    pub upstream_tls: Option<UpstreamTls>,
+    pub grpc_web: Option<GrpcWebConfig>,
+    pub grpc_json_transcoder: Option<GrpcJsonTranscoder>,
+    pub grpc_matcher: Option<GrpcMatcher>,
     pub route_cluster: LoadbalancerStrategy,
 }
 impl Route {
@@ -68,6 +102,7 @@ impl Route {
         });
 
         let count = cloned_cluster.get_routes_len() as i32;
+        let dns_server_name = dns_server_name(&route_vistor.route_cluster);
 
         Ok(Route {
             route_id: route_vistor.route_id,
@@ -76,13 +111,28 @@ impl Route {
             allow_deny_list: route_vistor.allow_deny_list,
             authentication: route_vistor.authentication,
             anomaly_detection: route_vistor.anomaly_detection,
-            liveness_status: Arc::new(RwLock::new(LivenessStatus {
+            liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                 current_liveness_count: count,
             })),
             rewrite_headers: route_vistor.rewrite_headers,
             liveness_config: route_vistor.liveness_config,
             health_check: route_vistor.health_check,
             ratelimit: route_vistor.ratelimit,
+            server_names: route_vistor.server_names,
+            client_identities: route_vistor.client_identities,
+            upstream_tls: match (route_vistor.upstream_tls, dns_server_name) {
+                (None, None) => None,
+                (upstream_tls, dns_server_name) => Some(UpstreamTls::with_default_server_name(
+                    upstream_tls.unwrap_or_default(),
+                    dns_server_name,
+                )?),
+            },
+            grpc_web: route_vistor.grpc_web,
+            grpc_json_transcoder: route_vistor
+                .grpc_json_transcoder
+                .map(GrpcJsonTranscoder::new)
+                .transpose()?,
+            grpc_matcher: route_vistor.grpc_matcher,
             route_cluster: from_loadbalancer_strategy_vistor(route_vistor.route_cluster),
         })
     }
@@ -95,8 +145,8 @@ impl Route {
         headers_option: Option<HeaderMap<HeaderValue>>,
     ) -> Result<Option<String>, anyhow::Error> {
         let matcher = self
-            .clone()
             .matcher
+            .as_ref()
             .ok_or("The matcher counld not be none for http")
             .map_err(|err| anyhow!(err))?;
 
@@ -126,6 +176,52 @@ impl Route {
         }
         Ok(Some(final_path))
     }
+    /**
+    *The route without the grpc matcher matches all the requests,otherwise the path must be the
+    /package.Service/Method of the matched service and method.
+    */
+    pub fn is_grpc_matched(&self, path: &str) -> bool {
+        let grpc_matcher = match &self.grpc_matcher {
+            Some(grpc_matcher) => grpc_matcher,
+            None => return true,
+        };
+        match split_grpc_path(path) {
+            Some((service, method)) => {
+                grpc_matcher.service == service
+                    && grpc_matcher
+                        .method
+                        .as_ref()
+                        .is_none_or(|item| item == method)
+            }
+            None => false,
+        }
+    }
+    /**
+    *The route without the server names matches all the requests,otherwise the server name
+    of the tls handshake must match one of them.
+    */
+    pub fn is_server_name_matched(&self, server_name_option: Option<&str>) -> bool {
+        match (&self.server_names, server_name_option) {
+            (None, _) => true,
+            (Some(server_names), Some(server_name)) => server_names
+                .iter()
+                .any(|item| server_name_is_matched(item, server_name)),
+            (Some(_), None) => false,
+        }
+    }
+    /**
+    *The route without the client identities allows all the clients,otherwise the verified
+    client certificate must match one of them.
+    */
+    pub fn is_client_identity_allowed(&self, client_identity: Option<&ClientIdentity>) -> bool {
+        match (&self.client_identities, client_identity) {
+            (None, _) => true,
+            (Some(client_identities), Some(client_identity)) => client_identities
+                .iter()
+                .any(|item| client_identity.is_matched(item)),
+            (Some(_), None) => false,
+        }
+    }
     pub async fn is_allowed(
         &self,
         ip: String,
@@ -151,6 +247,35 @@ impl Route {
         Ok(is_allowed)
     }
 }
+/**
+*The host name of the https dns route,which is kept as the server name of the upstream tls after
+ the endpoints have been resolved to the ip addresses.
+*/
+fn dns_server_name(route_cluster: &LoadbalancerStrategyVistor) -> Option<String> {
+    let dns_route = match route_cluster {
+        LoadbalancerStrategyVistor::DnsRoute(dns_route) => dns_route,
+        _ => return None,
+    };
+    let url = Url::parse(&dns_route.endpoint).ok()?;
+    match url.host() {
+        Some(Host::Domain(domain)) if url.scheme() == "https" => Some(domain.to_string()),
+        _ => None,
+    }
+}
+/**
+*Match the server name against the pattern case insensitively.The pattern like *.example.com
+ matches exactly one label before example.com.
+*/
+pub fn server_name_is_matched(pattern: &str, server_name: &str) -> bool {
+    let pattern = pattern.to_ascii_lowercase();
+    let server_name = server_name.to_ascii_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => server_name
+            .split_once('.')
+            .is_some_and(|(label, rest)| !label.is_empty() && rest == suffix),
+        None => pattern == server_name,
+    }
+}
 pub fn ip_is_allowed(
     allow_deny_list: Option<Vec<AllowDenyObject>>,
     ip: String,
@@ -190,11 +315,146 @@ pub enum ServiceType {
     Http2,
     Http2Tls,
 }
+/**
+*The certificate which is served to the handshakes whose server name matches one of the server
+ names.The key_passphrase decrypts the encrypted private key.
+*/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct CertificateConfig {
+    pub server_names: Vec<String>,
+    pub cert_str: String,
+    pub key_str: String,
+    pub key_passphrase: Option<String>,
+}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum ClientAuthMode {
+    #[default]
+    Required,
+    Optional,
+}
+/**
+*Verify the client certificates against the ca bundle.The verified identity is forwarded to the
+ upstreams in the identity header,and the header from the client is always removed.
+*/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct ClientAuthConfig {
+    pub ca_str: String,
+    #[serde(default)]
+    pub mode: ClientAuthMode,
+    pub identity_header: Option<String>,
+}
+/**
+*The tls settings to the upstreams of the route.The ca_str replaces the webpki roots,the cert_str
+ and the key_str are presented for the upstream mtls,and the server_name overrides the sni and the
+ name verified in the upstream certificate.The alpn protocols could be h2 and http/1.1.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub struct UpstreamTlsConfig {
+    pub ca_str: Option<String>,
+    pub cert_str: Option<String>,
+    pub key_str: Option<String>,
+    pub server_name: Option<String>,
+    #[serde(default)]
+    pub insecure_skip_verify: bool,
+    #[serde(default)]
+    pub alpn_protocols: Vec<String>,
+}
+/**
+*The grpc-web requests of the browsers are translated into the grpc ones on the route.The
+ allowed_origins are checked in the cors requests and all origins are allowed if it is empty,and
+ the max_age_seconds is the lifetime of the preflight result which defaults to 86400.The request
+ body is limited by the max_body_size which defaults to 4MiB,and the upstream should respond in
+ the timeout_seconds which defaults to 10.
+*/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct GrpcWebConfig {
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    pub max_age_seconds: Option<u64>,
+    pub max_body_size: Option<usize>,
+    pub timeout_seconds: Option<u64>,
+}
+/**
+*The grpc requests are routed by the full name of the service like helloworld.Greeter,and all
+ methods of the service are matched if the method is missing.
+*/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct GrpcMatcher {
+    pub service: String,
+    pub method: Option<String>,
+}
+/**
+*The json requests of the route are transcoded into the grpc ones by the google.api.http rules.The
+ descriptor set is read from the proto_descriptor file or the base64 proto_descriptor_bin,and the
+ services are the full names like helloworld.Greeter which default to all services of the set.The
+ request body and the upstream response are limited by the max_message_size which defaults to
+ 4MiB,and the upstream should respond in the timeout_seconds which defaults to 10.
+*/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct GrpcJsonTranscoderConfig {
+    pub proto_descriptor: Option<String>,
+    pub proto_descriptor_bin: Option<String>,
+    #[serde(default)]
+    pub services: Vec<String>,
+    pub max_message_size: Option<usize>,
+    pub timeout_seconds: Option<u64>,
+}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TlsVersion {
+    #[serde(rename = "TLSv1.2")]
+    Tls12,
+    #[serde(rename = "TLSv1.3")]
+    Tls13,
+}
+/**
+*The tls policy of the listener.Both TLSv1.2 and TLSv1.3 are enabled if the versions are missing,
+ and the cipher suites are the names like TLS13_AES_256_GCM_SHA384 which default to the safe ones
+ of rustls.The session cache keeps 256 sessions by default and 0 disables it.
+*/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct TlsPolicyConfig {
+    pub min_version: Option<TlsVersion>,
+    pub max_version: Option<TlsVersion>,
+    #[serde(default)]
+    pub cipher_suites: Vec<String>,
+    #[serde(default)]
+    pub alpn_protocols: Vec<String>,
+    #[serde(default)]
+    pub session_tickets: bool,
+    pub session_cache_size: Option<usize>,
+}
+/**
+*The certificate of the domains is obtained from the acme directory,which defaults to the lets
+ encrypt one,and it is renewed before it expires.The http-01 challenges are answered on the http
+ listeners,and the directory_tls could trust the ca of a private directory like pebble.
+*/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct AcmeConfig {
+    pub domains: Vec<String>,
+    pub contact_email: String,
+    pub directory_url: Option<String>,
+    pub directory_tls: Option<UpstreamTlsConfig>,
+    pub renew_before_days: Option<i64>,
+}
+/**
+*The ocsp responses of the certificates are fetched from the responder in the certificate,or the
+ responder_url if it is set,and they are stapled in the handshakes.
+*/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct OcspStaplingConfig {
+    pub responder_url: Option<String>,
+}
 #[derive(Debug, Clone, Default)]
 pub struct ServiceConfig {
     pub server_type: ServiceType,
     pub cert_str: Option<String>,
     pub key_str: Option<String>,
+    pub key_passphrase: Option<String>,
+    pub certificates: Vec<CertificateConfig>,
+    pub client_auth: Option<ClientAuthConfig>,
+    pub tls_policy: Option<TlsPolicyConfig>,
+    pub acme: Option<AcmeConfig>,
+    pub ocsp_stapling: Option<OcspStaplingConfig>,
     pub routes: Vec<Route>,
 }
 impl ServiceConfig {
@@ -207,6 +467,12 @@ impl ServiceConfig {
             server_type: service_config_vistor.server_type,
             cert_str: service_config_vistor.cert_str,
             key_str: service_config_vistor.key_str,
+            key_passphrase: service_config_vistor.key_passphrase,
+            certificates: service_config_vistor.certificates,
+            client_auth: service_config_vistor.client_auth,
+            tls_policy: service_config_vistor.tls_policy,
+            acme: service_config_vistor.acme,
+            ocsp_stapling: service_config_vistor.ocsp_stapling,
             routes,
         })
     }
@@ -232,12 +498,16 @@ pub struct StaticConifg {
     pub access_log: Option<String>,
     pub database_url: Option<String>,
     pub admin_port: String,
+    pub admin_bind_address: Option<String>,
+    pub admin_cors_origins: Option<Vec<String>>,
     pub config_file_path: Option<String>,
+    pub config_version_retention: Option<usize>,
 }
 #[derive(Debug, Clone, Default)]
 pub struct AppConfig {
     pub static_config: StaticConifg,
     pub api_service_config: Vec<ApiService>,
+    pub generation: u64,
 }
 #[cfg(test)]
 mod tests {
@@ -259,10 +529,13 @@ mod tests {
     use crate::vojo::health_check::{BaseHealthCheckParam, HttpHealthCheckParam};
     use crate::vojo::rate_limit::*;
     use crate::vojo::route::AnomalyDetectionStatus;
+    use crate::vojo::route::AtomicAliveStatus;
+    use crate::vojo::route::AtomicAnomalyDetectionStatus;
     use crate::vojo::route::BaseRoute;
 
     use crate::vojo::route::WeightBasedRoute;
     use crate::vojo::route::WeightRoute;
+    use arc_swap::ArcSwap;
     use dashmap::DashMap;
     use std::sync::atomic::AtomicIsize;
     use std::sync::Arc;
@@ -274,20 +547,22 @@ mod tests {
             host_name,
             route_id: get_uuid(),
             route_cluster: LoadbalancerStrategy::WeightBased(WeightBasedRoute {
-                routes: Arc::new(RwLock::new(vec![WeightRoute {
+                routes: Arc::new(ArcSwap::from_pointee(vec![WeightRoute {
                     base_route: BaseRoute {
                         endpoint: String::from("/"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     },
                     index: Arc::new(AtomicIsize::new(0)),
                     weight: 100,
                 }])),
             }),
-            liveness_status: Arc::new(RwLock::new(LivenessStatus {
+            liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                 current_liveness_count: 0,
             })),
             anomaly_detection: None,
@@ -297,6 +572,12 @@ mod tests {
             liveness_config: None,
             rewrite_headers: None,
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("/"),
                 prefix_rewrite: String::from("ssss"),
@@ -341,6 +622,64 @@ mod tests {
         assert!(allow_result.unwrap().is_some());
     }
     #[test]
+    fn test_server_name_is_matched_ok() {
+        let mut route = create_new_route_with_host_name(None);
+        assert!(route.is_server_name_matched(None));
+        route.server_names = Some(vec![
+            String::from("www.test.com"),
+            String::from("*.example.com"),
+        ]);
+        assert!(route.is_server_name_matched(Some("WWW.test.com")));
+        assert!(route.is_server_name_matched(Some("a.example.com")));
+        assert!(!route.is_server_name_matched(Some("a.b.example.com")));
+        assert!(!route.is_server_name_matched(Some("example.com")));
+        assert!(!route.is_server_name_matched(Some("test.com")));
+        assert!(!route.is_server_name_matched(None));
+    }
+    #[tokio::test]
+    async fn test_dns_route_server_name_ok() {
+        let new_route = |endpoint: &str| {
+            serde_json::from_value::<RouteVistor>(serde_json::json!({
+                "matcher": { "prefix": "/", "prefix_rewrite": "/" },
+                "route_cluster": { "type": "DnsRoute", "endpoint": endpoint }
+            }))
+            .unwrap()
+        };
+        let route = Route::from(new_route("https://backend.internal:8443/"))
+            .await
+            .unwrap();
+        assert_eq!(
+            route.upstream_tls.unwrap().server_name("10.0.0.1").unwrap(),
+            rustls::ServerName::try_from("backend.internal").unwrap()
+        );
+        let route = Route::from(new_route("http://backend.internal:8080/"))
+            .await
+            .unwrap();
+        assert!(route.upstream_tls.is_none());
+        let route = Route::from(new_route("https://10.0.0.1:8443/"))
+            .await
+            .unwrap();
+        assert!(route.upstream_tls.is_none());
+    }
+    #[test]
+    fn test_grpc_is_matched_ok() {
+        let mut route = create_new_route_with_host_name(None);
+        assert!(route.is_grpc_matched("/test"));
+        route.grpc_matcher = Some(GrpcMatcher {
+            service: String::from("helloworld.Greeter"),
+            method: None,
+        });
+        assert!(route.is_grpc_matched("/helloworld.Greeter/SayHello"));
+        assert!(!route.is_grpc_matched("/helloworld.Other/SayHello"));
+        assert!(!route.is_grpc_matched("/helloworld.Greeter"));
+        route.grpc_matcher = Some(GrpcMatcher {
+            service: String::from("helloworld.Greeter"),
+            method: Some(String::from("SayHello")),
+        });
+        assert!(route.is_grpc_matched("/helloworld.Greeter/SayHello"));
+        assert!(!route.is_grpc_matched("/helloworld.Greeter/SayGoodbye"));
+    }
+    #[test]
     fn test_serde_output_health_check() {
         let route = RouteVistor {
             host_name: None,
@@ -384,6 +723,12 @@ mod tests {
             }),
 
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -397,6 +742,12 @@ mod tests {
                 server_type: Default::default(),
                 cert_str: Default::default(),
                 key_str: Default::default(),
+                key_passphrase: None,
+                certificates: Default::default(),
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
             },
         };
         let t = vec![api_service];
@@ -434,6 +785,12 @@ mod tests {
             rewrite_headers: None,
 
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -447,6 +804,12 @@ mod tests {
                 server_type: Default::default(),
                 cert_str: Default::default(),
                 key_str: Default::default(),
+                key_passphrase: None,
+                certificates: Default::default(),
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
             },
         };
         let t = vec![api_service];
@@ -481,6 +844,12 @@ mod tests {
             allow_deny_list: None,
             authentication: None,
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             liveness_config: None,
             rewrite_headers: None,
 
@@ -497,6 +866,12 @@ mod tests {
                 server_type: Default::default(),
                 cert_str: Default::default(),
                 key_str: Default::default(),
+                key_passphrase: None,
+                certificates: Default::default(),
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
             },
         };
         let t = vec![api_service];
@@ -544,6 +919,12 @@ mod tests {
             authentication: None,
             health_check: None,
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -558,6 +939,12 @@ mod tests {
                 cert_str: Default::default(),
 
                 key_str: Default::default(),
+                key_passphrase: None,
+                certificates: Default::default(),
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
             },
         };
         let t = vec![api_service];
@@ -594,6 +981,12 @@ mod tests {
             rewrite_headers: None,
 
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -608,6 +1001,12 @@ mod tests {
                 server_type: Default::default(),
                 cert_str: Default::default(),
                 key_str: Default::default(),
+                key_passphrase: None,
+                certificates: Default::default(),
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
             },
         };
         let t = vec![api_service];
@@ -648,6 +1047,12 @@ mod tests {
             },
             authentication: Some(basic_auth),
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -661,6 +1066,12 @@ mod tests {
                 server_type: Default::default(),
                 cert_str: Default::default(),
                 key_str: Default::default(),
+                key_passphrase: None,
+                certificates: Default::default(),
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
             },
         };
         let t = vec![api_service];
@@ -700,6 +1111,12 @@ mod tests {
                 current_liveness_count: 0,
             },
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             authentication: Some(api_key_auth),
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
@@ -714,6 +1131,12 @@ mod tests {
                 server_type: Default::default(),
                 cert_str: Default::default(),
                 key_str: Default::default(),
+                key_passphrase: None,
+                certificates: Default::default(),
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
             },
         };
         let t = vec![api_service];
@@ -762,6 +1185,12 @@ mod tests {
 
             authentication: None,
             ratelimit: Some(ratelimit),
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -775,6 +1204,12 @@ mod tests {
                 server_type: Default::default(),
                 cert_str: Default::default(),
                 key_str: Default::default(),
+                key_passphrase: None,
+                certificates: Default::default(),
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
             },
         };
         let t = vec![api_service];
@@ -822,6 +1257,12 @@ mod tests {
             liveness_config: None,
 
             ratelimit: Some(ratelimit),
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -835,6 +1276,12 @@ mod tests {
                 server_type: Default::default(),
                 cert_str: Default::default(),
                 key_str: Default::default(),
+                key_passphrase: None,
+                certificates: Default::default(),
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
             },
         };
         let t = vec![api_service];
@@ -876,6 +1323,12 @@ mod tests {
                 current_liveness_count: 0,
             },
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -889,6 +1342,12 @@ mod tests {
                 server_type: Default::default(),
                 cert_str: Default::default(),
                 key_str: Default::default(),
+                key_passphrase: None,
+                certificates: Default::default(),
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
             },
         };
         let t = vec![api_service];
@@ -1,14 +1,23 @@
 use crate::vojo::allow_deny_ip::AllowDenyObject;
 use crate::vojo::anomaly_detection::AnomalyDetectionType;
+use crate::vojo::app_config::AcmeConfig;
 use crate::vojo::app_config::ApiService;
 use crate::vojo::app_config::AppConfig;
+use crate::vojo::app_config::CertificateConfig;
+use crate::vojo::app_config::ClientAuthConfig;
+use crate::vojo::app_config::GrpcJsonTranscoderConfig;
+use crate::vojo::app_config::GrpcMatcher;
+use crate::vojo::app_config::GrpcWebConfig;
 use crate::vojo::app_config::LivenessConfig;
 use crate::vojo::app_config::LivenessStatus;
 use crate::vojo::app_config::Matcher;
+use crate::vojo::app_config::OcspStaplingConfig;
 use crate::vojo::app_config::Route;
 use crate::vojo::app_config::ServiceConfig;
 use crate::vojo::app_config::ServiceType;
 use crate::vojo::app_config::StaticConifg;
+use crate::vojo::app_config::TlsPolicyConfig;
+use crate::vojo::app_config::UpstreamTlsConfig;
 use crate::vojo::authentication::AuthenticationStrategy;
 use crate::vojo::health_check::HealthCheckType;
 use crate::vojo::rate_limit::RatelimitStrategy;
@@ -17,8 +26,8 @@ use crate::vojo::route::BaseRoute;
 use crate::vojo::route::HeaderValueMappingType;
 use crate::vojo::route::LoadbalancerStrategy;
 use crate::vojo::route::{
-    HeaderBasedRoute, PollBaseRoute, PollRoute, RandomBaseRoute, RandomRoute, WeightBasedRoute,
-    WeightRoute,
+    DnsRecordType, DnsRoute, HeaderBasedRoute, PollBaseRoute, PollRoute, RandomBaseRoute,
+    RandomRoute, RegistryRoute, WeightBasedRoute, WeightRoute,
 };
 use std::collections::HashMap;
 
@@ -44,6 +53,13 @@ pub struct ServiceConfigVistor {
     pub server_type: ServiceType,
     pub cert_str: Option<String>,
     pub key_str: Option<String>,
+    pub key_passphrase: Option<String>,
+    #[serde(default)]
+    pub certificates: Vec<CertificateConfig>,
+    pub client_auth: Option<ClientAuthConfig>,
+    pub tls_policy: Option<TlsPolicyConfig>,
+    pub acme: Option<AcmeConfig>,
+    pub ocsp_stapling: Option<OcspStaplingConfig>,
     pub routes: Vec<RouteVistor>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -61,13 +77,19 @@ pub struct RouteVistor {
     pub liveness_config: Option<LivenessConfig>,
     pub health_check: Option<HealthCheckType>,
     pub ratelimit: Option<Box<dyn RatelimitStrategy>>,
+    pub server_names: Option<Vec<String>>,
+    pub client_identities: Option<Vec<String>>,
+    pub upstream_tls: Option<UpstreamTlsConfig>,
+    pub grpc_web: Option<GrpcWebConfig>,
+    pub grpc_json_transcoder: Option<GrpcJsonTranscoderConfig>,
+    pub grpc_matcher: Option<GrpcMatcher>,
     pub route_cluster: LoadbalancerStrategyVistor,
 }
 
 impl RouteVistor {
     async fn from(route: Route) -> Result<RouteVistor, anyhow::Error> {
         let load = from_loadbalancer_strategy(route.route_cluster).await;
-        let liveness_status = route.liveness_status.read().await;
+        let liveness_status = route.liveness_status.load();
         Ok(RouteVistor {
             route_id: route.route_id,
             host_name: route.host_name,
@@ -76,10 +98,19 @@ impl RouteVistor {
             allow_deny_list: route.allow_deny_list,
             authentication: route.authentication,
             anomaly_detection: route.anomaly_detection,
-            liveness_status: liveness_status.clone(),
+            liveness_status,
             liveness_config: route.liveness_config,
             health_check: route.health_check,
             ratelimit: route.ratelimit,
+            server_names: route.server_names,
+            client_identities: route.client_identities,
+            upstream_tls: route
+                .upstream_tls
+                .map(|item| item.config)
+                .filter(|item| *item != UpstreamTlsConfig::default()),
+            grpc_web: route.grpc_web,
+            grpc_json_transcoder: route.grpc_json_transcoder.map(|item| item.config),
+            grpc_matcher: route.grpc_matcher,
             route_cluster: load,
         })
     }
@@ -94,6 +125,12 @@ impl ServiceConfigVistor {
             server_type: service_config.server_type,
             cert_str: service_config.cert_str,
             key_str: service_config.key_str,
+            key_passphrase: service_config.key_passphrase,
+            certificates: service_config.certificates,
+            client_auth: service_config.client_auth,
+            tls_policy: service_config.tls_policy,
+            acme: service_config.acme,
+            ocsp_stapling: service_config.ocsp_stapling,
             routes,
         })
     }
@@ -148,6 +185,8 @@ pub enum LoadbalancerStrategyVistor {
     HeaderBasedRoute(HeaderBasedRouteVistor),
     RandomRoute(RandomRouteVistor),
     WeightBasedRoute(WeightBasedRouteVistor),
+    DnsRoute(DnsRouteVistor),
+    RegistryRoute(RegistryRouteVistor),
 }
 impl Default for LoadbalancerStrategyVistor {
     fn default() -> Self {
@@ -169,6 +208,10 @@ impl LoadbalancerStrategyVistor {
             LoadbalancerStrategyVistor::WeightBasedRoute(weight_based_route_vistor) => {
                 weight_based_route_vistor.routes.len()
             }
+            LoadbalancerStrategyVistor::DnsRoute(dns_route_vistor) => dns_route_vistor.routes.len(),
+            LoadbalancerStrategyVistor::RegistryRoute(registry_route_vistor) => {
+                registry_route_vistor.routes.len()
+            }
         }
     }
 }
@@ -188,6 +231,12 @@ pub fn from_loadbalancer_strategy_vistor(
         LoadbalancerStrategyVistor::WeightBasedRoute(weight_based_route_vistor) => {
             LoadbalancerStrategy::WeightBased(WeightBasedRoute::from(weight_based_route_vistor))
         }
+        LoadbalancerStrategyVistor::DnsRoute(dns_route_vistor) => {
+            LoadbalancerStrategy::DnsBased(DnsRoute::from(dns_route_vistor))
+        }
+        LoadbalancerStrategyVistor::RegistryRoute(registry_route_vistor) => {
+            LoadbalancerStrategy::RegistryBased(RegistryRoute::from(registry_route_vistor))
+        }
     }
 }
 pub async fn from_loadbalancer_strategy(
@@ -210,6 +259,14 @@ pub async fn from_loadbalancer_strategy(
                 WeightBasedRouteVistor::from(weight_based_route).await,
             )
         }
+        LoadbalancerStrategy::DnsBased(dns_route) => {
+            LoadbalancerStrategyVistor::DnsRoute(DnsRouteVistor::from(dns_route).await)
+        }
+        LoadbalancerStrategy::RegistryBased(registry_route) => {
+            LoadbalancerStrategyVistor::RegistryRoute(
+                RegistryRouteVistor::from(registry_route).await,
+            )
+        }
     }
 }
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -253,13 +310,11 @@ pub struct BaseRouteVistor {
 }
 impl BaseRouteVistor {
     pub async fn from(base_route: BaseRoute) -> Self {
-        let is_alive = base_route.is_alive.read().await;
-        let anomaly_detection_status = base_route.anomaly_detection_status.read().await;
         BaseRouteVistor {
+            is_alive: base_route.is_alive.load(),
+            anomaly_detection_status: base_route.anomaly_detection_status.load(),
             endpoint: base_route.endpoint,
             try_file: base_route.try_file,
-            is_alive: *is_alive,
-            anomaly_detection_status: anomaly_detection_status.clone(),
         }
     }
 }
@@ -341,9 +396,51 @@ pub struct WeightBasedRouteVistor {
 }
 impl WeightBasedRouteVistor {
     pub async fn from(weight_based_route: WeightBasedRoute) -> Self {
-        let list = weight_based_route.routes.read().await;
+        let list = weight_based_route.routes.load();
         WeightBasedRouteVistor {
-            routes: WeightRouteVistor::new_list(list.clone()).await,
+            routes: WeightRouteVistor::new_list(list.to_vec()).await,
+        }
+    }
+}
+fn default_refresh_interval() -> u64 {
+    30
+}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DnsRouteVistor {
+    pub endpoint: String,
+    #[serde(default)]
+    pub record_type: DnsRecordType,
+    #[serde(default = "default_weight")]
+    pub weight: i32,
+    #[serde(default = "default_refresh_interval")]
+    pub refresh_interval: u64,
+    #[serde(default, skip_deserializing)]
+    pub routes: Vec<WeightRouteVistor>,
+}
+impl DnsRouteVistor {
+    pub async fn from(dns_route: DnsRoute) -> Self {
+        let list = dns_route.cluster.routes.load();
+        DnsRouteVistor {
+            endpoint: dns_route.endpoint,
+            record_type: dns_route.record_type,
+            weight: dns_route.weight,
+            refresh_interval: dns_route.refresh_interval,
+            routes: WeightRouteVistor::new_list(list.to_vec()).await,
+        }
+    }
+}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegistryRouteVistor {
+    pub cluster_name: String,
+    #[serde(default, skip_deserializing)]
+    pub routes: Vec<WeightRouteVistor>,
+}
+impl RegistryRouteVistor {
+    pub async fn from(registry_route: RegistryRoute) -> Self {
+        let list = registry_route.cluster.routes.load();
+        RegistryRouteVistor {
+            cluster_name: registry_route.cluster_name,
+            routes: WeightRouteVistor::new_list(list.to_vec()).await,
         }
     }
 }
@@ -366,13 +463,15 @@ mod tests {
     use crate::vojo::anomaly_detection::BaseAnomalyDetectionParam;
     use crate::vojo::anomaly_detection::HttpAnomalyDetectionParam;
 
+    use crate::vojo::app_config::AtomicLivenessStatus;
     use crate::vojo::health_check::BaseHealthCheckParam;
     use crate::vojo::health_check::HttpHealthCheckParam;
     use crate::vojo::route::HeaderValueMappingType;
     use crate::vojo::route::RegexMatch;
+    use crate::vojo::route::{AtomicAliveStatus, AtomicAnomalyDetectionStatus};
+    use arc_swap::ArcSwap;
     use std::sync::atomic::AtomicIsize;
     use std::sync::Arc;
-    use tokio::sync::RwLock;
     #[tokio::test]
     async fn test_from_api_service_vistor_ok1() {
         let route = RouteVistor {
@@ -416,6 +515,12 @@ mod tests {
             }),
 
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -429,6 +534,12 @@ mod tests {
                 server_type: Default::default(),
                 cert_str: Default::default(),
                 key_str: Default::default(),
+                key_passphrase: None,
+                certificates: Default::default(),
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
             },
         };
         let api_services = vec![api_service_vistor];
@@ -481,6 +592,12 @@ mod tests {
             }),
 
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -494,6 +611,12 @@ mod tests {
                 server_type: Default::default(),
                 cert_str: Default::default(),
                 key_str: Default::default(),
+                key_passphrase: None,
+                certificates: Default::default(),
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
             },
         };
         let api_services = vec![api_service_vistor];
@@ -506,14 +629,16 @@ mod tests {
             host_name: None,
             route_id: get_uuid(),
             route_cluster: LoadbalancerStrategy::WeightBased(WeightBasedRoute {
-                routes: Arc::new(RwLock::new(vec![WeightRoute {
+                routes: Arc::new(ArcSwap::from_pointee(vec![WeightRoute {
                     base_route: BaseRoute {
                         endpoint: String::from("/"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     },
                     index: Arc::new(AtomicIsize::new(0)),
                     weight: 100,
@@ -526,7 +651,7 @@ mod tests {
                 },
                 path: String::from("value"),
             })),
-            liveness_status: Arc::new(RwLock::new(LivenessStatus {
+            liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                 current_liveness_count: 0,
             })),
             anomaly_detection: Some(AnomalyDetectionType::Http(HttpAnomalyDetectionParam {
@@ -544,6 +669,12 @@ mod tests {
             }),
 
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -557,6 +688,12 @@ mod tests {
                 server_type: Default::default(),
                 cert_str: Default::default(),
                 key_str: Default::default(),
+                key_passphrase: None,
+                certificates: Default::default(),
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
             },
         };
         let api_services = vec![api_service];
@@ -569,14 +706,16 @@ mod tests {
             host_name: None,
             route_id: get_uuid(),
             route_cluster: LoadbalancerStrategy::WeightBased(WeightBasedRoute {
-                routes: Arc::new(RwLock::new(vec![WeightRoute {
+                routes: Arc::new(ArcSwap::from_pointee(vec![WeightRoute {
                     base_route: BaseRoute {
                         endpoint: String::from("/"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     },
                     index: Arc::new(AtomicIsize::new(0)),
                     weight: 100,
@@ -589,7 +728,7 @@ mod tests {
                 },
                 path: String::from("value"),
             })),
-            liveness_status: Arc::new(RwLock::new(LivenessStatus {
+            liveness_status: Arc::new(AtomicLivenessStatus::from(LivenessStatus {
                 current_liveness_count: 0,
             })),
             anomaly_detection: Some(AnomalyDetectionType::Http(HttpAnomalyDetectionParam {
@@ -607,6 +746,12 @@ mod tests {
             }),
 
             ratelimit: None,
+            server_names: None,
+            client_identities: None,
+            upstream_tls: None,
+            grpc_web: None,
+            grpc_json_transcoder: None,
+            grpc_matcher: None,
             matcher: Some(Matcher {
                 prefix: String::from("ss"),
                 prefix_rewrite: String::from("ssss"),
@@ -620,6 +765,12 @@ mod tests {
                 server_type: Default::default(),
                 cert_str: Default::default(),
                 key_str: Default::default(),
+                key_passphrase: None,
+                certificates: Default::default(),
+                client_auth: None,
+                tls_policy: None,
+                acme: None,
+                ocsp_stapling: None,
             },
         };
         let api_services = vec![api_service];
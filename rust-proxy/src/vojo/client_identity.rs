@@ -0,0 +1,213 @@
+use openssl::x509::X509;
+
+/**
+*The identity of the client certificate which has been verified by the tls handshake.
+*/
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ClientIdentity {
+    pub subject: String,
+    pub uris: Vec<String>,
+    pub dns_names: Vec<String>,
+}
+impl ClientIdentity {
+    pub fn from_der(der: &[u8]) -> Result<Self, anyhow::Error> {
+        let certificate = X509::from_der(der)
+            .map_err(|e| anyhow!("Parse the client certificate error,the error is {}.", e))?;
+        let mut subject_items = vec![];
+        for entry in certificate.subject_name().entries() {
+            let name = entry
+                .object()
+                .nid()
+                .short_name()
+                .map_err(|e| anyhow!("Parse the subject error,the error is {}.", e))?;
+            let value = String::from_utf8(entry.data().as_slice().to_vec())?;
+            subject_items.push(format!("{}={}", name, escape_rdn_value(&value)));
+        }
+        let mut uris = vec![];
+        let mut dns_names = vec![];
+        if let Some(subject_alt_names) = certificate.subject_alt_names() {
+            for item in subject_alt_names.iter() {
+                if let Some(uri) = item.uri() {
+                    uris.push(String::from(uri));
+                } else if let Some(dns_name) = item.dnsname() {
+                    dns_names.push(String::from(dns_name));
+                }
+            }
+        }
+        Ok(ClientIdentity {
+            subject: subject_items.join(","),
+            uris,
+            dns_names,
+        })
+    }
+    /**
+    *The pattern matches the subject,one of the uris(e.g. the spiffe id) or one of the dns names.
+     The pattern ending with * matches the values which start with the rest of the pattern.
+    */
+    pub fn is_matched(&self, pattern: &str) -> bool {
+        let is_matched = |value: &String| match pattern.strip_suffix('*') {
+            Some(prefix) => value.starts_with(prefix),
+            None => value == pattern,
+        };
+        is_matched(&self.subject)
+            || self.uris.iter().any(is_matched)
+            || self.dns_names.iter().any(is_matched)
+    }
+    /**
+    *Format the identity like the x-forwarded-client-cert header of envoy,e.g.
+     Subject="O=silverwind,CN=client";URI="spiffe://example.org/ns/default/sa/client".
+     Every value is quoted,so the value could not inject the separators.
+    */
+    pub fn to_header_value(&self) -> String {
+        let mut items = vec![format!("Subject={}", quote_header_value(&self.subject))];
+        items.extend(
+            self.uris
+                .iter()
+                .map(|item| format!("URI={}", quote_header_value(item))),
+        );
+        items.extend(
+            self.dns_names
+                .iter()
+                .map(|item| format!("DNS={}", quote_header_value(item))),
+        );
+        items.join(";")
+    }
+}
+/**
+*Escape the value of the relative distinguished name like RFC 4514,so the value containing ",CN="
+ could not be parsed as another attribute of the subject.
+*/
+fn escape_rdn_value(value: &str) -> String {
+    let mut escaped_value = String::new();
+    let char_count = value.chars().count();
+    for (index, c) in value.chars().enumerate() {
+        let need_escape = matches!(c, '"' | '+' | ',' | ';' | '<' | '>' | '\\')
+            || (index == 0 && (c == '#' || c == ' '))
+            || (index == char_count - 1 && c == ' ');
+        if need_escape {
+            escaped_value.push('\\');
+        }
+        escaped_value.push(c);
+    }
+    escaped_value
+}
+/**
+*Quote the value and escape the quote and the backslash,the characters which are not visible ascii
+ and the % are percent-encoded,so the value is always a valid header value.
+*/
+fn quote_header_value(value: &str) -> String {
+    let mut quoted_value = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted_value.push('\\');
+                quoted_value.push(c);
+            }
+            '%' => quoted_value.push_str("%25"),
+            c if c.is_ascii_graphic() || c == ' ' => quoted_value.push(c),
+            c => {
+                let mut buffer = [0; 4];
+                for byte in c.encode_utf8(&mut buffer).bytes() {
+                    quoted_value.push_str(format!("%{:02X}", byte).as_str());
+                }
+            }
+        }
+    }
+    quoted_value.push('"');
+    quoted_value
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+    use openssl::asn1::Asn1Time;
+    use openssl::ec::{EcGroup, EcKey};
+    use openssl::hash::MessageDigest;
+    use openssl::nid::Nid;
+    use openssl::pkey::PKey;
+    use openssl::x509::extension::SubjectAlternativeName;
+    use openssl::x509::{X509Builder, X509NameBuilder};
+    use std::env;
+    use std::io::BufReader;
+    fn get_client_identity() -> ClientIdentity {
+        let path = env::current_dir()
+            .unwrap()
+            .join("config")
+            .join("test_client_cert.pem");
+        let cert_str = std::fs::read_to_string(path).unwrap();
+        let mut cer_reader = BufReader::new(cert_str.as_bytes());
+        let certs = rustls_pemfile::certs(&mut cer_reader).unwrap();
+        ClientIdentity::from_der(&certs[0]).unwrap()
+    }
+    #[test]
+    fn test_client_identity_from_der_ok() {
+        let client_identity = get_client_identity();
+        assert_eq!(client_identity.subject, "O=silverwind,CN=client");
+        assert_eq!(
+            client_identity.uris,
+            vec![String::from("spiffe://example.org/ns/default/sa/client")]
+        );
+        assert_eq!(
+            client_identity.dns_names,
+            vec![String::from("client.example.org")]
+        );
+        assert_eq!(
+            client_identity.to_header_value(),
+            "Subject=\"O=silverwind,CN=client\";URI=\"spiffe://example.org/ns/default/sa/client\";DNS=\"client.example.org\""
+        );
+        assert!(ClientIdentity::from_der(b"test").is_err());
+    }
+    #[test]
+    fn test_client_identity_with_special_characters_ok() {
+        let mut name_builder = X509NameBuilder::new().unwrap();
+        name_builder
+            .append_entry_by_text("O", "evil,CN=admin")
+            .unwrap();
+        name_builder.append_entry_by_text("CN", "客户端").unwrap();
+        let name = name_builder.build();
+        let key = PKey::from_ec_key(
+            EcKey::generate(&EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap()).unwrap(),
+        )
+        .unwrap();
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        builder
+            .append_extension(
+                SubjectAlternativeName::new()
+                    .uri("spiffe://example.org/a;DNS=admin\"")
+                    .build(&builder.x509v3_context(None, None))
+                    .unwrap(),
+            )
+            .unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+        let der = builder.build().to_der().unwrap();
+
+        let client_identity = ClientIdentity::from_der(&der).unwrap();
+        assert_eq!(client_identity.subject, "O=evil\\,CN=admin,CN=客户端");
+        assert!(!client_identity.is_matched("O=evil,CN=admin*"));
+        let header_value = client_identity.to_header_value();
+        assert_eq!(
+            header_value,
+            "Subject=\"O=evil\\\\,CN=admin,CN=%E5%AE%A2%E6%88%B7%E7%AB%AF\";URI=\"spiffe://example.org/a;DNS=admin\\\"\""
+        );
+        assert!(HeaderValue::from_str(&header_value).is_ok());
+    }
+    #[test]
+    fn test_client_identity_is_matched_ok() {
+        let client_identity = get_client_identity();
+        assert!(client_identity.is_matched("O=silverwind,CN=client"));
+        assert!(client_identity.is_matched("spiffe://example.org/ns/default/sa/client"));
+        assert!(client_identity.is_matched("spiffe://example.org/ns/default/*"));
+        assert!(client_identity.is_matched("client.example.org"));
+        assert!(!client_identity.is_matched("spiffe://example.org/ns/payments/*"));
+        assert!(!client_identity.is_matched("CN=client"));
+    }
+}
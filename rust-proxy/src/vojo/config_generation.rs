@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+/**
+*The generation is increased by every config change,and the applied generation is the latest one
+ whose listeners,routes and health check tasks have been reconciled.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct ConfigGeneration {
+    pub generation: u64,
+    pub applied_generation: u64,
+}
+fn default_wait_seconds() -> u64 {
+    30
+}
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigGenerationWaitQuery {
+    pub generation: u64,
+    #[serde(default = "default_wait_seconds")]
+    pub timeout: u64,
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigVersion {
+    pub version: i64,
+    pub create_time: i64,
+    pub message: Option<String>,
+}
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigVersionDiffQuery {
+    pub from_version: i64,
+    pub to_version: i64,
+}
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigVersionDiff {
+    pub from_version: i64,
+    pub to_version: i64,
+    pub diff: String,
+}
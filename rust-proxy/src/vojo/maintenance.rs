@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, strum_macros::Display,
+)]
+pub enum EndpointStatus {
+    #[default]
+    Active,
+    Draining,
+    Disabled,
+}
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointStatusVistor {
+    pub endpoint: String,
+    pub status: EndpointStatus,
+}
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointStatusResponse {
+    pub endpoint: String,
+    pub status: EndpointStatus,
+    pub in_flight: i64,
+    pub drained: bool,
+}
+fn default_wait_seconds() -> u64 {
+    30
+}
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointWaitQuery {
+    pub endpoint: String,
+    #[serde(default = "default_wait_seconds")]
+    pub timeout: u64,
+}
@@ -6,7 +6,12 @@ pub mod anomaly_detection;
 pub mod app_config_vistor;
 pub mod authentication;
 pub mod base_response;
+pub mod client_identity;
+pub mod config_generation;
+pub mod config_version;
 pub mod health_check;
 pub mod lets_encrypt;
+pub mod maintenance;
 pub mod rate_limit;
 pub mod route;
+pub mod service_registry;
@@ -1,11 +1,14 @@
+use super::app_config::AtomicLivenessStatus;
 use super::app_config::LivenessConfig;
-use super::app_config::LivenessStatus;
 use super::app_config_vistor::BaseRouteVistor;
+use crate::maintenance::endpoint_maintenance;
 use crate::vojo::anomaly_detection::HttpAnomalyDetectionParam;
 use crate::vojo::app_config_vistor::{
-    HeaderBasedRouteVistor, HeaderRouteVistor, PollBaseRouteVistor, PollRouteVistor,
-    RandomBaseRouteVistor, RandomRouteVistor, WeightBasedRouteVistor, WeightRouteVistor,
+    DnsRouteVistor, HeaderBasedRouteVistor, HeaderRouteVistor, PollBaseRouteVistor,
+    PollRouteVistor, RandomBaseRouteVistor, RandomRouteVistor, RegistryRouteVistor,
+    WeightBasedRouteVistor, WeightRouteVistor,
 };
+use arc_swap::ArcSwap;
 use core::fmt::Debug;
 use http::HeaderMap;
 use http::HeaderValue;
@@ -14,21 +17,23 @@ use rand::prelude::*;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::sync::atomic::{AtomicIsize, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicI32, AtomicIsize, AtomicU8, AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tokio::time::{sleep, Duration};
+use tokio::time::{sleep, Duration, Instant};
 #[derive(Debug, Clone)]
 pub enum LoadbalancerStrategy {
     PollRoute(PollRoute),
     HeaderBased(HeaderBasedRoute),
     Random(RandomRoute),
     WeightBased(WeightBasedRoute),
+    DnsBased(DnsRoute),
+    RegistryBased(RegistryRoute),
 }
 
 impl LoadbalancerStrategy {
     pub async fn get_route(
-        &mut self,
+        &self,
         headers: HeaderMap<HeaderValue>,
     ) -> Result<BaseRoute, anyhow::Error> {
         match self {
@@ -39,9 +44,15 @@ impl LoadbalancerStrategy {
             LoadbalancerStrategy::Random(poll_route) => poll_route.get_route(headers).await,
 
             LoadbalancerStrategy::WeightBased(poll_route) => poll_route.get_route(headers).await,
+
+            LoadbalancerStrategy::DnsBased(dns_route) => dns_route.get_route(headers).await,
+
+            LoadbalancerStrategy::RegistryBased(registry_route) => {
+                registry_route.get_route(headers).await
+            }
         }
     }
-    pub async fn get_all_route(&mut self) -> Result<Vec<BaseRoute>, anyhow::Error> {
+    pub async fn get_all_route(&self) -> Result<Vec<BaseRoute>, anyhow::Error> {
         match self {
             LoadbalancerStrategy::PollRoute(poll_route) => poll_route.get_all_route().await,
             LoadbalancerStrategy::HeaderBased(poll_route) => poll_route.get_all_route().await,
@@ -49,6 +60,12 @@ impl LoadbalancerStrategy {
             LoadbalancerStrategy::Random(poll_route) => poll_route.get_all_route().await,
 
             LoadbalancerStrategy::WeightBased(poll_route) => poll_route.get_all_route().await,
+
+            LoadbalancerStrategy::DnsBased(dns_route) => dns_route.get_all_route().await,
+
+            LoadbalancerStrategy::RegistryBased(registry_route) => {
+                registry_route.get_all_route().await
+            }
         }
     }
 }
@@ -56,22 +73,96 @@ impl LoadbalancerStrategy {
 pub struct AnomalyDetectionStatus {
     pub consecutive_5xx: i32,
 }
+#[derive(Debug, Default)]
+pub struct AtomicAnomalyDetectionStatus {
+    pub consecutive_5xx: AtomicI32,
+}
+impl AtomicAnomalyDetectionStatus {
+    pub fn load(&self) -> AnomalyDetectionStatus {
+        AnomalyDetectionStatus {
+            consecutive_5xx: self.consecutive_5xx.load(Ordering::SeqCst),
+        }
+    }
+    pub fn store(&self, anomaly_detection_status: AnomalyDetectionStatus) {
+        self.consecutive_5xx
+            .store(anomaly_detection_status.consecutive_5xx, Ordering::SeqCst);
+    }
+}
+impl From<AnomalyDetectionStatus> for AtomicAnomalyDetectionStatus {
+    fn from(anomaly_detection_status: AnomalyDetectionStatus) -> Self {
+        AtomicAnomalyDetectionStatus {
+            consecutive_5xx: AtomicI32::new(anomaly_detection_status.consecutive_5xx),
+        }
+    }
+}
+const ALIVE_STATUS_UNKNOWN: u8 = 0;
+const ALIVE_STATUS_ALIVE: u8 = 1;
+const ALIVE_STATUS_DEAD: u8 = 2;
+fn encode_alive_status(is_alive: Option<bool>) -> u8 {
+    match is_alive {
+        None => ALIVE_STATUS_UNKNOWN,
+        Some(true) => ALIVE_STATUS_ALIVE,
+        Some(false) => ALIVE_STATUS_DEAD,
+    }
+}
+fn decode_alive_status(value: u8) -> Option<bool> {
+    match value {
+        ALIVE_STATUS_ALIVE => Some(true),
+        ALIVE_STATUS_DEAD => Some(false),
+        _ => None,
+    }
+}
+/**
+*The Option<bool> of the liveness in one atomic,and the None means the endpoint has not been
+ checked yet.
+*/
+#[derive(Debug, Default)]
+pub struct AtomicAliveStatus {
+    value: AtomicU8,
+}
+impl AtomicAliveStatus {
+    pub fn new(is_alive: Option<bool>) -> Self {
+        AtomicAliveStatus {
+            value: AtomicU8::new(encode_alive_status(is_alive)),
+        }
+    }
+    pub fn load(&self) -> Option<bool> {
+        decode_alive_status(self.value.load(Ordering::SeqCst))
+    }
+    pub fn store(&self, is_alive: Option<bool>) {
+        self.value
+            .store(encode_alive_status(is_alive), Ordering::SeqCst);
+    }
+    /**
+     *Return false if the status has been changed by others.
+     */
+    pub fn compare_exchange(&self, current: Option<bool>, new: Option<bool>) -> bool {
+        self.value
+            .compare_exchange(
+                encode_alive_status(current),
+                encode_alive_status(new),
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            )
+            .is_ok()
+    }
+}
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct BaseRoute {
     pub endpoint: String,
     pub try_file: Option<String>,
     #[serde(skip_deserializing)]
-    pub is_alive: Arc<RwLock<Option<bool>>>,
+    pub is_alive: Arc<AtomicAliveStatus>,
     #[serde(skip_serializing, skip_deserializing)]
-    pub anomaly_detection_status: Arc<RwLock<AnomalyDetectionStatus>>,
+    pub anomaly_detection_status: Arc<AtomicAnomalyDetectionStatus>,
 }
 impl BaseRoute {
     pub fn from(base_route_vistor: BaseRouteVistor) -> Self {
         BaseRoute {
             endpoint: base_route_vistor.endpoint,
             try_file: base_route_vistor.try_file,
-            is_alive: Arc::new(RwLock::new(base_route_vistor.is_alive)),
-            anomaly_detection_status: Arc::new(RwLock::new(
+            is_alive: Arc::new(AtomicAliveStatus::new(base_route_vistor.is_alive)),
+            anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
                 base_route_vistor.anomaly_detection_status,
             )),
         }
@@ -79,48 +170,38 @@ impl BaseRoute {
 }
 
 impl BaseRoute {
-    async fn update_ok(&self, liveness_status_lock: Arc<RwLock<LivenessStatus>>) -> bool {
-        let mut is_alive_lock = self.is_alive.write().await;
-        if is_alive_lock.is_none() {
-            *is_alive_lock = Some(true);
-            info!(
-                "Update the liveness of route-{} to ok succesfully!",
-                self.endpoint.clone(),
-            );
-            return true;
-        } else if !is_alive_lock.unwrap() {
-            *is_alive_lock = Some(true);
-            let mut liveness_status = liveness_status_lock.write().await;
-            liveness_status.current_liveness_count += 1;
-            info!(
-                "Update the liveness of route-{} to ok succesfully,and the current liveness count is {}.",
-                self.endpoint.clone(),liveness_status.current_liveness_count);
-            return true;
+    pub fn is_accepting_traffic(&self) -> bool {
+        endpoint_maintenance::is_accepting_traffic(self.endpoint.as_str())
+    }
+    /**
+     *The endpoint which has not been checked is treated as alive.
+     */
+    pub fn is_available(&self) -> bool {
+        self.is_alive.load().unwrap_or(true) && self.is_accepting_traffic()
+    }
+    fn update_ok(&self, liveness_status: &AtomicLivenessStatus) -> bool {
+        let is_alive = self.is_alive.load();
+        if is_alive == Some(true) || !self.is_alive.compare_exchange(is_alive, Some(true)) {
+            return false;
         }
-        false
-    }
-    async fn update_fail(&self, liveness_status_lock: Arc<RwLock<LivenessStatus>>) -> bool {
-        let mut is_alive_lock = self.is_alive.write().await;
-        if is_alive_lock.is_none() || is_alive_lock.unwrap() {
-            let mut liveness_status = liveness_status_lock.write().await;
-            liveness_status.current_liveness_count -= 1;
-            *is_alive_lock = Some(false);
+        if is_alive.is_some() {
+            let current_liveness_count = liveness_status
+                .current_liveness_count
+                .fetch_add(1, Ordering::SeqCst)
+                + 1;
             info!(
-                "Update the liveness of route-{} to fail succesfully,and the current liveness count is {}.",
-                self.endpoint.clone(),liveness_status.current_liveness_count);
-            return true;
+                "Update the liveness of route-{} to ok succesfully,and the current liveness count is {}.",
+                self.endpoint.clone(),current_liveness_count);
         }
-        false
+        true
     }
     pub async fn update_health_check_status_with_ok(
         &self,
-        liveness_status_lock: Arc<RwLock<LivenessStatus>>,
+        liveness_status: Arc<AtomicLivenessStatus>,
     ) -> bool {
-        let is_alive_lock = self.is_alive.read().await;
-        let is_alive = is_alive_lock.unwrap_or(false);
+        let is_alive = self.is_alive.load().unwrap_or(false);
         if !is_alive {
-            drop(is_alive_lock);
-            self.update_ok(liveness_status_lock).await
+            self.update_ok(&liveness_status)
         } else {
             info!(
             "Update the liveness of route-{} to ok unsuccesfully,as the current status of the endpoint is alive!",
@@ -129,98 +210,117 @@ impl BaseRoute {
             false
         }
     }
+    /**
+    *The liveness count is decreased only when it is more than the min liveness count,so the
+     concurrent updates could not eject too many endpoints.
+    */
     pub async fn update_health_check_status_with_fail(
         &self,
-        liveness_status_lock: Arc<RwLock<LivenessStatus>>,
+        liveness_status: Arc<AtomicLivenessStatus>,
         liveness_config: LivenessConfig,
     ) -> bool {
-        let liveness_status = liveness_status_lock.read().await;
-        if liveness_status.current_liveness_count <= liveness_config.min_liveness_count {
+        let is_alive = self.is_alive.load();
+        let current_liveness_count = liveness_status
+            .current_liveness_count
+            .load(Ordering::SeqCst);
+        if current_liveness_count <= liveness_config.min_liveness_count {
             error!(
                 "Update the liveness of route-{} to fail unsuccesfully,as the current liveness count:{} is less than the liveness count:{} in the config!",
                 self.endpoint.clone(),
-                liveness_status.current_liveness_count,
+                current_liveness_count,
                 liveness_config.min_liveness_count
             );
             return false;
         }
-        let is_alive_lock = self.is_alive.read().await;
-        let is_alive = is_alive_lock.unwrap_or(true);
-        if is_alive {
-            drop(liveness_status);
-            drop(is_alive_lock);
-            self.update_fail(liveness_status_lock.clone()).await;
-            info!(
-                "Update the liveness of route-{} to fail succesfully!",
-                self.endpoint.clone()
-            );
-            return true;
-        } else {
+        if !is_alive.unwrap_or(true) {
             info!(
             "Update the liveness of route-{} to fail unsuccesfully,as the current status of the endpoint is not alive!",
             self.endpoint.clone(),
         );
+            return false;
+        }
+        let decrease_result = liveness_status.current_liveness_count.fetch_update(
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+            |count| (count > liveness_config.min_liveness_count).then_some(count - 1),
+        );
+        let current_liveness_count = match decrease_result {
+            Ok(count) => count - 1,
+            Err(_) => return false,
+        };
+        if !self.is_alive.compare_exchange(is_alive, Some(false)) {
+            liveness_status
+                .current_liveness_count
+                .fetch_add(1, Ordering::SeqCst);
+            return false;
         }
-        false
+        info!(
+            "Update the liveness of route-{} to fail succesfully,and the current liveness count is {}.",
+            self.endpoint.clone(),current_liveness_count);
+        true
     }
     pub async fn trigger_http_anomaly_detection(
         &self,
         http_anomaly_detection_param: HttpAnomalyDetectionParam,
-        liveness_status_lock: Arc<RwLock<LivenessStatus>>,
+        liveness_status: Arc<AtomicLivenessStatus>,
         is_5xx: bool,
         liveness_config: LivenessConfig,
     ) -> Result<(), anyhow::Error> {
         let consecutive_5xx_config = http_anomaly_detection_param.consecutive_5xx;
-        let mut anomaly_detection_status = self
-            .anomaly_detection_status
-            .try_write()
-            .map_err(|e| anyhow!("{}", e))?;
-        if !is_5xx && anomaly_detection_status.consecutive_5xx > 0 {
-            anomaly_detection_status.consecutive_5xx = 0;
+        if !is_5xx {
+            self.anomaly_detection_status
+                .consecutive_5xx
+                .store(0, Ordering::SeqCst);
             return Ok(());
         }
-
-        if anomaly_detection_status.consecutive_5xx < consecutive_5xx_config - 1 {
-            anomaly_detection_status.consecutive_5xx += 1;
-        } else {
-            drop(anomaly_detection_status);
-            let update_success = self
-                .update_health_check_status_with_fail(liveness_status_lock.clone(), liveness_config)
+        let increase_result = self.anomaly_detection_status.consecutive_5xx.fetch_update(
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+            |consecutive_5xx| {
+                (consecutive_5xx < consecutive_5xx_config - 1).then_some(consecutive_5xx + 1)
+            },
+        );
+        if increase_result.is_ok() {
+            return Ok(());
+        }
+        let update_success = self
+            .update_health_check_status_with_fail(liveness_status.clone(), liveness_config)
+            .await;
+        if update_success {
+            let is_alive = self.is_alive.clone();
+            let ejection_second = http_anomaly_detection_param
+                .base_anomaly_detection_param
+                .ejection_second;
+            let anomaly_detection_status = self.anomaly_detection_status.clone();
+            tokio::spawn(async move {
+                BaseRoute::wait_for_alive(
+                    is_alive,
+                    ejection_second,
+                    liveness_status,
+                    anomaly_detection_status,
+                )
                 .await;
-            if update_success {
-                let alive_lock = self.is_alive.clone();
-                let ejection_second = http_anomaly_detection_param
-                    .base_anomaly_detection_param
-                    .ejection_second;
-                let anomaly_detection_status_lock = self.anomaly_detection_status.clone();
-                tokio::spawn(async move {
-                    BaseRoute::wait_for_alive(
-                        alive_lock,
-                        ejection_second,
-                        liveness_status_lock,
-                        anomaly_detection_status_lock,
-                    )
-                    .await;
-                    info!("Wait for alive successfully!");
-                });
-            }
+                info!("Wait for alive successfully!");
+            });
         }
         Ok(())
     }
 
     pub async fn wait_for_alive(
-        is_alive_lock: Arc<RwLock<Option<bool>>>,
+        is_alive: Arc<AtomicAliveStatus>,
         wait_second: u64,
-        liveness_status_lock: Arc<RwLock<LivenessStatus>>,
-        anomaly_detection_status_lock: Arc<RwLock<AnomalyDetectionStatus>>,
+        liveness_status: Arc<AtomicLivenessStatus>,
+        anomaly_detection_status: Arc<AtomicAnomalyDetectionStatus>,
     ) {
         sleep(Duration::from_secs(wait_second)).await;
-        let mut is_alive_option = is_alive_lock.write().await;
-        let mut liveness_status = liveness_status_lock.write().await;
-        let mut anomaly_detection_status = anomaly_detection_status_lock.write().await;
-        *is_alive_option = Some(true);
-        liveness_status.current_liveness_count += 1;
-        anomaly_detection_status.consecutive_5xx = 0;
+        if is_alive.compare_exchange(Some(false), Some(true)) {
+            liveness_status
+                .current_liveness_count
+                .fetch_add(1, Ordering::SeqCst);
+        }
+        anomaly_detection_status
+            .consecutive_5xx
+            .store(0, Ordering::SeqCst);
     }
 }
 
@@ -301,7 +401,7 @@ impl HeaderBasedRoute {
 // #[typetag::serde]
 // #[async_trait]
 impl HeaderBasedRoute {
-    async fn get_all_route(&mut self) -> Result<Vec<BaseRoute>, anyhow::Error> {
+    async fn get_all_route(&self) -> Result<Vec<BaseRoute>, anyhow::Error> {
         Ok(self
             .routes
             .iter()
@@ -309,18 +409,14 @@ impl HeaderBasedRoute {
             .collect::<Vec<BaseRoute>>())
     }
 
-    async fn get_route(
-        &mut self,
-        headers: HeaderMap<HeaderValue>,
-    ) -> Result<BaseRoute, anyhow::Error> {
-        let mut alive_cluster: Vec<HeaderRoute> = vec![];
-        for item in self.routes.clone() {
-            let is_alve_result = item.base_route.is_alive.read().await;
-            // let is_alive_option = is_alve_result.unwrap();
-            let is_alive = is_alve_result.unwrap_or(true);
-            if is_alive {
-                alive_cluster.push(item.clone());
-            }
+    async fn get_route(&self, headers: HeaderMap<HeaderValue>) -> Result<BaseRoute, anyhow::Error> {
+        let alive_cluster = self
+            .routes
+            .iter()
+            .filter(|item| item.base_route.is_available())
+            .collect::<Vec<&HeaderRoute>>();
+        if alive_cluster.is_empty() {
+            return Err(anyhow!("Can not find alive host in the clusters"));
         }
         for item in alive_cluster.iter() {
             let headers_contais_key = headers.contains_key(item.header_key.clone());
@@ -329,19 +425,19 @@ impl HeaderBasedRoute {
             }
             let header_value = headers.get(item.header_key.clone()).unwrap();
             let header_value_str = header_value.to_str().unwrap();
-            match item.clone().header_value_mapping_type {
+            match &item.header_value_mapping_type {
                 HeaderValueMappingType::Regex(regex_str) => {
                     let re = Regex::new(&regex_str.value).unwrap();
                     let capture_option = re.captures(header_value_str);
                     if capture_option.is_none() {
                         continue;
                     } else {
-                        return Ok(item.clone().base_route);
+                        return Ok(item.base_route.clone());
                     }
                 }
                 HeaderValueMappingType::Text(text_str) => {
                     if text_str.value == header_value_str {
-                        return Ok(item.clone().base_route);
+                        return Ok(item.base_route.clone());
                     } else {
                         continue;
                     }
@@ -360,7 +456,7 @@ impl HeaderBasedRoute {
                         }
                     }
                     if flag {
-                        return Ok(item.clone().base_route);
+                        return Ok(item.base_route.clone());
                     }
                 }
             }
@@ -398,7 +494,7 @@ impl RandomRoute {
 }
 
 impl RandomRoute {
-    async fn get_all_route(&mut self) -> Result<Vec<BaseRoute>, anyhow::Error> {
+    async fn get_all_route(&self) -> Result<Vec<BaseRoute>, anyhow::Error> {
         Ok(self
             .routes
             .iter()
@@ -407,17 +503,17 @@ impl RandomRoute {
     }
 
     async fn get_route(
-        &mut self,
+        &self,
         _headers: HeaderMap<HeaderValue>,
     ) -> Result<BaseRoute, anyhow::Error> {
-        let mut alive_cluster: Vec<BaseRoute> = vec![];
-        for item in self.routes.clone() {
-            let is_alve_result = item.base_route.is_alive.read().await;
-            let is_alive = is_alve_result.unwrap_or(true);
-            if is_alive {
-                alive_cluster.push(item.base_route.clone());
-            }
-            drop(is_alve_result);
+        let alive_cluster = self
+            .routes
+            .iter()
+            .filter(|item| item.base_route.is_available())
+            .map(|item| &item.base_route)
+            .collect::<Vec<&BaseRoute>>();
+        if alive_cluster.is_empty() {
+            return Err(anyhow!("Can not find alive host in the clusters"));
         }
         let mut rng = thread_rng();
         let index = rng.gen_range(0..alive_cluster.len());
@@ -454,121 +550,276 @@ impl PollRoute {
 }
 
 impl PollRoute {
-    async fn get_all_route(&mut self) -> Result<Vec<BaseRoute>, anyhow::Error> {
+    async fn get_all_route(&self) -> Result<Vec<BaseRoute>, anyhow::Error> {
         Ok(self
             .routes
-            .iter_mut()
+            .iter()
             .map(|item| item.base_route.clone())
             .collect::<Vec<BaseRoute>>())
     }
 
     async fn get_route(
-        &mut self,
+        &self,
         _headers: HeaderMap<HeaderValue>,
     ) -> Result<BaseRoute, anyhow::Error> {
-        let mut alive_cluster: Vec<PollBaseRoute> = vec![];
-        for item in self.routes.clone() {
-            let is_alve_result = item.base_route.is_alive.read().await;
-            let is_alive = is_alve_result.unwrap_or(true);
-            if is_alive {
-                alive_cluster.push(item.clone());
-            }
-        }
+        let alive_cluster = self
+            .routes
+            .iter()
+            .filter(|item| item.base_route.is_available())
+            .collect::<Vec<&PollBaseRoute>>();
         if alive_cluster.is_empty() {
             return Err(anyhow!("Can not find alive host in the clusters"));
         }
         let older = self.current_index.fetch_add(1, Ordering::SeqCst);
         let len = alive_cluster.len();
         let current_index = older % len;
-        let dst = alive_cluster[current_index].clone();
+        let dst = alive_cluster[current_index];
         if log_enabled!(Level::Debug) {
             debug!("PollRoute current index:{}", current_index as i32);
         }
-        Ok(dst.base_route)
+        Ok(dst.base_route.clone())
     }
 }
+/**
+*The routes are replaced as a whole by the discovery,so the request reads the snapshot of the
+ routes without any lock.
+*/
 #[derive(Debug, Clone, Default)]
 pub struct WeightBasedRoute {
-    pub routes: Arc<RwLock<Vec<WeightRoute>>>,
+    pub routes: Arc<ArcSwap<Vec<WeightRoute>>>,
 }
 impl WeightBasedRoute {
     pub fn from(weight_based_route_vistor: WeightBasedRouteVistor) -> Self {
         WeightBasedRoute {
-            routes: Arc::new(RwLock::new(WeightRoute::new_list(
+            routes: Arc::new(ArcSwap::from_pointee(WeightRoute::new_list(
                 weight_based_route_vistor.routes,
             ))),
         }
     }
+    /**
+    *The routes which are still in the list keep their health and anomaly detection status.
+     Return the change of the liveness count.
+    */
+    pub async fn update_routes(&self, endpoints: Vec<(String, i32)>) -> i32 {
+        let mut liveness_count_change = 0;
+        self.routes.rcu(|old_routes| {
+            let (routes, change) = merge_weight_routes(old_routes, &endpoints);
+            liveness_count_change = change;
+            routes
+        });
+        liveness_count_change
+    }
+}
+/**
+*The rcu may run the merge more than once,so the merge only logs and never changes the status.
+*/
+fn merge_weight_routes(
+    old_routes: &[WeightRoute],
+    endpoints: &[(String, i32)],
+) -> (Vec<WeightRoute>, i32) {
+    let mut routes: Vec<WeightRoute> = vec![];
+    let mut liveness_count_change = 0;
+    for (endpoint, weight) in endpoints.iter() {
+        if routes
+            .iter()
+            .any(|item| &item.base_route.endpoint == endpoint)
+        {
+            continue;
+        }
+        match old_routes
+            .iter()
+            .find(|item| &item.base_route.endpoint == endpoint)
+        {
+            Some(old_route) => routes.push(WeightRoute {
+                base_route: old_route.base_route.clone(),
+                weight: *weight,
+                index: old_route.index.clone(),
+            }),
+            None => {
+                info!("Add the endpoint-{} to the cluster.", endpoint);
+                liveness_count_change += 1;
+                routes.push(WeightRoute {
+                    base_route: BaseRoute {
+                        endpoint: endpoint.clone(),
+                        ..Default::default()
+                    },
+                    weight: *weight,
+                    index: Arc::new(AtomicIsize::new(*weight as isize)),
+                });
+            }
+        }
+    }
+    for old_route in old_routes.iter() {
+        if routes
+            .iter()
+            .any(|item| item.base_route.endpoint == old_route.base_route.endpoint)
+        {
+            continue;
+        }
+        info!(
+            "Remove the endpoint-{} from the cluster.",
+            old_route.base_route.endpoint
+        );
+        if old_route.base_route.is_alive.load().unwrap_or(true) {
+            liveness_count_change -= 1;
+        }
+    }
+    (routes, liveness_count_change)
 }
 
 impl WeightBasedRoute {
-    async fn get_all_route(&mut self) -> Result<Vec<BaseRoute>, anyhow::Error> {
-        let read_lock = self.routes.read().await;
-        let array = read_lock
+    async fn get_all_route(&self) -> Result<Vec<BaseRoute>, anyhow::Error> {
+        Ok(self
+            .routes
+            .load()
             .iter()
             .map(|item| item.base_route.clone())
-            .collect::<Vec<BaseRoute>>();
-        Ok(array)
+            .collect::<Vec<BaseRoute>>())
     }
 
-    async fn get_route(
-        &mut self,
-        _headers: HeaderMap<HeaderValue>,
-    ) -> Result<BaseRoute, anyhow::Error> {
-        let cluster_read_lock = self.routes.read().await;
-        let mut all_cluster_dead = true;
-        for (pos, e) in cluster_read_lock.iter().enumerate() {
-            let is_alive_option_lock = e.base_route.is_alive.read().await;
-            let is_alive = is_alive_option_lock.unwrap_or(true);
-            if is_alive {
-                all_cluster_dead = false;
+    fn select_route(routes: &[WeightRoute]) -> Option<BaseRoute> {
+        for (pos, e) in routes.iter().enumerate() {
+            if e.base_route.is_available() {
                 let old_value = e.index.fetch_sub(1, Ordering::SeqCst);
                 if old_value > 0 {
                     if log_enabled!(Level::Debug) {
                         debug!("WeightRoute current index:{}", pos as i32);
                     }
-                    return Ok(e.base_route.clone());
+                    return Some(e.base_route.clone());
                 }
             }
         }
+        None
+    }
+    /**
+    *The index of every route is reset to its weight when the indexes of all the available routes
+    have been used up.The selection is retried after the reset,as the other requests may use up the
+    indexes before this one.
+    */
+    async fn get_route(
+        &self,
+        _headers: HeaderMap<HeaderValue>,
+    ) -> Result<BaseRoute, anyhow::Error> {
+        let routes = self.routes.load();
+        loop {
+            if !routes
+                .iter()
+                .any(|item| item.base_route.is_available() && item.weight > 0)
+            {
+                return Err(anyhow!("There are no alive host!"));
+            }
+            if let Some(base_route) = WeightBasedRoute::select_route(&routes) {
+                return Ok(base_route);
+            }
+            let index_is_alive = routes.iter().any(|item| {
+                item.base_route.is_available() && item.index.load(Ordering::SeqCst).is_positive()
+            });
+            if !index_is_alive {
+                routes
+                    .iter()
+                    .for_each(|item| item.index.store(item.weight as isize, Ordering::SeqCst));
+            }
+        }
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum DnsRecordType {
+    #[default]
+    A,
+    Srv,
+}
+#[derive(Debug, Clone, Default)]
+pub struct DnsRoute {
+    pub endpoint: String,
+    pub record_type: DnsRecordType,
+    pub weight: i32,
+    pub refresh_interval: u64,
+    pub last_refresh_time: Arc<RwLock<Option<Instant>>>,
+    pub cluster: WeightBasedRoute,
+}
+impl DnsRoute {
+    pub fn from(dns_route_vistor: DnsRouteVistor) -> Self {
+        DnsRoute {
+            endpoint: dns_route_vistor.endpoint,
+            record_type: dns_route_vistor.record_type,
+            weight: dns_route_vistor.weight,
+            refresh_interval: dns_route_vistor.refresh_interval,
+            last_refresh_time: Arc::new(RwLock::new(None)),
+            cluster: WeightBasedRoute {
+                routes: Arc::new(ArcSwap::from_pointee(WeightRoute::new_list(
+                    dns_route_vistor.routes,
+                ))),
+            },
+        }
+    }
+}
 
-        drop(cluster_read_lock);
-        if all_cluster_dead {
-            return Err(anyhow!("There are no alive host!"));
+impl DnsRoute {
+    async fn get_all_route(&self) -> Result<Vec<BaseRoute>, anyhow::Error> {
+        self.cluster.get_all_route().await
+    }
+
+    async fn get_route(&self, headers: HeaderMap<HeaderValue>) -> Result<BaseRoute, anyhow::Error> {
+        if self.cluster.routes.load().is_empty() {
+            return Err(anyhow!(
+                "The endpoint-{} has not been resolved yet!",
+                self.endpoint
+            ));
         }
-        let mut new_lock = self.routes.write().await;
-        let index_is_alive = new_lock.iter().any(|f| {
-            let tt = f.index.load(Ordering::SeqCst);
-            tt.is_positive()
-        });
-        if !index_is_alive {
-            (*new_lock).iter_mut().for_each(|mut weight_route| {
-                weight_route.index = Arc::new(AtomicIsize::from(weight_route.weight as isize))
-            });
+        self.cluster.get_route(headers).await
+    }
+    pub async fn need_refresh(&self) -> bool {
+        let last_refresh_time = self.last_refresh_time.read().await;
+        match *last_refresh_time {
+            Some(time) => time.elapsed() >= Duration::from_secs(self.refresh_interval),
+            None => true,
         }
-        drop(new_lock);
-        let cluster_read_lock2 = self.routes.read().await;
+    }
+    pub async fn update_routes(&self, resolved_endpoints: Vec<(String, i32)>) -> i32 {
+        let liveness_count_change = self.cluster.update_routes(resolved_endpoints).await;
+        *self.last_refresh_time.write().await = Some(Instant::now());
+        liveness_count_change
+    }
+}
+#[derive(Debug, Clone, Default)]
+pub struct RegistryRoute {
+    pub cluster_name: String,
+    pub cluster: WeightBasedRoute,
+}
+impl RegistryRoute {
+    pub fn from(registry_route_vistor: RegistryRouteVistor) -> Self {
+        RegistryRoute {
+            cluster_name: registry_route_vistor.cluster_name,
+            cluster: WeightBasedRoute {
+                routes: Arc::new(ArcSwap::from_pointee(WeightRoute::new_list(
+                    registry_route_vistor.routes,
+                ))),
+            },
+        }
+    }
+}
 
-        for (pos, e) in cluster_read_lock2.iter().enumerate() {
-            let is_alive_option_lock = e.base_route.is_alive.read().await;
-            let is_alive = is_alive_option_lock.unwrap_or(true);
-            if is_alive {
-                let old_value = e.index.fetch_sub(1, Ordering::SeqCst);
-                if old_value > 0 {
-                    if log_enabled!(Level::Debug) {
-                        debug!("WeightRoute current index:{}", pos as i32);
-                    }
-                    return Ok(e.base_route.clone());
-                }
-            }
+impl RegistryRoute {
+    async fn get_all_route(&self) -> Result<Vec<BaseRoute>, anyhow::Error> {
+        self.cluster.get_all_route().await
+    }
+
+    async fn get_route(&self, headers: HeaderMap<HeaderValue>) -> Result<BaseRoute, anyhow::Error> {
+        if self.cluster.routes.load().is_empty() {
+            return Err(anyhow!(
+                "There are no instances registered in the cluster-{}!",
+                self.cluster_name
+            ));
         }
-        Err(anyhow!("WeightRoute get route error"))
+        self.cluster.get_route(headers).await
     }
 }
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::vojo::anomaly_detection::BaseAnomalyDetectionParam;
+    use crate::vojo::app_config::LivenessStatus;
+    use crate::vojo::maintenance::EndpointStatus;
     use std::vec;
     #[derive(PartialEq, Eq, Debug)]
     pub struct BaseRouteWithoutLock {
@@ -579,8 +830,8 @@ mod tests {
     }
     impl BaseRouteWithoutLock {
         async fn new(base_route: BaseRoute) -> Self {
-            let is_alive = *base_route.is_alive.read().await;
-            let anomaly_detection_status = base_route.anomaly_detection_status.read().await.clone();
+            let is_alive = base_route.is_alive.load();
+            let anomaly_detection_status = base_route.anomaly_detection_status.load().clone();
             BaseRouteWithoutLock {
                 endpoint: base_route.endpoint,
                 try_file: base_route.try_file,
@@ -597,10 +848,12 @@ mod tests {
                     BaseRoute {
                         endpoint: String::from("http://localhost:4444"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     }
                 },
             },
@@ -609,10 +862,12 @@ mod tests {
                     BaseRoute {
                         endpoint: String::from("http://localhost:5555"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     }
                 },
             },
@@ -621,10 +876,12 @@ mod tests {
                     BaseRoute {
                         endpoint: String::from("http://localhost:5555"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     }
                 },
             },
@@ -637,10 +894,12 @@ mod tests {
                     BaseRoute {
                         endpoint: String::from("http://localhost:4444"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     }
                 },
             },
@@ -649,10 +908,12 @@ mod tests {
                     BaseRoute {
                         endpoint: String::from("http://localhost:5555"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     }
                 },
             },
@@ -661,10 +922,12 @@ mod tests {
                     BaseRoute {
                         endpoint: String::from("http://localhost:5555"),
                         try_file: None,
-                        is_alive: Arc::new(RwLock::new(None)),
-                        anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                            consecutive_5xx: 100,
-                        })),
+                        is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                        anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                            AnomalyDetectionStatus {
+                                consecutive_5xx: 100,
+                            },
+                        )),
                     }
                 },
             },
@@ -676,10 +939,12 @@ mod tests {
                 base_route: BaseRoute {
                     endpoint: String::from("http://localhost:4444"),
                     try_file: None,
-                    is_alive: Arc::new(RwLock::new(None)),
-                    anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                        consecutive_5xx: 100,
-                    })),
+                    is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                    anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                        AnomalyDetectionStatus {
+                            consecutive_5xx: 100,
+                        },
+                    )),
                 },
                 weight: 100,
                 index: Arc::new(AtomicIsize::new(0)),
@@ -687,11 +952,13 @@ mod tests {
             WeightRoute {
                 base_route: BaseRoute {
                     endpoint: String::from("http://localhost:5555"),
-                    anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                        consecutive_5xx: 100,
-                    })),
+                    anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                        AnomalyDetectionStatus {
+                            consecutive_5xx: 100,
+                        },
+                    )),
                     try_file: None,
-                    is_alive: Arc::new(RwLock::new(None)),
+                    is_alive: Arc::new(AtomicAliveStatus::new(None)),
                 },
                 weight: 100,
                 index: Arc::new(AtomicIsize::new(0)),
@@ -700,10 +967,12 @@ mod tests {
                 base_route: BaseRoute {
                     endpoint: String::from("http://localhost:6666"),
                     try_file: None,
-                    is_alive: Arc::new(RwLock::new(None)),
-                    anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                        consecutive_5xx: 100,
-                    })),
+                    is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                    anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                        AnomalyDetectionStatus {
+                            consecutive_5xx: 100,
+                        },
+                    )),
                 },
                 weight: 100,
                 index: Arc::new(AtomicIsize::new(0)),
@@ -716,10 +985,12 @@ mod tests {
                 base_route: BaseRoute {
                     endpoint: String::from("http://localhost:4444"),
                     try_file: None,
-                    is_alive: Arc::new(RwLock::new(None)),
-                    anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                        consecutive_5xx: 100,
-                    })),
+                    is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                    anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                        AnomalyDetectionStatus {
+                            consecutive_5xx: 100,
+                        },
+                    )),
                 },
                 header_key: String::from("x-client"),
                 header_value_mapping_type: HeaderValueMappingType::Regex(RegexMatch {
@@ -730,10 +1001,12 @@ mod tests {
                 base_route: BaseRoute {
                     endpoint: String::from("http://localhost:5555"),
                     try_file: None,
-                    is_alive: Arc::new(RwLock::new(None)),
-                    anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                        consecutive_5xx: 100,
-                    })),
+                    is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                    anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                        AnomalyDetectionStatus {
+                            consecutive_5xx: 100,
+                        },
+                    )),
                 },
                 header_key: String::from("x-client"),
                 header_value_mapping_type: HeaderValueMappingType::Split(SplitSegment {
@@ -749,10 +1022,12 @@ mod tests {
                 base_route: BaseRoute {
                     endpoint: String::from("http://localhost:7777"),
                     try_file: None,
-                    is_alive: Arc::new(RwLock::new(None)),
-                    anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                        consecutive_5xx: 100,
-                    })),
+                    is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                    anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                        AnomalyDetectionStatus {
+                            consecutive_5xx: 100,
+                        },
+                    )),
                 },
                 header_key: String::from("x-client"),
                 header_value_mapping_type: HeaderValueMappingType::Split(SplitSegment {
@@ -768,10 +1043,12 @@ mod tests {
                 base_route: BaseRoute {
                     endpoint: String::from("http://localhost:8888"),
                     try_file: None,
-                    is_alive: Arc::new(RwLock::new(None)),
-                    anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                        consecutive_5xx: 100,
-                    })),
+                    is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                    anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                        AnomalyDetectionStatus {
+                            consecutive_5xx: 100,
+                        },
+                    )),
                 },
                 header_key: String::from("x-client"),
                 header_value_mapping_type: HeaderValueMappingType::Text(TextMatch {
@@ -790,7 +1067,7 @@ mod tests {
     #[tokio::test]
     async fn test_poll_route_successfully() {
         let routes = get_poll_routes();
-        let mut poll_rate = PollRoute {
+        let poll_rate = PollRoute {
             current_index: Default::default(),
             routes: routes.clone(),
         };
@@ -803,9 +1080,59 @@ mod tests {
         }
     }
     #[tokio::test]
+    async fn test_poll_route_skip_draining_endpoint() {
+        let routes = vec![
+            PollBaseRoute {
+                base_route: BaseRoute {
+                    endpoint: String::from("http://localhost:19020"),
+                    try_file: None,
+                    is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                    anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                        AnomalyDetectionStatus { consecutive_5xx: 0 },
+                    )),
+                },
+            },
+            PollBaseRoute {
+                base_route: BaseRoute {
+                    endpoint: String::from("http://localhost:19021"),
+                    try_file: None,
+                    is_alive: Arc::new(AtomicAliveStatus::new(None)),
+                    anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                        AnomalyDetectionStatus { consecutive_5xx: 0 },
+                    )),
+                },
+            },
+        ];
+        let poll_route = PollRoute {
+            current_index: Default::default(),
+            routes,
+        };
+        endpoint_maintenance::set_endpoint_status(
+            String::from("http://localhost:19020"),
+            EndpointStatus::Draining,
+        );
+        for _ in 0..10 {
+            let current_route = poll_route.get_route(HeaderMap::new()).await.unwrap();
+            assert_eq!(current_route.endpoint, "http://localhost:19021");
+        }
+        endpoint_maintenance::set_endpoint_status(
+            String::from("http://localhost:19021"),
+            EndpointStatus::Disabled,
+        );
+        assert!(poll_route.get_route(HeaderMap::new()).await.is_err());
+        endpoint_maintenance::set_endpoint_status(
+            String::from("http://localhost:19020"),
+            EndpointStatus::Active,
+        );
+        endpoint_maintenance::set_endpoint_status(
+            String::from("http://localhost:19021"),
+            EndpointStatus::Active,
+        );
+    }
+    #[tokio::test]
     async fn test_random_route_successfully() {
         let routes = get_random_routes();
-        let mut random_rate = RandomRoute { routes };
+        let random_rate = RandomRoute { routes };
         for _ in 0..100 {
             random_rate.get_route(HeaderMap::new()).await.unwrap();
         }
@@ -813,8 +1140,8 @@ mod tests {
     #[tokio::test]
     async fn test_weight_route_successfully() {
         let routes = get_weight_routes();
-        let mut weight_route = WeightBasedRoute {
-            routes: Arc::new(RwLock::new(routes.clone())),
+        let weight_route = WeightBasedRoute {
+            routes: Arc::new(ArcSwap::from_pointee(routes.clone())),
         };
 
         for _ in 0..100 {
@@ -848,12 +1175,82 @@ mod tests {
             );
         }
     }
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_weight_route_concurrently() {
+        let weight_route = Arc::new(WeightBasedRoute {
+            routes: Arc::new(ArcSwap::from_pointee(vec![
+                WeightRoute {
+                    base_route: BaseRoute {
+                        endpoint: String::from("http://localhost:4444"),
+                        ..Default::default()
+                    },
+                    weight: 1,
+                    index: Arc::new(AtomicIsize::new(0)),
+                },
+                WeightRoute {
+                    base_route: BaseRoute {
+                        endpoint: String::from("http://localhost:5555"),
+                        ..Default::default()
+                    },
+                    weight: 1,
+                    index: Arc::new(AtomicIsize::new(0)),
+                },
+            ])),
+        });
+        let mut tasks = vec![];
+        for _ in 0..8 {
+            let weight_route = weight_route.clone();
+            tasks.push(tokio::spawn(async move {
+                for _ in 0..1000 {
+                    assert!(weight_route.get_route(HeaderMap::new()).await.is_ok());
+                }
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+    }
+    #[tokio::test]
+    async fn test_dns_route_update_routes_successfully() {
+        let dns_route = DnsRoute {
+            endpoint: String::from("http://backend.internal:8080"),
+            weight: 100,
+            refresh_interval: 30,
+            ..Default::default()
+        };
+        assert!(dns_route.get_route(HeaderMap::new()).await.is_err());
+        let liveness_count_change = dns_route
+            .update_routes(vec![
+                (String::from("http://10.0.0.1:8080/"), 100),
+                (String::from("http://10.0.0.2:8080/"), 100),
+            ])
+            .await;
+        assert_eq!(liveness_count_change, 2);
+        let old_routes = dns_route.get_all_route().await.unwrap();
+        old_routes[0].is_alive.store(Some(false));
+
+        let liveness_count_change = dns_route
+            .update_routes(vec![
+                (String::from("http://10.0.0.1:8080/"), 50),
+                (String::from("http://10.0.0.3:8080/"), 100),
+            ])
+            .await;
+        assert_eq!(liveness_count_change, 0);
+        let routes = dns_route.cluster.routes.load();
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].weight, 50);
+        assert_eq!(routes[0].base_route.is_alive.load(), Some(false));
+        assert_eq!(routes[1].base_route.endpoint, "http://10.0.0.3:8080/");
+
+        let base_route = dns_route.get_route(HeaderMap::new()).await.unwrap();
+        assert_eq!(base_route.endpoint, "http://10.0.0.3:8080/");
+    }
 
     #[tokio::test]
     async fn test_header_based_route_successfully() {
         let routes = get_header_based_routes();
         let header_route = HeaderBasedRoute { routes };
-        let mut header_route = LoadbalancerStrategy::HeaderBased(header_route);
+        let header_route = LoadbalancerStrategy::HeaderBased(header_route);
         let mut headermap1 = HeaderMap::new();
         headermap1.insert("x-client", "100zh-CN,zh;q=0.9,en;q=0.8".parse().unwrap());
         let result1 = header_route.get_route(headermap1.clone()).await;
@@ -883,22 +1280,22 @@ mod tests {
         let base_route = BaseRoute {
             endpoint: String::from("/"),
             try_file: None,
-            is_alive: Arc::new(RwLock::new(None)),
-            anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                consecutive_5xx: 0,
-            })),
+            is_alive: Arc::new(AtomicAliveStatus::new(None)),
+            anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                AnomalyDetectionStatus { consecutive_5xx: 0 },
+            )),
         };
-        let liveness_status_lock = Arc::new(RwLock::new(LivenessStatus {
+        let liveness_status_lock = Arc::new(AtomicLivenessStatus::from(LivenessStatus {
             current_liveness_count: 3,
         }));
 
         base_route
             .update_health_check_status_with_ok(liveness_status_lock.clone())
             .await;
-        let is_alive_option = base_route.is_alive.read().await;
+        let is_alive_option = base_route.is_alive.load();
         assert!(is_alive_option.is_some(),);
         assert!(is_alive_option.unwrap(),);
-        let liveness_status = liveness_status_lock.read().await;
+        let liveness_status = liveness_status_lock.load();
         assert_eq!(liveness_status.current_liveness_count, 3);
     }
     #[tokio::test]
@@ -906,22 +1303,22 @@ mod tests {
         let base_route = BaseRoute {
             endpoint: String::from("/"),
             try_file: None,
-            is_alive: Arc::new(RwLock::new(Some(true))),
-            anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                consecutive_5xx: 0,
-            })),
+            is_alive: Arc::new(AtomicAliveStatus::new(Some(true))),
+            anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                AnomalyDetectionStatus { consecutive_5xx: 0 },
+            )),
         };
-        let liveness_status_lock = Arc::new(RwLock::new(LivenessStatus {
+        let liveness_status_lock = Arc::new(AtomicLivenessStatus::from(LivenessStatus {
             current_liveness_count: 3,
         }));
 
         base_route
             .update_health_check_status_with_ok(liveness_status_lock.clone())
             .await;
-        let is_alive_option = base_route.is_alive.read().await;
+        let is_alive_option = base_route.is_alive.load();
         assert!(is_alive_option.is_some(),);
         assert!(is_alive_option.unwrap(),);
-        let liveness_status = liveness_status_lock.read().await;
+        let liveness_status = liveness_status_lock.load();
         assert_eq!(liveness_status.current_liveness_count, 3);
     }
     #[tokio::test]
@@ -929,22 +1326,22 @@ mod tests {
         let base_route = BaseRoute {
             endpoint: String::from("/"),
             try_file: None,
-            is_alive: Arc::new(RwLock::new(Some(false))),
-            anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                consecutive_5xx: 0,
-            })),
+            is_alive: Arc::new(AtomicAliveStatus::new(Some(false))),
+            anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                AnomalyDetectionStatus { consecutive_5xx: 0 },
+            )),
         };
-        let liveness_status_lock = Arc::new(RwLock::new(LivenessStatus {
+        let liveness_status_lock = Arc::new(AtomicLivenessStatus::from(LivenessStatus {
             current_liveness_count: 3,
         }));
 
         base_route
             .update_health_check_status_with_ok(liveness_status_lock.clone())
             .await;
-        let is_alive_option = base_route.is_alive.read().await;
+        let is_alive_option = base_route.is_alive.load();
         assert!(is_alive_option.is_some(),);
         assert!(is_alive_option.unwrap(),);
-        let liveness_status = liveness_status_lock.read().await;
+        let liveness_status = liveness_status_lock.load();
         assert_eq!(liveness_status.current_liveness_count, 4);
     }
 
@@ -953,12 +1350,12 @@ mod tests {
         let base_route = BaseRoute {
             endpoint: String::from("/"),
             try_file: None,
-            is_alive: Arc::new(RwLock::new(None)),
-            anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                consecutive_5xx: 0,
-            })),
+            is_alive: Arc::new(AtomicAliveStatus::new(None)),
+            anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                AnomalyDetectionStatus { consecutive_5xx: 0 },
+            )),
         };
-        let liveness_status_lock = Arc::new(RwLock::new(LivenessStatus {
+        let liveness_status_lock = Arc::new(AtomicLivenessStatus::from(LivenessStatus {
             current_liveness_count: 3,
         }));
 
@@ -971,7 +1368,7 @@ mod tests {
             )
             .await;
         assert!(!result);
-        let is_alive_option = base_route.is_alive.read().await;
+        let is_alive_option = base_route.is_alive.load();
         assert!(is_alive_option.is_none());
     }
     #[tokio::test]
@@ -979,12 +1376,12 @@ mod tests {
         let base_route = BaseRoute {
             endpoint: String::from("/"),
             try_file: None,
-            is_alive: Arc::new(RwLock::new(Some(true))),
-            anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                consecutive_5xx: 0,
-            })),
+            is_alive: Arc::new(AtomicAliveStatus::new(Some(true))),
+            anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                AnomalyDetectionStatus { consecutive_5xx: 0 },
+            )),
         };
-        let liveness_status_lock = Arc::new(RwLock::new(LivenessStatus {
+        let liveness_status_lock = Arc::new(AtomicLivenessStatus::from(LivenessStatus {
             current_liveness_count: 3,
         }));
 
@@ -998,10 +1395,10 @@ mod tests {
             .await;
         //update fails
         assert!(!result);
-        let is_alive_option = base_route.is_alive.read().await;
+        let is_alive_option = base_route.is_alive.load();
         assert!(is_alive_option.is_some());
         assert!(is_alive_option.unwrap());
-        let liveness_status = liveness_status_lock.read().await;
+        let liveness_status = liveness_status_lock.load();
         assert_eq!(liveness_status.current_liveness_count, 3);
     }
     #[tokio::test]
@@ -1009,12 +1406,12 @@ mod tests {
         let base_route = BaseRoute {
             endpoint: String::from("/"),
             try_file: None,
-            is_alive: Arc::new(RwLock::new(Some(false))),
-            anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                consecutive_5xx: 0,
-            })),
+            is_alive: Arc::new(AtomicAliveStatus::new(Some(false))),
+            anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                AnomalyDetectionStatus { consecutive_5xx: 0 },
+            )),
         };
-        let liveness_status_lock = Arc::new(RwLock::new(LivenessStatus {
+        let liveness_status_lock = Arc::new(AtomicLivenessStatus::from(LivenessStatus {
             current_liveness_count: 3,
         }));
 
@@ -1027,10 +1424,10 @@ mod tests {
             )
             .await;
         assert!(!result);
-        let is_alive_option = base_route.is_alive.read().await;
+        let is_alive_option = base_route.is_alive.load();
         assert!(is_alive_option.is_some(),);
         assert!(!is_alive_option.unwrap(),);
-        let liveness_status = liveness_status_lock.read().await;
+        let liveness_status = liveness_status_lock.load();
         assert_eq!(liveness_status.current_liveness_count, 3);
     }
 
@@ -1039,12 +1436,12 @@ mod tests {
         let base_route = BaseRoute {
             endpoint: String::from("/"),
             try_file: None,
-            is_alive: Arc::new(RwLock::new(Some(false))),
-            anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                consecutive_5xx: 0,
-            })),
+            is_alive: Arc::new(AtomicAliveStatus::new(Some(false))),
+            anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                AnomalyDetectionStatus { consecutive_5xx: 0 },
+            )),
         };
-        let liveness_status_lock = Arc::new(RwLock::new(LivenessStatus {
+        let liveness_status_lock = Arc::new(AtomicLivenessStatus::from(LivenessStatus {
             current_liveness_count: 3,
         }));
         let http_anomaly_detection_param = HttpAnomalyDetectionParam {
@@ -1062,7 +1459,7 @@ mod tests {
             )
             .await;
         assert!(result.is_ok());
-        let anomaly_detection_status = base_route.anomaly_detection_status.read().await;
+        let anomaly_detection_status = base_route.anomaly_detection_status.load();
         assert_eq!(anomaly_detection_status.consecutive_5xx, 1);
     }
     #[tokio::test]
@@ -1070,12 +1467,12 @@ mod tests {
         let base_route = BaseRoute {
             endpoint: String::from("/"),
             try_file: None,
-            is_alive: Arc::new(RwLock::new(Some(true))),
-            anomaly_detection_status: Arc::new(RwLock::new(AnomalyDetectionStatus {
-                consecutive_5xx: 1,
-            })),
+            is_alive: Arc::new(AtomicAliveStatus::new(Some(true))),
+            anomaly_detection_status: Arc::new(AtomicAnomalyDetectionStatus::from(
+                AnomalyDetectionStatus { consecutive_5xx: 1 },
+            )),
         };
-        let liveness_status_lock = Arc::new(RwLock::new(LivenessStatus {
+        let liveness_status_lock = Arc::new(AtomicLivenessStatus::from(LivenessStatus {
             current_liveness_count: 4,
         }));
         let http_anomaly_detection_param = HttpAnomalyDetectionParam {
@@ -1094,15 +1491,12 @@ mod tests {
             .await;
         assert!(result.is_ok(),);
         {
-            let anomaly_detection_status1 = base_route.anomaly_detection_status.read().await;
+            let anomaly_detection_status1 = base_route.anomaly_detection_status.load();
             assert_eq!(anomaly_detection_status1.consecutive_5xx, 2);
-            drop(anomaly_detection_status1);
-            let is_alive_option1 = base_route.is_alive.read().await;
+            let is_alive_option1 = base_route.is_alive.load();
             assert!(is_alive_option1.unwrap(),);
-            drop(is_alive_option1);
-            let liveness_status1 = liveness_status_lock.read().await;
+            let liveness_status1 = liveness_status_lock.load();
             assert_eq!(liveness_status1.current_liveness_count, 4);
-            drop(liveness_status1);
         }
         {
             let result2 = base_route
@@ -1116,25 +1510,22 @@ mod tests {
                 )
                 .await;
             assert!(result2.is_ok(),);
-            let anomaly_detection_status2 = base_route.anomaly_detection_status.read().await;
+            let anomaly_detection_status2 = base_route.anomaly_detection_status.load();
             assert_eq!(anomaly_detection_status2.consecutive_5xx, 2);
-            drop(anomaly_detection_status2);
 
-            let is_alive_option2 = base_route.is_alive.read().await;
+            let is_alive_option2 = base_route.is_alive.load();
             assert!(!is_alive_option2.unwrap(),);
-            drop(is_alive_option2);
 
-            let liveness_status2 = liveness_status_lock.read().await;
+            let liveness_status2 = liveness_status_lock.load();
             assert_eq!(liveness_status2.current_liveness_count, 3);
-            drop(liveness_status2);
         }
         sleep(Duration::from_secs(4)).await;
         {
-            let anomaly_detection_status3 = base_route.anomaly_detection_status.read().await;
+            let anomaly_detection_status3 = base_route.anomaly_detection_status.load();
             assert_eq!(anomaly_detection_status3.consecutive_5xx, 0);
-            let is_alive_option3 = base_route.is_alive.read().await;
+            let is_alive_option3 = base_route.is_alive.load();
             assert!(is_alive_option3.unwrap());
-            let liveness_status3 = liveness_status_lock.read().await;
+            let liveness_status3 = liveness_status_lock.load();
             assert_eq!(liveness_status3.current_liveness_count, 4);
         }
     }
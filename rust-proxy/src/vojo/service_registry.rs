@@ -0,0 +1,18 @@
+use crate::vojo::app_config_vistor::new_uuid;
+use serde::{Deserialize, Serialize};
+fn default_weight() -> i32 {
+    100
+}
+fn default_ttl() -> u64 {
+    30
+}
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistryInstanceVistor {
+    #[serde(default = "new_uuid")]
+    pub instance_id: String,
+    pub endpoint: String,
+    #[serde(default = "default_weight")]
+    pub weight: i32,
+    #[serde(default = "default_ttl")]
+    pub ttl: u64,
+}